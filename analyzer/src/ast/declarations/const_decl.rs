@@ -0,0 +1,72 @@
+use crate::{
+    ast::{
+        access_specifier::AccessSpecifier, expressions::Expression, identifier::Identifier,
+        type_specifier::TypeSpecifier, AstParse,
+    },
+    error::Result,
+    error_semantic,
+    interpreter::value::Value,
+    lexer::{token::Span, token_type::TokenType},
+    parser::Parser,
+    semantic::constant,
+};
+
+#[derive(Debug)]
+pub struct ConstDeclaration {
+    pub access_specifier: AccessSpecifier,
+    pub identifier: Identifier,
+    pub type_specifier: TypeSpecifier,
+    pub value: Expression,
+    /// The value folded while parsing, which replaces every use.
+    pub constant: Value,
+    pub span: Span,
+}
+
+impl AstParse for ConstDeclaration {
+    fn parse(parser: &mut Parser) -> Result<Self> {
+        let start = parser.current().span().clone();
+        let identifier = Identifier::parse(parser)?;
+        parser.next_token_and_expect(TokenType::Colon)?;
+        parser.next_token()?;
+        let type_specifier = TypeSpecifier::parse(parser)?;
+        parser.next_token_and_expect(TokenType::Assign)?;
+        parser.next_token()?;
+        let value = Expression::parse(parser)?;
+        let constant = parser.evaluate_constant(&value)?;
+        if constant::type_specifier(&constant).ok() != Some(type_specifier.clone()) {
+            return error_semantic!(value.span(), TypeMismatch, "Miss matched types");
+        }
+        parser.next_token_and_expect(TokenType::Semicolon)?;
+        parser
+            .constants()
+            .declare_constant(&identifier, constant.clone())?;
+        return Ok(Self::new(
+            AccessSpecifier::Private,
+            identifier,
+            type_specifier,
+            value,
+            constant,
+            parser.span_from(&start),
+        ));
+    }
+}
+
+impl ConstDeclaration {
+    pub fn new(
+        access_specifier: AccessSpecifier,
+        identifier: Identifier,
+        type_specifier: TypeSpecifier,
+        value: Expression,
+        constant: Value,
+        span: Span,
+    ) -> Self {
+        Self {
+            access_specifier,
+            identifier,
+            type_specifier,
+            value,
+            constant,
+            span,
+        }
+    }
+}
@@ -1,11 +1,20 @@
 use crate::{
     ast::{
-        access_specifier::AccessSpecifier, identifier::Identifier, parenthesis::Parenthesis,
+        access_specifier::AccessSpecifier,
+        declarations::struct_decl::{StructDeclaration, StructField},
+        expressions::Expression,
+        identifier::Identifier,
+        parenthesis::Parenthesis,
+        type_specifier::TypeSpecifier,
+        variable_type::VariableType,
         AstParse,
     },
     error::Result,
-    lexer::token_type::TokenType,
+    error_semantic,
+    interpreter::value::Value,
+    lexer::{token::Span, token_type::TokenType},
     parser::Parser,
+    semantic::constant,
     symbol_table::ToSymbol,
 };
 
@@ -13,32 +22,158 @@ use crate::{
 pub struct EnumDeclaration {
     pub access_specifier: AccessSpecifier,
     pub name: Identifier,
-    pub fields: Vec<Identifier>,
+    pub fields: Vec<EnumField>,
+    /// The struct the enum is stored as when its items carry values: the
+    /// tag of the item followed by the values of every item.
+    pub layout: Option<StructDeclaration>,
+    /// The `///` comment before the declaration.
+    pub doc: Option<String>,
+    pub span: Span,
+}
+
+/// An item of an enum and the number it is stored as.
+#[derive(Debug)]
+pub struct EnumField {
+    pub identifier: Identifier,
+    /// The types of the values the item carries, like `double` in
+    /// `Circle(double)`.
+    pub payload: Vec<TypeSpecifier>,
+    /// The value written after `=`, if any.
+    pub value: Option<Expression>,
+    /// The written value folded while parsing, or the previous item's plus
+    /// one.
+    pub discriminant: i32,
+    pub span: Span,
 }
 
 impl AstParse for EnumDeclaration {
     fn parse(parser: &mut Parser) -> Result<Self> {
+        let start = parser.current().span().clone();
         let name = Identifier::parse(parser)?;
         parser.next_token_and_expect(TokenType::OpenCurlyBrace)?;
-        let fields = Parenthesis::parse(parser, TokenType::CloseCurlyBrace, |parser| {
-            return Ok(Identifier::from(parser.expect(TokenType::Identifier)?));
-        })?;
-        let enum_decl = Self::new(AccessSpecifier::Private, name, fields);
+        let items = Parenthesis::parse(parser, TokenType::CloseCurlyBrace, EnumField::parse)?;
+
+        let mut fields: Vec<EnumField> = Vec::new();
+        let mut next: Option<i32> = Some(0);
+        for (mut item, discriminant) in items {
+            let Some(discriminant) = discriminant.or(next) else {
+                return error_semantic!(
+                    &item.span,
+                    ConstantEvaluation,
+                    format!("The value of {} overflows int", item.identifier)
+                );
+            };
+            if let Some(field) = fields.iter().find(|f| f.discriminant == discriminant) {
+                return error_semantic!(
+                    &item.span,
+                    DuplicateDiscriminant,
+                    format!(
+                        "{} has the same value {} as {}",
+                        item.identifier, discriminant, field.identifier
+                    )
+                );
+            }
+            next = discriminant.checked_add(1);
+            item.discriminant = discriminant;
+            fields.push(item);
+        }
+
+        let span = parser.span_from(&start);
+        let enum_decl = Self::new(AccessSpecifier::Private, name, fields, span);
         enum_decl.to_symbol(parser.symbol_table().borrow().root())?;
         return Ok(enum_decl);
     }
 }
 
+impl EnumField {
+    /// Parses `Name`, `Name(types)` and either followed by `= value`. The
+    /// folded value is returned apart since items without one are numbered
+    /// after the item before them.
+    fn parse(parser: &mut Parser) -> Result<(Self, Option<i32>)> {
+        let identifier = Identifier::from(parser.expect(TokenType::Identifier)?);
+        let mut payload = Vec::new();
+        if parser.next().token_type() == &TokenType::OpenParen {
+            parser.next_token()?;
+            payload = Parenthesis::parse(parser, TokenType::CloseParen, TypeSpecifier::parse)?;
+        }
+        let mut value = None;
+        let mut discriminant = None;
+        if parser.next().token_type() == &TokenType::Assign {
+            parser.next_token()?;
+            parser.next_token()?;
+            let expr = Expression::parse(parser)?;
+            let constant = parser.evaluate_constant(&expr)?;
+            let Value::Int(constant) = constant else {
+                return error_semantic!(
+                    expr.span(),
+                    TypeMismatch,
+                    format!(
+                        "Enum values must be int but found {}",
+                        constant::type_specifier(&constant).unwrap_or(TypeSpecifier::Void)
+                    )
+                );
+            };
+            value = Some(expr);
+            discriminant = Some(constant);
+        }
+        let item = Self {
+            span: parser.span_from(&identifier.span),
+            identifier,
+            payload,
+            value,
+            discriminant: 0,
+        };
+        return Ok((item, discriminant));
+    }
+}
+
 impl EnumDeclaration {
     pub fn new(
         access_specifier: AccessSpecifier,
         name: Identifier,
-        fields: Vec<Identifier>,
+        fields: Vec<EnumField>,
+        span: Span,
     ) -> Self {
+        let layout = Self::layout(&name, &fields);
         Self {
             access_specifier,
             name,
             fields,
+            layout,
+            doc: None,
+            span,
+        }
+    }
+
+    /// The field of the layout holding the value of the item.
+    pub const TAG: &'static str = "tag";
+
+    /// The field of the layout holding the `index`th value of `item`.
+    pub fn payload_field(item: &str, index: usize) -> String {
+        return format!("{}_{}", item, index);
+    }
+
+    fn layout(name: &Identifier, fields: &[EnumField]) -> Option<StructDeclaration> {
+        if fields.iter().all(|item| item.payload.is_empty()) {
+            return None;
+        }
+        let field = |name: String, type_specifier: TypeSpecifier, span: &Span| {
+            let identifier = Identifier::new(name, span.clone());
+            let field = VariableType::new(identifier, type_specifier);
+            return StructField::new(AccessSpecifier::Public, field);
+        };
+        let mut layout = vec![field(Self::TAG.to_owned(), TypeSpecifier::Int, &name.span)];
+        for item in fields.iter() {
+            for (index, type_specifier) in item.payload.iter().enumerate() {
+                let name = Self::payload_field(item.identifier.as_ref(), index);
+                layout.push(field(name, type_specifier.clone(), &item.span));
+            }
         }
+        return Some(StructDeclaration::new(
+            AccessSpecifier::Public,
+            name.clone(),
+            layout,
+            name.span.clone(),
+        ));
     }
 }
@@ -17,14 +17,19 @@ pub struct FnDeclaration {
     pub params: Vec<VariableType>,
     pub return_type: TypeSpecifier,
     pub body: BlockStmt,
+    /// The `///` comment before the declaration.
+    pub doc: Option<String>,
 }
 
 impl AstParse for FnDeclaration {
     fn parse(parser: &mut Parser) -> Result<Self> {
         let fn_name = Identifier::parse(parser)?;
-        let params = Self::parse_params(parser)?;
-        let return_type = Self::parse_return_type(parser)?;
-        let body = Self::parse_body(parser)?;
+        let (params, return_type, body) = parser.scope(|parser| {
+            let params = Self::parse_params(parser)?;
+            let return_type = Self::parse_return_type(parser)?;
+            let body = Self::parse_body(parser)?;
+            return Ok((params, return_type, body));
+        })?;
         let fn_decl =
             FnDeclaration::new(AccessSpecifier::Private, fn_name, params, return_type, body);
         fn_decl.to_symbol(parser.symbol_table().borrow().root())?;
@@ -46,13 +51,18 @@ impl FnDeclaration {
             params,
             return_type,
             body,
+            doc: None,
         }
     }
 
     pub(crate) fn parse_params(parser: &mut Parser) -> Result<Vec<VariableType>> {
         parser.next_token_and_expect(TokenType::OpenParen)?;
         return Parenthesis::parse(parser, TokenType::CloseParen, |parser| {
-            return Ok(VariableType::parse(parser)?);
+            let param = VariableType::parse(parser)?;
+            parser
+                .constants()
+                .declare_variable(param.identifier.as_ref());
+            return Ok(param);
         });
     }
 
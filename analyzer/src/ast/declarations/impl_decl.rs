@@ -6,7 +6,7 @@ use crate::{
     },
     error::Result,
     error_parser,
-    lexer::token_type::TokenType,
+    lexer::{token::Span, token_type::TokenType},
     parser::Parser,
     symbol_table::ToSymbol,
 };
@@ -15,6 +15,7 @@ use crate::{
 pub struct ImplDeclaration {
     pub name: TypeSpecifier,
     pub member_functions: Vec<MemberFunction>,
+    pub span: Span,
 }
 
 #[derive(Debug)]
@@ -30,10 +31,13 @@ pub struct MemberFunctionPrototype {
     pub name: Identifier,
     pub params: Vec<VariableType>,
     pub return_type: TypeSpecifier,
+    /// The `///` comment before the method.
+    pub doc: Option<String>,
 }
 
 impl AstParse for ImplDeclaration {
     fn parse(parser: &mut Parser) -> Result<Self> {
+        let start = parser.current().span().clone();
         parser.next_token()?; // skip impl token
         let name = TypeSpecifier::parse(parser)?;
 
@@ -45,30 +49,34 @@ impl AstParse for ImplDeclaration {
                 break;
             }
             if parser.current().token_type() == &TokenType::Eof {
-                return error_parser!(parser, "Missing close curly brace '}'");
+                return error_parser!(parser, UnclosedDelimiter, "Missing close curly brace '}'");
             }
             member_func.push(MemberFunction::parse(parser)?);
         }
-        let impl_decl = ImplDeclaration::new(name, member_func);
+        let span = parser.span_from(&start);
+        let impl_decl = ImplDeclaration::new(name, member_func, span);
         impl_decl.to_symbol(parser.symbol_table().borrow().root())?;
         return Ok(impl_decl);
     }
 }
 
 impl ImplDeclaration {
-    pub fn new(name: TypeSpecifier, member_functions: Vec<MemberFunction>) -> Self {
+    pub fn new(name: TypeSpecifier, member_functions: Vec<MemberFunction>, span: Span) -> Self {
         Self {
             name,
             member_functions,
+            span,
         }
     }
 }
 
 impl AstParse for MemberFunction {
     fn parse(parser: &mut Parser) -> Result<Self> {
-        let prototype = MemberFunctionPrototype::parse(parser)?;
-        let body = BlockStmt::parse(parser)?;
-        return Ok(MemberFunction::new(prototype, body));
+        return parser.scope(|parser| {
+            let prototype = MemberFunctionPrototype::parse(parser)?;
+            let body = BlockStmt::parse(parser)?;
+            return Ok(MemberFunction::new(prototype, body));
+        });
     }
 }
 
@@ -77,6 +85,16 @@ impl MemberFunction {
         Self { prototype, body }
     }
 
+    /// The receiver, if any, followed by the regular parameters.
+    pub fn params(&self) -> Vec<&VariableType> {
+        return self
+            .prototype
+            .method
+            .iter()
+            .chain(self.prototype.params.iter())
+            .collect();
+    }
+
     fn parse_method(parser: &mut Parser) -> Result<Option<VariableType>> {
         if parser.next().token_type() != &TokenType::OpenParen {
             return Ok(None);
@@ -84,6 +102,9 @@ impl MemberFunction {
         parser.next_token()?; // Skip fn keyword
         parser.next_token()?; // Skip open parenthesis
         let variable_type = VariableType::parse(parser)?;
+        parser
+            .constants()
+            .declare_variable(variable_type.identifier.as_ref());
         parser.next_token_and_expect(TokenType::CloseParen)?;
         return Ok(Some(variable_type));
     }
@@ -91,19 +112,16 @@ impl MemberFunction {
 
 impl AstParse for MemberFunctionPrototype {
     fn parse(parser: &mut Parser) -> Result<Self> {
+        let doc = parser.current().doc().cloned();
         let access_specifier = AccessSpecifier::parse(parser)?;
         parser.expect(TokenType::Fn)?;
         let method = MemberFunction::parse_method(parser)?;
         let name = Identifier::parse(parser)?;
         let params = FnDeclaration::parse_params(parser)?;
         let return_type = FnDeclaration::parse_return_type(parser)?;
-        return Ok(Self::new(
-            access_specifier,
-            method,
-            name,
-            params,
-            return_type,
-        ));
+        let mut prototype = Self::new(access_specifier, method, name, params, return_type);
+        prototype.doc = doc;
+        return Ok(prototype);
     }
 }
 
@@ -121,6 +139,7 @@ impl MemberFunctionPrototype {
             name,
             params,
             return_type,
+            doc: None,
         }
     }
 }
@@ -1,15 +1,25 @@
+pub mod const_decl;
 pub mod enum_decl;
 pub mod fn_decl;
 pub mod impl_decl;
 pub mod struct_decl;
 
 use self::{
-    enum_decl::EnumDeclaration, fn_decl::FnDeclaration, impl_decl::ImplDeclaration,
-    struct_decl::StructDeclaration,
+    const_decl::ConstDeclaration, enum_decl::EnumDeclaration, fn_decl::FnDeclaration,
+    impl_decl::ImplDeclaration, struct_decl::StructDeclaration,
 };
 
 use super::{access_specifier::AccessSpecifier, AstParse};
-use crate::{error::CompilerError, error_parser, lexer::token_type::TokenType, parser::Parser};
+use crate::{
+    error::CompilerError,
+    error_parser,
+    lexer::{
+        token::{Span, Token},
+        token_type::TokenType,
+    },
+    parser::Parser,
+    symbol_table::ToSymbol,
+};
 
 #[derive(Debug)]
 pub enum Declarations {
@@ -17,34 +27,98 @@ pub enum Declarations {
     Struct(StructDeclaration),
     Enum(EnumDeclaration),
     Impl(ImplDeclaration),
+    Const(ConstDeclaration),
 }
 
 impl AstParse for Declarations {
     fn parse(parser: &mut Parser) -> Result<Self, CompilerError> {
+        let doc = parser.current().doc().cloned();
         let access_specifier = AccessSpecifier::parse(parser)?;
         match parser.current().token_type() {
             TokenType::Fn => {
                 let mut fn_decl = FnDeclaration::parse(parser)?;
                 fn_decl.access_specifier = access_specifier;
+                fn_decl.doc = doc;
                 return Ok(Declarations::Fn(fn_decl));
             }
             TokenType::Struct => {
                 let mut struct_decl = StructDeclaration::parse(parser)?;
                 struct_decl.access_specifier = access_specifier;
+                struct_decl.doc = doc;
                 return Ok(Declarations::Struct(struct_decl));
             }
             TokenType::Enum => {
                 let mut enum_decl = EnumDeclaration::parse(parser)?;
                 enum_decl.access_specifier = access_specifier;
+                enum_decl.doc = doc;
                 return Ok(Declarations::Enum(enum_decl));
             }
             TokenType::Impl => {
                 if let AccessSpecifier::Public = access_specifier {
-                    return error_parser!(parser, "Unexpected access specifier");
+                    return error_parser!(
+                        parser,
+                        ExpectedDeclaration,
+                        "Unexpected access specifier"
+                    );
                 }
                 return Ok(Declarations::Impl(ImplDeclaration::parse(parser)?));
             }
-            _ => return error_parser!(parser, "Unknown declarations"),
+            TokenType::Const => {
+                let mut const_decl = ConstDeclaration::parse(parser)?;
+                const_decl.access_specifier = access_specifier;
+                const_decl.to_symbol(parser.symbol_table().borrow().root())?;
+                return Ok(Declarations::Const(const_decl));
+            }
+            _ => return error_parser!(parser, ExpectedDeclaration, "Unknown declarations"),
+        }
+    }
+}
+
+impl Declarations {
+    /// The span of the declaration, without its access specifier. Functions
+    /// start at their name.
+    pub fn span(&self) -> Span {
+        return match self {
+            Declarations::Fn(fn_decl) => fn_decl.fn_name.span.to(&fn_decl.body.span),
+            Declarations::Struct(struct_decl) => struct_decl.span.clone(),
+            Declarations::Enum(enum_decl) => enum_decl.span.clone(),
+            Declarations::Impl(impl_decl) => impl_decl.span.clone(),
+            Declarations::Const(const_decl) => const_decl.span.clone(),
+        };
+    }
+
+    /// Whether a token begins a declaration, which is where parsing resumes
+    /// after an error.
+    pub fn is_start(token_type: &TokenType) -> bool {
+        return matches!(
+            token_type,
+            TokenType::Fn
+                | TokenType::Struct
+                | TokenType::Enum
+                | TokenType::Impl
+                | TokenType::Const
+                | TokenType::Pub
+        );
+    }
+
+    /// Skips tokens up to the start of the next top-level declaration. The
+    /// token a failed declaration started at is never resumed from, so
+    /// parsing always moves forward.
+    pub(crate) fn synchronize(parser: &mut Parser, start: &Token) -> Result<(), CompilerError> {
+        let mut depth: usize = 0;
+        loop {
+            match parser.current().token_type() {
+                TokenType::Eof => return Ok(()),
+                TokenType::OpenCurlyBrace => depth += 1,
+                TokenType::CloseCurlyBrace => depth = depth.saturating_sub(1),
+                token_type
+                    if depth == 0 && Self::is_start(token_type) && parser.current() != start =>
+                {
+                    return Ok(());
+                }
+                _ => {}
+            }
+            parser.next_token()?;
         }
     }
 }
@@ -4,7 +4,7 @@ use crate::{
         variable_type::VariableType, AstParse,
     },
     error::Result,
-    lexer::token_type::TokenType,
+    lexer::{token::Span, token_type::TokenType},
     parser::Parser,
     symbol_table::ToSymbol,
 };
@@ -14,6 +14,9 @@ pub struct StructDeclaration {
     pub access_specifier: AccessSpecifier,
     pub name: Identifier,
     pub fields: Vec<StructField>,
+    /// The `///` comment before the declaration.
+    pub doc: Option<String>,
+    pub span: Span,
 }
 
 #[derive(Debug)]
@@ -24,12 +27,14 @@ pub struct StructField {
 
 impl AstParse for StructDeclaration {
     fn parse(parser: &mut Parser) -> Result<Self> {
+        let start = parser.current().span().clone();
         let name = Identifier::parse(parser)?;
         parser.next_token_and_expect(TokenType::OpenCurlyBrace)?;
         let fields = Parenthesis::parse(parser, TokenType::CloseCurlyBrace, |parser| {
             return StructField::parse(parser);
         })?;
-        let struct_decl = Self::new(AccessSpecifier::Private, name, fields);
+        let span = parser.span_from(&start);
+        let struct_decl = Self::new(AccessSpecifier::Private, name, fields, span);
         struct_decl.to_symbol(parser.symbol_table().borrow().root())?;
         return Ok(struct_decl);
     }
@@ -40,11 +45,14 @@ impl StructDeclaration {
         access_specifier: AccessSpecifier,
         name: Identifier,
         fields: Vec<StructField>,
+        span: Span,
     ) -> Self {
         Self {
             access_specifier,
             name,
             fields,
+            doc: None,
+            span,
         }
     }
 }
@@ -1,4 +1,9 @@
-use crate::{ast::AstParse, error::Result, lexer::token_type::TokenType, parser::Parser};
+use crate::{
+    ast::AstParse,
+    error::Result,
+    lexer::{token::Span, token_type::TokenType},
+    parser::Parser,
+};
 
 use super::{Expression, Precedence};
 
@@ -7,6 +12,7 @@ pub struct ArrayIndexExpression {
     pub array: Box<Expression>,
     pub index: Box<Expression>,
     pub second_index: Option<Box<Expression>>,
+    pub span: Span,
 }
 
 impl ArrayIndexExpression {
@@ -14,11 +20,13 @@ impl ArrayIndexExpression {
         array: Box<Expression>,
         index: Box<Expression>,
         second_index: Option<Box<Expression>>,
+        span: Span,
     ) -> Self {
         Self {
             array,
             index,
             second_index,
+            span,
         }
     }
 
@@ -39,10 +47,12 @@ impl ArrayIndexExpression {
         }
 
         parser.next_token_and_expect(TokenType::CloseBracket)?;
+        let span = parser.span_from(left.span());
         return Ok(Expression::ArrayIndex(Self::new(
             Box::from(left),
             Box::from(right),
             second_index,
+            span,
         )));
     }
 }
@@ -1,7 +1,7 @@
 use crate::{
     ast::{parenthesis::Parenthesis, AstParse},
     error::Result,
-    lexer::token_type::TokenType,
+    lexer::{token::Span, token_type::TokenType},
     parser::Parser,
 };
 
@@ -11,11 +11,12 @@ use super::{Expression, Precedence};
 pub struct ArrayInitExpression {
     pub array: Vec<Expression>,
     pub size: usize,
+    pub span: Span,
 }
 
 impl ArrayInitExpression {
-    pub fn new(array: Vec<Expression>, size: usize) -> Self {
-        Self { array, size }
+    pub fn new(array: Vec<Expression>, size: usize, span: Span) -> Self {
+        Self { array, size, span }
     }
 
     pub fn parse(parser: &mut Parser, precedence: Precedence) -> Result<Expression> {
@@ -23,11 +24,13 @@ impl ArrayInitExpression {
             return Expression::parse_expr(parser, precedence.next());
         }
 
+        let start = parser.current().span().clone();
         let array = Parenthesis::parse(parser, TokenType::CloseBracket, |parser| {
             return Expression::parse(parser);
         })?;
         let size = array.len();
 
-        return Ok(Expression::ArrayInit(Self::new(array, size)));
+        let span = parser.span_from(&start);
+        return Ok(Expression::ArrayInit(Self::new(array, size, span)));
     }
 }
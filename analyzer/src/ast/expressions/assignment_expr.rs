@@ -1,4 +1,8 @@
-use crate::{error::Result, lexer::token_type::TokenType, parser::Parser};
+use crate::{
+    error::Result,
+    lexer::{token::Span, token_type::TokenType},
+    parser::Parser,
+};
 
 use super::{Expression, Precedence};
 
@@ -6,11 +10,16 @@ use super::{Expression, Precedence};
 pub struct AssignmentExpression {
     pub variable: Box<Expression>,
     pub value: Box<Expression>,
+    pub span: Span,
 }
 
 impl AssignmentExpression {
-    pub fn new(variable: Box<Expression>, value: Box<Expression>) -> Self {
-        Self { variable, value }
+    pub fn new(variable: Box<Expression>, value: Box<Expression>, span: Span) -> Self {
+        Self {
+            variable,
+            value,
+            span,
+        }
     }
 
     pub fn parse(parser: &mut Parser, precedence: Precedence) -> Result<Expression> {
@@ -24,9 +33,11 @@ impl AssignmentExpression {
         parser.next_token()?;
         let right = Expression::parse_expr(parser, Precedence::default())?;
 
+        let span = parser.span_from(left.span());
         return Ok(Expression::Assignment(AssignmentExpression::new(
             Box::from(left),
             Box::from(right),
+            span,
         )));
     }
 }
@@ -1,7 +1,7 @@
 use super::{Expression, Precedence};
 use crate::{
     error::{CompilerError, Result},
-    lexer::token_type::TokenType,
+    lexer::{token::Span, token_type::TokenType},
     parser::Parser,
 };
 use std::str::FromStr;
@@ -11,6 +11,7 @@ pub struct BinaryExpression {
     pub left: Box<Expression>,
     pub operator: BinaryOperators,
     pub right: Box<Expression>,
+    pub span: Span,
 }
 
 #[derive(Debug)]
@@ -51,11 +52,17 @@ pub enum BinaryPrecedence {
 
 //TODO FIX Is valid token, Maybe ? -> rewrite the parse function for every binary precedence
 impl BinaryExpression {
-    pub fn new(left: Box<Expression>, operator: BinaryOperators, right: Box<Expression>) -> Self {
+    pub fn new(
+        left: Box<Expression>,
+        operator: BinaryOperators,
+        right: Box<Expression>,
+        span: Span,
+    ) -> Self {
         Self {
             left,
             operator,
             right,
+            span,
         }
     }
 
@@ -136,10 +143,12 @@ impl BinaryExpression {
         parser.next_token()?;
         let right = Self::parse(parser, precedence, binary_precedence)?;
 
+        let span = parser.span_from(left.span());
         return Ok(Expression::Binary(BinaryExpression::new(
             Box::from(left),
             op,
             Box::from(right),
+            span,
         )));
     }
 
@@ -157,10 +166,12 @@ impl BinaryExpression {
         let op = BinaryOperators::from_str(parser.current().text())?;
         parser.next_token()?;
         let right = Self::parse(parser, precedence, binary_precedence)?;
+        let span = parser.span_from(left.span());
         return Ok(Expression::Binary(BinaryExpression::new(
             Box::from(left),
             op,
             Box::from(right),
+            span,
         )));
     }
 
@@ -182,10 +193,12 @@ impl BinaryExpression {
         let op = BinaryOperators::from_str(parser.current().text())?;
         parser.next_token()?;
         let right = Self::parse(parser, precedence, binary_precedence)?;
+        let span = parser.span_from(left.span());
         return Ok(Expression::Binary(BinaryExpression::new(
             Box::from(left),
             op,
             Box::from(right),
+            span,
         )));
     }
 }
@@ -1,7 +1,7 @@
 use crate::{
     ast::{type_specifier::TypeSpecifier, AstParse},
     error::Result,
-    lexer::token_type::TokenType,
+    lexer::{token::Span, token_type::TokenType},
     parser::Parser,
 };
 
@@ -11,18 +11,25 @@ use super::{Expression, Precedence};
 pub struct CastExpression {
     pub target_type: TypeSpecifier,
     pub expression: Box<Expression>,
+    pub span: Span,
 }
 
 impl CastExpression {
-    pub fn new(target_type: TypeSpecifier, expression: Box<Expression>) -> Self {
+    pub fn new(target_type: TypeSpecifier, expression: Box<Expression>, span: Span) -> Self {
         Self {
             target_type,
             expression,
+            span,
         }
     }
 
     pub fn parse(parser: &mut Parser, precedence: Precedence) -> Result<Expression> {
         let left = Expression::parse_expr(parser, precedence.next())?;
+        return Self::parse_as(parser, left);
+    }
+
+    /// Wraps `left` in a cast if `as` follows it.
+    pub fn parse_as(parser: &mut Parser, left: Expression) -> Result<Expression> {
         if parser.next().token_type() != &TokenType::As {
             return Ok(left);
         }
@@ -30,7 +37,8 @@ impl CastExpression {
         parser.next_token()?;
 
         let target_type = TypeSpecifier::parse(parser)?;
-        let expr = CastExpression::new(target_type, Box::new(left));
+        let span = parser.span_from(left.span());
+        let expr = CastExpression::new(target_type, Box::new(left), span);
         return Ok(Expression::Cast(expr));
     }
 }
@@ -0,0 +1,26 @@
+use crate::{
+    ast::{expressions::Expression, identifier::Identifier},
+    lexer::token::Span,
+};
+
+/// An item of an enum, like `Color::Red` or `Shape::Circle(2.0)`, whose type
+/// is the enum.
+#[derive(Debug)]
+pub struct EnumItemExpression {
+    pub name: Identifier,
+    pub item: Identifier,
+    /// The values an item that carries data is built from.
+    pub args: Vec<Expression>,
+    pub span: Span,
+}
+
+impl EnumItemExpression {
+    pub fn new(name: Identifier, item: Identifier, args: Vec<Expression>, span: Span) -> Self {
+        Self {
+            name,
+            item,
+            args,
+            span,
+        }
+    }
+}
@@ -1,17 +1,21 @@
 use crate::{
     ast::{parenthesis::Parenthesis, AstParse},
     error::Result,
-    lexer::token_type::TokenType,
+    error_parser,
+    lexer::{token::Span, token_type::TokenType},
     parser::Parser,
 };
 
-use super::{Expression, Precedence};
+use super::{
+    cast_expr::CastExpression, enum_item_expr::EnumItemExpression, Expression, Precedence,
+};
 
 #[derive(Debug)]
 pub struct FnCallExpression {
     pub fn_name: Box<Expression>,
     pub namespace: Option<NameSpace>,
     pub args: Vec<Expression>,
+    pub span: Span,
 }
 
 #[derive(Debug)]
@@ -24,29 +28,40 @@ impl FnCallExpression {
         fn_name: Box<Expression>,
         namespace: Option<NameSpace>,
         args: Vec<Expression>,
+        span: Span,
     ) -> Self {
         Self {
             fn_name,
             namespace,
             args,
+            span,
         }
     }
 
     pub fn parse(parser: &mut Parser, precedence: Precedence) -> Result<Expression> {
+        let start = parser.current().span().clone();
         let left = Expression::parse_expr(parser, precedence.next())?;
-        let (namespace, left) = NameSpace::parse(parser, precedence, left)?;
+        let (namespace, left) = NameSpace::parse(parser, left)?;
 
         if parser.next().token_type() != &TokenType::OpenParen {
-            return Ok(left);
+            return match namespace {
+                Some(namespace) => {
+                    let enum_item = NameSpace::enum_item(parser, namespace, left)?;
+                    CastExpression::parse_as(parser, enum_item)
+                }
+                None => Ok(left),
+            };
         }
         parser.next_token()?;
         let args = Parenthesis::parse(parser, TokenType::CloseParen, |parser| {
             return Expression::parse(parser);
         })?;
+        let span = parser.span_from(&start);
         return Ok(Expression::FnCall(Self::new(
             Box::from(left),
             namespace,
             args,
+            span,
         )));
     }
 }
@@ -56,21 +71,32 @@ impl NameSpace {
         Self { name }
     }
 
-    pub fn parse(
-        parser: &mut Parser,
-        precedence: Precedence,
-        left: Expression,
-    ) -> Result<(Option<Self>, Expression)> {
-        if parser.next().token_type() != &TokenType::Colon {
+    pub fn parse(parser: &mut Parser, left: Expression) -> Result<(Option<Self>, Expression)> {
+        if parser.next().token_type() != &TokenType::DoubleColon {
             return Ok((None, left));
         }
         parser.next_token()?;
-        parser.next_token_and_expect(TokenType::Colon)?;
         parser.next_token()?;
         let namespace = NameSpace::new(Box::from(left));
         return Ok((
             Some(namespace),
-            Expression::parse_expr(parser, precedence.next())?,
+            Expression::parse_expr(parser, Precedence::Identifier)?,
         ));
     }
+
+    /// `Name::item` without a call is an item of the enum `Name`.
+    fn enum_item(parser: &mut Parser, namespace: Self, item: Expression) -> Result<Expression> {
+        return match (*namespace.name, item) {
+            (Expression::Identifier(name), Expression::Identifier(item)) => {
+                let span = name.span.to(&item.span);
+                let enum_item = EnumItemExpression::new(name.id, item.id, Vec::new(), span);
+                Ok(Expression::EnumItem(enum_item))
+            }
+            _ => error_parser!(
+                parser,
+                UnexpectedToken,
+                "Expect an enum item like `Color::Red`"
+            ),
+        };
+    }
 }
@@ -1,5 +1,8 @@
 use crate::{
-    ast::identifier::Identifier, error::Result, lexer::token_type::TokenType, parser::Parser,
+    ast::identifier::Identifier,
+    error::Result,
+    lexer::{token::Span, token_type::TokenType},
+    parser::Parser,
 };
 
 use super::{Expression, Precedence};
@@ -7,11 +10,13 @@ use super::{Expression, Precedence};
 #[derive(Debug)]
 pub struct IdentifierExpression {
     pub id: Identifier,
+    pub span: Span,
 }
 
 impl IdentifierExpression {
     pub fn new(id: Identifier) -> Self {
-        Self { id }
+        let span = id.span.clone();
+        Self { id, span }
     }
 
     pub fn parse(parser: &mut Parser, precedence: Precedence) -> Result<Expression> {
@@ -19,7 +24,7 @@ impl IdentifierExpression {
         if token.token_type() != &TokenType::Identifier {
             return Expression::parse_expr(parser, precedence.next());
         }
-        let id = Identifier::from(token.text());
+        let id = Identifier::from(token);
         return Ok(Expression::Identifier(IdentifierExpression::new(id)));
     }
 }
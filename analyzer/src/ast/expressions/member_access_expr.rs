@@ -1,4 +1,8 @@
-use crate::{error::Result, lexer::token_type::TokenType, parser::Parser};
+use crate::{
+    error::Result,
+    lexer::{token::Span, token_type::TokenType},
+    parser::Parser,
+};
 
 use super::{Expression, Precedence};
 
@@ -7,14 +11,21 @@ pub struct MemberAccessExpression {
     pub object: Box<Expression>,
     pub access: TokenType,
     pub property: Box<Expression>,
+    pub span: Span,
 }
 
 impl MemberAccessExpression {
-    pub fn new(object: Box<Expression>, access: TokenType, property: Box<Expression>) -> Self {
+    pub fn new(
+        object: Box<Expression>,
+        access: TokenType,
+        property: Box<Expression>,
+        span: Span,
+    ) -> Self {
         Self {
             object,
             access,
             property,
+            span,
         }
     }
 
@@ -29,10 +40,12 @@ impl MemberAccessExpression {
         parser.next_token()?;
         parser.next_token()?;
         let property = Expression::parse_expr(parser, precedence)?;
+        let span = parser.span_from(left.span());
         return Ok(Expression::MemberAccess(Self::new(
             Box::from(left),
             access,
             Box::from(property),
+            span,
         )));
     }
 }
@@ -3,6 +3,7 @@ pub mod array_init_expr;
 pub mod assignment_expr;
 pub mod binary_expr;
 pub mod cast_expr;
+pub mod enum_item_expr;
 pub mod fn_call_expr;
 pub mod identifier_expression;
 pub mod member_access_expr;
@@ -10,7 +11,7 @@ pub mod object_init_expr;
 pub mod primitive_expr;
 pub mod unary_expr;
 
-use crate::{error::Result, parser::Parser};
+use crate::{error::Result, lexer::token::Span, parser::Parser};
 
 use self::{
     array_index_expr::ArrayIndexExpression,
@@ -18,6 +19,7 @@ use self::{
     assignment_expr::AssignmentExpression,
     binary_expr::{BinaryExpression, BinaryPrecedence},
     cast_expr::CastExpression,
+    enum_item_expr::EnumItemExpression,
     fn_call_expr::FnCallExpression,
     identifier_expression::IdentifierExpression,
     member_access_expr::MemberAccessExpression,
@@ -39,6 +41,7 @@ pub enum Expression {
     ArrayInit(ArrayInitExpression),
     ObjectInit(ObjectInitExpression),
     Identifier(IdentifierExpression),
+    EnumItem(EnumItemExpression),
     Primitive(PrimitiveExpression),
     Cast(CastExpression),
 }
@@ -65,6 +68,23 @@ impl AstParse for Expression {
 }
 
 impl Expression {
+    pub fn span(&self) -> &Span {
+        return match self {
+            Expression::Binary(binary_expr) => &binary_expr.span,
+            Expression::Assignment(assignment_expr) => &assignment_expr.span,
+            Expression::Unary(unary_expr) => &unary_expr.span,
+            Expression::ArrayIndex(array_index_expr) => &array_index_expr.span,
+            Expression::MemberAccess(member_access_expr) => &member_access_expr.span,
+            Expression::FnCall(fn_call_expr) => &fn_call_expr.span,
+            Expression::ArrayInit(array_init_expr) => &array_init_expr.span,
+            Expression::ObjectInit(object_init_expr) => &object_init_expr.span,
+            Expression::Identifier(identifier_expr) => &identifier_expr.span,
+            Expression::EnumItem(enum_item_expr) => &enum_item_expr.span,
+            Expression::Primitive(primitive_expr) => &primitive_expr.span,
+            Expression::Cast(cast_expr) => &cast_expr.span,
+        };
+    }
+
     pub(super) fn parse_expr(parser: &mut Parser, precedence: Precedence) -> Result<Self> {
         match precedence {
             Precedence::Binary => {
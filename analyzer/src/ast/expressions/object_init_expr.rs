@@ -4,7 +4,7 @@ use crate::{
         parenthesis::Parenthesis, AstParse,
     },
     error::Result,
-    lexer::token_type::TokenType,
+    lexer::{token::Span, token_type::TokenType},
     parser::Parser,
 };
 
@@ -14,6 +14,7 @@ use super::{Expression, Precedence};
 pub struct ObjectInitExpression {
     pub object: Box<Expression>,
     pub fields: Vec<Field>,
+    pub span: Span,
 }
 
 #[derive(Debug)]
@@ -23,9 +24,14 @@ pub struct Field {
 }
 
 impl ObjectInitExpression {
-    pub fn new(object: Box<Expression>, fields: Vec<Field>) -> Self {
-        Self { object, fields }
+    pub fn new(object: Box<Expression>, fields: Vec<Field>, span: Span) -> Self {
+        Self {
+            object,
+            fields,
+            span,
+        }
     }
+
     pub fn parse(parser: &mut Parser, precedence: Precedence) -> Result<Expression> {
         let left = Expression::parse_expr(parser, precedence.next())?;
         if parser.next().token_type() != &TokenType::OpenCurlyBrace {
@@ -35,7 +41,12 @@ impl ObjectInitExpression {
         let fields = Parenthesis::parse(parser, TokenType::CloseCurlyBrace, |parser| {
             return Field::parse(parser);
         })?;
-        return Ok(Expression::ObjectInit(Self::new(Box::from(left), fields)));
+        let span = parser.span_from(left.span());
+        return Ok(Expression::ObjectInit(Self::new(
+            Box::from(left),
+            fields,
+            span,
+        )));
     }
 }
 
@@ -2,7 +2,7 @@ use crate::{
     ast::{type_specifier::TypeSpecifier, AstParse},
     error::Result,
     error_parser,
-    lexer::token_type::TokenType,
+    lexer::{token::Span, token_type::TokenType},
     parser::Parser,
 };
 
@@ -12,13 +12,15 @@ use super::Expression;
 pub struct PrimitiveExpression {
     pub type_specifier: TypeSpecifier,
     pub value: String,
+    pub span: Span,
 }
 
 impl PrimitiveExpression {
-    pub fn new(type_specifier: TypeSpecifier, value: String) -> Self {
+    pub fn new(type_specifier: TypeSpecifier, value: String, span: Span) -> Self {
         Self {
             type_specifier,
             value,
+            span,
         }
     }
 
@@ -27,6 +29,7 @@ impl PrimitiveExpression {
             return Ok(expr);
         }
         let token = parser.current();
+        let span = token.span().clone();
         let primitive_expr = match token.token_type() {
             TokenType::Number => {
                 let type_specifier = if parser.current().text().contains(".") {
@@ -34,17 +37,42 @@ impl PrimitiveExpression {
                 } else {
                     TypeSpecifier::Int
                 };
-                Self::new(type_specifier, token.text().to_string())
+                Self::new(type_specifier, token.text().to_string(), span)
             }
-            TokenType::String => Self::new(TypeSpecifier::Str, token.text().to_string()),
-            TokenType::Char => Self::new(TypeSpecifier::Char, token.text().to_string()),
-            TokenType::True => Self::new(TypeSpecifier::Bool, token.text().to_string()),
-            TokenType::False => Self::new(TypeSpecifier::Bool, token.text().to_string()),
-            _ => return error_parser!(parser, "Unknown Expression!"),
+            TokenType::String => Self::new(TypeSpecifier::Str, token.text().to_string(), span),
+            TokenType::Char => Self::new(TypeSpecifier::Char, token.text().to_string(), span),
+            TokenType::True => Self::new(TypeSpecifier::Bool, token.text().to_string(), span),
+            TokenType::False => Self::new(TypeSpecifier::Bool, token.text().to_string(), span),
+            _ => return error_parser!(parser, ExpectedExpression, "Unknown Expression!"),
         };
         return Ok(Expression::Primitive(primitive_expr));
     }
 
+    /// The bytes of a char or str literal with its escape sequences replaced.
+    pub fn bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut iter = self.value.bytes();
+        while let Some(byte) = iter.next() {
+            if byte != b'\\' {
+                bytes.push(byte);
+                continue;
+            }
+            bytes.push(match iter.next() {
+                Some(b'a') => 0x07,
+                Some(b'b') => 0x08,
+                Some(b'f') => 0x0c,
+                Some(b'n') => b'\n',
+                Some(b'r') => b'\r',
+                Some(b't') => b'\t',
+                Some(b'v') => 0x0b,
+                Some(b'0') => 0,
+                Some(other) => other,
+                None => b'\\',
+            });
+        }
+        return bytes;
+    }
+
     fn parse_parenthesis(parser: &mut Parser) -> Result<Option<Expression>> {
         if parser.current().token_type() != &TokenType::OpenParen {
             return Ok(None);
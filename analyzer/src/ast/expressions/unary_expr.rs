@@ -1,4 +1,8 @@
-use crate::{error::Result, lexer::token_type::TokenType, parser::Parser};
+use crate::{
+    error::Result,
+    lexer::{token::Span, token_type::TokenType},
+    parser::Parser,
+};
 
 use super::{Expression, Precedence};
 
@@ -7,6 +11,7 @@ pub struct UnaryExpression {
     pub operator: UnaryOperators,
     pub value: Box<Expression>,
     pub expression_type: UnaryExpressionType,
+    pub span: Span,
 }
 
 #[derive(Debug)]
@@ -32,11 +37,13 @@ impl UnaryExpression {
         operator: UnaryOperators,
         value: Box<Expression>,
         expression_type: UnaryExpressionType,
+        span: Span,
     ) -> Self {
         Self {
             operator,
             value,
             expression_type,
+            span,
         }
     }
 
@@ -53,10 +60,12 @@ impl UnaryExpression {
             _ => return Ok(left),
         };
         parser.next_token()?;
+        let span = parser.span_from(left.span());
         return Ok(Expression::Unary(UnaryExpression::new(
             operator,
             Box::from(left),
             UnaryExpressionType::Postfix,
+            span,
         )));
     }
 
@@ -70,9 +79,16 @@ impl UnaryExpression {
         } else {
             return Ok(None);
         };
+        let start = parser.current().span().clone();
         parser.next_token()?;
         let value = Expression::parse_expr(parser, precedence.next())?;
-        let expr = UnaryExpression::new(operator, Box::from(value), UnaryExpressionType::Prefix);
+        let span = parser.span_from(&start);
+        let expr = UnaryExpression::new(
+            operator,
+            Box::from(value),
+            UnaryExpressionType::Prefix,
+            span,
+        );
         return Ok(Some(Expression::Unary(expr)));
     }
 }
@@ -1,13 +1,24 @@
 use crate::{
     ast::AstParse,
     error::Result,
-    lexer::{token::Token, token_type::TokenType},
+    lexer::{
+        token::{Span, Token},
+        token_type::TokenType,
+    },
     parser::Parser,
 };
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, Clone)]
 pub struct Identifier {
     pub id: String,
+    pub span: Span,
+}
+
+/// Identifiers name the same thing wherever they are written.
+impl PartialEq for Identifier {
+    fn eq(&self, other: &Self) -> bool {
+        return self.id == other.id;
+    }
 }
 
 impl AsRef<str> for Identifier {
@@ -16,33 +27,33 @@ impl AsRef<str> for Identifier {
     }
 }
 
-impl ToString for Identifier {
-    fn to_string(&self) -> String {
-        return self.id.clone();
+impl std::fmt::Display for Identifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "{}", self.id);
     }
 }
 
 impl Identifier {
-    pub fn new(id: String) -> Self {
-        Self { id }
+    pub fn new(id: String, span: Span) -> Self {
+        Self { id, span }
     }
 }
 
 impl AstParse for Identifier {
     fn parse(parser: &mut Parser) -> Result<Self> {
         let token = parser.next_token_and_expect(TokenType::Identifier)?;
-        return Ok(Identifier::new(token.text().to_owned()));
+        return Ok(Identifier::from(token));
     }
 }
 
 impl From<&str> for Identifier {
     fn from(value: &str) -> Self {
-        return Self::new(value.to_owned());
+        return Self::new(value.to_owned(), Span::default());
     }
 }
 
 impl From<&Token> for Identifier {
     fn from(value: &Token) -> Self {
-        return Self::new(value.text().to_owned());
+        return Self::new(value.text().to_owned(), value.span().clone());
     }
 }
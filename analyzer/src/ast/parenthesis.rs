@@ -14,7 +14,7 @@ impl Parenthesis {
                 break;
             }
             if parser.current().token_type() == &TokenType::Eof {
-                return error_parser!(parser, format!("Missing {}", close));
+                return error_parser!(parser, UnclosedDelimiter, format!("Missing {}", close));
             }
             vec.push(callback(parser)?);
             parser.next_token()?;
@@ -26,6 +26,7 @@ impl Parenthesis {
             }
             return error_parser!(
                 parser,
+                UnexpectedToken,
                 format!(
                     "Expect Comma or {} but found {}",
                     close,
@@ -21,15 +21,24 @@ impl AstParse for Program {
     fn parse(parser: &mut Parser) -> Result<Self> {
         let mut decls = Vec::new();
         while parser.current().token_type() != &TokenType::Eof {
-            decls.push(Declarations::parse(parser)?);
-            parser.next_token()?;
+            let start = parser.current().clone();
+            match Declarations::parse(parser) {
+                Ok(decl) => {
+                    decls.push(decl);
+                    parser.next_token()?;
+                }
+                Err(err) => {
+                    parser.report(err);
+                    Declarations::synchronize(parser, &start)?;
+                }
+            }
         }
         return Ok(Program::new(decls));
     }
 }
 
-impl Into<ProgramRef> for Program {
-    fn into(self) -> ProgramRef {
-        return Rc::new(RefCell::new(self));
+impl From<Program> for ProgramRef {
+    fn from(value: Program) -> Self {
+        return Rc::new(RefCell::new(value));
     }
 }
@@ -1,8 +1,11 @@
 use crate::{
-    ast::{expressions::Expression, AstParse},
+    ast::{declarations::Declarations, expressions::Expression, AstParse},
     error::Result,
     error_parser,
-    lexer::token_type::TokenType,
+    lexer::{
+        token::{Span, Token},
+        token_type::TokenType,
+    },
     parser::Parser,
 };
 
@@ -11,43 +14,137 @@ use super::Statements;
 #[derive(Debug)]
 pub struct BlockStmt {
     pub stmts: Vec<Statements>,
+    pub span: Span,
+}
+
+/// Where parsing of a block continues after a broken statement.
+enum Recovery {
+    /// After the current token, which ended the broken statement.
+    Next,
+    /// At the current token, which starts the next statement.
+    Resume,
+    /// Nowhere, the current token closed the block.
+    Closed,
 }
 
 impl AstParse for BlockStmt {
     fn parse(parser: &mut Parser) -> Result<Self> {
-        parser.next_token_and_expect(TokenType::OpenCurlyBrace)?;
+        let start = parser
+            .next_token_and_expect(TokenType::OpenCurlyBrace)?
+            .span()
+            .clone();
         let stmts = Self::parse_loop(parser)?;
-        return Ok(BlockStmt::new(stmts));
+        return Ok(BlockStmt::new(stmts, parser.span_from(&start)));
     }
 }
 
 impl BlockStmt {
-    pub fn new(stmts: Vec<Statements>) -> Self {
-        Self { stmts }
+    pub fn new(stmts: Vec<Statements>, span: Span) -> Self {
+        Self { stmts, span }
+    }
+
+    pub fn ends_with_return(&self) -> bool {
+        return matches!(self.stmts.last(), Some(Statements::Return(_)));
     }
 
     fn parse_loop(parser: &mut Parser) -> Result<Vec<Statements>> {
-        let mut vec = Vec::new();
-        loop {
-            parser.next_token()?;
-            if parser.current().token_type() == &TokenType::CloseCurlyBrace {
-                break;
-            }
-            if parser.current().token_type() == &TokenType::Eof {
-                return error_parser!(parser, "Missing close curly brace '}'");
+        return parser.scope(|parser| {
+            let mut vec = Vec::new();
+            let mut resume = false;
+            loop {
+                if !std::mem::take(&mut resume) {
+                    parser.next_token()?;
+                }
+                if parser.current().token_type() == &TokenType::CloseCurlyBrace {
+                    break;
+                }
+                if Self::is_unclosed(parser) {
+                    return error_parser!(
+                        parser,
+                        UnclosedDelimiter,
+                        "Missing close curly brace '}'"
+                    );
+                }
+
+                let start = parser.current().clone();
+                match Self::parse_statement(parser) {
+                    Ok(stmt) => vec.push(stmt),
+                    // Nothing after the end of the file or the start of the
+                    // next declaration belongs to this block.
+                    Err(err) if Self::is_unclosed(parser) => return Err(err),
+                    Err(err) => {
+                        parser.report(err);
+                        match Self::synchronize(parser, &start)? {
+                            Recovery::Next => {}
+                            Recovery::Resume => resume = true,
+                            Recovery::Closed => break,
+                        }
+                    }
+                }
             }
+            return Ok(vec);
+        });
+    }
 
-            if Self::is_statement(parser) {
-                vec.push(Statements::parse(parser)?);
-            } else if parser.current().token_type() == &TokenType::OpenCurlyBrace {
-                vec.push(Statements::Block(Self::new(Self::parse_loop(parser)?)));
-            } else {
-                let expr = Expression::parse(parser)?;
-                parser.next_token_and_expect(TokenType::Semicolon)?;
-                vec.push(Statements::Expression(expr));
+    fn parse_statement(parser: &mut Parser) -> Result<Statements> {
+        if Self::is_statement(parser) {
+            return Statements::parse(parser);
+        }
+        if parser.current().token_type() == &TokenType::OpenCurlyBrace {
+            let start = parser.current().span().clone();
+            let stmts = Self::parse_loop(parser)?;
+            return Ok(Statements::Block(Self::new(
+                stmts,
+                parser.span_from(&start),
+            )));
+        }
+        let expr = Expression::parse(parser)?;
+        parser.next_token_and_expect(TokenType::Semicolon)?;
+        return Ok(Statements::Expression(expr));
+    }
+
+    /// Skips the rest of a statement that failed to parse, up to the `;`
+    /// ending it, the `}` closing a block it opened or the keyword of the
+    /// next statement, other than the one the broken statement started at.
+    fn synchronize(parser: &mut Parser, start: &Token) -> Result<Recovery> {
+        let mut depth = 0;
+        loop {
+            match parser.current().token_type() {
+                TokenType::Eof => return Ok(Recovery::Next),
+                TokenType::Semicolon if depth == 0 => return Ok(Recovery::Next),
+                TokenType::CloseCurlyBrace if depth == 0 => return Ok(Recovery::Closed),
+                TokenType::CloseCurlyBrace => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(Recovery::Next);
+                    }
+                }
+                TokenType::OpenCurlyBrace => depth += 1,
+                token_type if Self::is_declaration(token_type) => {
+                    return error_parser!(
+                        parser,
+                        UnclosedDelimiter,
+                        "Missing close curly brace '}'"
+                    );
+                }
+                _ if depth == 0 && Self::is_statement(parser) && parser.current() != start => {
+                    return Ok(Recovery::Resume);
+                }
+                _ => {}
             }
+            parser.next_token()?;
         }
-        return Ok(vec);
+    }
+
+    /// Tokens that only start a top-level declaration, which means the block
+    /// was never closed. Local constants are statements.
+    fn is_declaration(token_type: &TokenType) -> bool {
+        return token_type != &TokenType::Const && Declarations::is_start(token_type);
+    }
+
+    fn is_unclosed(parser: &Parser) -> bool {
+        let token_type = parser.current().token_type();
+        return token_type == &TokenType::Eof || Self::is_declaration(token_type);
     }
 
     fn is_statement(parser: &Parser) -> bool {
@@ -56,9 +153,15 @@ impl BlockStmt {
             TokenType::Let
                 | TokenType::Return
                 | TokenType::If
+                | TokenType::Switch
+                | TokenType::Match
                 | TokenType::For
                 | TokenType::While
+                | TokenType::Do
                 | TokenType::Defer
-        );
+                | TokenType::Const
+                | TokenType::Break
+                | TokenType::Continue
+        ) || Statements::is_label(parser);
     }
 }
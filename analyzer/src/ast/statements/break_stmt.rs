@@ -0,0 +1,38 @@
+use crate::{
+    ast::{identifier::Identifier, AstParse},
+    error::Result,
+    lexer::{token::Span, token_type::TokenType},
+    parser::Parser,
+};
+
+/// `break;` or `break label;`, which leaves the innermost loop or the loop
+/// with that label.
+#[derive(Debug)]
+pub struct BreakStmt {
+    pub label: Option<Identifier>,
+    pub span: Span,
+}
+
+impl AstParse for BreakStmt {
+    fn parse(parser: &mut Parser) -> Result<Self> {
+        let start = parser.current().span().clone();
+        let label = parse_label(parser)?;
+        parser.next_token_and_expect(TokenType::Semicolon)?;
+        return Ok(BreakStmt::new(label, parser.span_from(&start)));
+    }
+}
+
+impl BreakStmt {
+    pub fn new(label: Option<Identifier>, span: Span) -> Self {
+        Self { label, span }
+    }
+}
+
+/// The label after `break` or `continue`, if there is one.
+pub(crate) fn parse_label(parser: &mut Parser) -> Result<Option<Identifier>> {
+    if parser.next().token_type() != &TokenType::Identifier {
+        return Ok(None);
+    }
+    parser.next_token()?;
+    return Ok(Some(Identifier::from(parser.current())));
+}
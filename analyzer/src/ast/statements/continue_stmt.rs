@@ -0,0 +1,31 @@
+use crate::{
+    ast::{identifier::Identifier, AstParse},
+    error::Result,
+    lexer::{token::Span, token_type::TokenType},
+    parser::Parser,
+};
+
+use super::break_stmt::parse_label;
+
+/// `continue;` or `continue label;`, which starts the next iteration of the
+/// innermost loop or of the loop with that label.
+#[derive(Debug)]
+pub struct ContinueStmt {
+    pub label: Option<Identifier>,
+    pub span: Span,
+}
+
+impl AstParse for ContinueStmt {
+    fn parse(parser: &mut Parser) -> Result<Self> {
+        let start = parser.current().span().clone();
+        let label = parse_label(parser)?;
+        parser.next_token_and_expect(TokenType::Semicolon)?;
+        return Ok(ContinueStmt::new(label, parser.span_from(&start)));
+    }
+}
+
+impl ContinueStmt {
+    pub fn new(label: Option<Identifier>, span: Span) -> Self {
+        Self { label, span }
+    }
+}
@@ -1,26 +1,28 @@
 use crate::{
     ast::{expressions::Expression, AstParse},
     error::Result,
-    lexer::token_type::TokenType,
+    lexer::{token::Span, token_type::TokenType},
     parser::Parser,
 };
 
 #[derive(Debug)]
 pub struct DeferStmt {
     pub expr: Expression,
+    pub span: Span,
 }
 
 impl AstParse for DeferStmt {
     fn parse(parser: &mut Parser) -> Result<Self> {
+        let start = parser.current().span().clone();
         parser.next_token()?; // Skip defer keyword
         let expr = Expression::parse(parser)?;
         parser.next_token_and_expect(TokenType::Semicolon)?;
-        return Ok(DeferStmt::new(expr));
+        return Ok(DeferStmt::new(expr, parser.span_from(&start)));
     }
 }
 
 impl DeferStmt {
-    pub fn new(expr: Expression) -> Self {
-        Self { expr }
+    pub fn new(expr: Expression, span: Span) -> Self {
+        Self { expr, span }
     }
 }
@@ -0,0 +1,43 @@
+use crate::{
+    ast::{expressions::Expression, identifier::Identifier, AstParse},
+    error::Result,
+    lexer::{token::Span, token_type::TokenType},
+    parser::Parser,
+};
+
+use super::block_stmt::BlockStmt;
+
+/// `do { ... } while (condition);`, which runs its block once before the
+/// condition is first checked. `continue` jumps to the condition.
+#[derive(Debug)]
+pub struct DoWhileStmt {
+    pub label: Option<Identifier>,
+    pub block: BlockStmt,
+    pub condition: Expression,
+    pub span: Span,
+}
+
+impl AstParse for DoWhileStmt {
+    fn parse(parser: &mut Parser) -> Result<Self> {
+        let start = parser.current().span().clone();
+        let block = BlockStmt::parse(parser)?;
+        parser.next_token_and_expect(TokenType::While)?;
+        parser.next_token_and_expect(TokenType::OpenParen)?;
+        parser.next_token()?;
+        let condition = Expression::parse(parser)?;
+        parser.next_token_and_expect(TokenType::CloseParen)?;
+        parser.next_token_and_expect(TokenType::Semicolon)?;
+        return Ok(Self::new(block, condition, parser.span_from(&start)));
+    }
+}
+
+impl DoWhileStmt {
+    pub fn new(block: BlockStmt, condition: Expression, span: Span) -> Self {
+        Self {
+            label: None,
+            block,
+            condition,
+            span,
+        }
+    }
+}
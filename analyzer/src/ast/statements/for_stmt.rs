@@ -1,11 +1,12 @@
 use crate::{
     ast::{
         expressions::{assignment_expr::AssignmentExpression, Expression},
+        identifier::Identifier,
         AstParse,
     },
     error::Result,
     error_parser,
-    lexer::token_type::TokenType,
+    lexer::{token::Span, token_type::TokenType},
     parser::Parser,
 };
 
@@ -13,29 +14,35 @@ use super::{block_stmt::BlockStmt, let_stmt::LetStmt};
 
 #[derive(Debug)]
 pub struct ForStmt {
+    pub label: Option<Identifier>,
     pub init: VariableInit,
     pub condition: Expression,
     pub increment: Expression,
     pub block: BlockStmt,
+    pub span: Span,
 }
 #[derive(Debug)]
 pub enum VariableInit {
-    Let(LetStmt),
+    Let(Box<LetStmt>),
     Assign(AssignmentExpression),
 }
 
 impl AstParse for ForStmt {
     fn parse(parser: &mut Parser) -> Result<Self> {
+        let start = parser.current().span().clone();
         parser.next_token_and_expect(TokenType::OpenParen)?;
-        let init = VariableInit::parse(parser)?;
-        parser.next_token()?;
-        let condition = Expression::parse(parser)?;
-        parser.next_token_and_expect(TokenType::Semicolon)?;
-        parser.next_token()?;
-        let increment = Expression::parse(parser)?;
-        parser.next_token_and_expect(TokenType::CloseParen)?;
-        let block = BlockStmt::parse(parser)?;
-        return Ok(Self::new(init, condition, increment, block));
+        return parser.scope(|parser| {
+            let init = VariableInit::parse(parser)?;
+            parser.next_token()?;
+            let condition = Expression::parse(parser)?;
+            parser.next_token_and_expect(TokenType::Semicolon)?;
+            parser.next_token()?;
+            let increment = Expression::parse(parser)?;
+            parser.next_token_and_expect(TokenType::CloseParen)?;
+            let block = BlockStmt::parse(parser)?;
+            let span = parser.span_from(&start);
+            return Ok(Self::new(init, condition, increment, block, span));
+        });
     }
 }
 
@@ -45,12 +52,15 @@ impl ForStmt {
         condition: Expression,
         increment: Expression,
         block: BlockStmt,
+        span: Span,
     ) -> Self {
         Self {
+            label: None,
             init,
             condition,
             increment,
             block,
+            span,
         }
     }
 }
@@ -59,7 +69,7 @@ impl AstParse for VariableInit {
     fn parse(parser: &mut Parser) -> Result<Self> {
         parser.next_token()?;
         if parser.current().token_type() == &TokenType::Let {
-            return Ok(VariableInit::Let(LetStmt::parse(parser)?));
+            return Ok(VariableInit::Let(Box::new(LetStmt::parse(parser)?)));
         }
         let expr = Expression::parse(parser)?;
 
@@ -67,6 +77,6 @@ impl AstParse for VariableInit {
             parser.next_token()?;
             return Ok(VariableInit::Assign(expr));
         }
-        return error_parser!(parser, "Expect assignment expression");
+        return error_parser!(parser, ExpectedExpression, "Expect assignment expression");
     }
 }
@@ -1,7 +1,7 @@
 use crate::{
     ast::{expressions::Expression, AstParse},
     error::Result,
-    lexer::token_type::TokenType,
+    lexer::{token::Span, token_type::TokenType},
     parser::Parser,
 };
 
@@ -13,20 +13,24 @@ pub struct IfStmt {
     pub block: BlockStmt,
     pub else_ifs: Vec<ElseIfStmt>,
     pub else_stmt: Option<BlockStmt>,
+    pub span: Span,
 }
 
 #[derive(Debug)]
 pub struct ElseIfStmt {
     pub condition: Expression,
     pub block: BlockStmt,
+    pub span: Span,
 }
 
 impl AstParse for IfStmt {
     fn parse(parser: &mut Parser) -> Result<Self> {
+        let start = parser.current().span().clone();
         let (condition, block) = Self::parse_condition(parser)?;
         let else_ifs = Self::parse_else_if(parser)?;
         let else_stmt = Self::parse_else(parser)?;
-        return Ok(IfStmt::new(condition, block, else_ifs, else_stmt));
+        let span = parser.span_from(&start);
+        return Ok(IfStmt::new(condition, block, else_ifs, else_stmt, span));
     }
 }
 
@@ -36,12 +40,14 @@ impl IfStmt {
         block: BlockStmt,
         else_ifs: Vec<ElseIfStmt>,
         else_stmt: Option<BlockStmt>,
+        span: Span,
     ) -> Self {
         Self {
             condition,
             block,
             else_ifs,
             else_stmt,
+            span,
         }
     }
 
@@ -71,14 +77,19 @@ impl IfStmt {
 
 impl AstParse for ElseIfStmt {
     fn parse(parser: &mut Parser) -> Result<Self> {
+        let start = parser.current().span().clone();
         let (condition, block) = IfStmt::parse_condition(parser)?;
-        return Ok(Self::new(condition, block));
+        return Ok(Self::new(condition, block, parser.span_from(&start)));
     }
 }
 
 impl ElseIfStmt {
-    pub fn new(condition: Expression, block: BlockStmt) -> Self {
-        Self { condition, block }
+    pub fn new(condition: Expression, block: BlockStmt, span: Span) -> Self {
+        Self {
+            condition,
+            block,
+            span,
+        }
     }
 
     fn parse_else_if(parser: &mut Parser, vec: &mut Vec<Self>) -> Result<()> {
@@ -4,7 +4,7 @@ use crate::{
     },
     error::Result,
     error_parser,
-    lexer::token_type::TokenType,
+    lexer::{token::Span, token_type::TokenType},
     parser::Parser,
 };
 
@@ -13,26 +13,31 @@ pub struct LetStmt {
     pub identifier: Identifier,
     pub type_specifier: Option<TypeSpecifier>,
     pub value: Option<Expression>,
+    pub span: Span,
 }
 
 impl AstParse for LetStmt {
     fn parse(parser: &mut Parser) -> Result<Self> {
+        let start = parser.current().span().clone();
         let identifier = Identifier::parse(parser)?;
         parser.next_token()?;
         let type_specifier = Self::handle_type(parser)?;
+        parser.constants().declare_variable(identifier.as_ref());
 
         if parser.current().token_type() == &TokenType::Semicolon {
             if type_specifier.is_some() {
-                return Ok(LetStmt::new(identifier, type_specifier, None));
+                let span = parser.span_from(&start);
+                return Ok(LetStmt::new(identifier, type_specifier, None, span));
             }
-            return error_parser!(parser, "Expect TypeSpecifier");
+            return error_parser!(parser, ExpectedType, "Expect TypeSpecifier");
         }
 
         parser.expect(TokenType::Assign)?;
         parser.next_token()?;
         let value = Expression::parse(parser)?;
         parser.next_token_and_expect(TokenType::Semicolon)?;
-        return Ok(LetStmt::new(identifier, type_specifier, Some(value)));
+        let span = parser.span_from(&start);
+        return Ok(LetStmt::new(identifier, type_specifier, Some(value), span));
     }
 }
 
@@ -41,11 +46,13 @@ impl LetStmt {
         identifier: Identifier,
         type_specifier: Option<TypeSpecifier>,
         value: Option<Expression>,
+        span: Span,
     ) -> Self {
         Self {
             identifier,
             type_specifier,
             value,
+            span,
         }
     }
 
@@ -0,0 +1,138 @@
+use crate::{
+    ast::{expressions::Expression, identifier::Identifier, parenthesis::Parenthesis, AstParse},
+    diagnostic::{Diagnostic, ErrorCode},
+    error::{CompilerError, Result},
+    error_parser,
+    lexer::{token::Span, token_type::TokenType},
+    parser::Parser,
+};
+
+use super::block_stmt::BlockStmt;
+
+/// `match (shape) { Shape::Circle(r) => {...} _ => {...} }`. The arm naming
+/// the item of the value runs with the values the item carries bound to the
+/// names of its pattern, and `_` runs for the items no arm names.
+#[derive(Debug)]
+pub struct MatchStmt {
+    pub value: Expression,
+    pub arms: Vec<MatchArm>,
+    pub default: Option<BlockStmt>,
+    pub span: Span,
+}
+
+#[derive(Debug)]
+pub struct MatchArm {
+    pub name: Identifier,
+    pub item: Identifier,
+    /// The names the carried values are bound to, `None` for a `_`.
+    pub bindings: Vec<Option<Identifier>>,
+    pub block: BlockStmt,
+    pub span: Span,
+}
+
+impl AstParse for MatchStmt {
+    fn parse(parser: &mut Parser) -> Result<Self> {
+        let start = parser.current().span().clone();
+        parser.next_token_and_expect(TokenType::OpenParen)?;
+        parser.next_token()?;
+        let value = Expression::parse(parser)?;
+        parser.next_token_and_expect(TokenType::CloseParen)?;
+        parser.next_token_and_expect(TokenType::OpenCurlyBrace)?;
+
+        let mut arms = Vec::new();
+        let mut default = None;
+        loop {
+            parser.next_token()?;
+            match parser.current().token_type() {
+                TokenType::CloseCurlyBrace => break,
+                TokenType::Identifier => {
+                    if default.is_some() {
+                        // Reported, but the rest of the match still parses.
+                        parser.report(CompilerError::Syntactic(Box::new(
+                            Diagnostic::error(
+                                ErrorCode::UnexpectedToken,
+                                "`_` must be the last arm of a match",
+                            )
+                            .with_span(parser.current().span()),
+                        )));
+                    }
+                    if parser.current().text() == "_" {
+                        parser.next_token_and_expect(TokenType::FatArrow)?;
+                        default = Some(BlockStmt::parse(parser)?);
+                    } else {
+                        arms.push(MatchArm::parse(parser)?);
+                    }
+                }
+                _ => {
+                    return error_parser!(
+                        parser,
+                        UnexpectedToken,
+                        "Expect a pattern like `Shape::Circle(r)` or `_`"
+                    )
+                }
+            }
+        }
+        let span = parser.span_from(&start);
+        return Ok(Self::new(value, arms, default, span));
+    }
+}
+
+impl MatchStmt {
+    pub fn new(
+        value: Expression,
+        arms: Vec<MatchArm>,
+        default: Option<BlockStmt>,
+        span: Span,
+    ) -> Self {
+        Self {
+            value,
+            arms,
+            default,
+            span,
+        }
+    }
+}
+
+impl AstParse for MatchArm {
+    fn parse(parser: &mut Parser) -> Result<Self> {
+        let start = parser.current().span().clone();
+        let name = Identifier::from(parser.current());
+        parser.next_token_and_expect(TokenType::DoubleColon)?;
+        let item = Identifier::parse(parser)?;
+        let mut bindings = Vec::new();
+        if parser.next().token_type() == &TokenType::OpenParen {
+            parser.next_token()?;
+            bindings = Parenthesis::parse(parser, TokenType::CloseParen, |parser| {
+                let binding = Identifier::from(parser.expect(TokenType::Identifier)?);
+                return Ok(Some(binding).filter(|binding| binding.as_ref() != "_"));
+            })?;
+        }
+        parser.next_token_and_expect(TokenType::FatArrow)?;
+        let block = parser.scope(|parser| {
+            for binding in bindings.iter().flatten() {
+                parser.constants().declare_variable(binding.as_ref());
+            }
+            return BlockStmt::parse(parser);
+        })?;
+        let span = parser.span_from(&start);
+        return Ok(Self::new(name, item, bindings, block, span));
+    }
+}
+
+impl MatchArm {
+    pub fn new(
+        name: Identifier,
+        item: Identifier,
+        bindings: Vec<Option<Identifier>>,
+        block: BlockStmt,
+        span: Span,
+    ) -> Self {
+        Self {
+            name,
+            item,
+            bindings,
+            block,
+            span,
+        }
+    }
+}
@@ -1,16 +1,29 @@
 pub mod block_stmt;
+pub mod break_stmt;
+pub mod continue_stmt;
 pub mod defer_stmt;
+pub mod do_while_stmt;
 pub mod for_stmt;
 pub mod if_stmt;
 pub mod let_stmt;
+pub mod match_stmt;
 pub mod return_stmt;
+pub mod switch_stmt;
 pub mod while_stmt;
 
-use crate::{error::CompilerError, error_parser, lexer::token_type::TokenType, parser::Parser};
+use crate::{
+    ast::{declarations::const_decl::ConstDeclaration, identifier::Identifier},
+    error::CompilerError,
+    error_parser,
+    lexer::{token::Span, token_type::TokenType},
+    parser::Parser,
+};
 
 use self::{
-    block_stmt::BlockStmt, defer_stmt::DeferStmt, for_stmt::ForStmt, if_stmt::IfStmt,
-    let_stmt::LetStmt, return_stmt::ReturnStmt, while_stmt::WhileStmt,
+    block_stmt::BlockStmt, break_stmt::BreakStmt, continue_stmt::ContinueStmt,
+    defer_stmt::DeferStmt, do_while_stmt::DoWhileStmt, for_stmt::ForStmt, if_stmt::IfStmt,
+    let_stmt::LetStmt, match_stmt::MatchStmt, return_stmt::ReturnStmt, switch_stmt::SwitchStmt,
+    while_stmt::WhileStmt,
 };
 
 use super::{expressions::Expression, AstParse};
@@ -18,11 +31,17 @@ use super::{expressions::Expression, AstParse};
 #[derive(Debug)]
 pub enum Statements {
     Let(LetStmt),
+    Const(ConstDeclaration),
     Return(ReturnStmt),
     Defer(DeferStmt),
-    For(ForStmt),
+    For(Box<ForStmt>),
     While(WhileStmt),
+    DoWhile(DoWhileStmt),
     If(IfStmt),
+    Switch(SwitchStmt),
+    Match(MatchStmt),
+    Break(BreakStmt),
+    Continue(ContinueStmt),
     Block(BlockStmt),
     Expression(Expression),
 }
@@ -31,13 +50,76 @@ impl AstParse for Statements {
     fn parse(parser: &mut Parser) -> Result<Self, CompilerError> {
         return Ok(match parser.current().token_type() {
             TokenType::Let => Statements::Let(LetStmt::parse(parser)?),
+            TokenType::Const => Statements::Const(ConstDeclaration::parse(parser)?),
             TokenType::Return => Statements::Return(ReturnStmt::parse(parser)?),
             TokenType::OpenCurlyBrace => Statements::Block(BlockStmt::parse(parser)?),
             TokenType::Defer => Statements::Defer(DeferStmt::parse(parser)?),
-            TokenType::For => Statements::For(ForStmt::parse(parser)?),
+            TokenType::For => Statements::For(Box::new(ForStmt::parse(parser)?)),
             TokenType::While => Statements::While(WhileStmt::parse(parser)?),
+            TokenType::Do => Statements::DoWhile(DoWhileStmt::parse(parser)?),
             TokenType::If => Statements::If(IfStmt::parse(parser)?),
-            _ => return error_parser!(parser, "Unknown Statement"),
+            TokenType::Switch => Statements::Switch(SwitchStmt::parse(parser)?),
+            TokenType::Match => Statements::Match(MatchStmt::parse(parser)?),
+            TokenType::Break => Statements::Break(BreakStmt::parse(parser)?),
+            TokenType::Continue => Statements::Continue(ContinueStmt::parse(parser)?),
+            TokenType::Identifier if Self::is_label(parser) => Self::parse_labeled(parser)?,
+            _ => return error_parser!(parser, ExpectedStatement, "Unknown Statement"),
+        });
+    }
+}
+
+impl Statements {
+    pub fn span(&self) -> &Span {
+        return match self {
+            Statements::Let(let_stmt) => &let_stmt.span,
+            Statements::Const(const_decl) => &const_decl.span,
+            Statements::Return(return_stmt) => &return_stmt.span,
+            Statements::Defer(defer_stmt) => &defer_stmt.span,
+            Statements::For(for_stmt) => &for_stmt.span,
+            Statements::While(while_stmt) => &while_stmt.span,
+            Statements::DoWhile(do_while_stmt) => &do_while_stmt.span,
+            Statements::If(if_stmt) => &if_stmt.span,
+            Statements::Switch(switch_stmt) => &switch_stmt.span,
+            Statements::Match(match_stmt) => &match_stmt.span,
+            Statements::Break(break_stmt) => &break_stmt.span,
+            Statements::Continue(continue_stmt) => &continue_stmt.span,
+            Statements::Block(block_stmt) => &block_stmt.span,
+            Statements::Expression(expr) => expr.span(),
+        };
+    }
+
+    /// Whether the current token is the `label:` in front of a loop.
+    pub(crate) fn is_label(parser: &Parser) -> bool {
+        return parser.current().token_type() == &TokenType::Identifier
+            && parser.next().token_type() == &TokenType::Colon;
+    }
+
+    /// Parses a loop with a label, like `outer: while (...) {}`, which
+    /// `break` and `continue` can name to leave it from a nested loop.
+    fn parse_labeled(parser: &mut Parser) -> Result<Self, CompilerError> {
+        let label = Identifier::from(parser.current());
+        parser.next_token()?; // skip label
+        parser.next_token()?; // skip colon
+        return Ok(match parser.current().token_type() {
+            TokenType::For => {
+                let mut for_stmt = ForStmt::parse(parser)?;
+                for_stmt.span = label.span.to(&for_stmt.span);
+                for_stmt.label = Some(label);
+                Statements::For(Box::new(for_stmt))
+            }
+            TokenType::While => {
+                let mut while_stmt = WhileStmt::parse(parser)?;
+                while_stmt.span = label.span.to(&while_stmt.span);
+                while_stmt.label = Some(label);
+                Statements::While(while_stmt)
+            }
+            TokenType::Do => {
+                let mut do_while_stmt = DoWhileStmt::parse(parser)?;
+                do_while_stmt.span = label.span.to(&do_while_stmt.span);
+                do_while_stmt.label = Some(label);
+                Statements::DoWhile(do_while_stmt)
+            }
+            _ => return error_parser!(parser, ExpectedStatement, "Only loops can have a label"),
         });
     }
 }
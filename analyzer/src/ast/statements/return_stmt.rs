@@ -1,30 +1,32 @@
 use crate::{
     ast::{expressions::Expression, AstParse},
     error::Result,
-    lexer::token_type::TokenType,
+    lexer::{token::Span, token_type::TokenType},
     parser::Parser,
 };
 
 #[derive(Debug)]
 pub struct ReturnStmt {
     pub value: Option<Expression>,
+    pub span: Span,
 }
 
 impl ReturnStmt {
-    pub fn new(value: Option<Expression>) -> Self {
-        Self { value }
+    pub fn new(value: Option<Expression>, span: Span) -> Self {
+        Self { value, span }
     }
 }
 
 impl AstParse for ReturnStmt {
     fn parse(parser: &mut Parser) -> Result<Self> {
+        let start = parser.current().span().clone();
         if parser.next().token_type() == &TokenType::Semicolon {
             parser.next_token()?;
-            return Ok(ReturnStmt::new(None));
+            return Ok(ReturnStmt::new(None, parser.span_from(&start)));
         }
         parser.next_token()?;
         let value = Expression::parse(parser)?;
         parser.next_token_and_expect(TokenType::Semicolon)?;
-        return Ok(ReturnStmt::new(Some(value)));
+        return Ok(ReturnStmt::new(Some(value), parser.span_from(&start)));
     }
 }
@@ -0,0 +1,111 @@
+use crate::{
+    ast::{expressions::Expression, AstParse},
+    diagnostic::{Diagnostic, ErrorCode},
+    error::{CompilerError, Result},
+    error_parser,
+    lexer::{token::Span, token_type::TokenType},
+    parser::Parser,
+};
+
+use super::block_stmt::BlockStmt;
+
+/// `switch (value) { case A: {...} default: {...} }`. Cases do not fall
+/// through, and `break` and `continue` refer to the loops around the switch.
+#[derive(Debug)]
+pub struct SwitchStmt {
+    pub value: Expression,
+    pub cases: Vec<CaseStmt>,
+    pub default: Option<BlockStmt>,
+    pub span: Span,
+}
+
+#[derive(Debug)]
+pub struct CaseStmt {
+    /// A constant, or an item of the enum switched over.
+    pub value: Expression,
+    pub block: BlockStmt,
+    pub span: Span,
+}
+
+impl AstParse for SwitchStmt {
+    fn parse(parser: &mut Parser) -> Result<Self> {
+        let start = parser.current().span().clone();
+        parser.next_token_and_expect(TokenType::OpenParen)?;
+        parser.next_token()?;
+        let value = Expression::parse(parser)?;
+        parser.next_token_and_expect(TokenType::CloseParen)?;
+        parser.next_token_and_expect(TokenType::OpenCurlyBrace)?;
+
+        let mut cases = Vec::new();
+        let mut default = None;
+        loop {
+            parser.next_token()?;
+            match parser.current().token_type() {
+                TokenType::CloseCurlyBrace => break,
+                TokenType::Case | TokenType::Default => {
+                    if default.is_some() {
+                        // Reported, but the rest of the switch still parses.
+                        parser.report(CompilerError::Syntactic(Box::new(
+                            Diagnostic::error(
+                                ErrorCode::UnexpectedToken,
+                                "`default` must be the last case of a switch",
+                            )
+                            .with_span(parser.current().span()),
+                        )));
+                    }
+                    Self::parse_case(parser, &mut cases, &mut default)?;
+                }
+                _ => return error_parser!(parser, UnexpectedToken, "Expect `case` or `default`"),
+            }
+        }
+        let span = parser.span_from(&start);
+        return Ok(Self::new(value, cases, default, span));
+    }
+}
+
+impl SwitchStmt {
+    pub fn new(
+        value: Expression,
+        cases: Vec<CaseStmt>,
+        default: Option<BlockStmt>,
+        span: Span,
+    ) -> Self {
+        Self {
+            value,
+            cases,
+            default,
+            span,
+        }
+    }
+
+    fn parse_case(
+        parser: &mut Parser,
+        cases: &mut Vec<CaseStmt>,
+        default: &mut Option<BlockStmt>,
+    ) -> Result<()> {
+        if parser.current().token_type() == &TokenType::Case {
+            cases.push(CaseStmt::parse(parser)?);
+            return Ok(());
+        }
+        parser.next_token_and_expect(TokenType::Colon)?;
+        *default = Some(BlockStmt::parse(parser)?);
+        return Ok(());
+    }
+}
+
+impl AstParse for CaseStmt {
+    fn parse(parser: &mut Parser) -> Result<Self> {
+        let start = parser.current().span().clone();
+        parser.next_token()?;
+        let value = Expression::parse(parser)?;
+        parser.next_token_and_expect(TokenType::Colon)?;
+        let block = BlockStmt::parse(parser)?;
+        return Ok(Self::new(value, block, parser.span_from(&start)));
+    }
+}
+
+impl CaseStmt {
+    pub fn new(value: Expression, block: BlockStmt, span: Span) -> Self {
+        Self { value, block, span }
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
-    ast::{expressions::Expression, AstParse},
+    ast::{expressions::Expression, identifier::Identifier, AstParse},
     error::Result,
-    lexer::token_type::TokenType,
+    lexer::{token::Span, token_type::TokenType},
     parser::Parser,
 };
 
@@ -9,23 +9,31 @@ use super::block_stmt::BlockStmt;
 
 #[derive(Debug)]
 pub struct WhileStmt {
+    pub label: Option<Identifier>,
     pub condition: Expression,
     pub block: BlockStmt,
+    pub span: Span,
 }
 
 impl AstParse for WhileStmt {
     fn parse(parser: &mut Parser) -> Result<Self> {
+        let start = parser.current().span().clone();
         parser.next_token_and_expect(TokenType::OpenParen)?;
         parser.next_token()?;
         let condition = Expression::parse(parser)?;
         parser.next_token_and_expect(TokenType::CloseParen)?;
         let block = BlockStmt::parse(parser)?;
-        return Ok(Self::new(condition, block));
+        return Ok(Self::new(condition, block, parser.span_from(&start)));
     }
 }
 
 impl WhileStmt {
-    pub fn new(condition: Expression, block: BlockStmt) -> Self {
-        Self { condition, block }
+    pub fn new(condition: Expression, block: BlockStmt, span: Span) -> Self {
+        Self {
+            label: None,
+            condition,
+            block,
+            span,
+        }
     }
 }
@@ -1,8 +1,13 @@
-use std::{num::ParseIntError, usize};
+use crate::{
+    diagnostic::{Diagnostic, ErrorCode},
+    error::{CompilerError, Result},
+    error_parser,
+    interpreter::value::Value,
+    lexer::token_type::TokenType,
+    parser::Parser,
+};
 
-use crate::{error::Result, error_parser, lexer::token_type::TokenType, parser::Parser};
-
-use super::{identifier::Identifier, AstParse};
+use super::{expressions::Expression, identifier::Identifier, AstParse};
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum TypeSpecifier {
@@ -19,9 +24,22 @@ pub enum TypeSpecifier {
     ArrayType {
         type_specifier: Box<TypeSpecifier>,
         size: usize,
+        written_size: WrittenSize,
     },
 }
 
+/// The size of an array type as the source wrote it, such as a constant's
+/// name, which the formatter prints instead of the folded number. It is
+/// `None` for types the compiler makes up, and types compare by `size` only.
+#[derive(Debug, Clone, Default)]
+pub struct WrittenSize(pub Option<String>);
+
+impl PartialEq for WrittenSize {
+    fn eq(&self, _other: &Self) -> bool {
+        return true;
+    }
+}
+
 impl AstParse for TypeSpecifier {
     fn parse(parser: &mut Parser) -> Result<Self> {
         if parser.current().token_type() == &TokenType::Identifier {
@@ -31,6 +49,31 @@ impl AstParse for TypeSpecifier {
     }
 }
 
+impl std::fmt::Display for TypeSpecifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            TypeSpecifier::Void => write!(f, "void"),
+            TypeSpecifier::Pointer(type_specifier) => write!(f, "{}*", type_specifier),
+            TypeSpecifier::ArrayType { .. } => {
+                // Sizes are written outermost first, as in `int[2][3]`.
+                let mut sizes = String::new();
+                let mut item = self;
+                while let TypeSpecifier::ArrayType {
+                    type_specifier,
+                    size,
+                    ..
+                } = item
+                {
+                    sizes += format!("[{}]", size).as_str();
+                    item = type_specifier;
+                }
+                write!(f, "{}{}", item, sizes)
+            }
+            _ => write!(f, "{}", self.to_symbol().unwrap_or_default()),
+        };
+    }
+}
+
 impl TypeSpecifier {
     pub fn to_symbol(&self) -> Result<&str> {
         Ok(match self {
@@ -42,10 +85,24 @@ impl TypeSpecifier {
             TypeSpecifier::Str => "str",
             TypeSpecifier::Bool => "bool",
             TypeSpecifier::UserDefine(id) => id.as_ref(),
-            _ => todo!("Type cannot be symbol"),
+            _ => {
+                return Err(CompilerError::Semantic(Box::new(Diagnostic::error(
+                    ErrorCode::NotAStruct,
+                    format!("`{}` is not the name of a type", self),
+                ))))
+            }
         })
     }
 
+    /// An array of `size` items of `type_specifier`.
+    pub fn array(type_specifier: TypeSpecifier, size: usize) -> Self {
+        return TypeSpecifier::ArrayType {
+            type_specifier: Box::new(type_specifier),
+            size,
+            written_size: WrittenSize::default(),
+        };
+    }
+
     pub fn is_primitive(&self) -> bool {
         return matches!(
             self,
@@ -76,7 +133,7 @@ impl TypeSpecifier {
             TokenType::CharKeyword => TypeSpecifier::Char,
             TokenType::Str => TypeSpecifier::Str,
             TokenType::Void => TypeSpecifier::Void,
-            _ => return error_parser!(parser, "Unknown TypeSpecifier"),
+            _ => return error_parser!(parser, ExpectedType, "Unknown TypeSpecifier"),
         });
     }
 
@@ -103,22 +160,35 @@ impl TypeSpecifier {
     fn handle_array(parser: &mut Parser, type_specifier: TypeSpecifier) -> Result<Self> {
         parser.next_token()?; // skip open bracket '['
 
-        let size: usize = parser
-            .current()
-            .text()
-            .parse()
-            .or_else(|err: ParseIntError| error_parser!(parser, err.to_string()))?;
+        let start = parser.current().span().clone();
+        let size = Expression::parse(parser)?;
+        let written_size = match &size {
+            Expression::Primitive(_) => WrittenSize::default(),
+            _ => WrittenSize(Some(parser.source(&parser.span_from(&start)))),
+        };
+        let size = match parser.evaluate_constant(&size)? {
+            Value::Int(size) if size >= 0 => size as usize,
+            Value::Usize(size) => size as usize,
+            _ => {
+                return error_parser!(
+                    parser,
+                    InvalidArraySize,
+                    "Array size must be a non-negative integer"
+                )
+            }
+        };
 
         parser.next_token_and_expect(TokenType::CloseBracket)?;
 
         // Parse 2D array
         if parser.next().token_type() == &TokenType::OpenBracket {
-            return Self::handle_two_dimensional_array(parser, type_specifier, size);
+            return Self::handle_two_dimensional_array(parser, type_specifier, size, written_size);
         }
 
         return Ok(TypeSpecifier::ArrayType {
             type_specifier: Box::from(type_specifier),
             size,
+            written_size,
         });
     }
 
@@ -126,17 +196,23 @@ impl TypeSpecifier {
         parser: &mut Parser,
         type_specifier: TypeSpecifier,
         size: usize,
+        written_size: WrittenSize,
     ) -> Result<Self> {
         parser.next_token()?; // skip close bracket
         let type_specifier = Self::handle_array(parser, type_specifier)?;
         if let TypeSpecifier::ArrayType { type_specifier, .. } = &type_specifier {
             if let TypeSpecifier::ArrayType { .. } = type_specifier.as_ref() {
-                return error_parser!(parser, "Arrays can only be 1 or 2 dimensional");
+                return error_parser!(
+                    parser,
+                    InvalidArraySize,
+                    "Arrays can only be 1 or 2 dimensional"
+                );
             }
         }
         return Ok(TypeSpecifier::ArrayType {
             type_specifier: Box::from(type_specifier),
             size,
+            written_size,
         });
     }
 
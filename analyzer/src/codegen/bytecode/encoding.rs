@@ -0,0 +1,523 @@
+use crate::error::{CompilerError, Result};
+
+use super::module::{
+    Constant, EnumLayout, FieldLayout, Function, Instruction, Module, Primitive, StructLayout,
+    MAX_FRAME_SIZE,
+};
+
+/// Marks `.mbc` files, the last byte is the format version.
+const MAGIC: [u8; 4] = *b"MBC\x02";
+
+/// Encoding of `Index { length: None }`.
+const UNCHECKED: u32 = u32::MAX;
+
+impl Module {
+    /// Serializes the module to the `.mbc` format. Integers are little endian
+    /// and strings are prefixed with their length.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut writer = Writer::default();
+        writer.bytes.extend_from_slice(&MAGIC);
+        writer.u32(self.entry);
+
+        writer.u32(self.constants.len() as u32);
+        for constant in self.constants.iter() {
+            writer.constant(constant);
+        }
+
+        writer.u32(self.structs.len() as u32);
+        for layout in self.structs.iter() {
+            writer.string(&layout.name);
+            writer.u32(layout.size);
+            writer.u32(layout.fields.len() as u32);
+            for field in layout.fields.iter() {
+                writer.string(&field.name);
+                writer.u32(field.offset);
+                writer.u32(field.size);
+            }
+        }
+
+        writer.u32(self.enums.len() as u32);
+        for layout in self.enums.iter() {
+            writer.string(&layout.name);
+            writer.u32(layout.values.len() as u32);
+            for value in layout.values.iter() {
+                writer.bytes.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+
+        writer.u32(self.functions.len() as u32);
+        for function in self.functions.iter() {
+            writer.string(&function.name);
+            writer.u32(function.params_size);
+            writer.u32(function.locals_size);
+            writer.u32(function.return_size);
+            writer.u32(function.code.len() as u32);
+            for instruction in function.code.iter() {
+                writer.instruction(instruction);
+            }
+        }
+        return writer.bytes;
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Module> {
+        let mut reader = Reader { bytes, position: 0 };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(Reader::invalid("unknown file format"));
+        }
+        let mut module = Module {
+            entry: reader.u32()?,
+            ..Module::default()
+        };
+
+        for _ in 0..reader.u32()? {
+            module.constants.push(reader.constant()?);
+        }
+
+        for _ in 0..reader.u32()? {
+            let mut layout = StructLayout {
+                name: reader.string()?,
+                size: reader.u32()?,
+                fields: Vec::new(),
+            };
+            for _ in 0..reader.u32()? {
+                layout.fields.push(FieldLayout {
+                    name: reader.string()?,
+                    offset: reader.u32()?,
+                    size: reader.u32()?,
+                });
+            }
+            module.structs.push(layout);
+        }
+
+        for _ in 0..reader.u32()? {
+            let mut layout = EnumLayout {
+                name: reader.string()?,
+                values: Vec::new(),
+            };
+            for _ in 0..reader.u32()? {
+                layout.values.push(i32::from_le_bytes(reader.array()?));
+            }
+            module.enums.push(layout);
+        }
+
+        for _ in 0..reader.u32()? {
+            let mut function = Function {
+                name: reader.string()?,
+                params_size: reader.u32()?,
+                locals_size: reader.u32()?,
+                return_size: reader.u32()?,
+                code: Vec::new(),
+            };
+            for _ in 0..reader.u32()? {
+                function.code.push(reader.instruction()?);
+            }
+            module.functions.push(function);
+        }
+
+        if reader.position != bytes.len() {
+            return Err(Reader::invalid("unexpected data after the last function"));
+        }
+        module.validate()?;
+        return Ok(module);
+    }
+
+    /// Checks that every size fits in a frame and every index points into
+    /// what it indexes, so that a corrupted file is refused rather than run.
+    fn validate(&self) -> Result<()> {
+        if self.entry as usize >= self.functions.len() {
+            return Err(Reader::invalid("entry function does not exist"));
+        }
+        for function in self.functions.iter() {
+            if function.locals_size > MAX_FRAME_SIZE || function.return_size > MAX_FRAME_SIZE {
+                return Err(Reader::invalid(&format!(
+                    "frame of {} is too large",
+                    function.name
+                )));
+            }
+            if function.params_size > function.locals_size {
+                return Err(Reader::invalid(&format!(
+                    "parameters of {} do not fit in its frame",
+                    function.name
+                )));
+            }
+            if let Some(instruction) = function
+                .code
+                .iter()
+                .find(|instruction| !self.in_bounds(function, instruction))
+            {
+                return Err(Reader::invalid(&format!(
+                    "operand of {:?} in {} is out of bounds",
+                    instruction, function.name
+                )));
+            }
+        }
+        return Ok(());
+    }
+
+    fn in_bounds(&self, function: &Function, instruction: &Instruction) -> bool {
+        return match *instruction {
+            Instruction::Const(id) => (id as usize) < self.constants.len(),
+            Instruction::LoadLocal { slot, size } | Instruction::StoreLocal { slot, size } => {
+                slot as u64 + size as u64 <= function.locals_size as u64
+            }
+            Instruction::LocalAddr(slot) => slot <= function.locals_size,
+            Instruction::Pop(size)
+            | Instruction::Dup(size)
+            | Instruction::Load(size)
+            | Instruction::Store(size)
+            | Instruction::Offset(size)
+            | Instruction::Index { stride: size, .. } => size <= MAX_FRAME_SIZE,
+            Instruction::Jump(target) | Instruction::JumpIfFalse(target) => {
+                target as usize <= function.code.len()
+            }
+            Instruction::Call(id) => (id as usize) < self.functions.len(),
+            Instruction::CheckEnum(id) => (id as usize) < self.enums.len(),
+            _ => true,
+        };
+    }
+}
+
+#[derive(Default)]
+struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    fn u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn string(&mut self, value: &str) {
+        self.u32(value.len() as u32);
+        self.bytes.extend_from_slice(value.as_bytes());
+    }
+
+    fn constant(&mut self, constant: &Constant) {
+        match constant {
+            Constant::Int(value) => {
+                self.u8(0);
+                self.bytes.extend_from_slice(&value.to_le_bytes());
+            }
+            Constant::Usize(value) => {
+                self.u8(1);
+                self.bytes.extend_from_slice(&value.to_le_bytes());
+            }
+            Constant::Float(value) => {
+                self.u8(2);
+                self.bytes.extend_from_slice(&value.to_le_bytes());
+            }
+            Constant::Double(value) => {
+                self.u8(3);
+                self.bytes.extend_from_slice(&value.to_le_bytes());
+            }
+            Constant::Char(value) => {
+                self.u8(4);
+                self.u8(*value);
+            }
+            Constant::Bool(value) => {
+                self.u8(5);
+                self.u8(*value as u8);
+            }
+            Constant::Str(value) => {
+                self.u8(6);
+                self.string(value);
+            }
+            Constant::Null => self.u8(7),
+        }
+    }
+
+    fn instruction(&mut self, instruction: &Instruction) {
+        self.u8(opcode(instruction));
+        match instruction {
+            Instruction::Const(operand)
+            | Instruction::Pop(operand)
+            | Instruction::Dup(operand)
+            | Instruction::LocalAddr(operand)
+            | Instruction::Load(operand)
+            | Instruction::Store(operand)
+            | Instruction::Offset(operand)
+            | Instruction::Jump(operand)
+            | Instruction::JumpIfFalse(operand)
+            | Instruction::Call(operand)
+            | Instruction::CheckEnum(operand) => self.u32(*operand),
+            Instruction::LoadLocal { slot, size } | Instruction::StoreLocal { slot, size } => {
+                self.u32(*slot);
+                self.u32(*size);
+            }
+            Instruction::Index { stride, length } => {
+                self.u32(*stride);
+                self.u32(length.unwrap_or(UNCHECKED));
+            }
+            Instruction::Step { delta, postfix } => {
+                self.bytes.extend_from_slice(&delta.to_le_bytes());
+                self.u8(*postfix as u8);
+            }
+            Instruction::Cast(primitive) => self.u8(*primitive as u8),
+            _ => {}
+        }
+    }
+}
+
+fn opcode(instruction: &Instruction) -> u8 {
+    return match instruction {
+        Instruction::Const(_) => 0,
+        Instruction::Pop(_) => 1,
+        Instruction::Dup(_) => 2,
+        Instruction::LoadLocal { .. } => 3,
+        Instruction::StoreLocal { .. } => 4,
+        Instruction::LocalAddr(_) => 5,
+        Instruction::Load(_) => 6,
+        Instruction::Store(_) => 7,
+        Instruction::Offset(_) => 8,
+        Instruction::Index { .. } => 9,
+        Instruction::Add => 10,
+        Instruction::Subtract => 11,
+        Instruction::Multiply => 12,
+        Instruction::Divide => 13,
+        Instruction::Modulo => 14,
+        Instruction::Equal => 15,
+        Instruction::NotEqual => 16,
+        Instruction::Less => 17,
+        Instruction::LessEqual => 18,
+        Instruction::Greater => 19,
+        Instruction::GreaterEqual => 20,
+        Instruction::BitAnd => 21,
+        Instruction::BitOr => 22,
+        Instruction::BitXor => 23,
+        Instruction::ShiftLeft => 24,
+        Instruction::ShiftRight => 25,
+        Instruction::Negate => 26,
+        Instruction::Not => 27,
+        Instruction::BitNot => 28,
+        Instruction::Step { .. } => 29,
+        Instruction::Cast(_) => 30,
+        Instruction::Jump(_) => 31,
+        Instruction::JumpIfFalse(_) => 32,
+        Instruction::Call(_) => 33,
+        Instruction::Return => 34,
+        Instruction::CheckEnum(_) => 35,
+    };
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn invalid(reason: &str) -> CompilerError {
+        return CompilerError::CodeGen(format!("Invalid bytecode file: {}", reason));
+    }
+
+    fn take(&mut self, count: usize) -> Result<&'a [u8]> {
+        let end = self.position + count;
+        let bytes = self
+            .bytes
+            .get(self.position..end)
+            .ok_or_else(|| Self::invalid("unexpected end of file"))?;
+        self.position = end;
+        return Ok(bytes);
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        return Ok(array);
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        return Ok(self.take(1)?[0]);
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        return Ok(u32::from_le_bytes(self.array()?));
+    }
+
+    fn string(&mut self) -> Result<String> {
+        let length = self.u32()? as usize;
+        let bytes = self.take(length)?;
+        return String::from_utf8(bytes.to_vec()).map_err(|_| Self::invalid("string is not utf-8"));
+    }
+
+    fn constant(&mut self) -> Result<Constant> {
+        return Ok(match self.u8()? {
+            0 => Constant::Int(i32::from_le_bytes(self.array()?)),
+            1 => Constant::Usize(u64::from_le_bytes(self.array()?)),
+            2 => Constant::Float(f32::from_le_bytes(self.array()?)),
+            3 => Constant::Double(f64::from_le_bytes(self.array()?)),
+            4 => Constant::Char(self.u8()?),
+            5 => Constant::Bool(self.u8()? != 0),
+            6 => Constant::Str(self.string()?),
+            7 => Constant::Null,
+            tag => return Err(Self::invalid(&format!("unknown constant tag {}", tag))),
+        });
+    }
+
+    fn instruction(&mut self) -> Result<Instruction> {
+        return Ok(match self.u8()? {
+            0 => Instruction::Const(self.u32()?),
+            1 => Instruction::Pop(self.u32()?),
+            2 => Instruction::Dup(self.u32()?),
+            3 => Instruction::LoadLocal {
+                slot: self.u32()?,
+                size: self.u32()?,
+            },
+            4 => Instruction::StoreLocal {
+                slot: self.u32()?,
+                size: self.u32()?,
+            },
+            5 => Instruction::LocalAddr(self.u32()?),
+            6 => Instruction::Load(self.u32()?),
+            7 => Instruction::Store(self.u32()?),
+            8 => Instruction::Offset(self.u32()?),
+            9 => Instruction::Index {
+                stride: self.u32()?,
+                length: Some(self.u32()?).filter(|length| *length != UNCHECKED),
+            },
+            10 => Instruction::Add,
+            11 => Instruction::Subtract,
+            12 => Instruction::Multiply,
+            13 => Instruction::Divide,
+            14 => Instruction::Modulo,
+            15 => Instruction::Equal,
+            16 => Instruction::NotEqual,
+            17 => Instruction::Less,
+            18 => Instruction::LessEqual,
+            19 => Instruction::Greater,
+            20 => Instruction::GreaterEqual,
+            21 => Instruction::BitAnd,
+            22 => Instruction::BitOr,
+            23 => Instruction::BitXor,
+            24 => Instruction::ShiftLeft,
+            25 => Instruction::ShiftRight,
+            26 => Instruction::Negate,
+            27 => Instruction::Not,
+            28 => Instruction::BitNot,
+            29 => Instruction::Step {
+                delta: i32::from_le_bytes(self.array()?),
+                postfix: self.u8()? != 0,
+            },
+            30 => Instruction::Cast(match self.u8()? {
+                0 => Primitive::Int,
+                1 => Primitive::Usize,
+                2 => Primitive::Float,
+                3 => Primitive::Double,
+                4 => Primitive::Char,
+                5 => Primitive::Bool,
+                tag => return Err(Self::invalid(&format!("unknown cast type {}", tag))),
+            }),
+            31 => Instruction::Jump(self.u32()?),
+            32 => Instruction::JumpIfFalse(self.u32()?),
+            33 => Instruction::Call(self.u32()?),
+            34 => Instruction::Return,
+            35 => Instruction::CheckEnum(self.u32()?),
+            opcode => return Err(Self::invalid(&format!("unknown opcode {}", opcode))),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `main` returns `add(1, 2)`.
+    fn module() -> Module {
+        return Module {
+            constants: vec![Constant::Int(1), Constant::Int(2)],
+            functions: vec![
+                Function {
+                    name: String::from("main"),
+                    params_size: 0,
+                    locals_size: 0,
+                    return_size: 1,
+                    code: vec![
+                        Instruction::Const(0),
+                        Instruction::Const(1),
+                        Instruction::Call(1),
+                        Instruction::Return,
+                    ],
+                },
+                Function {
+                    name: String::from("add"),
+                    params_size: 2,
+                    locals_size: 2,
+                    return_size: 1,
+                    code: vec![
+                        Instruction::LoadLocal { slot: 0, size: 2 },
+                        Instruction::Add,
+                        Instruction::Return,
+                    ],
+                },
+            ],
+            ..Module::default()
+        };
+    }
+
+    fn decode_error(module: Module) -> String {
+        return Module::decode(&module.encode()).unwrap_err().to_string();
+    }
+
+    #[test]
+    fn round_trips() {
+        assert_eq!(Module::decode(&module().encode()).unwrap(), module());
+    }
+
+    #[test]
+    fn rejects_truncated_files() {
+        let bytes = module().encode();
+        assert!(Module::decode(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn rejects_frames_larger_than_the_limit() {
+        let mut module = module();
+        module.functions[0].locals_size = u32::MAX;
+        assert_eq!(
+            decode_error(module),
+            "Invalid bytecode file: frame of main is too large"
+        );
+    }
+
+    #[test]
+    fn rejects_parameters_outside_the_frame() {
+        let mut module = module();
+        module.functions[1].locals_size = 1;
+        assert_eq!(
+            decode_error(module),
+            "Invalid bytecode file: parameters of add do not fit in its frame"
+        );
+    }
+
+    #[test]
+    fn rejects_operands_out_of_bounds() {
+        let out_of_bounds = [
+            (0, Instruction::Const(2)),
+            (0, Instruction::Call(2)),
+            (0, Instruction::Jump(5)),
+            (0, Instruction::CheckEnum(0)),
+            (0, Instruction::Load(u32::MAX)),
+            (1, Instruction::LoadLocal { slot: 1, size: 2 }),
+            (
+                1,
+                Instruction::StoreLocal {
+                    slot: u32::MAX,
+                    size: 1,
+                },
+            ),
+            (1, Instruction::LocalAddr(3)),
+        ];
+        for (function, instruction) in out_of_bounds {
+            let mut module = module();
+            module.functions[function].code[0] = instruction;
+            assert!(
+                decode_error(module).ends_with("is out of bounds"),
+                "{:?} was accepted",
+                instruction
+            );
+        }
+    }
+}
@@ -0,0 +1,507 @@
+use crate::{
+    ast::{
+        declarations::enum_decl::EnumDeclaration,
+        expressions::{
+            array_index_expr::ArrayIndexExpression,
+            array_init_expr::ArrayInitExpression,
+            assignment_expr::AssignmentExpression,
+            binary_expr::{BinaryExpression, BinaryOperators},
+            cast_expr::CastExpression,
+            enum_item_expr::EnumItemExpression,
+            fn_call_expr::FnCallExpression,
+            identifier_expression::IdentifierExpression,
+            member_access_expr::MemberAccessExpression,
+            object_init_expr::ObjectInitExpression,
+            primitive_expr::PrimitiveExpression,
+            unary_expr::{UnaryExpression, UnaryExpressionType, UnaryOperators},
+            Expression,
+        },
+        type_specifier::TypeSpecifier,
+    },
+    codegen::type_context::MemberChain,
+    error::{CompilerError, Result},
+    lexer::token_type::TokenType,
+};
+
+use super::{
+    module::{Constant, Instruction},
+    BytecodeGenerator, ToBytecode,
+};
+
+/// What a member access left on the stack.
+pub(crate) enum Member {
+    Address(TypeSpecifier),
+    Value(TypeSpecifier),
+}
+
+impl<'a> ToBytecode<'a> for Expression {
+    fn to_bytecode(&'a self, gen: &mut BytecodeGenerator<'a>) -> Result<()> {
+        return match self {
+            Expression::Binary(binary_expr) => binary_expr.to_bytecode(gen),
+            Expression::Assignment(assignment_expr) => gen.assign(assignment_expr, true),
+            Expression::Unary(unary_expr) => unary_expr.to_bytecode(gen),
+            Expression::ArrayIndex(array_index) => array_index.to_bytecode(gen),
+            Expression::MemberAccess(member_access) => member_access.to_bytecode(gen),
+            Expression::FnCall(fn_call_expr) => fn_call_expr.to_bytecode(gen),
+            Expression::ArrayInit(array_init_expr) => array_init_expr.to_bytecode(gen),
+            Expression::ObjectInit(object_init_expr) => object_init_expr.to_bytecode(gen),
+            Expression::Cast(cast_expr) => cast_expr.to_bytecode(gen),
+            Expression::Identifier(identifier_expr) => identifier_expr.to_bytecode(gen),
+            Expression::EnumItem(enum_item_expr) => enum_item_expr.to_bytecode(gen),
+            Expression::Primitive(primitive_expr) => primitive_expr.to_bytecode(gen),
+        };
+    }
+}
+
+impl<'a> BytecodeGenerator<'a> {
+    /// Pushes a pointer to the storage `expr` refers to. Values that are not
+    /// stored anywhere are copied to a temporary first.
+    pub(crate) fn address(&mut self, expr: &'a Expression) -> Result<()> {
+        match expr {
+            Expression::Identifier(identifier) => {
+                let slot = self.local(identifier.id.as_ref())?;
+                self.emit(Instruction::LocalAddr(slot));
+            }
+            Expression::MemberAccess(member_access) => {
+                let object = self.object_address(&member_access.object, &member_access.access)?;
+                if let Member::Value(type_specifier) =
+                    self.member(object, &member_access.property)?
+                {
+                    self.spill(&type_specifier)?;
+                }
+            }
+            Expression::ArrayIndex(array_index) => {
+                self.index_address(array_index)?;
+            }
+            Expression::Unary(UnaryExpression {
+                operator: UnaryOperators::Indirection,
+                value,
+                ..
+            }) => value.to_bytecode(self)?,
+            _ => {
+                let type_specifier = self.context.expr_type(expr)?;
+                expr.to_bytecode(self)?;
+                self.spill(&type_specifier)?;
+            }
+        }
+        return Ok(());
+    }
+
+    /// Moves the value on the top of the stack to a temporary and pushes its address.
+    fn spill(&mut self, type_specifier: &TypeSpecifier) -> Result<()> {
+        let size = self.size_of(type_specifier)?;
+        let slot = self.temp(type_specifier)?;
+        self.emit(Instruction::StoreLocal { slot, size });
+        self.emit(Instruction::LocalAddr(slot));
+        return Ok(());
+    }
+
+    pub(crate) fn assign(
+        &mut self,
+        assignment: &'a AssignmentExpression,
+        keep: bool,
+    ) -> Result<()> {
+        let size = self.size_of(&self.context.expr_type(&assignment.variable)?)?;
+        assignment.value.to_bytecode(self)?;
+        if keep {
+            self.emit(Instruction::Dup(size));
+        }
+        if let Expression::Identifier(identifier) = assignment.variable.as_ref() {
+            let slot = self.local(identifier.id.as_ref())?;
+            self.emit(Instruction::StoreLocal { slot, size });
+            return Ok(());
+        }
+        self.address(&assignment.variable)?;
+        self.emit(Instruction::Store(size));
+        return Ok(());
+    }
+
+    /// Pushes the address of the struct `object` refers to and returns its type.
+    fn object_address(
+        &mut self,
+        object: &'a Expression,
+        access: &TokenType,
+    ) -> Result<TypeSpecifier> {
+        let object_type = self.context.expr_type(object)?;
+        if access != &TokenType::Arrow {
+            self.address(object)?;
+            return Ok(object_type);
+        }
+        object.to_bytecode(self)?;
+        return match object_type {
+            TypeSpecifier::Pointer(inner) => Ok(*inner),
+            _ => Err(CompilerError::CodeGen(String::from(
+                "Arrow access only can use with pointers",
+            ))),
+        };
+    }
+
+    /// Calls a member function on the struct whose address is on the stack,
+    /// passing it by pointer or by value depending on the receiver.
+    fn method_call(&mut self, struct_name: &str, fn_call: &'a FnCallExpression) -> Result<Member> {
+        let name = fn_call.fn_name.identifier()?.id.to_string();
+        let signature = self.context.method(struct_name, &name)?.clone();
+        match &signature.receiver {
+            Some(TypeSpecifier::Pointer(_)) => {}
+            Some(receiver) => {
+                let size = self.size_of(receiver)?;
+                self.emit(Instruction::Load(size));
+            }
+            None => {
+                self.emit(Instruction::Pop(1));
+            }
+        }
+        for arg in fn_call.args.iter() {
+            arg.to_bytecode(self)?;
+        }
+        let id = self.function_id(&BytecodeGenerator::method_name(struct_name, &name))?;
+        self.emit(Instruction::Call(id));
+        return Ok(Member::Value(signature.return_type));
+    }
+
+    fn index_address(&mut self, array_index: &'a ArrayIndexExpression) -> Result<TypeSpecifier> {
+        let (element, length) = match self.context.expr_type(&array_index.array)? {
+            TypeSpecifier::ArrayType {
+                type_specifier,
+                size,
+                ..
+            } => {
+                self.address(&array_index.array)?;
+                (*type_specifier, Some(size as u32))
+            }
+            TypeSpecifier::Pointer(type_specifier) => {
+                array_index.array.to_bytecode(self)?;
+                (*type_specifier, None)
+            }
+            _ => {
+                return Err(CompilerError::CodeGen(String::from(
+                    "Expect the array type",
+                )))
+            }
+        };
+        array_index.index.to_bytecode(self)?;
+        let stride = self.size_of(&element)?;
+        self.emit(Instruction::Index { stride, length });
+
+        let Some(second_index) = &array_index.second_index else {
+            return Ok(element);
+        };
+        let TypeSpecifier::ArrayType {
+            type_specifier,
+            size,
+            ..
+        } = element
+        else {
+            return Err(CompilerError::CodeGen(String::from(
+                "Expect the two dimensional array type",
+            )));
+        };
+        second_index.to_bytecode(self)?;
+        let stride = self.size_of(&type_specifier)?;
+        self.emit(Instruction::Index {
+            stride,
+            length: Some(size as u32),
+        });
+        return Ok(*type_specifier);
+    }
+}
+
+/// The address of the struct is on the stack, `Object` is its type.
+impl<'a> MemberChain<'a> for BytecodeGenerator<'a> {
+    type Object = TypeSpecifier;
+    type Member = Member;
+
+    fn object_type(&self, object: &TypeSpecifier) -> Result<TypeSpecifier> {
+        return Ok(object.clone());
+    }
+
+    fn field_member(
+        &mut self,
+        _object: TypeSpecifier,
+        struct_name: &str,
+        name: &str,
+    ) -> Result<Member> {
+        let offset = self.field(struct_name, name)?.offset;
+        if offset > 0 {
+            self.emit(Instruction::Offset(offset));
+        }
+        return Ok(Member::Address(
+            self.context.field(struct_name, name)?.clone(),
+        ));
+    }
+
+    fn method_member(
+        &mut self,
+        _object: TypeSpecifier,
+        struct_name: &str,
+        fn_call: &'a FnCallExpression,
+    ) -> Result<Member> {
+        return self.method_call(struct_name, fn_call);
+    }
+
+    fn member_object(&mut self, member: Member, access: &TokenType) -> Result<TypeSpecifier> {
+        let is_arrow = access == &TokenType::Arrow;
+        let inner = match member {
+            Member::Address(inner) if is_arrow => {
+                self.emit(Instruction::Load(1));
+                inner
+            }
+            Member::Value(inner) if !is_arrow => {
+                self.spill(&inner)?;
+                inner
+            }
+            Member::Address(inner) | Member::Value(inner) => inner,
+        };
+        return Ok(match inner {
+            TypeSpecifier::Pointer(pointee) if is_arrow => *pointee,
+            inner => inner,
+        });
+    }
+}
+
+impl<'a> ToBytecode<'a> for BinaryExpression {
+    fn to_bytecode(&'a self, gen: &mut BytecodeGenerator<'a>) -> Result<()> {
+        if let BinaryOperators::And | BinaryOperators::Or = self.operator {
+            // `a && b` is `a ? b : false` and `a || b` is `a ? true : b`.
+            let is_and = matches!(self.operator, BinaryOperators::And);
+            self.left.to_bytecode(gen)?;
+            let right = gen.emit(Instruction::JumpIfFalse(0));
+            if is_and {
+                self.right.to_bytecode(gen)?;
+            } else {
+                let id = gen.constant(Constant::Bool(true));
+                gen.emit(Instruction::Const(id));
+            }
+            let end = gen.emit(Instruction::Jump(0));
+            gen.patch(right);
+            if is_and {
+                let id = gen.constant(Constant::Bool(false));
+                gen.emit(Instruction::Const(id));
+            } else {
+                self.right.to_bytecode(gen)?;
+            }
+            gen.patch(end);
+            return Ok(());
+        }
+
+        self.left.to_bytecode(gen)?;
+        self.right.to_bytecode(gen)?;
+        gen.emit(match self.operator {
+            BinaryOperators::Add => Instruction::Add,
+            BinaryOperators::Subtract => Instruction::Subtract,
+            BinaryOperators::Multiply => Instruction::Multiply,
+            BinaryOperators::Divide => Instruction::Divide,
+            BinaryOperators::Modulo => Instruction::Modulo,
+            BinaryOperators::Equal => Instruction::Equal,
+            BinaryOperators::NotEqual => Instruction::NotEqual,
+            BinaryOperators::BitAnd => Instruction::BitAnd,
+            BinaryOperators::BitOr => Instruction::BitOr,
+            BinaryOperators::BitXor => Instruction::BitXor,
+            BinaryOperators::GreaterThan => Instruction::Greater,
+            BinaryOperators::LessThan => Instruction::Less,
+            BinaryOperators::GreaterThanOrEqual => Instruction::GreaterEqual,
+            BinaryOperators::LessThanOrEqual => Instruction::LessEqual,
+            BinaryOperators::BitLeft => Instruction::ShiftLeft,
+            BinaryOperators::BitRight => Instruction::ShiftRight,
+            BinaryOperators::And | BinaryOperators::Or => unreachable!(),
+        });
+        return Ok(());
+    }
+}
+
+impl<'a> ToBytecode<'a> for UnaryExpression {
+    fn to_bytecode(&'a self, gen: &mut BytecodeGenerator<'a>) -> Result<()> {
+        match self.operator {
+            UnaryOperators::Address => return gen.address(&self.value),
+            UnaryOperators::Indirection => {
+                let TypeSpecifier::Pointer(pointee) = gen.context.expr_type(&self.value)? else {
+                    return Err(CompilerError::CodeGen(String::from(
+                        "Expected pointer type",
+                    )));
+                };
+                let size = gen.size_of(&pointee)?;
+                self.value.to_bytecode(gen)?;
+                gen.emit(Instruction::Load(size));
+                return Ok(());
+            }
+            UnaryOperators::Increment | UnaryOperators::Decrement => {
+                let delta = match gen.context.expr_type(&self.value)? {
+                    TypeSpecifier::Pointer(pointee) => gen.size_of(&pointee)? as i32,
+                    _ => 1,
+                };
+                let delta = if matches!(self.operator, UnaryOperators::Increment) {
+                    delta
+                } else {
+                    -delta
+                };
+                gen.address(&self.value)?;
+                gen.emit(Instruction::Step {
+                    delta,
+                    postfix: matches!(self.expression_type, UnaryExpressionType::Postfix),
+                });
+                return Ok(());
+            }
+            _ => {}
+        }
+        self.value.to_bytecode(gen)?;
+        match self.operator {
+            UnaryOperators::Minus => {
+                gen.emit(Instruction::Negate);
+            }
+            UnaryOperators::LogicalNot => {
+                gen.emit(Instruction::Not);
+            }
+            UnaryOperators::BitwiseNot => {
+                gen.emit(Instruction::BitNot);
+            }
+            _ => {}
+        }
+        return Ok(());
+    }
+}
+
+impl<'a> ToBytecode<'a> for ArrayIndexExpression {
+    fn to_bytecode(&'a self, gen: &mut BytecodeGenerator<'a>) -> Result<()> {
+        let element = gen.index_address(self)?;
+        let size = gen.size_of(&element)?;
+        gen.emit(Instruction::Load(size));
+        return Ok(());
+    }
+}
+
+impl<'a> ToBytecode<'a> for MemberAccessExpression {
+    fn to_bytecode(&'a self, gen: &mut BytecodeGenerator<'a>) -> Result<()> {
+        let object = gen.object_address(&self.object, &self.access)?;
+        if let Member::Address(type_specifier) = gen.member(object, &self.property)? {
+            let size = gen.size_of(&type_specifier)?;
+            gen.emit(Instruction::Load(size));
+        }
+        return Ok(());
+    }
+}
+
+impl<'a> ToBytecode<'a> for FnCallExpression {
+    fn to_bytecode(&'a self, gen: &mut BytecodeGenerator<'a>) -> Result<()> {
+        let name = self.fn_name.identifier()?.id.to_string();
+        for arg in self.args.iter() {
+            arg.to_bytecode(gen)?;
+        }
+        let name = match &self.namespace {
+            Some(namespace) => {
+                let struct_name = namespace.name.identifier()?;
+                BytecodeGenerator::method_name(struct_name.id.as_ref(), &name)
+            }
+            None => name,
+        };
+        let id = gen.function_id(&name)?;
+        gen.emit(Instruction::Call(id));
+        return Ok(());
+    }
+}
+
+impl<'a> ToBytecode<'a> for ArrayInitExpression {
+    fn to_bytecode(&'a self, gen: &mut BytecodeGenerator<'a>) -> Result<()> {
+        for item in self.array.iter() {
+            item.to_bytecode(gen)?;
+        }
+        return Ok(());
+    }
+}
+
+impl<'a> ToBytecode<'a> for ObjectInitExpression {
+    /// Pushes the fields in declaration order, missing ones as zero.
+    fn to_bytecode(&'a self, gen: &mut BytecodeGenerator<'a>) -> Result<()> {
+        let name = self.object.identifier()?.id.to_string();
+        let fields = gen.context.struct_layout(&name)?.fields.clone();
+        for (field_name, type_specifier) in fields.iter() {
+            match self
+                .fields
+                .iter()
+                .find(|field| field.name.as_ref() == field_name)
+            {
+                Some(field) => field.value.to_bytecode(gen)?,
+                None => gen.zero(type_specifier)?,
+            }
+        }
+        return Ok(());
+    }
+}
+
+impl<'a> ToBytecode<'a> for CastExpression {
+    fn to_bytecode(&'a self, gen: &mut BytecodeGenerator<'a>) -> Result<()> {
+        self.expression.to_bytecode(gen)?;
+        if let TypeSpecifier::UserDefine(name) = &self.target_type {
+            // Enums are stored as ints, only the value needs checking.
+            let id = gen.enum_ids.get(name.as_ref()).ok_or_else(|| {
+                CompilerError::CodeGen(format!("Cannot cast to the complex type {}", name))
+            })?;
+            gen.emit(Instruction::CheckEnum(*id));
+            return Ok(());
+        }
+        let primitive = BytecodeGenerator::primitive(&self.target_type)?;
+        gen.emit(Instruction::Cast(primitive));
+        return Ok(());
+    }
+}
+
+impl<'a> ToBytecode<'a> for IdentifierExpression {
+    fn to_bytecode(&'a self, gen: &mut BytecodeGenerator<'a>) -> Result<()> {
+        let name = self.id.as_ref();
+        let size = gen.size_of(gen.context.variable(name)?)?;
+        let slot = gen.local(name)?;
+        gen.emit(Instruction::LoadLocal { slot, size });
+        return Ok(());
+    }
+}
+
+impl<'a> ToBytecode<'a> for EnumItemExpression {
+    /// An item of a tagged enum pushes the fields of its layout like an
+    /// object init, with the payload fields of other items as zero.
+    fn to_bytecode(&'a self, gen: &mut BytecodeGenerator<'a>) -> Result<()> {
+        let (name, item) = (self.name.as_ref(), self.item.as_ref());
+        let value = gen.context.enum_item(name, item)?;
+        let id = gen.constant(Constant::Int(value));
+        if !gen.context.is_tagged(name) {
+            gen.emit(Instruction::Const(id));
+            return Ok(());
+        }
+        let fields = gen.context.struct_layout(name)?.fields.clone();
+        let payload: Vec<String> = (0..self.args.len())
+            .map(|index| EnumDeclaration::payload_field(item, index))
+            .collect();
+        for (field_name, type_specifier) in fields.iter() {
+            if field_name == EnumDeclaration::TAG {
+                gen.emit(Instruction::Const(id));
+                continue;
+            }
+            match payload.iter().position(|field| field == field_name) {
+                Some(index) => self.args[index].to_bytecode(gen)?,
+                None => gen.zero(type_specifier)?,
+            }
+        }
+        return Ok(());
+    }
+}
+
+impl<'a> ToBytecode<'a> for PrimitiveExpression {
+    fn to_bytecode(&'a self, gen: &mut BytecodeGenerator<'a>) -> Result<()> {
+        let invalid = || CompilerError::CodeGen(format!("Invalid literal {}", self.value));
+        let constant = match self.type_specifier {
+            TypeSpecifier::Int => {
+                Constant::Int(self.value.parse::<i64>().map_err(|_| invalid())? as i32)
+            }
+            TypeSpecifier::Usize => Constant::Usize(self.value.parse().map_err(|_| invalid())?),
+            TypeSpecifier::Float => Constant::Float(self.value.parse().map_err(|_| invalid())?),
+            TypeSpecifier::Double => Constant::Double(self.value.parse().map_err(|_| invalid())?),
+            TypeSpecifier::Bool => Constant::Bool(self.value == "true"),
+            TypeSpecifier::Char => Constant::Char(*self.bytes().first().ok_or_else(invalid)?),
+            TypeSpecifier::Str => {
+                Constant::Str(String::from_utf8_lossy(&self.bytes()).into_owned())
+            }
+            _ => return Err(invalid()),
+        };
+        let id = gen.constant(constant);
+        gen.emit(Instruction::Const(id));
+        return Ok(());
+    }
+}
@@ -0,0 +1,245 @@
+pub mod encoding;
+pub mod expressions;
+pub mod module;
+pub mod program;
+pub mod statements;
+
+use std::collections::HashMap;
+
+use crate::{
+    ast::{expressions::Expression, program::Program, type_specifier::TypeSpecifier},
+    error::{CompilerError, Result},
+};
+
+use self::module::{Constant, FieldLayout, Instruction, Module, Primitive};
+
+use super::{
+    loops::{Defers, Loops},
+    type_context::TypeContext,
+};
+
+/// Compiles an analyzed program to a bytecode [`Module`].
+///
+/// Locals get fixed slots in the frame of their function, temporaries that
+/// need an address included. `defer` expressions are compiled again at every
+/// exit of their block.
+pub struct BytecodeGenerator<'a> {
+    program: &'a Program,
+    pub(crate) context: TypeContext,
+    module: Module,
+    function_ids: HashMap<String, u32>,
+    struct_ids: HashMap<String, usize>,
+    enum_ids: HashMap<String, u32>,
+    constant_ids: HashMap<String, u32>,
+    code: Vec<Instruction>,
+    scopes: Vec<HashMap<String, u32>>,
+    defers: Defers<&'a Expression>,
+    loops: Loops<LoopJumps>,
+    locals_size: u32,
+    return_type: TypeSpecifier,
+}
+
+/// The jumps of the `break` and `continue` statements of a loop, which are
+/// patched once their targets are known.
+#[derive(Debug, Default)]
+pub(crate) struct LoopJumps {
+    breaks: Vec<usize>,
+    continues: Vec<usize>,
+}
+
+pub trait ToBytecode<'a> {
+    fn to_bytecode(&'a self, gen: &mut BytecodeGenerator<'a>) -> Result<()>;
+}
+
+impl<'a> BytecodeGenerator<'a> {
+    pub fn new(program: &'a Program) -> Self {
+        Self {
+            program,
+            context: TypeContext::new(program),
+            module: Module::default(),
+            function_ids: HashMap::new(),
+            struct_ids: HashMap::new(),
+            enum_ids: HashMap::new(),
+            constant_ids: HashMap::new(),
+            code: Vec::new(),
+            scopes: Vec::new(),
+            defers: Defers::default(),
+            loops: Loops::default(),
+            locals_size: 0,
+            return_type: TypeSpecifier::Void,
+        }
+    }
+
+    pub fn generate(mut self) -> Result<Module> {
+        let program = self.program;
+        program.to_bytecode(&mut self)?;
+        return Ok(self.module);
+    }
+
+    pub(crate) fn emit(&mut self, instruction: Instruction) -> usize {
+        self.code.push(instruction);
+        return self.code.len() - 1;
+    }
+
+    /// The address of the next instruction.
+    pub(crate) fn here(&self) -> u32 {
+        return self.code.len() as u32;
+    }
+
+    /// Points the jump at `address` to the next instruction.
+    pub(crate) fn patch(&mut self, address: usize) {
+        let target = self.here();
+        match &mut self.code[address] {
+            Instruction::Jump(jump) | Instruction::JumpIfFalse(jump) => *jump = target,
+            _ => unreachable!("Only jumps can be patched"),
+        }
+    }
+
+    pub(crate) fn constant(&mut self, constant: Constant) -> u32 {
+        let key = format!("{:?}", constant);
+        if let Some(id) = self.constant_ids.get(&key) {
+            return *id;
+        }
+        let id = self.module.constants.len() as u32;
+        self.module.constants.push(constant);
+        self.constant_ids.insert(key, id);
+        return id;
+    }
+
+    pub(crate) fn push_scope(&mut self) {
+        self.context.push_scope();
+        self.scopes.push(HashMap::new());
+        self.defers.push_scope();
+    }
+
+    pub(crate) fn pop_scope(&mut self) {
+        self.context.pop_scope();
+        self.scopes.pop();
+        self.defers.pop_scope();
+    }
+
+    /// Allocates the slots of a new local variable.
+    pub(crate) fn declare(&mut self, name: &str, type_specifier: &TypeSpecifier) -> Result<u32> {
+        let slot = self.temp(type_specifier)?;
+        self.context.declare(name, type_specifier.clone());
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_owned(), slot);
+        }
+        return Ok(slot);
+    }
+
+    /// Allocates frame slots that no variable name refers to.
+    pub(crate) fn temp(&mut self, type_specifier: &TypeSpecifier) -> Result<u32> {
+        let slot = self.locals_size;
+        self.locals_size += self.size_of(type_specifier)?;
+        return Ok(slot);
+    }
+
+    pub(crate) fn local(&self, name: &str) -> Result<u32> {
+        return self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .copied()
+            .ok_or_else(|| CompilerError::CodeGen(format!("Unknown variable {}", name)));
+    }
+
+    pub(crate) fn function_id(&self, name: &str) -> Result<u32> {
+        return self
+            .function_ids
+            .get(name)
+            .copied()
+            .ok_or_else(|| CompilerError::CodeGen(format!("Unknown function {}", name)));
+    }
+
+    /// Number of slots a value of `type_specifier` occupies.
+    pub(crate) fn size_of(&self, type_specifier: &TypeSpecifier) -> Result<u32> {
+        return Ok(match type_specifier {
+            TypeSpecifier::Void => 0,
+            TypeSpecifier::ArrayType {
+                type_specifier,
+                size,
+                ..
+            } => self.size_of(type_specifier)? * *size as u32,
+            TypeSpecifier::UserDefine(name) if self.context.is_struct(name.as_ref()) => {
+                let id = self.struct_ids.get(name.as_ref()).ok_or_else(|| {
+                    CompilerError::CodeGen(format!("Layout of struct {} is unknown", name))
+                })?;
+                self.module.structs[*id].size
+            }
+            _ => 1,
+        });
+    }
+
+    pub(crate) fn field(&self, struct_name: &str, name: &str) -> Result<&FieldLayout> {
+        return self
+            .struct_ids
+            .get(struct_name)
+            .and_then(|id| {
+                self.module.structs[*id]
+                    .fields
+                    .iter()
+                    .find(|field| field.name == name)
+            })
+            .ok_or_else(|| {
+                CompilerError::CodeGen(format!("Unknown field {}.{}", struct_name, name))
+            });
+    }
+
+    /// Pushes the default value of `type_specifier`, one constant per slot.
+    pub(crate) fn zero(&mut self, type_specifier: &TypeSpecifier) -> Result<()> {
+        let constant = match type_specifier {
+            TypeSpecifier::Void => return Ok(()),
+            TypeSpecifier::ArrayType {
+                type_specifier,
+                size,
+                ..
+            } => {
+                for _ in 0..*size {
+                    self.zero(type_specifier)?;
+                }
+                return Ok(());
+            }
+            TypeSpecifier::UserDefine(name) if self.context.is_struct(name.as_ref()) => {
+                let layout = self.context.struct_layout(name.as_ref())?;
+                let fields: Vec<TypeSpecifier> = layout
+                    .fields
+                    .iter()
+                    .map(|(_, type_specifier)| type_specifier.clone())
+                    .collect();
+                for field in fields.iter() {
+                    self.zero(field)?;
+                }
+                return Ok(());
+            }
+            TypeSpecifier::Int | TypeSpecifier::UserDefine(_) => Constant::Int(0),
+            TypeSpecifier::Usize => Constant::Usize(0),
+            TypeSpecifier::Float => Constant::Float(0.0),
+            TypeSpecifier::Double => Constant::Double(0.0),
+            TypeSpecifier::Char => Constant::Char(0),
+            TypeSpecifier::Str => Constant::Str(String::new()),
+            TypeSpecifier::Bool => Constant::Bool(false),
+            TypeSpecifier::Pointer(_) => Constant::Null,
+        };
+        let id = self.constant(constant);
+        self.emit(Instruction::Const(id));
+        return Ok(());
+    }
+
+    pub(crate) fn primitive(type_specifier: &TypeSpecifier) -> Result<Primitive> {
+        return Ok(match type_specifier {
+            TypeSpecifier::Int => Primitive::Int,
+            TypeSpecifier::Usize => Primitive::Usize,
+            TypeSpecifier::Float => Primitive::Float,
+            TypeSpecifier::Double => Primitive::Double,
+            TypeSpecifier::Char => Primitive::Char,
+            TypeSpecifier::Bool => Primitive::Bool,
+            _ => {
+                return Err(CompilerError::CodeGen(String::from(
+                    "Cannot cast to the complex type",
+                )))
+            }
+        });
+    }
+}
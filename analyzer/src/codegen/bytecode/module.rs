@@ -0,0 +1,175 @@
+use std::fmt;
+
+/// A compiled program: the constant pool, struct layouts, enum values and
+/// function table that the virtual machine runs, starting from the `entry` function.
+///
+/// Every scalar occupies one slot. Structs and arrays are flattened into
+/// consecutive slots, so a pointer is the index of the first slot.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Module {
+    pub constants: Vec<Constant>,
+    pub structs: Vec<StructLayout>,
+    pub enums: Vec<EnumLayout>,
+    pub functions: Vec<Function>,
+    pub entry: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Constant {
+    Int(i32),
+    Usize(u64),
+    Float(f32),
+    Double(f64),
+    Char(u8),
+    Bool(bool),
+    Str(String),
+    Null,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructLayout {
+    pub name: String,
+    pub fields: Vec<FieldLayout>,
+    pub size: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldLayout {
+    pub name: String,
+    pub offset: u32,
+    pub size: u32,
+}
+
+/// The values the items of an enum are stored as.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumLayout {
+    pub name: String,
+    pub values: Vec<i32>,
+}
+
+/// The most slots the frame of a function, or the value it returns, may
+/// take, so that no call can ask for more memory than a machine has.
+pub const MAX_FRAME_SIZE: u32 = 1 << 20;
+
+/// A function whose parameters occupy the first `params_size` slots of its
+/// `locals_size` slots large frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+    pub name: String,
+    pub params_size: u32,
+    pub locals_size: u32,
+    pub return_size: u32,
+    pub code: Vec<Instruction>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Primitive {
+    Int,
+    Usize,
+    Float,
+    Double,
+    Char,
+    Bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Instruction {
+    /// Pushes a value of the constant pool.
+    Const(u32),
+    Pop(u32),
+    /// Duplicates the top `n` slots.
+    Dup(u32),
+    LoadLocal {
+        slot: u32,
+        size: u32,
+    },
+    /// Pops `size` slots into the frame.
+    StoreLocal {
+        slot: u32,
+        size: u32,
+    },
+    /// Pushes a pointer to a slot of the frame.
+    LocalAddr(u32),
+    /// Pops a pointer and pushes the `n` slots it points to.
+    Load(u32),
+    /// Pops a pointer and then the `n` slots stored through it.
+    Store(u32),
+    /// Moves the pointer on the top of the stack `n` slots further.
+    Offset(u32),
+    /// Pops an index and a pointer and pushes the pointer to the element.
+    /// Indexing an array checks the index against its `length`.
+    Index {
+        stride: u32,
+        length: Option<u32>,
+    },
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Modulo,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    BitAnd,
+    BitOr,
+    BitXor,
+    ShiftLeft,
+    ShiftRight,
+    Negate,
+    Not,
+    BitNot,
+    /// Pops a pointer, adds `delta` to the pointed value and pushes the new
+    /// value, or the old one when `postfix` is set.
+    Step {
+        delta: i32,
+        postfix: bool,
+    },
+    Cast(Primitive),
+    /// Checks that the int on the top of the stack is a value of the enum.
+    CheckEnum(u32),
+    Jump(u32),
+    JumpIfFalse(u32),
+    Call(u32),
+    /// Returns the top `return_size` slots of the current function.
+    Return,
+}
+
+impl fmt::Display for Module {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "constants:")?;
+        for (index, constant) in self.constants.iter().enumerate() {
+            writeln!(f, "    {:>4}: {:?}", index, constant)?;
+        }
+        for layout in self.structs.iter() {
+            writeln!(f, "struct {} ({} slots):", layout.name, layout.size)?;
+            for field in layout.fields.iter() {
+                writeln!(
+                    f,
+                    "    {} at {} ({} slots)",
+                    field.name, field.offset, field.size
+                )?;
+            }
+        }
+        for layout in self.enums.iter() {
+            writeln!(f, "enum {}: {:?}", layout.name, layout.values)?;
+        }
+        for (index, function) in self.functions.iter().enumerate() {
+            writeln!(
+                f,
+                "fn #{} {} (params: {}, locals: {}, returns: {}):",
+                index,
+                function.name,
+                function.params_size,
+                function.locals_size,
+                function.return_size
+            )?;
+            for (address, instruction) in function.code.iter().enumerate() {
+                writeln!(f, "    {:>4}: {:?}", address, instruction)?;
+            }
+        }
+        return Ok(());
+    }
+}
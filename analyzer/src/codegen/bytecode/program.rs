@@ -0,0 +1,147 @@
+use crate::{
+    ast::{
+        declarations::Declarations, program::Program, statements::block_stmt::BlockStmt,
+        type_specifier::TypeSpecifier, variable_type::VariableType,
+    },
+    error::{CompilerError, Result},
+};
+
+use super::{
+    module::{EnumLayout, FieldLayout, Function, Instruction, StructLayout, MAX_FRAME_SIZE},
+    BytecodeGenerator, ToBytecode,
+};
+
+impl<'a> ToBytecode<'a> for Program {
+    fn to_bytecode(&'a self, gen: &mut BytecodeGenerator<'a>) -> Result<()> {
+        for struct_decl in self.sorted_structs()? {
+            let mut layout = StructLayout {
+                name: struct_decl.name.to_string(),
+                fields: Vec::new(),
+                size: 0,
+            };
+            for field in struct_decl.fields.iter() {
+                let size = gen.size_of(&field.field.type_specifier)?;
+                layout.fields.push(FieldLayout {
+                    name: field.field.identifier.to_string(),
+                    offset: layout.size,
+                    size,
+                });
+                layout.size += size;
+            }
+            gen.struct_ids
+                .insert(layout.name.clone(), gen.module.structs.len());
+            gen.module.structs.push(layout);
+        }
+        for decl in self.declarations.iter() {
+            if let Declarations::Enum(enum_decl) = decl {
+                let layout = EnumLayout {
+                    name: enum_decl.name.to_string(),
+                    values: enum_decl
+                        .fields
+                        .iter()
+                        .map(|item| item.discriminant)
+                        .collect(),
+                };
+                gen.enum_ids
+                    .insert(layout.name.clone(), gen.module.enums.len() as u32);
+                gen.module.enums.push(layout);
+            }
+        }
+
+        // Functions get their ids first so calls can refer to any of them.
+        let mut bodies = Vec::new();
+        for decl in self.declarations.iter() {
+            match decl {
+                Declarations::Fn(fn_decl) => {
+                    let params: Vec<&VariableType> = fn_decl.params.iter().collect();
+                    gen.add_function(fn_decl.fn_name.to_string(), &fn_decl.return_type)?;
+                    bodies.push((params, &fn_decl.return_type, &fn_decl.body));
+                }
+                Declarations::Impl(impl_decl) => {
+                    let struct_name = impl_decl.name.to_symbol()?;
+                    for member_fn in impl_decl.member_functions.iter() {
+                        let name = BytecodeGenerator::method_name(
+                            struct_name,
+                            member_fn.prototype.name.as_ref(),
+                        );
+                        gen.add_function(name, &member_fn.prototype.return_type)?;
+                        bodies.push((
+                            member_fn.params(),
+                            &member_fn.prototype.return_type,
+                            &member_fn.body,
+                        ));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for (id, (params, return_type, body)) in bodies.into_iter().enumerate() {
+            gen.function(id, &params, return_type, body)?;
+        }
+
+        gen.module.entry =
+            gen.function_ids.get("main").copied().ok_or_else(|| {
+                CompilerError::CodeGen(String::from("Program has no main function"))
+            })?;
+        return Ok(());
+    }
+}
+
+impl<'a> BytecodeGenerator<'a> {
+    pub(crate) fn method_name(struct_name: &str, name: &str) -> String {
+        return format!("{}::{}", struct_name, name);
+    }
+
+    fn add_function(&mut self, name: String, return_type: &TypeSpecifier) -> Result<()> {
+        let function = Function {
+            name: name.clone(),
+            params_size: 0,
+            locals_size: 0,
+            return_size: self.size_of(return_type)?,
+            code: Vec::new(),
+        };
+        self.function_ids
+            .insert(name, self.module.functions.len() as u32);
+        self.module.functions.push(function);
+        return Ok(());
+    }
+
+    fn function(
+        &mut self,
+        id: usize,
+        params: &[&VariableType],
+        return_type: &TypeSpecifier,
+        body: &'a BlockStmt,
+    ) -> Result<()> {
+        self.code = Vec::new();
+        self.locals_size = 0;
+        self.return_type = return_type.clone();
+
+        self.push_scope();
+        for param in params.iter() {
+            self.declare(param.identifier.as_ref(), &param.type_specifier)?;
+        }
+        let params_size = self.locals_size;
+        let result = body.to_bytecode(self);
+        self.pop_scope();
+        result?;
+
+        if !body.ends_with_return() {
+            self.zero(return_type)?;
+            self.emit(Instruction::Return);
+        }
+
+        let function = &mut self.module.functions[id];
+        if self.locals_size > MAX_FRAME_SIZE || function.return_size > MAX_FRAME_SIZE {
+            return Err(CompilerError::CodeGen(format!(
+                "Frame of {} is larger than the {} slots the VM allows",
+                function.name, MAX_FRAME_SIZE
+            )));
+        }
+        function.params_size = params_size;
+        function.locals_size = self.locals_size;
+        function.code = std::mem::take(&mut self.code);
+        return Ok(());
+    }
+}
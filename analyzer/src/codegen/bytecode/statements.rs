@@ -0,0 +1,362 @@
+use crate::{
+    ast::{
+        declarations::enum_decl::EnumDeclaration,
+        expressions::Expression,
+        identifier::Identifier,
+        statements::{
+            block_stmt::BlockStmt,
+            break_stmt::BreakStmt,
+            continue_stmt::ContinueStmt,
+            defer_stmt::DeferStmt,
+            do_while_stmt::DoWhileStmt,
+            for_stmt::{ForStmt, VariableInit},
+            if_stmt::IfStmt,
+            let_stmt::LetStmt,
+            match_stmt::{MatchArm, MatchStmt},
+            return_stmt::ReturnStmt,
+            switch_stmt::SwitchStmt,
+            while_stmt::WhileStmt,
+            Statements,
+        },
+        type_specifier::TypeSpecifier,
+    },
+    codegen::{loops::Jump, type_context::TypeContext},
+    error::{CompilerError, Result},
+};
+
+use super::{
+    module::{Constant, Instruction},
+    BytecodeGenerator, LoopJumps, ToBytecode,
+};
+
+impl<'a> ToBytecode<'a> for Statements {
+    fn to_bytecode(&'a self, gen: &mut BytecodeGenerator<'a>) -> Result<()> {
+        return match self {
+            Statements::Let(let_stmt) => let_stmt.to_bytecode(gen),
+            Statements::Const(_) => Ok(()),
+            Statements::Return(return_stmt) => return_stmt.to_bytecode(gen),
+            Statements::Defer(defer_stmt) => defer_stmt.to_bytecode(gen),
+            Statements::For(for_stmt) => for_stmt.to_bytecode(gen),
+            Statements::While(while_stmt) => while_stmt.to_bytecode(gen),
+            Statements::DoWhile(do_while_stmt) => do_while_stmt.to_bytecode(gen),
+            Statements::If(if_stmt) => if_stmt.to_bytecode(gen),
+            Statements::Switch(switch_stmt) => switch_stmt.to_bytecode(gen),
+            Statements::Match(match_stmt) => match_stmt.to_bytecode(gen),
+            Statements::Break(break_stmt) => break_stmt.to_bytecode(gen),
+            Statements::Continue(continue_stmt) => continue_stmt.to_bytecode(gen),
+            Statements::Block(block_stmt) => block_stmt.to_bytecode(gen),
+            Statements::Expression(expr) => gen.discard(expr),
+        };
+    }
+}
+
+impl<'a> BytecodeGenerator<'a> {
+    /// Evaluates `expr` for its side effects only.
+    pub(crate) fn discard(&mut self, expr: &'a Expression) -> Result<()> {
+        if let Expression::Assignment(assignment) = expr {
+            return self.assign(assignment, false);
+        }
+        let size = self.size_of(&self.context.expr_type(expr)?)?;
+        expr.to_bytecode(self)?;
+        if size > 0 {
+            self.emit(Instruction::Pop(size));
+        }
+        return Ok(());
+    }
+
+    /// Compiles the body of a loop labeled `label`, whose `continue`s jump
+    /// to the end of it. Returns the jumps of its `break`s, which the caller
+    /// patches once the loop ends.
+    fn loop_body(
+        &mut self,
+        label: Option<&Identifier>,
+        block: &'a BlockStmt,
+    ) -> Result<Vec<usize>> {
+        self.loops
+            .push(label, self.defers.depth(), LoopJumps::default());
+        let result = block.to_bytecode(self);
+        let jumps = self.loops.pop()?.targets;
+        result?;
+        for jump in jumps.continues {
+            self.patch(jump);
+        }
+        return Ok(jumps.breaks);
+    }
+
+    /// Compiles a `break` or `continue` to the loop labeled `label`, which
+    /// runs the deferred expressions of the blocks it leaves first.
+    fn leave_loop(&mut self, label: Option<&Identifier>, jump: Jump) -> Result<()> {
+        let defers = self.defers.leaving(self.loops.target(label)?);
+        for expr in defers {
+            self.discard(expr)?;
+        }
+        let address = self.emit(Instruction::Jump(0));
+        let jumps = &mut self.loops.target(label)?.targets;
+        match jump {
+            Jump::Break => jumps.breaks.push(address),
+            Jump::Continue => jumps.continues.push(address),
+        }
+        return Ok(());
+    }
+}
+
+impl<'a> ToBytecode<'a> for BlockStmt {
+    fn to_bytecode(&'a self, gen: &mut BytecodeGenerator<'a>) -> Result<()> {
+        gen.push_scope();
+        let result = self.stmts_to_bytecode(gen);
+        gen.pop_scope();
+        return result;
+    }
+}
+
+impl BlockStmt {
+    fn stmts_to_bytecode<'a>(&'a self, gen: &mut BytecodeGenerator<'a>) -> Result<()> {
+        for stmt in self.stmts.iter() {
+            stmt.to_bytecode(gen)?;
+        }
+        if !self.ends_with_return() {
+            for expr in gen.defers.pending(1) {
+                gen.discard(expr)?;
+            }
+        }
+        return Ok(());
+    }
+}
+
+impl<'a> ToBytecode<'a> for LetStmt {
+    fn to_bytecode(&'a self, gen: &mut BytecodeGenerator<'a>) -> Result<()> {
+        let type_specifier = self.type_specifier.as_ref().ok_or_else(|| {
+            CompilerError::CodeGen(format!("Type of {} is unknown", self.identifier))
+        })?;
+        match &self.value {
+            Some(value) => value.to_bytecode(gen)?,
+            None => gen.zero(type_specifier)?,
+        }
+        let size = gen.size_of(type_specifier)?;
+        let slot = gen.declare(self.identifier.as_ref(), type_specifier)?;
+        gen.emit(Instruction::StoreLocal { slot, size });
+        return Ok(());
+    }
+}
+
+impl<'a> ToBytecode<'a> for ReturnStmt {
+    /// The value is computed before the deferred expressions run.
+    fn to_bytecode(&'a self, gen: &mut BytecodeGenerator<'a>) -> Result<()> {
+        match &self.value {
+            Some(value) => value.to_bytecode(gen)?,
+            None => gen.zero(&gen.return_type.clone())?,
+        }
+        for expr in gen.defers.all() {
+            gen.discard(expr)?;
+        }
+        gen.emit(Instruction::Return);
+        return Ok(());
+    }
+}
+
+impl<'a> ToBytecode<'a> for DeferStmt {
+    fn to_bytecode(&'a self, gen: &mut BytecodeGenerator<'a>) -> Result<()> {
+        gen.defers.push(&self.expr);
+        return Ok(());
+    }
+}
+
+impl<'a> ToBytecode<'a> for BreakStmt {
+    fn to_bytecode(&'a self, gen: &mut BytecodeGenerator<'a>) -> Result<()> {
+        return gen.leave_loop(self.label.as_ref(), Jump::Break);
+    }
+}
+
+impl<'a> ToBytecode<'a> for ContinueStmt {
+    fn to_bytecode(&'a self, gen: &mut BytecodeGenerator<'a>) -> Result<()> {
+        return gen.leave_loop(self.label.as_ref(), Jump::Continue);
+    }
+}
+
+impl<'a> ToBytecode<'a> for ForStmt {
+    fn to_bytecode(&'a self, gen: &mut BytecodeGenerator<'a>) -> Result<()> {
+        gen.push_scope();
+        let result = self.loop_to_bytecode(gen);
+        gen.pop_scope();
+        return result;
+    }
+}
+
+impl ForStmt {
+    fn loop_to_bytecode<'a>(&'a self, gen: &mut BytecodeGenerator<'a>) -> Result<()> {
+        match &self.init {
+            VariableInit::Let(let_stmt) => let_stmt.to_bytecode(gen)?,
+            VariableInit::Assign(assign) => gen.assign(assign, false)?,
+        }
+        let start = gen.here();
+        self.condition.to_bytecode(gen)?;
+        let exit = gen.emit(Instruction::JumpIfFalse(0));
+        let breaks = gen.loop_body(self.label.as_ref(), &self.block)?;
+        gen.discard(&self.increment)?;
+        gen.emit(Instruction::Jump(start));
+        gen.patch(exit);
+        for jump in breaks {
+            gen.patch(jump);
+        }
+        return Ok(());
+    }
+}
+
+impl<'a> ToBytecode<'a> for WhileStmt {
+    fn to_bytecode(&'a self, gen: &mut BytecodeGenerator<'a>) -> Result<()> {
+        let start = gen.here();
+        self.condition.to_bytecode(gen)?;
+        let exit = gen.emit(Instruction::JumpIfFalse(0));
+        let breaks = gen.loop_body(self.label.as_ref(), &self.block)?;
+        gen.emit(Instruction::Jump(start));
+        gen.patch(exit);
+        for jump in breaks {
+            gen.patch(jump);
+        }
+        return Ok(());
+    }
+}
+
+impl<'a> ToBytecode<'a> for DoWhileStmt {
+    fn to_bytecode(&'a self, gen: &mut BytecodeGenerator<'a>) -> Result<()> {
+        let start = gen.here();
+        let breaks = gen.loop_body(self.label.as_ref(), &self.block)?;
+        self.condition.to_bytecode(gen)?;
+        let exit = gen.emit(Instruction::JumpIfFalse(0));
+        gen.emit(Instruction::Jump(start));
+        gen.patch(exit);
+        for jump in breaks {
+            gen.patch(jump);
+        }
+        return Ok(());
+    }
+}
+
+impl<'a> ToBytecode<'a> for IfStmt {
+    fn to_bytecode(&'a self, gen: &mut BytecodeGenerator<'a>) -> Result<()> {
+        let mut exits = Vec::new();
+        let branches = std::iter::once((&self.condition, &self.block)).chain(
+            self.else_ifs
+                .iter()
+                .map(|else_if| (&else_if.condition, &else_if.block)),
+        );
+        for (condition, block) in branches {
+            condition.to_bytecode(gen)?;
+            let next = gen.emit(Instruction::JumpIfFalse(0));
+            block.to_bytecode(gen)?;
+            exits.push(gen.emit(Instruction::Jump(0)));
+            gen.patch(next);
+        }
+        if let Some(else_stmt) = &self.else_stmt {
+            else_stmt.to_bytecode(gen)?;
+        }
+        for exit in exits {
+            gen.patch(exit);
+        }
+        return Ok(());
+    }
+}
+
+impl<'a> ToBytecode<'a> for SwitchStmt {
+    /// Stores the value in a temporary and compares it with every case in
+    /// turn, so nothing stays on the stack while a case runs.
+    fn to_bytecode(&'a self, gen: &mut BytecodeGenerator<'a>) -> Result<()> {
+        let type_specifier = gen.context.expr_type(&self.value)?;
+        self.value.to_bytecode(gen)?;
+        let slot = gen.temp(&type_specifier)?;
+        gen.emit(Instruction::StoreLocal { slot, size: 1 });
+
+        let mut exits = Vec::new();
+        for case in self.cases.iter() {
+            let value = gen.context.case_value(&case.value)?;
+            let constant = match type_specifier {
+                TypeSpecifier::Usize => Constant::Usize(value as u64),
+                TypeSpecifier::Char => Constant::Char(value as u8),
+                _ => Constant::Int(value as i32),
+            };
+            gen.emit(Instruction::LoadLocal { slot, size: 1 });
+            let id = gen.constant(constant);
+            gen.emit(Instruction::Const(id));
+            gen.emit(Instruction::Equal);
+            let next = gen.emit(Instruction::JumpIfFalse(0));
+            case.block.to_bytecode(gen)?;
+            exits.push(gen.emit(Instruction::Jump(0)));
+            gen.patch(next);
+        }
+        if let Some(default) = &self.default {
+            default.to_bytecode(gen)?;
+        }
+        for exit in exits {
+            gen.patch(exit);
+        }
+        return Ok(());
+    }
+}
+
+impl<'a> ToBytecode<'a> for MatchStmt {
+    /// Stores the value in a temporary and compares its tag with the item of
+    /// every arm in turn. An arm copies the carried values into its bindings.
+    fn to_bytecode(&'a self, gen: &mut BytecodeGenerator<'a>) -> Result<()> {
+        let type_specifier = gen.context.expr_type(&self.value)?;
+        let enum_name = TypeContext::struct_name(&type_specifier)?.to_owned();
+        let size = gen.size_of(&type_specifier)?;
+        self.value.to_bytecode(gen)?;
+        let slot = gen.temp(&type_specifier)?;
+        gen.emit(Instruction::StoreLocal { slot, size });
+        let mut tag = slot;
+        if gen.context.is_tagged(&enum_name) {
+            tag += gen.field(&enum_name, EnumDeclaration::TAG)?.offset;
+        }
+
+        let mut exits = Vec::new();
+        for arm in self.arms.iter() {
+            let value = gen
+                .context
+                .enum_item(arm.name.as_ref(), arm.item.as_ref())?;
+            gen.emit(Instruction::LoadLocal { slot: tag, size: 1 });
+            let id = gen.constant(Constant::Int(value));
+            gen.emit(Instruction::Const(id));
+            gen.emit(Instruction::Equal);
+            let next = gen.emit(Instruction::JumpIfFalse(0));
+            gen.push_scope();
+            let result = arm.to_bytecode_from(slot, gen);
+            gen.pop_scope();
+            result?;
+            exits.push(gen.emit(Instruction::Jump(0)));
+            gen.patch(next);
+        }
+        if let Some(default) = &self.default {
+            default.to_bytecode(gen)?;
+        }
+        for exit in exits {
+            gen.patch(exit);
+        }
+        return Ok(());
+    }
+}
+
+impl MatchArm {
+    /// Binds the values carried in the tagged enum stored at `slot`, then
+    /// runs the block.
+    fn to_bytecode_from<'a>(&'a self, slot: u32, gen: &mut BytecodeGenerator<'a>) -> Result<()> {
+        let (name, item) = (self.name.as_ref(), self.item.as_ref());
+        let payload = gen.context.enum_payload(name, item)?.to_vec();
+        for (index, binding) in self.bindings.iter().enumerate() {
+            let Some(binding) = binding else {
+                continue;
+            };
+            let field = gen.field(name, &EnumDeclaration::payload_field(item, index))?;
+            let (offset, size) = (field.offset, field.size);
+            gen.emit(Instruction::LoadLocal {
+                slot: slot + offset,
+                size,
+            });
+            let binding_slot = gen.declare(binding.as_ref(), &payload[index])?;
+            gen.emit(Instruction::StoreLocal {
+                slot: binding_slot,
+                size,
+            });
+        }
+        return self.block.to_bytecode(gen);
+    }
+}
@@ -0,0 +1,185 @@
+use crate::{
+    ast::{
+        declarations::{
+            enum_decl::EnumDeclaration,
+            fn_decl::FnDeclaration,
+            impl_decl::{ImplDeclaration, MemberFunction},
+            struct_decl::StructDeclaration,
+        },
+        statements::block_stmt::BlockStmt,
+        type_specifier::TypeSpecifier,
+        variable_type::VariableType,
+    },
+    error::{CompilerError, Result},
+};
+
+use super::{CGenerator, ToC};
+
+impl ToC for EnumDeclaration {
+    fn to_c(&self, _gen: &mut CGenerator) -> Result<String> {
+        let name = CGenerator::ident(self.name.as_ref());
+        let items: Vec<String> = self
+            .fields
+            .iter()
+            .map(|item| {
+                let item_name = CGenerator::enum_item_name(&name, item.identifier.as_ref());
+                return format!("{} = {}", item_name, item.discriminant);
+            })
+            .collect();
+        return Ok(format!(
+            "typedef enum {} {{ {} }} {};\n",
+            name,
+            items.join(", "),
+            name
+        ));
+    }
+}
+
+impl ToC for StructDeclaration {
+    fn to_c(&self, gen: &mut CGenerator) -> Result<String> {
+        let mut content = format!("struct {} {{\n", CGenerator::ident(self.name.as_ref()));
+        gen.indented(|gen| -> Result<()> {
+            for field in self.fields.iter() {
+                let field = &field.field;
+                let name = CGenerator::ident(field.identifier.as_ref());
+                let declarator = CGenerator::declarator(&field.type_specifier, &name)?;
+                content += &gen.line(&format!("{};", declarator));
+            }
+            return Ok(());
+        })?;
+        content += "};\n";
+        return Ok(content);
+    }
+}
+
+impl ToC for FnDeclaration {
+    fn to_c(&self, gen: &mut CGenerator) -> Result<String> {
+        gen.is_main = self.fn_name.as_ref() == "main";
+        let header = self.prototype()?;
+        let params: Vec<&VariableType> = self.params.iter().collect();
+        let content = gen.function(&header, &params, &self.return_type, &self.body);
+        gen.is_main = false;
+        return content;
+    }
+}
+
+impl FnDeclaration {
+    pub(crate) fn prototype(&self) -> Result<String> {
+        if self.fn_name.as_ref() == "main" {
+            if !self.params.is_empty() {
+                return Err(CompilerError::CodeGen(String::from(
+                    "main function cannot take parameters",
+                )));
+            }
+            return Ok(String::from("int main(void)"));
+        }
+        let params: Vec<&VariableType> = self.params.iter().collect();
+        return CGenerator::prototype(
+            &CGenerator::ident(self.fn_name.as_ref()),
+            &params,
+            &self.return_type,
+        );
+    }
+}
+
+impl ImplDeclaration {
+    /// Renders every member function as a separate definition.
+    pub(crate) fn to_c(&self, gen: &mut CGenerator) -> Result<Vec<String>> {
+        let struct_name = self.name.to_symbol()?;
+        let mut definitions = Vec::new();
+        for member_fn in self.member_functions.iter() {
+            let header = member_fn.c_prototype(struct_name)?;
+            let return_type = &member_fn.prototype.return_type;
+            definitions.push(gen.function(
+                &header,
+                &member_fn.params(),
+                return_type,
+                &member_fn.body,
+            )?);
+        }
+        return Ok(definitions);
+    }
+
+    pub(crate) fn prototypes(&self) -> Result<String> {
+        let struct_name = self.name.to_symbol()?;
+        let mut content = String::new();
+        for member_fn in self.member_functions.iter() {
+            content += &format!("{};\n", member_fn.c_prototype(struct_name)?);
+        }
+        return Ok(content);
+    }
+}
+
+impl MemberFunction {
+    fn c_prototype(&self, struct_name: &str) -> Result<String> {
+        let name = CGenerator::member_fn_name(struct_name, self.prototype.name.as_ref());
+        return CGenerator::prototype(&name, &self.params(), &self.prototype.return_type);
+    }
+}
+
+impl CGenerator {
+    fn prototype(
+        name: &str,
+        params: &[&VariableType],
+        return_type: &TypeSpecifier,
+    ) -> Result<String> {
+        if let TypeSpecifier::ArrayType { .. } = return_type {
+            return Err(CompilerError::CodeGen(format!(
+                "Function {} cannot return an array in C",
+                name
+            )));
+        }
+        let params = if params.is_empty() {
+            String::from("void")
+        } else {
+            params
+                .iter()
+                .map(|param| {
+                    CGenerator::declarator(&param.type_specifier, &CGenerator::param_name(param))
+                })
+                .collect::<Result<Vec<String>>>()?
+                .join(", ")
+        };
+        return Ok(format!(
+            "{}({})",
+            Self::declarator(return_type, name)?,
+            params
+        ));
+    }
+
+    fn function(
+        &mut self,
+        header: &str,
+        params: &[&VariableType],
+        return_type: &TypeSpecifier,
+        body: &BlockStmt,
+    ) -> Result<String> {
+        self.return_type = return_type.clone();
+        let is_void_main = self.is_main && return_type == &TypeSpecifier::Void;
+
+        self.context.push_scope();
+        for param in params.iter() {
+            self.context
+                .declare(param.identifier.as_ref(), param.type_specifier.clone());
+        }
+        let body = self.indented(|gen| -> Result<String> {
+            let mut content = String::new();
+            for param in params.iter() {
+                if let TypeSpecifier::ArrayType { .. } = param.type_specifier {
+                    let name = CGenerator::ident(param.identifier.as_ref());
+                    let declarator = CGenerator::declarator(&param.type_specifier, &name)?;
+                    let copy = gen.copy_array(&name, &CGenerator::param_name(param));
+                    content += &gen.line(&format!("{}; {};", declarator, copy));
+                }
+            }
+            content += &body.statements_to_c(gen)?;
+            if is_void_main && !body.ends_with_return() {
+                content += &gen.line("return 0;");
+            }
+            return Ok(content);
+        });
+        self.context.pop_scope();
+
+        return Ok(format!("{} {{\n{}}}\n", header, body?));
+    }
+}
@@ -0,0 +1,369 @@
+use crate::{
+    ast::{
+        declarations::enum_decl::EnumDeclaration,
+        expressions::{
+            array_index_expr::ArrayIndexExpression,
+            array_init_expr::ArrayInitExpression,
+            assignment_expr::AssignmentExpression,
+            binary_expr::{BinaryExpression, BinaryOperators},
+            cast_expr::CastExpression,
+            enum_item_expr::EnumItemExpression,
+            fn_call_expr::FnCallExpression,
+            identifier_expression::IdentifierExpression,
+            member_access_expr::MemberAccessExpression,
+            object_init_expr::ObjectInitExpression,
+            primitive_expr::PrimitiveExpression,
+            unary_expr::{UnaryExpression, UnaryExpressionType, UnaryOperators},
+            Expression,
+        },
+        type_specifier::TypeSpecifier,
+    },
+    codegen::type_context::{MemberChain, TypeContext},
+    error::{CompilerError, Result},
+    lexer::token_type::TokenType,
+};
+
+use super::{CGenerator, ToC};
+
+impl ToC for Expression {
+    fn to_c(&self, gen: &mut CGenerator) -> Result<String> {
+        return match self {
+            Expression::Binary(binary_expr) => binary_expr.to_c(gen),
+            Expression::Assignment(assignment_expr) => assignment_expr.to_c(gen),
+            Expression::Unary(unary_expr) => unary_expr.to_c(gen),
+            Expression::ArrayIndex(array_index) => array_index.to_c(gen),
+            Expression::MemberAccess(member_access) => member_access.to_c(gen),
+            Expression::FnCall(fn_call_expr) => fn_call_expr.to_c(gen),
+            Expression::ArrayInit(array_init_expr) => array_init_expr.to_c(gen),
+            Expression::ObjectInit(object_init_expr) => object_init_expr.to_c(gen),
+            Expression::Cast(cast_expr) => cast_expr.to_c(gen),
+            Expression::Identifier(identifier_expr) => identifier_expr.to_c(gen),
+            Expression::EnumItem(enum_item_expr) => enum_item_expr.to_c(gen),
+            Expression::Primitive(primitive_expr) => primitive_expr.to_c(gen),
+        };
+    }
+}
+
+impl ToC for BinaryExpression {
+    fn to_c(&self, gen: &mut CGenerator) -> Result<String> {
+        let left = self.left.to_c(gen)?;
+        let mut right = self.right.to_c(gen)?;
+        if let BinaryOperators::BitLeft | BinaryOperators::BitRight = self.operator {
+            // Shifting by the width or more is undefined in C.
+            let type_specifier = gen.context.expr_type(&self.left)?;
+            right = format!("({} & {})", right, TypeContext::shift_mask(&type_specifier));
+        }
+        return Ok(format!(
+            "({} {} {})",
+            left,
+            self.operator.to_c_operator(),
+            right
+        ));
+    }
+}
+
+impl BinaryOperators {
+    pub fn to_c_operator(&self) -> &'static str {
+        return match self {
+            BinaryOperators::Add => "+",
+            BinaryOperators::Subtract => "-",
+            BinaryOperators::Multiply => "*",
+            BinaryOperators::Divide => "/",
+            BinaryOperators::Modulo => "%",
+            BinaryOperators::Equal => "==",
+            BinaryOperators::NotEqual => "!=",
+            BinaryOperators::And => "&&",
+            BinaryOperators::Or => "||",
+            BinaryOperators::BitAnd => "&",
+            BinaryOperators::BitOr => "|",
+            BinaryOperators::BitXor => "^",
+            BinaryOperators::GreaterThan => ">",
+            BinaryOperators::LessThan => "<",
+            BinaryOperators::GreaterThanOrEqual => ">=",
+            BinaryOperators::LessThanOrEqual => "<=",
+            BinaryOperators::BitLeft => "<<",
+            BinaryOperators::BitRight => ">>",
+        };
+    }
+}
+
+impl ToC for AssignmentExpression {
+    fn to_c(&self, gen: &mut CGenerator) -> Result<String> {
+        let variable = CGenerator::strip(&self.variable.to_c(gen)?);
+        let value = CGenerator::strip(&self.value.to_c(gen)?);
+        return Ok(format!("({} = {})", variable, value));
+    }
+}
+
+impl ToC for UnaryExpression {
+    fn to_c(&self, gen: &mut CGenerator) -> Result<String> {
+        let value = self.value.to_c(gen)?;
+        let operator = match self.operator {
+            UnaryOperators::Plus => "+",
+            UnaryOperators::Minus => "-",
+            UnaryOperators::Increment => "++",
+            UnaryOperators::Decrement => "--",
+            UnaryOperators::LogicalNot => "!",
+            UnaryOperators::BitwiseNot => "~",
+            UnaryOperators::Address => "&",
+            UnaryOperators::Indirection => "*",
+        };
+        return Ok(match self.expression_type {
+            UnaryExpressionType::Prefix => format!("({}{})", operator, value),
+            UnaryExpressionType::Postfix => format!("({}{})", value, operator),
+        });
+    }
+}
+
+impl ToC for ArrayIndexExpression {
+    fn to_c(&self, gen: &mut CGenerator) -> Result<String> {
+        let array = self.array.to_c(gen)?;
+        let index = CGenerator::strip(&self.index.to_c(gen)?);
+        let mut content = format!("{}[{}]", array, index);
+        if let Some(second_index) = &self.second_index {
+            content += &format!("[{}]", CGenerator::strip(&second_index.to_c(gen)?));
+        }
+        return Ok(content);
+    }
+}
+
+impl ToC for MemberAccessExpression {
+    fn to_c(&self, gen: &mut CGenerator) -> Result<String> {
+        let object = CObject {
+            code: self.object.to_c(gen)?,
+            type_specifier: gen.context.expr_type(&self.object)?,
+            access: self.access.clone(),
+        };
+        return Ok(gen.member(object, &self.property)?.0);
+    }
+}
+
+/// A rendered struct, or pointer to one, whose next member is accessed
+/// with `access`.
+pub(crate) struct CObject {
+    code: String,
+    type_specifier: TypeSpecifier,
+    access: TokenType,
+}
+
+/// Members are rendered with their type.
+impl<'a> MemberChain<'a> for CGenerator {
+    type Object = CObject;
+    type Member = (String, TypeSpecifier);
+
+    fn object_type(&self, object: &CObject) -> Result<TypeSpecifier> {
+        return Ok(object.type_specifier.clone());
+    }
+
+    fn field_member(
+        &mut self,
+        object: CObject,
+        struct_name: &str,
+        name: &str,
+    ) -> Result<(String, TypeSpecifier)> {
+        let field = self.context.field(struct_name, name)?.clone();
+        let separator = if object.access == TokenType::Arrow {
+            "->"
+        } else {
+            "."
+        };
+        let code = format!("{}{}{}", object.code, separator, CGenerator::ident(name));
+        return Ok((code, field));
+    }
+
+    /// Calls a member function, passing the object when it takes a receiver.
+    fn method_member(
+        &mut self,
+        object: CObject,
+        struct_name: &str,
+        fn_call: &'a FnCallExpression,
+    ) -> Result<(String, TypeSpecifier)> {
+        let name = fn_call.fn_name.identifier()?.id.to_string();
+        let signature = self.context.method(struct_name, &name)?.clone();
+
+        let mut args = self.args(&fn_call.args)?;
+        if let Some(receiver) = &signature.receiver {
+            let is_pointer = object.access == TokenType::Arrow;
+            let receiver = match (receiver, is_pointer) {
+                (TypeSpecifier::Pointer(_), true) => object.code,
+                (TypeSpecifier::Pointer(_), false) => format!("&{}", object.code),
+                (_, true) => format!("(*{})", object.code),
+                (_, false) => object.code,
+            };
+            args.insert(0, receiver);
+        }
+        let code = format!(
+            "{}({})",
+            CGenerator::member_fn_name(struct_name, &name),
+            args.join(", ")
+        );
+        return Ok((code, signature.return_type));
+    }
+
+    fn member_object(
+        &mut self,
+        (code, type_specifier): (String, TypeSpecifier),
+        access: &TokenType,
+    ) -> Result<CObject> {
+        return Ok(CObject {
+            code,
+            type_specifier,
+            access: access.clone(),
+        });
+    }
+}
+
+impl CGenerator {
+    fn args(&mut self, args: &[Expression]) -> Result<Vec<String>> {
+        return args
+            .iter()
+            .map(|arg| Ok(CGenerator::strip(&arg.to_c(self)?)))
+            .collect();
+    }
+
+    /// Removes one pair of parentheses wrapping the whole expression.
+    pub(crate) fn strip(expr: &str) -> String {
+        if !expr.starts_with('(') || !expr.ends_with(')') {
+            return expr.to_owned();
+        }
+        let mut depth = 0;
+        for (i, ch) in expr.char_indices() {
+            match ch {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                _ => {}
+            }
+            if depth == 0 && i != expr.len() - 1 {
+                return expr.to_owned();
+            }
+        }
+        return expr[1..expr.len() - 1].to_owned();
+    }
+}
+
+impl ToC for FnCallExpression {
+    fn to_c(&self, gen: &mut CGenerator) -> Result<String> {
+        let name = self.fn_name.identifier()?.id.to_string();
+        let args = gen.args(&self.args)?;
+        let name = match &self.namespace {
+            Some(namespace) => {
+                let struct_name = namespace.name.identifier()?;
+                CGenerator::member_fn_name(struct_name.id.as_ref(), &name)
+            }
+            None => CGenerator::ident(&name),
+        };
+        return Ok(format!("{}({})", name, args.join(", ")));
+    }
+}
+
+impl ToC for ArrayInitExpression {
+    fn to_c(&self, gen: &mut CGenerator) -> Result<String> {
+        let element = match self.array.first() {
+            Some(first) => gen.context.expr_type(first)?,
+            None => {
+                return Err(CompilerError::CodeGen(String::from(
+                    "Empty array init expression",
+                )))
+            }
+        };
+        let array_type = TypeSpecifier::array(element, self.size);
+        let items = self
+            .array
+            .iter()
+            .map(|item| gen.initializer(item))
+            .collect::<Result<Vec<String>>>()?;
+        return Ok(format!(
+            "(({}){{{}}})",
+            CGenerator::type_name(&array_type)?,
+            items.join(", ")
+        ));
+    }
+}
+
+impl ToC for ObjectInitExpression {
+    fn to_c(&self, gen: &mut CGenerator) -> Result<String> {
+        let name = self.object.identifier()?.id.to_string();
+        let fields = self
+            .fields
+            .iter()
+            .map(|field| {
+                let value = gen.initializer(&field.value)?;
+                return Ok(format!(
+                    ".{} = {}",
+                    CGenerator::ident(field.name.as_ref()),
+                    value
+                ));
+            })
+            .collect::<Result<Vec<String>>>()?;
+        return Ok(format!(
+            "(({}){{{}}})",
+            CGenerator::ident(&name),
+            fields.join(", ")
+        ));
+    }
+}
+
+impl ToC for CastExpression {
+    fn to_c(&self, gen: &mut CGenerator) -> Result<String> {
+        let expr = self.expression.to_c(gen)?;
+        if let TypeSpecifier::UserDefine(name) = &self.target_type {
+            if gen.context.is_enum(name.as_ref()) {
+                gen.checked_enums.insert(name.to_string());
+                return Ok(format!(
+                    "{}({})",
+                    CGenerator::enum_check_name(name.as_ref()),
+                    expr
+                ));
+            }
+        }
+        return Ok(format!(
+            "(({}){})",
+            CGenerator::type_name(&self.target_type)?,
+            expr
+        ));
+    }
+}
+
+impl ToC for IdentifierExpression {
+    fn to_c(&self, _gen: &mut CGenerator) -> Result<String> {
+        return Ok(CGenerator::ident(self.id.as_ref()));
+    }
+}
+
+impl ToC for EnumItemExpression {
+    /// An item of a tagged enum is a compound literal of its layout.
+    fn to_c(&self, gen: &mut CGenerator) -> Result<String> {
+        let (name, item) = (self.name.as_ref(), self.item.as_ref());
+        if !gen.context.is_tagged(name) {
+            return Ok(CGenerator::enum_item_name(name, item));
+        }
+        let mut fields = vec![format!(
+            ".{} = {}",
+            EnumDeclaration::TAG,
+            gen.context.enum_item(name, item)?
+        )];
+        for (index, arg) in self.args.iter().enumerate() {
+            let field = CGenerator::ident(&EnumDeclaration::payload_field(item, index));
+            fields.push(format!(".{} = {}", field, gen.initializer(arg)?));
+        }
+        return Ok(format!(
+            "(({}){{{}}})",
+            CGenerator::ident(name),
+            fields.join(", ")
+        ));
+    }
+}
+
+impl ToC for PrimitiveExpression {
+    fn to_c(&self, _gen: &mut CGenerator) -> Result<String> {
+        return Ok(match self.type_specifier {
+            TypeSpecifier::Char => format!("'{}'", self.value),
+            TypeSpecifier::Str => format!("\"{}\"", self.value),
+            TypeSpecifier::Int => match self.value.parse::<i64>() {
+                Ok(value) => value.to_string(),
+                Err(_) => self.value.clone(),
+            },
+            _ => self.value.clone(),
+        });
+    }
+}
@@ -0,0 +1,303 @@
+pub mod declarations;
+pub mod expressions;
+pub mod program;
+pub mod statements;
+
+use std::collections::BTreeSet;
+
+use crate::{
+    ast::{
+        identifier::Identifier, program::ProgramRef, statements::block_stmt::BlockStmt,
+        type_specifier::TypeSpecifier, variable_type::VariableType,
+    },
+    error::{CompilerError, Result},
+};
+
+use super::{
+    loops::{Defers, Jump, Loops},
+    type_context::TypeContext,
+};
+
+const INDENT: &str = "    ";
+
+/// C keywords and names from the included headers that are valid Marble identifiers.
+const C_RESERVED: [&str; 21] = [
+    "auto", "extern", "goto", "inline", "long", "register", "restrict", "short", "signed",
+    "typedef", "union", "unsigned", "volatile", "NULL", "int32_t", "uint8_t", "size_t", "mb_ret",
+    "mb_match", "abort", "memcpy",
+];
+
+/// Lowers an analyzed program to a single C99 translation unit.
+///
+/// Functions keep their names, member functions become `Struct_name` and
+/// enum items become `Enum_Item`. An `int as Enum` calls `mb_to_Enum`,
+/// which aborts unless the value belongs to an item. Arrays are values:
+/// a variable initialized from another array and an array parameter,
+/// which C passes as a pointer, are copies made with `memcpy`. `defer`
+/// expressions are block scoped: they run in reverse order when their block
+/// ends or a `return`, `break` or `continue` leaves it. Jumps to a labeled
+/// loop become `goto`s.
+pub struct CGenerator {
+    program: ProgramRef,
+    pub(crate) context: TypeContext,
+    pub(crate) indent: usize,
+    pub(crate) defers: Defers<String>,
+    pub(crate) loops: Loops<LoopLabels>,
+    next_loop: usize,
+    pub(crate) return_type: TypeSpecifier,
+    pub(crate) is_main: bool,
+    /// The enums an `int` is cast to, which need a checking function.
+    pub(crate) checked_enums: BTreeSet<String>,
+    /// Whether an array is copied, which needs `memcpy`.
+    pub(crate) copies_arrays: bool,
+}
+
+/// The `goto` labels after the body and after the end of a loop, named by
+/// `id`, which are only written when a labeled `continue` or `break` uses
+/// them. A `break` inside the C `switch`es of the body also needs the label,
+/// since a plain one would only leave the switch.
+#[derive(Debug)]
+pub(crate) struct LoopLabels {
+    id: usize,
+    continued: bool,
+    broken: bool,
+    switches: usize,
+}
+
+pub trait ToC {
+    fn to_c(&self, gen: &mut CGenerator) -> Result<String>;
+}
+
+impl CGenerator {
+    pub fn new(program: ProgramRef) -> Self {
+        let context = TypeContext::new(&program.borrow());
+        Self {
+            program,
+            context,
+            indent: 0,
+            defers: Defers::default(),
+            loops: Loops::default(),
+            next_loop: 0,
+            return_type: TypeSpecifier::Void,
+            is_main: false,
+            checked_enums: BTreeSet::new(),
+            copies_arrays: false,
+        }
+    }
+
+    pub fn generate(&mut self) -> Result<String> {
+        let program = self.program.clone();
+        return program.borrow().to_c(self);
+    }
+
+    pub(crate) fn line(&self, content: &str) -> String {
+        return format!("{}{}\n", INDENT.repeat(self.indent), content);
+    }
+
+    /// Runs `callback` one indentation level deeper.
+    pub(crate) fn indented<T>(&mut self, callback: impl FnOnce(&mut Self) -> T) -> T {
+        self.indent += 1;
+        let result = callback(self);
+        self.indent -= 1;
+        return result;
+    }
+
+    /// Renders the copy of the array `source` into the array variable `dest`.
+    pub(crate) fn copy_array(&mut self, dest: &str, source: &str) -> String {
+        self.copies_arrays = true;
+        return format!("memcpy({}, {}, sizeof({}))", dest, source, dest);
+    }
+
+    /// The C name of a parameter. An array parameter is only a pointer to
+    /// the argument, so its name is left to the copy the body makes.
+    pub(crate) fn param_name(param: &VariableType) -> String {
+        let name = Self::ident(param.identifier.as_ref());
+        if let TypeSpecifier::ArrayType { .. } = param.type_specifier {
+            return format!("mb_arg_{}", name);
+        }
+        return name;
+    }
+
+    /// Renders the deferred expressions `defers` as statements.
+    pub(crate) fn deferred(&self, defers: &[String]) -> String {
+        return defers
+            .iter()
+            .map(|expr| self.line(&format!("{};", expr)))
+            .collect();
+    }
+
+    /// Renders the body of a loop labeled `label`, and the statement that
+    /// follows the loop.
+    pub(crate) fn loop_body(
+        &mut self,
+        label: Option<&Identifier>,
+        block: &BlockStmt,
+    ) -> Result<(String, String)> {
+        let labels = LoopLabels {
+            id: self.next_loop,
+            continued: false,
+            broken: false,
+            switches: 0,
+        };
+        self.next_loop += 1;
+        self.loops.push(label, self.defers.depth(), labels);
+        let body = self.indented(|gen| block.statements_to_c(gen));
+        let labels = self.loops.pop()?.targets;
+        let mut body = body?;
+        if labels.continued {
+            body += &self.indented(|gen| gen.line(&format!("mb_continue_{}:;", labels.id)));
+        }
+        let after = match labels.broken {
+            true => self.line(&format!("mb_break_{}:;", labels.id)),
+            false => String::new(),
+        };
+        return Ok((format!("{{\n{}{}", body, self.line("}").trim_end()), after));
+    }
+
+    /// Renders the cases of a `switch`, counting it as a switch of the
+    /// innermost loop while they are rendered.
+    pub(crate) fn switch_body(
+        &mut self,
+        render: impl FnOnce(&mut Self) -> Result<String>,
+    ) -> Result<String> {
+        if let Ok(target) = self.loops.target(None) {
+            target.targets.switches += 1;
+        }
+        let body = self.indented(render);
+        if let Ok(target) = self.loops.target(None) {
+            target.targets.switches -= 1;
+        }
+        return body;
+    }
+
+    /// Renders a `break` or `continue` to the loop labeled `label`, after
+    /// the deferred expressions of the blocks it leaves.
+    pub(crate) fn leave_loop(&mut self, label: Option<&Identifier>, jump: Jump) -> Result<String> {
+        let target = self.loops.target(label)?;
+        let defers = self.defers.leaving(target);
+        let jump = match (label, jump) {
+            (None, Jump::Break) if target.targets.switches == 0 => String::from("break;"),
+            (None, Jump::Continue) => String::from("continue;"),
+            (_, Jump::Break) => {
+                target.targets.broken = true;
+                format!("goto mb_break_{};", target.targets.id)
+            }
+            (Some(_), Jump::Continue) => {
+                target.targets.continued = true;
+                format!("goto mb_continue_{};", target.targets.id)
+            }
+        };
+        if defers.is_empty() {
+            return Ok(self.line(&jump));
+        }
+        let mut content = self.line("{");
+        self.indented(|gen| {
+            content += &gen.deferred(&defers);
+            content += &gen.line(&jump);
+        });
+        content += &self.line("}");
+        return Ok(content);
+    }
+
+    /// Builds a C declarator such as `int32_t (*name)[3]` for `type_specifier`.
+    pub fn declarator(type_specifier: &TypeSpecifier, name: &str) -> Result<String> {
+        return Ok(match type_specifier {
+            TypeSpecifier::Pointer(inner) => {
+                if let TypeSpecifier::ArrayType { .. } = inner.as_ref() {
+                    Self::declarator(inner, &format!("(*{})", name))?
+                } else {
+                    Self::declarator(inner, &format!("*{}", name))?
+                }
+            }
+            TypeSpecifier::ArrayType {
+                type_specifier,
+                size,
+                ..
+            } => Self::declarator(type_specifier, &format!("{}[{}]", name, size))?,
+            primitive => {
+                let base = Self::base_type(primitive)?;
+                format!("{} {}", base, name).trim_end().to_owned()
+            }
+        });
+    }
+
+    /// The spelling of a type without a declarator name, e.g. for casts.
+    pub fn type_name(type_specifier: &TypeSpecifier) -> Result<String> {
+        return Self::declarator(type_specifier, "");
+    }
+
+    fn base_type(type_specifier: &TypeSpecifier) -> Result<String> {
+        return Ok(match type_specifier {
+            TypeSpecifier::Int => "int32_t".to_owned(),
+            TypeSpecifier::Usize => "size_t".to_owned(),
+            TypeSpecifier::Float => "float".to_owned(),
+            TypeSpecifier::Double => "double".to_owned(),
+            TypeSpecifier::Char => "uint8_t".to_owned(),
+            TypeSpecifier::Str => "const char*".to_owned(),
+            TypeSpecifier::Bool => "bool".to_owned(),
+            TypeSpecifier::Void => "void".to_owned(),
+            TypeSpecifier::UserDefine(identifier) => Self::ident(identifier.as_ref()),
+            _ => {
+                return Err(CompilerError::CodeGen(String::from(
+                    "Complex type has no base type",
+                )))
+            }
+        });
+    }
+
+    /// Escapes Marble identifiers that collide with C keywords or macros.
+    pub fn ident(name: &str) -> String {
+        if C_RESERVED.contains(&name) {
+            return format!("{}_", name);
+        }
+        return name.to_owned();
+    }
+
+    pub fn member_fn_name(struct_name: &str, name: &str) -> String {
+        return format!("{}_{}", struct_name, name);
+    }
+
+    pub fn enum_item_name(enum_name: &str, item: &str) -> String {
+        return format!("{}_{}", enum_name, item);
+    }
+
+    pub fn enum_check_name(enum_name: &str) -> String {
+        return format!("mb_to_{}", enum_name);
+    }
+
+    /// The function behind `int as Enum`, which returns the value if an
+    /// item of the enum is stored as it and aborts otherwise.
+    pub(crate) fn enum_check(&self, enum_name: &str) -> Result<(String, String)> {
+        let prototype = format!(
+            "static {} {}({} value)",
+            Self::ident(enum_name),
+            Self::enum_check_name(enum_name),
+            Self::type_name(&TypeSpecifier::Int)?
+        );
+        let mut definition = format!("{} {{\n{}switch (value) {{\n", prototype, INDENT);
+        for value in self.context.enum_values(enum_name)? {
+            definition += &format!("{}case {}:\n", INDENT, value);
+        }
+        definition += &format!(
+            "{}{}return ({})value;\n{}default:\n{}{}abort();\n{}}}\n}}\n",
+            INDENT,
+            INDENT,
+            Self::ident(enum_name),
+            INDENT,
+            INDENT,
+            INDENT,
+            INDENT
+        );
+        return Ok((prototype, definition));
+    }
+
+    /// Default value for variables declared without an initializer.
+    pub(crate) fn zero_value(&self, type_specifier: &TypeSpecifier) -> &'static str {
+        return match type_specifier {
+            TypeSpecifier::ArrayType { .. } => "{0}",
+            TypeSpecifier::UserDefine(name) if self.context.is_struct(name.as_ref()) => "{0}",
+            TypeSpecifier::Pointer(_) | TypeSpecifier::Str => "NULL",
+            _ => "0",
+        };
+    }
+}
@@ -0,0 +1,152 @@
+use std::collections::HashSet;
+
+use crate::{
+    ast::{
+        declarations::{enum_decl::EnumDeclaration, struct_decl::StructDeclaration, Declarations},
+        program::Program,
+        type_specifier::TypeSpecifier,
+    },
+    error::{CompilerError, Result},
+};
+
+use super::{CGenerator, ToC};
+
+impl ToC for Program {
+    fn to_c(&self, gen: &mut CGenerator) -> Result<String> {
+        let mut content = String::from("/* Generated by the Marble compiler. */\n");
+        content += "#include <stdbool.h>\n#include <stddef.h>\n#include <stdint.h>\n\n";
+
+        let mut types = String::new();
+        let mut sections = Vec::new();
+        let mut prototypes = String::new();
+        let mut definitions = Vec::new();
+
+        for decl in self.declarations.iter() {
+            match decl {
+                Declarations::Enum(enum_decl) if enum_decl.layout.is_none() => {
+                    types += &enum_decl.to_c(gen)?
+                }
+                Declarations::Enum(EnumDeclaration {
+                    layout: Some(struct_decl),
+                    ..
+                })
+                | Declarations::Struct(struct_decl) => {
+                    let name = CGenerator::ident(struct_decl.name.as_ref());
+                    types += &format!("typedef struct {} {};\n", name, name);
+                }
+                _ => {}
+            }
+        }
+        sections.push(types);
+
+        for struct_decl in self.sorted_structs()? {
+            sections.push(struct_decl.to_c(gen)?);
+        }
+
+        for decl in self.declarations.iter() {
+            match decl {
+                Declarations::Fn(fn_decl) => {
+                    prototypes += &format!("{};\n", fn_decl.prototype()?);
+                    definitions.push(fn_decl.to_c(gen)?);
+                }
+                Declarations::Impl(impl_decl) => {
+                    prototypes += &impl_decl.prototypes()?;
+                    definitions.append(&mut impl_decl.to_c(gen)?);
+                }
+                _ => {}
+            }
+        }
+        if !gen.checked_enums.is_empty() {
+            prototypes += "void abort(void);\n";
+        }
+        if gen.copies_arrays {
+            prototypes += "void *memcpy(void *dest, const void *src, size_t n);\n";
+        }
+        for name in gen.checked_enums.clone() {
+            let (prototype, definition) = gen.enum_check(&name)?;
+            prototypes += &format!("{};\n", prototype);
+            definitions.push(definition);
+        }
+        sections.push(prototypes);
+        sections.append(&mut definitions);
+
+        let sections: Vec<String> = sections
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect();
+        content += &sections.join("\n");
+        return Ok(content);
+    }
+}
+
+impl Program {
+    /// Orders the structs, the layouts of enums whose items carry values
+    /// included, so every struct is defined after the structs it contains by
+    /// value.
+    pub(crate) fn sorted_structs(&self) -> Result<Vec<&StructDeclaration>> {
+        let structs: Vec<&StructDeclaration> = self
+            .declarations
+            .iter()
+            .filter_map(|decl| match decl {
+                Declarations::Struct(struct_decl) => Some(struct_decl),
+                Declarations::Enum(enum_decl) => enum_decl.layout.as_ref(),
+                _ => None,
+            })
+            .collect();
+
+        let mut sorted = Vec::new();
+        let mut done = HashSet::new();
+        for struct_decl in structs.iter() {
+            Self::visit_struct(
+                struct_decl,
+                &structs,
+                &mut done,
+                &mut Vec::new(),
+                &mut sorted,
+            )?;
+        }
+        return Ok(sorted);
+    }
+
+    fn visit_struct<'a>(
+        struct_decl: &'a StructDeclaration,
+        structs: &[&'a StructDeclaration],
+        done: &mut HashSet<String>,
+        visiting: &mut Vec<String>,
+        sorted: &mut Vec<&'a StructDeclaration>,
+    ) -> Result<()> {
+        let name = struct_decl.name.to_string();
+        if done.contains(&name) {
+            return Ok(());
+        }
+        if visiting.contains(&name) {
+            return Err(CompilerError::CodeGen(format!(
+                "Struct {} contains itself by value",
+                name
+            )));
+        }
+        visiting.push(name.clone());
+        for field in struct_decl.fields.iter() {
+            let Some(dependency) = Self::value_dependency(&field.field.type_specifier) else {
+                continue;
+            };
+            if let Some(dependency) = structs.iter().find(|item| item.name.as_ref() == dependency) {
+                Self::visit_struct(dependency, structs, done, visiting, sorted)?;
+            }
+        }
+        visiting.pop();
+        done.insert(name);
+        sorted.push(struct_decl);
+        return Ok(());
+    }
+
+    fn value_dependency(type_specifier: &TypeSpecifier) -> Option<&str> {
+        return match type_specifier {
+            TypeSpecifier::UserDefine(identifier) => Some(identifier.as_ref()),
+            TypeSpecifier::ArrayType { type_specifier, .. } => {
+                Self::value_dependency(type_specifier)
+            }
+            _ => None,
+        };
+    }
+}
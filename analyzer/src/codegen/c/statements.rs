@@ -0,0 +1,351 @@
+use crate::{
+    ast::{
+        declarations::enum_decl::EnumDeclaration,
+        expressions::Expression,
+        statements::{
+            block_stmt::BlockStmt,
+            break_stmt::BreakStmt,
+            continue_stmt::ContinueStmt,
+            defer_stmt::DeferStmt,
+            do_while_stmt::DoWhileStmt,
+            for_stmt::{ForStmt, VariableInit},
+            if_stmt::IfStmt,
+            let_stmt::LetStmt,
+            match_stmt::{MatchArm, MatchStmt},
+            return_stmt::ReturnStmt,
+            switch_stmt::SwitchStmt,
+            while_stmt::WhileStmt,
+            Statements,
+        },
+        type_specifier::TypeSpecifier,
+    },
+    codegen::{loops::Jump, type_context::TypeContext},
+    error::{CompilerError, Result},
+};
+
+use super::{CGenerator, ToC};
+
+impl ToC for Statements {
+    fn to_c(&self, gen: &mut CGenerator) -> Result<String> {
+        return match self {
+            Statements::Let(let_stmt) => {
+                let declaration = let_stmt.to_c(gen)?;
+                Ok(gen.line(&format!("{};", declaration)))
+            }
+            Statements::Const(_) => Ok(String::new()),
+            Statements::Return(return_stmt) => return_stmt.to_c(gen),
+            Statements::Defer(defer_stmt) => defer_stmt.to_c(gen),
+            Statements::For(for_stmt) => for_stmt.to_c(gen),
+            Statements::While(while_stmt) => while_stmt.to_c(gen),
+            Statements::DoWhile(do_while_stmt) => do_while_stmt.to_c(gen),
+            Statements::If(if_stmt) => if_stmt.to_c(gen),
+            Statements::Switch(switch_stmt) => switch_stmt.to_c(gen),
+            Statements::Match(match_stmt) => match_stmt.to_c(gen),
+            Statements::Break(break_stmt) => break_stmt.to_c(gen),
+            Statements::Continue(continue_stmt) => continue_stmt.to_c(gen),
+            Statements::Block(block_stmt) => {
+                let block = block_stmt.to_c(gen)?;
+                Ok(gen.line(&block))
+            }
+            Statements::Expression(expr) => {
+                let expr = CGenerator::strip(&expr.to_c(gen)?);
+                Ok(gen.line(&format!("{};", expr)))
+            }
+        };
+    }
+}
+
+// Renders `{ ... }` without a trailing newline so it can follow a header.
+impl ToC for BlockStmt {
+    fn to_c(&self, gen: &mut CGenerator) -> Result<String> {
+        let body = gen.indented(|gen| self.statements_to_c(gen))?;
+        return Ok(format!("{{\n{}{}", body, gen.line("}").trim_end()));
+    }
+}
+
+impl BlockStmt {
+    /// Renders the statements of the block followed by its deferred expressions.
+    pub(crate) fn statements_to_c(&self, gen: &mut CGenerator) -> Result<String> {
+        gen.context.push_scope();
+        gen.defers.push_scope();
+        let content = self
+            .stmts
+            .iter()
+            .map(|stmt| stmt.to_c(gen))
+            .collect::<Result<String>>()
+            .map(|content| {
+                if self.ends_with_return() {
+                    return content;
+                }
+                return content + &gen.deferred(&gen.defers.pending(1));
+            });
+        gen.defers.pop_scope();
+        gen.context.pop_scope();
+        return content;
+    }
+}
+
+impl ToC for LetStmt {
+    /// Renders the declaration without the terminating semicolon.
+    fn to_c(&self, gen: &mut CGenerator) -> Result<String> {
+        let type_specifier = self.type_specifier.as_ref().ok_or_else(|| {
+            CompilerError::CodeGen(format!("Type of {} is unknown", self.identifier))
+        })?;
+        let name = CGenerator::ident(self.identifier.as_ref());
+        let declarator = CGenerator::declarator(type_specifier, &name)?;
+
+        if let (TypeSpecifier::ArrayType { .. }, Some(value)) = (type_specifier, &self.value) {
+            if !matches!(value, Expression::ArrayInit(_)) {
+                // C cannot initialize an array from another one.
+                let source = CGenerator::strip(&value.to_c(gen)?);
+                gen.context
+                    .declare(self.identifier.as_ref(), type_specifier.clone());
+                return Ok(format!(
+                    "{}; {}",
+                    declarator,
+                    gen.copy_array(&name, &source)
+                ));
+            }
+        }
+        let value = match &self.value {
+            Some(value) => gen.initializer(value)?,
+            None => gen.zero_value(type_specifier).to_owned(),
+        };
+        gen.context
+            .declare(self.identifier.as_ref(), type_specifier.clone());
+        return Ok(format!("{} = {}", declarator, value));
+    }
+}
+
+impl ToC for ReturnStmt {
+    fn to_c(&self, gen: &mut CGenerator) -> Result<String> {
+        let value = match &self.value {
+            Some(value) => Some(CGenerator::strip(&value.to_c(gen)?)),
+            None if gen.is_main => Some(String::from("0")),
+            None => None,
+        };
+
+        if gen.defers.is_empty() {
+            return Ok(match value {
+                Some(value) => gen.line(&format!("return {};", value)),
+                None => gen.line("return;"),
+            });
+        }
+
+        // The value is computed before the deferred expressions run.
+        let mut content = gen.line("{");
+        gen.indented(|gen| -> Result<()> {
+            let defers = gen.deferred(&gen.defers.all());
+            match value {
+                Some(value) if gen.return_type != TypeSpecifier::Void => {
+                    let declarator = if gen.is_main {
+                        String::from("int mb_ret")
+                    } else {
+                        CGenerator::declarator(&gen.return_type, "mb_ret")?
+                    };
+                    content += &gen.line(&format!("{} = {};", declarator, value));
+                    content += &defers;
+                    content += &gen.line("return mb_ret;");
+                }
+                Some(value) => {
+                    content += &defers;
+                    content += &gen.line(&format!("return {};", value));
+                }
+                None => {
+                    content += &defers;
+                    content += &gen.line("return;");
+                }
+            }
+            return Ok(());
+        })?;
+        content += &gen.line("}");
+        return Ok(content);
+    }
+}
+
+impl ToC for DeferStmt {
+    fn to_c(&self, gen: &mut CGenerator) -> Result<String> {
+        let expr = CGenerator::strip(&self.expr.to_c(gen)?);
+        gen.defers.push(expr);
+        return Ok(String::new());
+    }
+}
+
+impl ToC for BreakStmt {
+    fn to_c(&self, gen: &mut CGenerator) -> Result<String> {
+        return gen.leave_loop(self.label.as_ref(), Jump::Break);
+    }
+}
+
+impl ToC for ContinueStmt {
+    fn to_c(&self, gen: &mut CGenerator) -> Result<String> {
+        return gen.leave_loop(self.label.as_ref(), Jump::Continue);
+    }
+}
+
+impl ToC for ForStmt {
+    fn to_c(&self, gen: &mut CGenerator) -> Result<String> {
+        gen.context.push_scope();
+        let content = self.for_to_c(gen);
+        gen.context.pop_scope();
+        return content;
+    }
+}
+
+impl ForStmt {
+    fn for_to_c(&self, gen: &mut CGenerator) -> Result<String> {
+        let init = match &self.init {
+            VariableInit::Let(let_stmt) => let_stmt.to_c(gen)?,
+            VariableInit::Assign(assign) => CGenerator::strip(&assign.to_c(gen)?),
+        };
+        let condition = CGenerator::strip(&self.condition.to_c(gen)?);
+        let increment = CGenerator::strip(&self.increment.to_c(gen)?);
+        let (block, after) = gen.loop_body(self.label.as_ref(), &self.block)?;
+        let content = gen.line(&format!(
+            "for ({}; {}; {}) {}",
+            init, condition, increment, block
+        ));
+        return Ok(content + &after);
+    }
+}
+
+impl ToC for WhileStmt {
+    fn to_c(&self, gen: &mut CGenerator) -> Result<String> {
+        let condition = CGenerator::strip(&self.condition.to_c(gen)?);
+        let (block, after) = gen.loop_body(self.label.as_ref(), &self.block)?;
+        return Ok(gen.line(&format!("while ({}) {}", condition, block)) + &after);
+    }
+}
+
+impl ToC for DoWhileStmt {
+    fn to_c(&self, gen: &mut CGenerator) -> Result<String> {
+        let (block, after) = gen.loop_body(self.label.as_ref(), &self.block)?;
+        let condition = CGenerator::strip(&self.condition.to_c(gen)?);
+        return Ok(gen.line(&format!("do {} while ({});", block, condition)) + &after);
+    }
+}
+
+impl ToC for IfStmt {
+    fn to_c(&self, gen: &mut CGenerator) -> Result<String> {
+        let condition = CGenerator::strip(&self.condition.to_c(gen)?);
+        let mut content = format!("if ({}) {}", condition, self.block.to_c(gen)?);
+        for else_if in self.else_ifs.iter() {
+            let condition = CGenerator::strip(&else_if.condition.to_c(gen)?);
+            content += &format!(" else if ({}) {}", condition, else_if.block.to_c(gen)?);
+        }
+        if let Some(else_stmt) = &self.else_stmt {
+            content += &format!(" else {}", else_stmt.to_c(gen)?);
+        }
+        return Ok(gen.line(&content));
+    }
+}
+
+impl ToC for SwitchStmt {
+    /// Every case ends with a `break` since cases do not fall through.
+    fn to_c(&self, gen: &mut CGenerator) -> Result<String> {
+        let value = CGenerator::strip(&self.value.to_c(gen)?);
+        let body = gen.switch_body(|gen| {
+            let mut content = String::new();
+            for case in self.cases.iter() {
+                let value = match &case.value {
+                    Expression::EnumItem(enum_item) => enum_item.to_c(gen)?,
+                    value => gen.context.case_value(value)?.to_string(),
+                };
+                let block = case.block.to_c(gen)?;
+                content += &gen.line(&format!("case {}: {} break;", value, block));
+            }
+            if let Some(default) = &self.default {
+                let block = default.to_c(gen)?;
+                content += &gen.line(&format!("default: {} break;", block));
+            }
+            return Ok(content);
+        })?;
+        let content = gen.line(&format!("switch ({}) {{", value));
+        return Ok(content + &body + &gen.line("}"));
+    }
+}
+
+impl ToC for MatchStmt {
+    /// Switches on the tag of a copy of the value named `mb_match`, from
+    /// which each arm copies the carried values into its bindings.
+    fn to_c(&self, gen: &mut CGenerator) -> Result<String> {
+        let value = CGenerator::strip(&self.value.to_c(gen)?);
+        let enum_name = TypeContext::struct_name(&gen.context.expr_type(&self.value)?)?.to_owned();
+        if !gen.context.is_tagged(&enum_name) {
+            let body = gen.switch_body(|gen| self.arms_to_c(gen))?;
+            let content = gen.line(&format!("switch ({}) {{", value));
+            return Ok(content + &body + &gen.line("}"));
+        }
+        let mut content = gen.line("{");
+        content += &gen.indented(|gen| {
+            let name = CGenerator::ident(&enum_name);
+            let declaration = gen.line(&format!("{} mb_match = {};", name, value));
+            let header = gen.line("switch (mb_match.tag) {");
+            let body = gen.switch_body(|gen| self.arms_to_c(gen))?;
+            return Ok::<String, CompilerError>(declaration + &header + &body + &gen.line("}"));
+        })?;
+        return Ok(content + &gen.line("}"));
+    }
+}
+
+impl MatchStmt {
+    fn arms_to_c(&self, gen: &mut CGenerator) -> Result<String> {
+        let mut content = String::new();
+        for arm in self.arms.iter() {
+            content += &arm.to_c(gen)?;
+        }
+        if let Some(default) = &self.default {
+            let block = default.to_c(gen)?;
+            content += &gen.line(&format!("default: {} break;", block));
+        }
+        return Ok(content);
+    }
+}
+
+impl ToC for MatchArm {
+    fn to_c(&self, gen: &mut CGenerator) -> Result<String> {
+        let (name, item) = (self.name.as_ref(), self.item.as_ref());
+        let value = gen.context.enum_item(name, item)?;
+        if self.bindings.iter().all(|binding| binding.is_none()) {
+            let block = self.block.to_c(gen)?;
+            return Ok(gen.line(&format!("case {}: {} break;", value, block)));
+        }
+        let payload = gen.context.enum_payload(name, item)?.to_vec();
+        let content = gen.line(&format!("case {}: {{", value));
+        gen.context.push_scope();
+        let body = gen.indented(|gen| {
+            let mut content = String::new();
+            for (index, binding) in self.bindings.iter().enumerate() {
+                let Some(binding) = binding else {
+                    continue;
+                };
+                let declarator =
+                    CGenerator::declarator(&payload[index], &CGenerator::ident(binding.as_ref()))?;
+                let field = CGenerator::ident(&EnumDeclaration::payload_field(item, index));
+                content += &gen.line(&format!("{} = mb_match.{};", declarator, field));
+                gen.context
+                    .declare(binding.as_ref(), payload[index].clone());
+            }
+            let block = self.block.to_c(gen)?;
+            return Ok::<String, CompilerError>(content + &gen.line(&block));
+        });
+        gen.context.pop_scope();
+        return Ok(content + &body? + &gen.line("} break;"));
+    }
+}
+
+impl CGenerator {
+    /// Array initializers are written as brace lists when they initialize a
+    /// declaration and as compound literals everywhere else.
+    pub(crate) fn initializer(&mut self, expr: &Expression) -> Result<String> {
+        if let Expression::ArrayInit(array_init) = expr {
+            let items = array_init
+                .array
+                .iter()
+                .map(|item| self.initializer(item))
+                .collect::<Result<Vec<String>>>()?;
+            return Ok(format!("{{{}}}", items.join(", ")));
+        }
+        return Ok(CGenerator::strip(&expr.to_c(self)?));
+    }
+}
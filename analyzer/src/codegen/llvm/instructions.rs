@@ -0,0 +1,398 @@
+use crate::{
+    ast::type_specifier::TypeSpecifier,
+    codegen::type_context::TypeContext,
+    error::{CompilerError, Result},
+    ir::{BinaryOp, Function, Instruction, Temp, Terminator, UnaryOp, Value},
+};
+
+use super::LlvmGenerator;
+
+impl LlvmGenerator<'_> {
+    pub(crate) fn instruction_to_llvm(
+        &mut self,
+        function: &Function,
+        instruction: &Instruction,
+    ) -> Result<()> {
+        match instruction {
+            Instruction::Copy { .. } => {}
+            Instruction::Binary {
+                dest,
+                operator,
+                left,
+                right,
+            } => self.binary(function, *dest, *operator, left, right)?,
+            Instruction::Unary {
+                dest,
+                operator,
+                value,
+            } => self.unary(function, *dest, *operator, value)?,
+            Instruction::Cast { dest, value } => self.cast(function, *dest, value)?,
+            Instruction::Alloca { dest } => {
+                let pointee = LlvmGenerator::pointee(&function.temps[dest.0])?;
+                let alloca = format!(
+                    "{} = alloca {}",
+                    LlvmGenerator::temp(*dest),
+                    self.llvm_type(&pointee)?
+                );
+                self.allocas.push(alloca);
+            }
+            Instruction::Load { dest, address } => {
+                let instruction = format!(
+                    "{} = load {}, {}",
+                    LlvmGenerator::temp(*dest),
+                    self.llvm_type(&function.temps[dest.0])?,
+                    self.typed(function, address)?
+                );
+                self.instruction(&instruction);
+            }
+            Instruction::Store { address, value } => {
+                // The stored type is the one of the address: enum items are
+                // `int` constants in the IR.
+                let pointee = LlvmGenerator::pointee(&function.type_of(address))?;
+                let instruction = format!(
+                    "store {} {}, {}",
+                    self.llvm_type(&pointee)?,
+                    self.value(value)?,
+                    self.typed(function, address)?
+                );
+                self.instruction(&instruction);
+            }
+            Instruction::Field {
+                dest,
+                address,
+                field,
+            } => {
+                let pointee = LlvmGenerator::pointee(&function.type_of(address))?;
+                let struct_name = TypeContext::struct_name(&pointee)?;
+                let index = self
+                    .context
+                    .struct_layout(struct_name)?
+                    .fields
+                    .iter()
+                    .position(|(name, _)| name == field)
+                    .ok_or_else(|| {
+                        CompilerError::CodeGen(format!("Unknown field {}.{}", struct_name, field))
+                    })?;
+                let instruction = format!(
+                    "{} = getelementptr inbounds {}, {}, i32 0, i32 {}",
+                    LlvmGenerator::temp(*dest),
+                    LlvmGenerator::struct_type(struct_name),
+                    self.typed(function, address)?,
+                    index
+                );
+                self.instruction(&instruction);
+            }
+            Instruction::Element {
+                dest,
+                address,
+                index,
+            } => {
+                let pointee = LlvmGenerator::pointee(&function.type_of(address))?;
+                let instruction = format!(
+                    "{} = getelementptr inbounds {}, {}, i64 0, {}",
+                    LlvmGenerator::temp(*dest),
+                    self.llvm_type(&pointee)?,
+                    self.typed(function, address)?,
+                    self.typed(function, index)?
+                );
+                self.instruction(&instruction);
+            }
+            Instruction::Offset {
+                dest,
+                pointer,
+                index,
+            } => {
+                let pointee = match LlvmGenerator::pointee(&function.type_of(pointer))? {
+                    TypeSpecifier::Void => String::from("i8"),
+                    pointee => self.llvm_type(&pointee)?,
+                };
+                let instruction = format!(
+                    "{} = getelementptr inbounds {}, {}, {}",
+                    LlvmGenerator::temp(*dest),
+                    pointee,
+                    self.typed(function, pointer)?,
+                    self.typed(function, index)?
+                );
+                self.instruction(&instruction);
+            }
+            Instruction::Call {
+                dest,
+                function: name,
+                args,
+            } => self.call(*dest, name, args)?,
+        }
+        return Ok(());
+    }
+
+    /// Arguments take the types of the parameters, which an `int` enum item
+    /// or a cast alias may not spell the same way.
+    fn call(&mut self, dest: Option<Temp>, name: &str, args: &[Value]) -> Result<()> {
+        let (return_type, params) = self
+            .signatures
+            .get(name)
+            .cloned()
+            .ok_or_else(|| CompilerError::CodeGen(format!("Unknown function {}", name)))?;
+        let mut rendered = Vec::new();
+        for (arg, param) in args.iter().zip(params.iter()) {
+            rendered.push(format!("{} {}", self.llvm_type(param)?, self.value(arg)?));
+        }
+        let call = format!(
+            "call {} @{}({})",
+            self.llvm_type(&return_type)?,
+            name,
+            rendered.join(", ")
+        );
+        match dest {
+            Some(dest) => self.instruction(&format!("{} = {}", LlvmGenerator::temp(dest), call)),
+            None => self.instruction(&call),
+        }
+        return Ok(());
+    }
+
+    fn binary(
+        &mut self,
+        function: &Function,
+        dest: Temp,
+        operator: BinaryOp,
+        left: &Value,
+        right: &Value,
+    ) -> Result<()> {
+        let type_specifier = function.type_of(left);
+        let is_float = Self::is_float(&type_specifier);
+        let is_signed = type_specifier == TypeSpecifier::Int;
+
+        let select = |float: &'static str, signed: &'static str, unsigned: &'static str| {
+            if is_float {
+                return float;
+            }
+            if is_signed {
+                return signed;
+            }
+            return unsigned;
+        };
+        let operation = match operator {
+            BinaryOp::Add => select("fadd", "add", "add"),
+            BinaryOp::Subtract => select("fsub", "sub", "sub"),
+            BinaryOp::Multiply => select("fmul", "mul", "mul"),
+            BinaryOp::Divide => select("fdiv", "sdiv", "udiv"),
+            BinaryOp::Modulo => select("frem", "srem", "urem"),
+            BinaryOp::BitAnd => "and",
+            BinaryOp::BitOr => "or",
+            BinaryOp::BitXor => "xor",
+            BinaryOp::ShiftLeft => "shl",
+            BinaryOp::ShiftRight => select("", "ashr", "lshr"),
+            BinaryOp::Equal => select("oeq", "eq", "eq"),
+            BinaryOp::NotEqual => select("une", "ne", "ne"),
+            BinaryOp::Greater => select("ogt", "sgt", "ugt"),
+            BinaryOp::Less => select("olt", "slt", "ult"),
+            BinaryOp::GreaterEqual => select("oge", "sge", "uge"),
+            BinaryOp::LessEqual => select("ole", "sle", "ule"),
+        };
+        let llvm_type = self.llvm_type(&type_specifier)?;
+        let left = self.value(left)?;
+        let mut right = self.value(right)?;
+        if let BinaryOp::ShiftLeft | BinaryOp::ShiftRight = operator {
+            // Shifting by the width or more is poison in LLVM.
+            let mask = format!("{}.mask", LlvmGenerator::temp(dest));
+            let instruction = format!(
+                "{} = and {} {}, {}",
+                mask,
+                llvm_type,
+                right,
+                TypeContext::shift_mask(&type_specifier)
+            );
+            self.instruction(&instruction);
+            right = mask;
+        }
+        let dest = LlvmGenerator::temp(dest);
+        let instruction = match operator.is_comparison() {
+            true => {
+                let compare = if is_float { "fcmp" } else { "icmp" };
+                format!(
+                    "{} = {} {} {} {}, {}",
+                    dest, compare, operation, llvm_type, left, right
+                )
+            }
+            false => format!("{} = {} {} {}, {}", dest, operation, llvm_type, left, right),
+        };
+        self.instruction(&instruction);
+        return Ok(());
+    }
+
+    fn unary(
+        &mut self,
+        function: &Function,
+        dest: Temp,
+        operator: UnaryOp,
+        value: &Value,
+    ) -> Result<()> {
+        let type_specifier = function.type_of(value);
+        let llvm_type = self.llvm_type(&type_specifier)?;
+        let value = self.value(value)?;
+        let operation = match operator {
+            UnaryOp::Negate if Self::is_float(&type_specifier) => {
+                format!("fneg {} {}", llvm_type, value)
+            }
+            UnaryOp::Negate => format!("sub {} 0, {}", llvm_type, value),
+            UnaryOp::Not => format!("xor {} {}, true", llvm_type, value),
+            UnaryOp::BitNot => format!("xor {} {}, -1", llvm_type, value),
+        };
+        self.instruction(&format!("{} = {}", LlvmGenerator::temp(dest), operation));
+        return Ok(());
+    }
+
+    /// Converts a value with the rules of a C cast. `char`, `usize` and
+    /// `bool` are unsigned. An `int` cast to an enum traps unless an item
+    /// is stored as it.
+    fn cast(&mut self, function: &Function, dest: Temp, value: &Value) -> Result<()> {
+        let source = function.type_of(value);
+        let target = function.temps[dest.0].clone();
+        if !self.aliases.contains_key(&dest) {
+            let instruction = self.convert(function, value, &source, &target)?;
+            self.instruction(&format!("{} = {}", LlvmGenerator::temp(dest), instruction));
+        }
+        match &target {
+            TypeSpecifier::UserDefine(name) if self.context.is_enum(name.as_ref()) => {
+                let value = self.value(&Value::Temp(dest))?;
+                self.check_enum(name.as_ref(), &value, dest)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn convert(
+        &mut self,
+        function: &Function,
+        value: &Value,
+        source: &TypeSpecifier,
+        target: &TypeSpecifier,
+    ) -> Result<String> {
+        let typed = self.typed(function, value)?;
+        let to = self.llvm_type(target)?;
+        if Self::is_pointer(source) && Self::is_pointer(target) {
+            return Ok(format!("bitcast {} to {}", typed, to));
+        }
+        return Ok(match (Self::is_float(source), Self::is_float(target)) {
+            (false, false) if target == &TypeSpecifier::Bool => {
+                format!("icmp ne {}, 0", typed)
+            }
+            (false, false) => {
+                let (from_bits, to_bits) = (Self::bits(source)?, Self::bits(target)?);
+                let operation = if to_bits < from_bits {
+                    "trunc"
+                } else if Self::is_signed(source) {
+                    "sext"
+                } else {
+                    "zext"
+                };
+                format!("{} {} to {}", operation, typed, to)
+            }
+            (true, false) if target == &TypeSpecifier::Bool => {
+                format!("fcmp une {}, 0.0", typed)
+            }
+            (true, false) if Self::is_signed(target) => format!("fptosi {} to {}", typed, to),
+            (true, false) => format!("fptoui {} to {}", typed, to),
+            (false, true) if Self::is_signed(source) => format!("sitofp {} to {}", typed, to),
+            (false, true) => format!("uitofp {} to {}", typed, to),
+            (true, true) if target == &TypeSpecifier::Double => {
+                format!("fpext {} to {}", typed, to)
+            }
+            (true, true) => format!("fptrunc {} to {}", typed, to),
+        });
+    }
+
+    /// Traps unless `value` is the number of an item of the enum `name`.
+    /// The check splits the IR block, the rest of it continues in a new
+    /// LLVM block.
+    fn check_enum(&mut self, name: &str, value: &str, dest: Temp) -> Result<()> {
+        let valid = format!("cast.valid.{}", dest.0);
+        let invalid = format!("cast.invalid.{}", dest.0);
+        let cases: Vec<String> = self
+            .context
+            .enum_values(name)?
+            .iter()
+            .map(|item| format!("i32 {}, label %{}", item, valid))
+            .collect();
+        self.instruction(&format!(
+            "switch i32 {}, label %{} [ {} ]",
+            value,
+            invalid,
+            cases.join(" ")
+        ));
+        self.start_block(&invalid);
+        self.trap();
+        self.start_block(&valid);
+        return Ok(());
+    }
+
+    fn is_float(type_specifier: &TypeSpecifier) -> bool {
+        return matches!(type_specifier, TypeSpecifier::Float | TypeSpecifier::Double);
+    }
+
+    fn is_pointer(type_specifier: &TypeSpecifier) -> bool {
+        return matches!(
+            type_specifier,
+            TypeSpecifier::Pointer(_) | TypeSpecifier::Str
+        );
+    }
+
+    fn is_signed(type_specifier: &TypeSpecifier) -> bool {
+        return matches!(
+            type_specifier,
+            TypeSpecifier::Int | TypeSpecifier::UserDefine(_)
+        );
+    }
+
+    fn bits(type_specifier: &TypeSpecifier) -> Result<u32> {
+        return Ok(match type_specifier {
+            TypeSpecifier::Bool => 1,
+            TypeSpecifier::Char => 8,
+            TypeSpecifier::Int | TypeSpecifier::UserDefine(_) => 32,
+            TypeSpecifier::Usize => 64,
+            _ => {
+                return Err(CompilerError::CodeGen(String::from(
+                    "Cannot cast the complex type",
+                )))
+            }
+        });
+    }
+
+    pub(crate) fn terminator_to_llvm(
+        &mut self,
+        function: &Function,
+        terminator: &Terminator,
+    ) -> Result<()> {
+        let instruction = match terminator {
+            Terminator::Jump(target) => format!("br label %{}", target),
+            Terminator::Branch {
+                then, otherwise, ..
+            } if then == otherwise => format!("br label %{}", then),
+            Terminator::Branch {
+                condition,
+                then,
+                otherwise,
+            } => format!(
+                "br i1 {}, label %{}, label %{}",
+                self.value(condition)?,
+                then,
+                otherwise
+            ),
+            Terminator::Return(_)
+                if function.name == "main" && function.return_type == TypeSpecifier::Void =>
+            {
+                String::from("ret i32 0")
+            }
+            Terminator::Return(Some(value)) if function.return_type != TypeSpecifier::Void => {
+                format!(
+                    "ret {} {}",
+                    self.llvm_type(&function.return_type)?,
+                    self.value(value)?
+                )
+            }
+            Terminator::Return(_) => String::from("ret void"),
+            Terminator::Unreachable => String::from("unreachable"),
+        };
+        self.instruction(&instruction);
+        return Ok(());
+    }
+}
@@ -0,0 +1,240 @@
+pub mod instructions;
+pub mod program;
+
+use std::collections::HashMap;
+
+use crate::{
+    ast::{program::Program, type_specifier::TypeSpecifier},
+    error::{CompilerError, Result},
+    ir::{lower::IrBuilder, opt::PassManager, Constant, Function, Temp, Value},
+};
+
+use super::type_context::TypeContext;
+
+const INDENT: &str = "  ";
+
+/// Renders an analyzed program as a textual LLVM IR module (`.ll`).
+///
+/// The program is lowered to the IR [`Module`](crate::ir::Module) first and
+/// optionally optimized, so every temporary becomes an SSA register `%tN`
+/// and every IR block a label `bbN`. The IR uses typed pointers as
+/// understood by LLVM 14. Allocas are hoisted to an `entry` block, member
+/// functions become `@Struct.name` and enums are lowered to `i32`.
+pub struct LlvmGenerator<'a> {
+    program: &'a Program,
+    passes: Option<&'a PassManager>,
+    pub(crate) context: TypeContext,
+    strings: Vec<Vec<u8>>,
+    /// The return and parameter types of every function, by name.
+    signatures: HashMap<String, (TypeSpecifier, Vec<TypeSpecifier>)>,
+    /// Temporaries that need no instruction of their own, like copies and
+    /// casts between types LLVM does not tell apart, with the value they
+    /// are rendered as.
+    aliases: HashMap<Temp, Value>,
+    allocas: Vec<String>,
+    body: Vec<String>,
+    /// The label of the LLVM block instructions currently go to, which is
+    /// not the one of the IR block after a checked cast split it.
+    block: String,
+    /// Whether a checked cast calls `@llvm.trap`, which must be declared.
+    traps: bool,
+}
+
+impl<'a> LlvmGenerator<'a> {
+    pub fn new(program: &'a Program) -> Self {
+        Self {
+            program,
+            passes: None,
+            context: TypeContext::new(program),
+            strings: Vec::new(),
+            signatures: HashMap::new(),
+            aliases: HashMap::new(),
+            allocas: Vec::new(),
+            body: Vec::new(),
+            block: String::new(),
+            traps: false,
+        }
+    }
+
+    /// Runs `passes` on the IR before it is rendered.
+    pub fn with_passes(mut self, passes: &'a PassManager) -> Self {
+        self.passes = Some(passes);
+        return self;
+    }
+
+    pub fn generate(mut self) -> Result<String> {
+        let mut module = IrBuilder::new(self.program).generate()?;
+        if let Some(passes) = self.passes {
+            passes.run(&mut module);
+        }
+
+        let mut content = String::from("; Generated by the Marble compiler.\n\n");
+        let functions = self.functions(&module)?;
+
+        let types = self.types(&module)?;
+        if !types.is_empty() {
+            content += &types;
+            content += "\n";
+        }
+        if !self.strings.is_empty() {
+            for (id, bytes) in self.strings.iter().enumerate() {
+                content += &format!(
+                    "@.str.{} = private unnamed_addr constant [{} x i8] c\"{}\\00\"\n",
+                    id,
+                    bytes.len() + 1,
+                    Self::escape(bytes)
+                );
+            }
+            content += "\n";
+        }
+        content += &functions.join("\n");
+        if self.traps {
+            content += "\ndeclare void @llvm.trap()\n";
+        }
+        return Ok(content);
+    }
+
+    /// The LLVM spelling of `type_specifier`.
+    pub(crate) fn llvm_type(&self, type_specifier: &TypeSpecifier) -> Result<String> {
+        return Ok(match type_specifier {
+            TypeSpecifier::Int => "i32".to_owned(),
+            TypeSpecifier::Usize => "i64".to_owned(),
+            TypeSpecifier::Float => "float".to_owned(),
+            TypeSpecifier::Double => "double".to_owned(),
+            TypeSpecifier::Char => "i8".to_owned(),
+            TypeSpecifier::Bool => "i1".to_owned(),
+            TypeSpecifier::Str => "i8*".to_owned(),
+            TypeSpecifier::Void => "void".to_owned(),
+            TypeSpecifier::UserDefine(name) if self.context.is_struct(name.as_ref()) => {
+                Self::struct_type(name.as_ref())
+            }
+            TypeSpecifier::UserDefine(name) if self.context.is_enum(name.as_ref()) => {
+                "i32".to_owned()
+            }
+            TypeSpecifier::UserDefine(name) => {
+                return Err(CompilerError::CodeGen(format!("Unknown type {}", name)))
+            }
+            TypeSpecifier::Pointer(inner) if **inner == TypeSpecifier::Void => "i8*".to_owned(),
+            TypeSpecifier::Pointer(inner) => format!("{}*", self.llvm_type(inner)?),
+            TypeSpecifier::ArrayType {
+                type_specifier,
+                size,
+                ..
+            } => format!("[{} x {}]", size, self.llvm_type(type_specifier)?),
+        });
+    }
+
+    pub(crate) fn struct_type(name: &str) -> String {
+        return format!("%struct.{}", name);
+    }
+
+    pub(crate) fn pointee(type_specifier: &TypeSpecifier) -> Result<TypeSpecifier> {
+        return match type_specifier {
+            TypeSpecifier::Pointer(inner) => Ok(*inner.clone()),
+            _ => Err(CompilerError::CodeGen(String::from(
+                "Expected pointer type",
+            ))),
+        };
+    }
+
+    pub(crate) fn temp(temp: Temp) -> String {
+        return format!("%t{}", temp.0);
+    }
+
+    /// Renders `value` as an operand, following aliases to the value they
+    /// stand for.
+    pub(crate) fn value(&mut self, value: &Value) -> Result<String> {
+        return match value {
+            Value::Temp(temp) => match self.aliases.get(temp).cloned() {
+                Some(value) => self.value(&value),
+                None => Ok(Self::temp(*temp)),
+            },
+            Value::Constant(constant) => self.constant(constant),
+        };
+    }
+
+    /// Renders `value` as `type value`, the form instructions take.
+    pub(crate) fn typed(&mut self, function: &Function, value: &Value) -> Result<String> {
+        let llvm_type = self.llvm_type(&function.type_of(value))?;
+        return Ok(format!("{} {}", llvm_type, self.value(value)?));
+    }
+
+    fn constant(&mut self, constant: &Constant) -> Result<String> {
+        return Ok(match constant {
+            Constant::Int(value) => value.to_string(),
+            // LLVM integers have no sign, the constants are written signed.
+            Constant::Usize(value) => (*value as i64).to_string(),
+            Constant::Char(value) => (*value as i8).to_string(),
+            Constant::Float(value) => Self::float_constant(*value as f64),
+            Constant::Double(value) => Self::float_constant(*value),
+            Constant::Bool(value) => value.to_string(),
+            Constant::Str(bytes) => self.string(bytes),
+            Constant::Zero(type_specifier) => self.zero(type_specifier).to_owned(),
+        });
+    }
+
+    /// The default value of `type_specifier`.
+    pub(crate) fn zero(&self, type_specifier: &TypeSpecifier) -> &'static str {
+        return match type_specifier {
+            TypeSpecifier::Float | TypeSpecifier::Double => "0.0",
+            TypeSpecifier::Bool => "false",
+            TypeSpecifier::Pointer(_) | TypeSpecifier::Str => "null",
+            TypeSpecifier::ArrayType { .. } => "zeroinitializer",
+            TypeSpecifier::UserDefine(name) if self.context.is_struct(name.as_ref()) => {
+                "zeroinitializer"
+            }
+            _ => "0",
+        };
+    }
+
+    /// Adds a string literal to the module and returns a pointer to its first byte.
+    pub(crate) fn string(&mut self, bytes: &[u8]) -> String {
+        let id = match self.strings.iter().position(|string| string == bytes) {
+            Some(id) => id,
+            None => {
+                self.strings.push(bytes.to_vec());
+                self.strings.len() - 1
+            }
+        };
+        let array = format!("[{} x i8]", self.strings[id].len() + 1);
+        return format!(
+            "getelementptr inbounds ({}, {}* @.str.{}, i64 0, i64 0)",
+            array, array, id
+        );
+    }
+
+    fn escape(bytes: &[u8]) -> String {
+        return bytes
+            .iter()
+            .map(|byte| match byte {
+                b' '..=b'~' if *byte != b'"' && *byte != b'\\' => (*byte as char).to_string(),
+                _ => format!("\\{:02X}", byte),
+            })
+            .collect();
+    }
+
+    /// Floating point constants are written as the hexadecimal bits of a
+    /// double, which is exact for both `float` and `double`.
+    pub(crate) fn float_constant(value: f64) -> String {
+        return format!("0x{:016X}", value.to_bits());
+    }
+
+    /// Appends an instruction to the current block.
+    pub(crate) fn instruction(&mut self, instruction: &str) {
+        self.body.push(format!("{}{}", INDENT, instruction));
+    }
+
+    /// Starts the LLVM block `label` in the middle of an IR block.
+    pub(crate) fn start_block(&mut self, label: &str) {
+        self.body.push(format!("{}:", label));
+        self.block = label.to_owned();
+    }
+
+    /// Ends the current block with a trap, for values a checked operation
+    /// rejects.
+    pub(crate) fn trap(&mut self) {
+        self.traps = true;
+        self.instruction("call void @llvm.trap()");
+        self.instruction("unreachable");
+    }
+}
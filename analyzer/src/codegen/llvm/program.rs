@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+use crate::{
+    ast::type_specifier::TypeSpecifier,
+    error::{CompilerError, Result},
+    ir::{BlockId, Function, Instruction, Module, Temp, Value},
+};
+
+use super::{LlvmGenerator, INDENT};
+
+impl LlvmGenerator<'_> {
+    /// Renders the named struct types. Enums need no definition.
+    pub(crate) fn types(&self, module: &Module) -> Result<String> {
+        let mut content = String::new();
+        for struct_def in module.structs.iter() {
+            let fields = struct_def
+                .fields
+                .iter()
+                .map(|(_, type_specifier)| self.llvm_type(type_specifier))
+                .collect::<Result<Vec<String>>>()?;
+            content += &format!(
+                "{} = type {{ {} }}\n",
+                LlvmGenerator::struct_type(&struct_def.name),
+                fields.join(", ")
+            );
+        }
+        return Ok(content);
+    }
+
+    pub(crate) fn functions(&mut self, module: &Module) -> Result<Vec<String>> {
+        let main = module
+            .functions
+            .iter()
+            .find(|function| function.name == "main")
+            .ok_or_else(|| CompilerError::CodeGen(String::from("Program has no main function")))?;
+        if !main.params.is_empty() {
+            return Err(CompilerError::CodeGen(String::from(
+                "main function cannot take parameters",
+            )));
+        }
+
+        self.signatures = module
+            .functions
+            .iter()
+            .map(|function| {
+                let params = function
+                    .params
+                    .iter()
+                    .map(|param| function.temps[param.0].clone())
+                    .collect();
+                (function.name.clone(), (Self::return_type(function), params))
+            })
+            .collect();
+        return module
+            .functions
+            .iter()
+            .map(|function| self.function(function))
+            .collect();
+    }
+
+    /// `main` returns the exit status, even when it is declared `void`.
+    fn return_type(function: &Function) -> TypeSpecifier {
+        if function.name == "main" {
+            return TypeSpecifier::Int;
+        }
+        return function.return_type.clone();
+    }
+
+    /// Renders a function definition. The allocas of the IR are hoisted to
+    /// an `entry` block, so they run once even when the block holding them
+    /// is a loop, and the IR blocks follow it.
+    fn function(&mut self, function: &Function) -> Result<String> {
+        self.aliases = self.find_aliases(function)?;
+        self.allocas = Vec::new();
+
+        let mut bodies = Vec::new();
+        let mut exits = HashMap::new();
+        for (id, block) in function.blocks.iter().enumerate() {
+            let id = BlockId(id);
+            self.body = Vec::new();
+            self.block = id.to_string();
+            for instruction in block.instructions.iter() {
+                self.instruction_to_llvm(function, instruction)?;
+            }
+            self.terminator_to_llvm(function, &block.terminator)?;
+            exits.insert(id, self.block.clone());
+            bodies.push(std::mem::take(&mut self.body));
+        }
+
+        let params = function
+            .params
+            .iter()
+            .map(|param| self.typed(function, &Value::Temp(*param)))
+            .collect::<Result<Vec<String>>>()?;
+        let mut content = format!(
+            "define {} @{}({}) {{\nentry:\n",
+            self.llvm_type(&Self::return_type(function))?,
+            function.name,
+            params.join(", ")
+        );
+        for alloca in self.allocas.iter() {
+            content += &format!("{}{}\n", INDENT, alloca);
+        }
+        content += &format!("{}br label %{}\n", INDENT, BlockId(0));
+        for (id, (block, body)) in function.blocks.iter().zip(bodies).enumerate() {
+            content += &format!("{}:\n", BlockId(id));
+            for phi in block.phis.iter() {
+                let mut incoming = Vec::new();
+                for (predecessor, value) in phi.incoming.iter() {
+                    incoming.push(format!(
+                        "[ {}, %{} ]",
+                        self.value(value)?,
+                        exits[predecessor]
+                    ));
+                }
+                content += &format!(
+                    "{}{} = phi {} {}\n",
+                    INDENT,
+                    LlvmGenerator::temp(phi.dest),
+                    self.llvm_type(&function.temps[phi.dest.0])?,
+                    incoming.join(", ")
+                );
+            }
+            for line in body {
+                content += &line;
+                content += "\n";
+            }
+        }
+        content += "}\n";
+        return Ok(content);
+    }
+
+    /// Copies, and casts between types with the same LLVM spelling, are not
+    /// rendered: their destination stands for the value they read.
+    fn find_aliases(&self, function: &Function) -> Result<HashMap<Temp, Value>> {
+        let mut aliases = HashMap::new();
+        for block in function.blocks.iter() {
+            for instruction in block.instructions.iter() {
+                match instruction {
+                    Instruction::Copy { dest, value } => {
+                        aliases.insert(*dest, value.clone());
+                    }
+                    Instruction::Cast { dest, value }
+                        if self.llvm_type(&function.type_of(value))?
+                            == self.llvm_type(&function.temps[dest.0])? =>
+                    {
+                        aliases.insert(*dest, value.clone());
+                    }
+                    _ => {}
+                }
+            }
+        }
+        return Ok(aliases);
+    }
+}
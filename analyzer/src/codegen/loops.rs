@@ -0,0 +1,147 @@
+use crate::{
+    ast::identifier::Identifier,
+    error::{CompilerError, Result},
+};
+
+/// The loops around the statement being lowered, innermost last, for
+/// `break` and `continue` to find the one they jump out of. `T` is what a
+/// backend needs to reach the end and the next iteration of a loop.
+#[derive(Debug)]
+pub(crate) struct Loops<T> {
+    loops: Vec<Loop<T>>,
+}
+
+#[derive(Debug)]
+pub(crate) struct Loop<T> {
+    label: Option<String>,
+    /// How many scopes of deferred expressions are open outside the body of
+    /// the loop. A jump out of the body runs the ones of the scopes past it.
+    pub defer_depth: usize,
+    pub targets: T,
+}
+
+/// Where the `continue`s and the `break`s of a loop jump to, in backends
+/// that jump to labels.
+#[derive(Debug)]
+pub(crate) struct LoopTargets<L> {
+    pub next: L,
+    pub end: L,
+}
+
+impl<L: Clone> LoopTargets<L> {
+    pub fn get(&self, jump: Jump) -> L {
+        return match jump {
+            Jump::Break => self.end.clone(),
+            Jump::Continue => self.next.clone(),
+        };
+    }
+}
+
+/// Where a `break` or a `continue` jumps to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Jump {
+    Break,
+    Continue,
+}
+
+impl<T> Default for Loops<T> {
+    fn default() -> Self {
+        Self { loops: Vec::new() }
+    }
+}
+
+impl<T> Loops<T> {
+    /// Enters a loop, whose body is lowered next.
+    pub fn push(&mut self, label: Option<&Identifier>, defer_depth: usize, targets: T) {
+        self.loops.push(Loop {
+            label: label.map(ToString::to_string),
+            defer_depth,
+            targets,
+        });
+    }
+
+    /// Leaves the innermost loop once its body is lowered.
+    pub fn pop(&mut self) -> Result<Loop<T>> {
+        return self
+            .loops
+            .pop()
+            .ok_or_else(|| CompilerError::CodeGen(String::from("No loop to leave")));
+    }
+
+    /// The loop a `break` or `continue` with `label` jumps out of: the
+    /// innermost one, or the innermost one with that label.
+    pub fn target(&mut self, label: Option<&Identifier>) -> Result<&mut Loop<T>> {
+        return self
+            .loops
+            .iter_mut()
+            .rev()
+            .find(|target| match label {
+                Some(label) => target.label.as_deref() == Some(label.as_ref()),
+                None => true,
+            })
+            .ok_or_else(|| CompilerError::CodeGen(String::from("Jump out of no loop")));
+    }
+}
+
+/// The expressions deferred in the blocks around the statement being
+/// lowered, innermost block last. `T` is the expression, or what a backend
+/// has already made of it.
+#[derive(Debug)]
+pub(crate) struct Defers<T> {
+    scopes: Vec<Vec<T>>,
+}
+
+impl<T> Default for Defers<T> {
+    fn default() -> Self {
+        Self { scopes: Vec::new() }
+    }
+}
+
+impl<T: Clone> Defers<T> {
+    pub fn push_scope(&mut self) {
+        self.scopes.push(Vec::new());
+    }
+
+    pub fn pop_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    /// Defers `item` to the end of the innermost block.
+    pub fn push(&mut self, item: T) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.push(item);
+        }
+    }
+
+    /// How many blocks are open.
+    pub fn depth(&self) -> usize {
+        return self.scopes.len();
+    }
+
+    /// Whether none of the open blocks deferred anything.
+    pub fn is_empty(&self) -> bool {
+        return self.scopes.iter().all(Vec::is_empty);
+    }
+
+    /// What the innermost `depth` blocks deferred, newest first, which is
+    /// the order it runs in when they are left.
+    pub fn pending(&self, depth: usize) -> Vec<T> {
+        return self
+            .scopes
+            .iter()
+            .rev()
+            .take(depth)
+            .flat_map(|scope| scope.iter().rev().cloned())
+            .collect();
+    }
+
+    /// What runs when a `return` leaves every open block.
+    pub fn all(&self) -> Vec<T> {
+        return self.pending(self.depth());
+    }
+
+    /// What runs when a `break` or `continue` leaves the body of `target`.
+    pub fn leaving<L>(&self, target: &Loop<L>) -> Vec<T> {
+        return self.pending(self.depth() - target.defer_depth);
+    }
+}
@@ -0,0 +1,7 @@
+pub mod bytecode;
+pub mod c;
+pub mod llvm;
+pub mod loops;
+pub mod type_context;
+pub mod wasm;
+pub mod x86_64;
@@ -0,0 +1,503 @@
+use std::collections::HashMap;
+
+use crate::{
+    ast::{
+        declarations::{
+            fn_decl::FnDeclaration,
+            impl_decl::{ImplDeclaration, MemberFunction},
+            struct_decl::StructDeclaration,
+            Declarations,
+        },
+        expressions::{
+            array_index_expr::ArrayIndexExpression,
+            binary_expr::BinaryOperators,
+            fn_call_expr::FnCallExpression,
+            member_access_expr::MemberAccessExpression,
+            unary_expr::{UnaryExpression, UnaryOperators},
+            Expression,
+        },
+        program::Program,
+        type_specifier::TypeSpecifier,
+    },
+    error::{CompilerError, Result},
+    lexer::token_type::TokenType,
+    semantic::constant::{ConstEvaluate, ConstantScopes},
+};
+
+/// Type information every backend needs but the AST does not record: the
+/// signatures of functions and methods, struct layouts and the types of the
+/// variables that are currently in scope.
+#[derive(Debug, Default)]
+pub struct TypeContext {
+    functions: HashMap<String, FnSignature>,
+    structs: HashMap<String, StructLayout>,
+    enums: HashMap<String, Vec<EnumItemLayout>>,
+    scopes: Vec<HashMap<String, TypeSpecifier>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FnSignature {
+    pub receiver: Option<TypeSpecifier>,
+    pub params: Vec<TypeSpecifier>,
+    pub return_type: TypeSpecifier,
+}
+
+/// An item of an enum, the number it is stored as and the types of the
+/// values it carries.
+#[derive(Debug)]
+pub struct EnumItemLayout {
+    pub name: String,
+    pub value: i32,
+    pub payload: Vec<TypeSpecifier>,
+}
+
+#[derive(Debug, Default)]
+pub struct StructLayout {
+    pub fields: Vec<(String, TypeSpecifier)>,
+    pub methods: HashMap<String, FnSignature>,
+}
+
+impl TypeContext {
+    pub fn new(program: &Program) -> Self {
+        let mut context = Self::default();
+        for decl in program.declarations.iter() {
+            match decl {
+                Declarations::Fn(fn_decl) => {
+                    let signature = FnSignature::from(fn_decl);
+                    context
+                        .functions
+                        .insert(fn_decl.fn_name.to_string(), signature);
+                }
+                Declarations::Struct(struct_decl) => context.register_struct(struct_decl),
+                Declarations::Enum(enum_decl) => {
+                    let items = enum_decl
+                        .fields
+                        .iter()
+                        .map(|item| EnumItemLayout {
+                            name: item.identifier.to_string(),
+                            value: item.discriminant,
+                            payload: item.payload.clone(),
+                        })
+                        .collect();
+                    context.enums.insert(enum_decl.name.to_string(), items);
+                    if let Some(layout) = &enum_decl.layout {
+                        context.register_struct(layout);
+                    }
+                }
+                Declarations::Impl(impl_decl) => context.register_impl(impl_decl),
+                Declarations::Const(_) => {}
+            }
+        }
+        return context;
+    }
+
+    fn register_struct(&mut self, struct_decl: &StructDeclaration) {
+        let layout = self
+            .structs
+            .entry(struct_decl.name.to_string())
+            .or_default();
+        layout.fields = struct_decl
+            .fields
+            .iter()
+            .map(|field| {
+                let field = &field.field;
+                (field.identifier.to_string(), field.type_specifier.clone())
+            })
+            .collect();
+    }
+
+    fn register_impl(&mut self, impl_decl: &ImplDeclaration) {
+        let Ok(name) = impl_decl.name.to_symbol() else {
+            return;
+        };
+        let layout = self.structs.entry(name.to_owned()).or_default();
+        for member_fn in impl_decl.member_functions.iter() {
+            let signature = FnSignature::from(member_fn);
+            layout
+                .methods
+                .insert(member_fn.prototype.name.to_string(), signature);
+        }
+    }
+
+    pub fn push_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    pub fn pop_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    pub fn declare(&mut self, name: &str, type_specifier: TypeSpecifier) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_owned(), type_specifier);
+        }
+    }
+
+    pub fn variable(&self, name: &str) -> Result<&TypeSpecifier> {
+        return self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .ok_or_else(|| CompilerError::CodeGen(format!("Unknown variable {}", name)));
+    }
+
+    pub fn function(&self, name: &str) -> Result<&FnSignature> {
+        return self
+            .functions
+            .get(name)
+            .ok_or_else(|| CompilerError::CodeGen(format!("Unknown function {}", name)));
+    }
+
+    pub fn struct_layout(&self, name: &str) -> Result<&StructLayout> {
+        return self
+            .structs
+            .get(name)
+            .ok_or_else(|| CompilerError::CodeGen(format!("Unknown struct {}", name)));
+    }
+
+    pub fn method(&self, struct_name: &str, name: &str) -> Result<&FnSignature> {
+        return self
+            .struct_layout(struct_name)?
+            .methods
+            .get(name)
+            .ok_or_else(|| {
+                CompilerError::CodeGen(format!("Unknown function {}::{}", struct_name, name))
+            });
+    }
+
+    pub fn field(&self, struct_name: &str, name: &str) -> Result<&TypeSpecifier> {
+        return self
+            .struct_layout(struct_name)?
+            .fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, type_specifier)| type_specifier)
+            .ok_or_else(|| {
+                CompilerError::CodeGen(format!("Unknown field {}.{}", struct_name, name))
+            });
+    }
+
+    pub fn is_struct(&self, name: &str) -> bool {
+        return self.structs.contains_key(name);
+    }
+
+    /// Whether `name` is an enum stored as a plain int, which is the case
+    /// unless its items carry values.
+    pub fn is_enum(&self, name: &str) -> bool {
+        return self.enums.contains_key(name) && !self.is_tagged(name);
+    }
+
+    /// Whether `name` is an enum whose items carry values. Such an enum is
+    /// stored as the struct of its declaration's layout.
+    pub fn is_tagged(&self, name: &str) -> bool {
+        return self
+            .enums
+            .get(name)
+            .is_some_and(|items| items.iter().any(|item| !item.payload.is_empty()));
+    }
+
+    pub fn enums(&self) -> &HashMap<String, Vec<EnumItemLayout>> {
+        return &self.enums;
+    }
+
+    fn enum_item_layout(&self, enum_name: &str, item: &str) -> Result<&EnumItemLayout> {
+        return self
+            .enums
+            .get(enum_name)
+            .and_then(|items| items.iter().find(|layout| layout.name == item))
+            .ok_or_else(|| {
+                CompilerError::CodeGen(format!("Unknown item {}::{}", enum_name, item))
+            });
+    }
+
+    /// The number an enum item is stored as.
+    pub fn enum_item(&self, enum_name: &str, item: &str) -> Result<i32> {
+        return Ok(self.enum_item_layout(enum_name, item)?.value);
+    }
+
+    /// The types of the values an enum item carries.
+    pub fn enum_payload(&self, enum_name: &str, item: &str) -> Result<&[TypeSpecifier]> {
+        return Ok(&self.enum_item_layout(enum_name, item)?.payload);
+    }
+
+    /// Whether `value` is the number of one of the items of the enum.
+    pub fn is_enum_value(&self, enum_name: &str, value: i32) -> bool {
+        return self
+            .enums
+            .get(enum_name)
+            .is_some_and(|items| items.iter().any(|item| item.value == value));
+    }
+
+    /// The numbers the items of an enum are stored as, in ascending order,
+    /// which a checked `int as Enum` compares the value with.
+    pub fn enum_values(&self, enum_name: &str) -> Result<Vec<i32>> {
+        let items = self
+            .enums
+            .get(enum_name)
+            .ok_or_else(|| CompilerError::CodeGen(format!("Unknown enum {}", enum_name)))?;
+        let mut values: Vec<i32> = items.iter().map(|item| item.value).collect();
+        values.sort_unstable();
+        values.dedup();
+        return Ok(values);
+    }
+
+    /// The integer a `case` compares the switched value with.
+    pub fn case_value(&self, value: &Expression) -> Result<i64> {
+        return match value {
+            Expression::EnumItem(enum_item) => {
+                Ok(self.enum_item(enum_item.name.as_ref(), enum_item.item.as_ref())? as i64)
+            }
+            value => value.evaluate(&ConstantScopes::new())?.as_index(),
+        };
+    }
+
+    /// Computes the type of an already analyzed expression.
+    pub fn expr_type(&self, expr: &Expression) -> Result<TypeSpecifier> {
+        return match expr {
+            Expression::Binary(binary) => match binary.operator {
+                BinaryOperators::Equal
+                | BinaryOperators::NotEqual
+                | BinaryOperators::And
+                | BinaryOperators::Or
+                | BinaryOperators::GreaterThan
+                | BinaryOperators::LessThan
+                | BinaryOperators::GreaterThanOrEqual
+                | BinaryOperators::LessThanOrEqual => Ok(TypeSpecifier::Bool),
+                _ => self.expr_type(&binary.left),
+            },
+            Expression::Assignment(assignment) => self.expr_type(&assignment.variable),
+            Expression::Unary(unary) => self.unary_type(unary),
+            Expression::ArrayIndex(array_index) => self.array_index_type(array_index),
+            Expression::MemberAccess(member_access) => self.member_access_type(member_access),
+            Expression::FnCall(fn_call) => Ok(self.fn_call_signature(fn_call)?.return_type.clone()),
+            Expression::ArrayInit(array_init) => {
+                let first = array_init.array.first().ok_or_else(|| {
+                    CompilerError::CodeGen(String::from("Empty array init expression"))
+                })?;
+                Ok(TypeSpecifier::array(
+                    self.expr_type(first)?,
+                    array_init.size,
+                ))
+            }
+            Expression::ObjectInit(object_init) => Ok(TypeSpecifier::UserDefine(
+                object_init.object.identifier()?.id.clone(),
+            )),
+            Expression::Identifier(identifier) => {
+                Ok(self.variable(identifier.id.as_ref())?.clone())
+            }
+            Expression::EnumItem(enum_item) => {
+                Ok(TypeSpecifier::UserDefine(enum_item.name.clone()))
+            }
+            Expression::Primitive(primitive) => Ok(primitive.type_specifier.clone()),
+            Expression::Cast(cast) => Ok(cast.target_type.clone()),
+        };
+    }
+
+    fn unary_type(&self, unary: &UnaryExpression) -> Result<TypeSpecifier> {
+        let value = self.expr_type(&unary.value)?;
+        return match unary.operator {
+            UnaryOperators::Address => Ok(TypeSpecifier::Pointer(Box::new(value))),
+            UnaryOperators::Indirection => match value {
+                TypeSpecifier::Pointer(inner) => Ok(*inner),
+                _ => Err(CompilerError::CodeGen(String::from(
+                    "Expected pointer type",
+                ))),
+            },
+            UnaryOperators::LogicalNot => Ok(TypeSpecifier::Bool),
+            _ => Ok(value),
+        };
+    }
+
+    fn array_index_type(&self, array_index: &ArrayIndexExpression) -> Result<TypeSpecifier> {
+        let element = match self.expr_type(&array_index.array)? {
+            TypeSpecifier::ArrayType { type_specifier, .. } => *type_specifier,
+            TypeSpecifier::Pointer(type_specifier) => *type_specifier,
+            _ => {
+                return Err(CompilerError::CodeGen(String::from(
+                    "Expect the array type",
+                )))
+            }
+        };
+        if array_index.second_index.is_none() {
+            return Ok(element);
+        }
+        return match element {
+            TypeSpecifier::ArrayType { type_specifier, .. } => Ok(*type_specifier),
+            _ => Err(CompilerError::CodeGen(String::from(
+                "Expect the two dimensional array type",
+            ))),
+        };
+    }
+
+    fn member_access_type(&self, member_access: &MemberAccessExpression) -> Result<TypeSpecifier> {
+        let object = self.expr_type(&member_access.object)?;
+        return self.property_type(&object, &member_access.property);
+    }
+
+    /// Resolves the type of `property` accessed on a value of type `object`.
+    pub fn property_type(
+        &self,
+        object: &TypeSpecifier,
+        property: &Expression,
+    ) -> Result<TypeSpecifier> {
+        let mut context = self;
+        return context.member(object.clone(), property);
+    }
+
+    /// Returns the struct name behind a value or pointer type.
+    pub fn struct_name(type_specifier: &TypeSpecifier) -> Result<&str> {
+        return match type_specifier {
+            TypeSpecifier::UserDefine(identifier) => Ok(identifier.as_ref()),
+            TypeSpecifier::Pointer(inner) => Self::struct_name(inner),
+            _ => Err(CompilerError::CodeGen(String::from(
+                "Member access only can use with user define type",
+            ))),
+        };
+    }
+
+    /// The mask of a shift amount. Shifts wrap the amount at the width of
+    /// the shifted type, like the interpreter's `wrapping_shl`.
+    pub fn shift_mask(type_specifier: &TypeSpecifier) -> u32 {
+        return match type_specifier {
+            TypeSpecifier::Usize => 63,
+            TypeSpecifier::Char => 7,
+            _ => 31,
+        };
+    }
+
+    pub fn fn_call_signature(&self, fn_call: &FnCallExpression) -> Result<&FnSignature> {
+        let name = fn_call.fn_name.identifier()?;
+        if let Some(namespace) = &fn_call.namespace {
+            let struct_name = namespace.name.identifier()?;
+            return self.method(struct_name.id.as_ref(), name.id.as_ref());
+        }
+        return self.function(name.id.as_ref());
+    }
+}
+
+/// How a backend resolves one step of a member access. The parser nests
+/// chained accesses like `a.b.c` inside the property, so `member` resolves
+/// `b` on `a`, makes the result the object of `.c` and resolves `c` on it.
+pub(crate) trait MemberChain<'a> {
+    /// A struct, or a pointer to one, whose members are accessed.
+    type Object;
+    /// What a field or a method call resolves to.
+    type Member;
+
+    /// The type of `object`, which names its struct.
+    fn object_type(&self, object: &Self::Object) -> Result<TypeSpecifier>;
+
+    /// Resolves the field `name` of `object`, a `struct_name`.
+    fn field_member(
+        &mut self,
+        object: Self::Object,
+        struct_name: &str,
+        name: &str,
+    ) -> Result<Self::Member>;
+
+    /// Calls the member function of `struct_name` that `fn_call` names on
+    /// `object`.
+    fn method_member(
+        &mut self,
+        object: Self::Object,
+        struct_name: &str,
+        fn_call: &'a FnCallExpression,
+    ) -> Result<Self::Member>;
+
+    /// Makes `member` the object of the next property, accessed with
+    /// `access`.
+    fn member_object(&mut self, member: Self::Member, access: &TokenType) -> Result<Self::Object>;
+
+    /// Resolves `property` accessed on `object`.
+    fn member(&mut self, object: Self::Object, property: &'a Expression) -> Result<Self::Member> {
+        let object_type = self.object_type(&object)?;
+        let struct_name = TypeContext::struct_name(&object_type)?;
+        return match property {
+            Expression::Identifier(identifier) => {
+                self.field_member(object, struct_name, identifier.id.as_ref())
+            }
+            Expression::FnCall(fn_call) => self.method_member(object, struct_name, fn_call),
+            Expression::MemberAccess(member_access) => {
+                let inner = self.member(object, &member_access.object)?;
+                let inner = self.member_object(inner, &member_access.access)?;
+                self.member(inner, &member_access.property)
+            }
+            _ => Err(CompilerError::CodeGen(String::from(
+                "Invalid property expression",
+            ))),
+        };
+    }
+}
+
+/// Only the types of members, which do not depend on the access.
+impl<'a> MemberChain<'a> for &TypeContext {
+    type Object = TypeSpecifier;
+    type Member = TypeSpecifier;
+
+    fn object_type(&self, object: &TypeSpecifier) -> Result<TypeSpecifier> {
+        return Ok(object.clone());
+    }
+
+    fn field_member(
+        &mut self,
+        _object: TypeSpecifier,
+        struct_name: &str,
+        name: &str,
+    ) -> Result<TypeSpecifier> {
+        return Ok(self.field(struct_name, name)?.clone());
+    }
+
+    fn method_member(
+        &mut self,
+        _object: TypeSpecifier,
+        struct_name: &str,
+        fn_call: &'a FnCallExpression,
+    ) -> Result<TypeSpecifier> {
+        let name = fn_call.fn_name.identifier()?;
+        return Ok(self
+            .method(struct_name, name.id.as_ref())?
+            .return_type
+            .clone());
+    }
+
+    fn member_object(
+        &mut self,
+        member: TypeSpecifier,
+        _access: &TokenType,
+    ) -> Result<TypeSpecifier> {
+        return Ok(member);
+    }
+}
+
+impl From<&FnDeclaration> for FnSignature {
+    fn from(fn_decl: &FnDeclaration) -> Self {
+        return Self {
+            receiver: None,
+            params: fn_decl
+                .params
+                .iter()
+                .map(|param| param.type_specifier.clone())
+                .collect(),
+            return_type: fn_decl.return_type.clone(),
+        };
+    }
+}
+
+impl From<&MemberFunction> for FnSignature {
+    fn from(member_fn: &MemberFunction) -> Self {
+        let prototype = &member_fn.prototype;
+        return Self {
+            receiver: prototype
+                .method
+                .as_ref()
+                .map(|method| method.type_specifier.clone()),
+            params: prototype
+                .params
+                .iter()
+                .map(|param| param.type_specifier.clone())
+                .collect(),
+            return_type: prototype.return_type.clone(),
+        };
+    }
+}
@@ -0,0 +1,718 @@
+use crate::{
+    ast::{
+        declarations::enum_decl::EnumDeclaration,
+        expressions::{
+            array_index_expr::ArrayIndexExpression,
+            array_init_expr::ArrayInitExpression,
+            assignment_expr::AssignmentExpression,
+            binary_expr::{BinaryExpression, BinaryOperators},
+            cast_expr::CastExpression,
+            enum_item_expr::EnumItemExpression,
+            fn_call_expr::FnCallExpression,
+            identifier_expression::IdentifierExpression,
+            member_access_expr::MemberAccessExpression,
+            object_init_expr::ObjectInitExpression,
+            primitive_expr::PrimitiveExpression,
+            unary_expr::{UnaryExpression, UnaryExpressionType, UnaryOperators},
+            Expression,
+        },
+        type_specifier::TypeSpecifier,
+    },
+    codegen::type_context::{MemberChain, TypeContext},
+    error::{CompilerError, Result},
+    lexer::token_type::TokenType,
+};
+
+use super::{ToWat, WatGenerator};
+
+/// What a member access left on the operand stack.
+pub(crate) enum Member {
+    Address(TypeSpecifier),
+    Value(TypeSpecifier),
+}
+
+/// How a scalar is represented on the operand stack.
+enum Kind {
+    Signed,
+    Unsigned,
+    Wide,
+    Single,
+    Double,
+}
+
+impl<'a> ToWat<'a> for Expression {
+    fn to_wat(&'a self, gen: &mut WatGenerator<'a>) -> Result<()> {
+        return match self {
+            Expression::Binary(binary_expr) => binary_expr.to_wat(gen),
+            Expression::Assignment(assignment_expr) => gen.assign(assignment_expr),
+            Expression::Unary(unary_expr) => unary_expr.to_wat(gen),
+            Expression::ArrayIndex(array_index) => array_index.to_wat(gen),
+            Expression::MemberAccess(member_access) => member_access.to_wat(gen),
+            Expression::FnCall(fn_call_expr) => fn_call_expr.to_wat(gen),
+            Expression::ArrayInit(array_init_expr) => array_init_expr.to_wat(gen),
+            Expression::ObjectInit(object_init_expr) => object_init_expr.to_wat(gen),
+            Expression::Cast(cast_expr) => cast_expr.to_wat(gen),
+            Expression::Identifier(identifier_expr) => identifier_expr.to_wat(gen),
+            Expression::EnumItem(enum_item_expr) => enum_item_expr.to_wat(gen),
+            Expression::Primitive(primitive_expr) => primitive_expr.to_wat(gen),
+        };
+    }
+}
+
+impl<'a> WatGenerator<'a> {
+    /// Pushes a pointer to the storage `expr` refers to. Scalars that are
+    /// not stored anywhere are copied to a temporary first.
+    pub(crate) fn address(&mut self, expr: &'a Expression) -> Result<()> {
+        match expr {
+            Expression::Identifier(identifier) => {
+                let offset = self.local(identifier.id.as_ref())?;
+                self.frame_address(offset);
+            }
+            Expression::MemberAccess(member_access) => {
+                let object = self.object_address(&member_access.object, &member_access.access)?;
+                if let Member::Value(type_specifier) =
+                    self.member(object, &member_access.property)?
+                {
+                    self.spill(&type_specifier)?;
+                }
+            }
+            Expression::ArrayIndex(array_index) => {
+                self.index_address(array_index)?;
+            }
+            Expression::Unary(UnaryExpression {
+                operator: UnaryOperators::Indirection,
+                value,
+                ..
+            }) => value.to_wat(self)?,
+            _ => {
+                let type_specifier = self.context.expr_type(expr)?;
+                expr.to_wat(self)?;
+                self.spill(&type_specifier)?;
+            }
+        }
+        return Ok(());
+    }
+
+    /// Moves the scalar on the stack to a temporary and pushes its address instead.
+    fn spill(&mut self, type_specifier: &TypeSpecifier) -> Result<()> {
+        if self.is_aggregate(type_specifier) {
+            return Ok(());
+        }
+        let Some(value_type) = Self::value_type(type_specifier) else {
+            return Err(CompilerError::CodeGen(String::from(
+                "Void value cannot be addressed",
+            )));
+        };
+        let temporary = self.scratch(value_type);
+        self.emit(&format!("local.set {}", temporary));
+        let slot = self.slot(type_specifier)?;
+        self.frame_address(slot);
+        self.emit(&format!("local.get {}", temporary));
+        self.store(type_specifier)?;
+        self.frame_address(slot);
+        return Ok(());
+    }
+
+    /// Stores the value and pushes it again as the result of the assignment.
+    pub(crate) fn assign(&mut self, assignment: &'a AssignmentExpression) -> Result<()> {
+        let type_specifier = self.context.expr_type(&assignment.variable)?;
+        let value_type = Self::value_type(&type_specifier).unwrap_or("i32");
+        let temporary = self.scratch(value_type);
+        assignment.value.to_wat(self)?;
+        self.emit(&format!("local.set {}", temporary));
+        self.address(&assignment.variable)?;
+        self.emit(&format!("local.get {}", temporary));
+        self.store(&type_specifier)?;
+        self.emit(&format!("local.get {}", temporary));
+        return Ok(());
+    }
+
+    /// Pushes the address of the struct `object` refers to and returns its type.
+    fn object_address(
+        &mut self,
+        object: &'a Expression,
+        access: &TokenType,
+    ) -> Result<TypeSpecifier> {
+        let object_type = self.context.expr_type(object)?;
+        if access != &TokenType::Arrow {
+            self.address(object)?;
+            return Ok(object_type);
+        }
+        object.to_wat(self)?;
+        return match object_type {
+            TypeSpecifier::Pointer(inner) => Ok(*inner),
+            _ => Err(CompilerError::CodeGen(String::from(
+                "Arrow access only can use with pointers",
+            ))),
+        };
+    }
+
+    /// Calls a member function on the struct whose address is on the stack.
+    /// Receivers are passed by address either way, by value ones are copied
+    /// by the callee.
+    fn method_call(&mut self, struct_name: &str, fn_call: &'a FnCallExpression) -> Result<Member> {
+        let name = fn_call.fn_name.identifier()?.id.to_string();
+        let signature = self.context.method(struct_name, &name)?.clone();
+        let receiver = match signature.receiver {
+            Some(_) => {
+                let receiver = self.scratch("i32");
+                self.emit(&format!("local.set {}", receiver));
+                Some(receiver)
+            }
+            None => {
+                self.emit("drop");
+                None
+            }
+        };
+        self.call(
+            &WatGenerator::method_name(struct_name, &name),
+            receiver,
+            &fn_call.args,
+            &signature.return_type,
+        )?;
+        return Ok(Member::Value(signature.return_type));
+    }
+
+    /// Pushes the arguments from left to right and calls `name`. Aggregate
+    /// arguments are copied first, aggregate results are written to a
+    /// temporary whose address the callee returns.
+    fn call(
+        &mut self,
+        name: &str,
+        receiver: Option<String>,
+        args: &'a [Expression],
+        return_type: &TypeSpecifier,
+    ) -> Result<()> {
+        if self.is_aggregate(return_type) {
+            let result = self.slot(return_type)?;
+            self.frame_address(result);
+        }
+        if let Some(receiver) = receiver {
+            self.emit(&format!("local.get {}", receiver));
+        }
+        for arg in args.iter() {
+            let type_specifier = self.context.expr_type(arg)?;
+            arg.to_wat(self)?;
+            if self.is_aggregate(&type_specifier) {
+                let source = self.scratch("i32");
+                self.emit(&format!("local.set {}", source));
+                let copy = self.slot(&type_specifier)?;
+                self.frame_address(copy);
+                self.emit(&format!("local.get {}", source));
+                self.store(&type_specifier)?;
+                self.frame_address(copy);
+            }
+        }
+        self.emit(&format!("call ${}", name));
+        return Ok(());
+    }
+
+    /// Pushes the address of the indexed element and returns its type.
+    fn index_address(&mut self, array_index: &'a ArrayIndexExpression) -> Result<TypeSpecifier> {
+        let element = match self.context.expr_type(&array_index.array)? {
+            TypeSpecifier::ArrayType { type_specifier, .. } => {
+                self.address(&array_index.array)?;
+                *type_specifier
+            }
+            TypeSpecifier::Pointer(type_specifier) => {
+                array_index.array.to_wat(self)?;
+                *type_specifier
+            }
+            _ => {
+                return Err(CompilerError::CodeGen(String::from(
+                    "Expect the array type",
+                )))
+            }
+        };
+        self.element_address(&element, &array_index.index)?;
+
+        let Some(second_index) = &array_index.second_index else {
+            return Ok(element);
+        };
+        let TypeSpecifier::ArrayType { type_specifier, .. } = element else {
+            return Err(CompilerError::CodeGen(String::from(
+                "Expect the two dimensional array type",
+            )));
+        };
+        self.element_address(&type_specifier, second_index)?;
+        return Ok(*type_specifier);
+    }
+
+    /// Moves the base address on the stack to the element `index`.
+    fn element_address(&mut self, element: &TypeSpecifier, index: &'a Expression) -> Result<()> {
+        let stride = self.size_of(element)?;
+        let index_type = self.context.expr_type(index)?;
+        index.to_wat(self)?;
+        self.convert(&index_type, &TypeSpecifier::Int);
+        if stride != 1 {
+            self.emit(&format!("i32.const {}", stride));
+            self.emit("i32.mul");
+        }
+        self.emit("i32.add");
+        return Ok(());
+    }
+
+    fn kind(type_specifier: &TypeSpecifier) -> Kind {
+        return match type_specifier {
+            TypeSpecifier::Int | TypeSpecifier::UserDefine(_) => Kind::Signed,
+            TypeSpecifier::Usize => Kind::Wide,
+            TypeSpecifier::Float => Kind::Single,
+            TypeSpecifier::Double => Kind::Double,
+            _ => Kind::Unsigned,
+        };
+    }
+
+    /// Brings an `i32` result back to the range of `char` and `bool`.
+    fn normalize(&mut self, type_specifier: &TypeSpecifier) {
+        match type_specifier {
+            TypeSpecifier::Char => {
+                self.emit("i32.const 255");
+                self.emit("i32.and");
+            }
+            TypeSpecifier::Bool => {
+                self.emit("i32.const 0");
+                self.emit("i32.ne");
+            }
+            _ => {}
+        }
+    }
+
+    /// Converts the scalar on the stack from `source` to `target` with the
+    /// rules of a C cast. Float to integer conversions saturate.
+    pub(crate) fn convert(&mut self, source: &TypeSpecifier, target: &TypeSpecifier) {
+        let from = Self::kind(source);
+        let instruction = match (target, &from) {
+            (TypeSpecifier::Void, _) | (TypeSpecifier::ArrayType { .. }, _) => return,
+            (TypeSpecifier::Bool, _) => {
+                let value_type = Self::value_type(source).unwrap_or("i32");
+                self.emit(&format!("{}.const 0", value_type));
+                self.emit(&format!("{}.ne", value_type));
+                return;
+            }
+            (TypeSpecifier::Usize, Kind::Signed) => "i64.extend_i32_s",
+            (TypeSpecifier::Usize, Kind::Unsigned) => "i64.extend_i32_u",
+            (TypeSpecifier::Usize, Kind::Wide) => "",
+            (TypeSpecifier::Usize, Kind::Single) => "i64.trunc_sat_f32_u",
+            (TypeSpecifier::Usize, Kind::Double) => "i64.trunc_sat_f64_u",
+            (TypeSpecifier::Float, Kind::Signed) => "f32.convert_i32_s",
+            (TypeSpecifier::Float, Kind::Unsigned) => "f32.convert_i32_u",
+            (TypeSpecifier::Float, Kind::Wide) => "f32.convert_i64_u",
+            (TypeSpecifier::Float, Kind::Single) => "",
+            (TypeSpecifier::Float, Kind::Double) => "f32.demote_f64",
+            (TypeSpecifier::Double, Kind::Signed) => "f64.convert_i32_s",
+            (TypeSpecifier::Double, Kind::Unsigned) => "f64.convert_i32_u",
+            (TypeSpecifier::Double, Kind::Wide) => "f64.convert_i64_u",
+            (TypeSpecifier::Double, Kind::Single) => "f64.promote_f32",
+            (TypeSpecifier::Double, Kind::Double) => "",
+            (TypeSpecifier::Char, Kind::Single) => "i32.trunc_sat_f32_u",
+            (TypeSpecifier::Char, Kind::Double) => "i32.trunc_sat_f64_u",
+            (_, Kind::Signed) | (_, Kind::Unsigned) => "",
+            (_, Kind::Wide) => "i32.wrap_i64",
+            (_, Kind::Single) => "i32.trunc_sat_f32_s",
+            (_, Kind::Double) => "i32.trunc_sat_f64_s",
+        };
+        if !instruction.is_empty() {
+            self.emit(instruction);
+        }
+        if *target == TypeSpecifier::Char
+            && !matches!(source, TypeSpecifier::Char | TypeSpecifier::Bool)
+        {
+            self.normalize(target);
+        }
+    }
+}
+
+/// The address of the struct is on the operand stack, `Object` is its type.
+impl<'a> MemberChain<'a> for WatGenerator<'a> {
+    type Object = TypeSpecifier;
+    type Member = Member;
+
+    fn object_type(&self, object: &TypeSpecifier) -> Result<TypeSpecifier> {
+        return Ok(object.clone());
+    }
+
+    fn field_member(
+        &mut self,
+        _object: TypeSpecifier,
+        struct_name: &str,
+        name: &str,
+    ) -> Result<Member> {
+        let (offset, type_specifier) = self.field(struct_name, name)?;
+        if offset > 0 {
+            self.emit(&format!("i32.const {}", offset));
+            self.emit("i32.add");
+        }
+        return Ok(Member::Address(type_specifier));
+    }
+
+    fn method_member(
+        &mut self,
+        _object: TypeSpecifier,
+        struct_name: &str,
+        fn_call: &'a FnCallExpression,
+    ) -> Result<Member> {
+        return self.method_call(struct_name, fn_call);
+    }
+
+    fn member_object(&mut self, member: Member, access: &TokenType) -> Result<TypeSpecifier> {
+        let is_arrow = access == &TokenType::Arrow;
+        let inner = match member {
+            Member::Address(inner) if is_arrow => {
+                self.emit("i32.load");
+                inner
+            }
+            Member::Address(inner) | Member::Value(inner) => inner,
+        };
+        return Ok(match inner {
+            TypeSpecifier::Pointer(pointee) if is_arrow => *pointee,
+            inner => inner,
+        });
+    }
+}
+
+impl<'a> ToWat<'a> for BinaryExpression {
+    fn to_wat(&'a self, gen: &mut WatGenerator<'a>) -> Result<()> {
+        if let BinaryOperators::And | BinaryOperators::Or = self.operator {
+            self.left.to_wat(gen)?;
+            gen.open("if (result i32)");
+            if matches!(self.operator, BinaryOperators::And) {
+                self.right.to_wat(gen)?;
+                gen.otherwise();
+                gen.emit("i32.const 0");
+            } else {
+                gen.emit("i32.const 1");
+                gen.otherwise();
+                self.right.to_wat(gen)?;
+            }
+            gen.close();
+            return Ok(());
+        }
+
+        let type_specifier = gen.context.expr_type(&self.left)?;
+        let value_type = WatGenerator::value_type(&type_specifier).unwrap_or("i32");
+        let kind = WatGenerator::kind(&type_specifier);
+        let is_float = matches!(kind, Kind::Single | Kind::Double);
+        let sign = match kind {
+            Kind::Signed => "_s",
+            Kind::Unsigned | Kind::Wide => "_u",
+            Kind::Single | Kind::Double => "",
+        };
+
+        self.left.to_wat(gen)?;
+        self.right.to_wat(gen)?;
+
+        let operation = match self.operator {
+            BinaryOperators::Equal => String::from("eq"),
+            BinaryOperators::NotEqual => String::from("ne"),
+            BinaryOperators::GreaterThan => format!("gt{}", sign),
+            BinaryOperators::LessThan => format!("lt{}", sign),
+            BinaryOperators::GreaterThanOrEqual => format!("ge{}", sign),
+            BinaryOperators::LessThanOrEqual => format!("le{}", sign),
+            _ => String::new(),
+        };
+        if !operation.is_empty() {
+            gen.emit(&format!("{}.{}", value_type, operation));
+            return Ok(());
+        }
+
+        if is_float && matches!(self.operator, BinaryOperators::Modulo) {
+            // Wasm has no float remainder, the result has the sign of the dividend.
+            let left = gen.scratch(value_type);
+            let right = gen.scratch(value_type);
+            gen.emit(&format!("local.set {}", right));
+            gen.emit(&format!("local.tee {}", left));
+            gen.emit(&format!("local.get {}", left));
+            gen.emit(&format!("local.get {}", right));
+            gen.emit(&format!("{}.div", value_type));
+            gen.emit(&format!("{}.trunc", value_type));
+            gen.emit(&format!("local.get {}", right));
+            gen.emit(&format!("{}.mul", value_type));
+            gen.emit(&format!("{}.sub", value_type));
+            return Ok(());
+        }
+        let operation = match self.operator {
+            BinaryOperators::Add => String::from("add"),
+            BinaryOperators::Subtract => String::from("sub"),
+            BinaryOperators::Multiply => String::from("mul"),
+            BinaryOperators::Divide if is_float => String::from("div"),
+            BinaryOperators::Divide => format!("div{}", sign),
+            BinaryOperators::Modulo => format!("rem{}", sign),
+            BinaryOperators::BitAnd if !is_float => String::from("and"),
+            BinaryOperators::BitOr if !is_float => String::from("or"),
+            BinaryOperators::BitXor if !is_float => String::from("xor"),
+            BinaryOperators::BitLeft if !is_float => String::from("shl"),
+            BinaryOperators::BitRight if !is_float => format!("shr{}", sign),
+            _ => {
+                return Err(CompilerError::CodeGen(String::from(
+                    "Bitwise operators cannot be used with floating point types",
+                )))
+            }
+        };
+        if let BinaryOperators::BitLeft | BinaryOperators::BitRight = self.operator {
+            // Wasm wraps the amount at 32 or 64 bits, a char needs 8.
+            let mask = TypeContext::shift_mask(&type_specifier);
+            gen.emit(&format!("{}.const {}", value_type, mask));
+            gen.emit(&format!("{}.and", value_type));
+        }
+        gen.emit(&format!("{}.{}", value_type, operation));
+        gen.normalize(&type_specifier);
+        return Ok(());
+    }
+}
+
+impl<'a> ToWat<'a> for UnaryExpression {
+    fn to_wat(&'a self, gen: &mut WatGenerator<'a>) -> Result<()> {
+        let type_specifier = gen.context.expr_type(&self.value)?;
+        let value_type = WatGenerator::value_type(&type_specifier).unwrap_or("i32");
+        match self.operator {
+            UnaryOperators::Address => return gen.address(&self.value),
+            UnaryOperators::Indirection => {
+                let TypeSpecifier::Pointer(pointee) = type_specifier else {
+                    return Err(CompilerError::CodeGen(String::from(
+                        "Expected pointer type",
+                    )));
+                };
+                self.value.to_wat(gen)?;
+                return gen.load(&pointee);
+            }
+            UnaryOperators::Increment | UnaryOperators::Decrement => {
+                let delta = match &type_specifier {
+                    TypeSpecifier::Pointer(pointee) => gen.size_of(pointee)?,
+                    _ => 1,
+                };
+                let operation = match self.operator {
+                    UnaryOperators::Increment => "add",
+                    _ => "sub",
+                };
+                let address = gen.scratch("i32");
+                let old = gen.scratch(value_type);
+                let new = gen.scratch(value_type);
+                gen.address(&self.value)?;
+                gen.emit(&format!("local.tee {}", address));
+                gen.emit(&format!("local.get {}", address));
+                gen.load(&type_specifier)?;
+                gen.emit(&format!("local.tee {}", old));
+                gen.emit(&format!("{}.const {}", value_type, delta));
+                gen.emit(&format!("{}.{}", value_type, operation));
+                gen.normalize(&type_specifier);
+                gen.emit(&format!("local.tee {}", new));
+                gen.store(&type_specifier)?;
+                match self.expression_type {
+                    UnaryExpressionType::Postfix => gen.emit(&format!("local.get {}", old)),
+                    _ => gen.emit(&format!("local.get {}", new)),
+                }
+                return Ok(());
+            }
+            _ => {}
+        }
+        let is_float = matches!(type_specifier, TypeSpecifier::Float | TypeSpecifier::Double);
+        match self.operator {
+            UnaryOperators::Minus if is_float => {
+                self.value.to_wat(gen)?;
+                gen.emit(&format!("{}.neg", value_type));
+            }
+            UnaryOperators::Minus => {
+                gen.emit(&format!("{}.const 0", value_type));
+                self.value.to_wat(gen)?;
+                gen.emit(&format!("{}.sub", value_type));
+                gen.normalize(&type_specifier);
+            }
+            UnaryOperators::LogicalNot => {
+                self.value.to_wat(gen)?;
+                gen.emit("i32.eqz");
+            }
+            UnaryOperators::BitwiseNot if type_specifier == TypeSpecifier::Bool => {
+                self.value.to_wat(gen)?;
+                gen.emit("i32.const 1");
+                gen.emit("i32.xor");
+            }
+            UnaryOperators::BitwiseNot => {
+                self.value.to_wat(gen)?;
+                gen.emit(&format!("{}.const -1", value_type));
+                gen.emit(&format!("{}.xor", value_type));
+                gen.normalize(&type_specifier);
+            }
+            _ => self.value.to_wat(gen)?,
+        }
+        return Ok(());
+    }
+}
+
+impl<'a> ToWat<'a> for ArrayIndexExpression {
+    fn to_wat(&'a self, gen: &mut WatGenerator<'a>) -> Result<()> {
+        let element = gen.index_address(self)?;
+        return gen.load(&element);
+    }
+}
+
+impl<'a> ToWat<'a> for MemberAccessExpression {
+    fn to_wat(&'a self, gen: &mut WatGenerator<'a>) -> Result<()> {
+        let object = gen.object_address(&self.object, &self.access)?;
+        if let Member::Address(type_specifier) = gen.member(object, &self.property)? {
+            gen.load(&type_specifier)?;
+        }
+        return Ok(());
+    }
+}
+
+impl<'a> ToWat<'a> for FnCallExpression {
+    fn to_wat(&'a self, gen: &mut WatGenerator<'a>) -> Result<()> {
+        let name = self.fn_name.identifier()?.id.to_string();
+        let return_type = gen.context.fn_call_signature(self)?.return_type.clone();
+        let name = match &self.namespace {
+            Some(namespace) => {
+                let struct_name = namespace.name.identifier()?;
+                WatGenerator::method_name(struct_name.id.as_ref(), &name)
+            }
+            None => name,
+        };
+        return gen.call(&name, None, &self.args, &return_type);
+    }
+}
+
+impl<'a> ToWat<'a> for ArrayInitExpression {
+    fn to_wat(&'a self, gen: &mut WatGenerator<'a>) -> Result<()> {
+        let first = self
+            .array
+            .first()
+            .ok_or_else(|| CompilerError::CodeGen(String::from("Empty array init expression")))?;
+        let element = gen.context.expr_type(first)?;
+        let stride = gen.size_of(&element)?;
+        let array = gen.slot(&TypeSpecifier::array(element.clone(), self.size))?;
+        for (index, item) in self.array.iter().enumerate() {
+            gen.frame_address(array + index as i32 * stride);
+            item.to_wat(gen)?;
+            gen.store(&element)?;
+        }
+        gen.frame_address(array);
+        return Ok(());
+    }
+}
+
+impl<'a> ToWat<'a> for ObjectInitExpression {
+    /// Missing fields stay zero.
+    fn to_wat(&'a self, gen: &mut WatGenerator<'a>) -> Result<()> {
+        let identifier = self.object.identifier()?.id.clone();
+        let name = identifier.to_string();
+        let type_specifier = TypeSpecifier::UserDefine(identifier);
+        let object = gen.slot(&type_specifier)?;
+        gen.frame_address(object);
+        gen.zero(&type_specifier)?;
+        for field in self.fields.iter() {
+            let (offset, field_type) = gen.field(&name, field.name.as_ref())?;
+            gen.frame_address(object + offset);
+            field.value.to_wat(gen)?;
+            gen.store(&field_type)?;
+        }
+        gen.frame_address(object);
+        return Ok(());
+    }
+}
+
+impl<'a> ToWat<'a> for CastExpression {
+    fn to_wat(&'a self, gen: &mut WatGenerator<'a>) -> Result<()> {
+        let source = gen.context.expr_type(&self.expression)?;
+        self.expression.to_wat(gen)?;
+        gen.convert(&source, &self.target_type);
+        if let TypeSpecifier::UserDefine(name) = &self.target_type {
+            if gen.context.is_enum(name.as_ref()) {
+                return gen.check_enum(name.as_ref());
+            }
+        }
+        return Ok(());
+    }
+}
+
+impl WatGenerator<'_> {
+    /// Traps with `unreachable` unless an item of the enum is stored as the
+    /// `i32` on top of the stack.
+    fn check_enum(&mut self, enum_name: &str) -> Result<()> {
+        let value = self.scratch("i32");
+        let valid = self.label();
+        self.emit(&format!("local.set {}", value));
+        self.open(&format!("block {}", valid));
+        for item in self.context.enum_values(enum_name)? {
+            self.emit(&format!("local.get {}", value));
+            self.emit(&format!("i32.const {}", item));
+            self.emit("i32.eq");
+            self.emit(&format!("br_if {}", valid));
+        }
+        self.emit("unreachable");
+        self.close();
+        self.emit(&format!("local.get {}", value));
+        return Ok(());
+    }
+}
+
+impl<'a> ToWat<'a> for IdentifierExpression {
+    fn to_wat(&'a self, gen: &mut WatGenerator<'a>) -> Result<()> {
+        let name = self.id.as_ref();
+        let type_specifier = gen.context.variable(name)?.clone();
+        let offset = gen.local(name)?;
+        gen.frame_address(offset);
+        return gen.load(&type_specifier);
+    }
+}
+
+impl<'a> ToWat<'a> for EnumItemExpression {
+    /// An item of a tagged enum is built like an object init of its layout.
+    fn to_wat(&'a self, gen: &mut WatGenerator<'a>) -> Result<()> {
+        let (name, item) = (self.name.as_ref(), self.item.as_ref());
+        let value = gen.context.enum_item(name, item)?;
+        if !gen.context.is_tagged(name) {
+            gen.emit(&format!("i32.const {}", value));
+            return Ok(());
+        }
+        let type_specifier = TypeSpecifier::UserDefine(self.name.clone());
+        let object = gen.slot(&type_specifier)?;
+        gen.frame_address(object);
+        gen.zero(&type_specifier)?;
+        let (offset, tag_type) = gen.field(name, EnumDeclaration::TAG)?;
+        gen.frame_address(object + offset);
+        gen.constant(&tag_type, &value.to_string());
+        gen.store(&tag_type)?;
+        for (index, arg) in self.args.iter().enumerate() {
+            let field = EnumDeclaration::payload_field(item, index);
+            let (offset, field_type) = gen.field(name, &field)?;
+            gen.frame_address(object + offset);
+            arg.to_wat(gen)?;
+            gen.store(&field_type)?;
+        }
+        gen.frame_address(object);
+        return Ok(());
+    }
+}
+
+impl<'a> ToWat<'a> for PrimitiveExpression {
+    fn to_wat(&'a self, gen: &mut WatGenerator<'a>) -> Result<()> {
+        let invalid = || CompilerError::CodeGen(format!("Invalid literal {}", self.value));
+        match self.type_specifier {
+            TypeSpecifier::Int => {
+                let value = self.value.parse::<i64>().map_err(|_| invalid())? as i32;
+                gen.emit(&format!("i32.const {}", value));
+            }
+            TypeSpecifier::Usize => {
+                let value: u64 = self.value.parse().map_err(|_| invalid())?;
+                gen.emit(&format!("i64.const {}", value));
+            }
+            TypeSpecifier::Bool => {
+                gen.emit(&format!("i32.const {}", (self.value == "true") as u8));
+            }
+            TypeSpecifier::Char => {
+                let value = *self.bytes().first().ok_or_else(invalid)?;
+                gen.emit(&format!("i32.const {}", value));
+            }
+            TypeSpecifier::Str => {
+                let address = gen.string(self.bytes());
+                gen.emit(&format!("i32.const {}", address));
+            }
+            TypeSpecifier::Float | TypeSpecifier::Double => {
+                let value: f64 = self.value.parse().map_err(|_| invalid())?;
+                let value_type = WatGenerator::value_type(&self.type_specifier).unwrap_or("f64");
+                gen.emit(&format!("{}.const {:?}", value_type, value));
+            }
+            _ => return Err(invalid()),
+        }
+        return Ok(());
+    }
+}
@@ -0,0 +1,336 @@
+pub mod expressions;
+pub mod program;
+pub mod statements;
+
+use std::collections::HashMap;
+
+use crate::{
+    ast::{expressions::Expression, program::Program, type_specifier::TypeSpecifier},
+    error::{CompilerError, Result},
+};
+
+use super::{
+    loops::{Defers, LoopTargets, Loops},
+    type_context::TypeContext,
+};
+
+const INDENT: &str = "  ";
+
+/// Pages of linear memory, the shadow stack starts at the end.
+const MEMORY_PAGES: i32 = 2;
+
+/// String literals are placed from here, address 0 stays the null pointer.
+const DATA_START: i32 = 16;
+
+/// Lowers an analyzed program to a WebAssembly text module (`.wat`).
+///
+/// Variables live in frames of a shadow stack in linear memory, addressed
+/// through the `$fp` local of every function, so pointers are plain `i32`
+/// addresses. Structs and arrays are handled through their address: the
+/// callee copies aggregate arguments and writes aggregate results through a
+/// hidden pointer passed first. `pub` functions and `main` are exported.
+pub struct WatGenerator<'a> {
+    program: &'a Program,
+    pub(crate) context: TypeContext,
+    layouts: HashMap<String, StructLayout>,
+    data: Vec<u8>,
+    strings: HashMap<Vec<u8>, i32>,
+    code: Vec<String>,
+    locals: Vec<(String, &'static str)>,
+    indent: usize,
+    scopes: Vec<HashMap<String, i32>>,
+    defers: Defers<&'a Expression>,
+    loops: Loops<LoopTargets<String>>,
+    frame_size: i32,
+    next_id: usize,
+    return_type: TypeSpecifier,
+    result_slot: Option<i32>,
+    is_main: bool,
+}
+
+pub(crate) struct StructLayout {
+    fields: Vec<(String, i32, TypeSpecifier)>,
+    size: i32,
+    align: i32,
+}
+
+pub trait ToWat<'a> {
+    fn to_wat(&'a self, gen: &mut WatGenerator<'a>) -> Result<()>;
+}
+
+impl<'a> WatGenerator<'a> {
+    pub fn new(program: &'a Program) -> Self {
+        Self {
+            program,
+            context: TypeContext::new(program),
+            layouts: HashMap::new(),
+            data: Vec::new(),
+            strings: HashMap::new(),
+            code: Vec::new(),
+            locals: Vec::new(),
+            indent: 0,
+            scopes: Vec::new(),
+            defers: Defers::default(),
+            loops: Loops::default(),
+            frame_size: 0,
+            next_id: 0,
+            return_type: TypeSpecifier::Void,
+            result_slot: None,
+            is_main: false,
+        }
+    }
+
+    pub fn generate(mut self) -> Result<String> {
+        let program = self.program;
+        let (functions, exports) = program.functions_to_wat(&mut self)?;
+
+        let mut content = String::from(";; Generated by the Marble compiler.\n(module\n");
+        content += &format!("  (memory (export \"memory\") {})\n", MEMORY_PAGES);
+        content += &format!(
+            "  (global $sp (mut i32) (i32.const {}))\n",
+            MEMORY_PAGES * 65536
+        );
+        if !self.data.is_empty() {
+            content += &format!(
+                "  (data (i32.const {}) \"{}\")\n",
+                DATA_START,
+                Self::escape(&self.data)
+            );
+        }
+        for export in exports.iter() {
+            content += &format!("  (export \"{}\" (func ${}))\n", export, export);
+        }
+        for function in functions.iter() {
+            content += function;
+        }
+        content += ")\n";
+        return Ok(content);
+    }
+
+    pub(crate) fn emit(&mut self, instruction: &str) {
+        let indent = INDENT.repeat(self.indent + 2);
+        self.code.push(format!("{}{}", indent, instruction));
+    }
+
+    /// Emits `block`, `loop` or `if` and indents what follows up to `end`.
+    pub(crate) fn open(&mut self, instruction: &str) {
+        self.emit(instruction);
+        self.indent += 1;
+    }
+
+    pub(crate) fn otherwise(&mut self) {
+        self.indent -= 1;
+        self.emit("else");
+        self.indent += 1;
+    }
+
+    pub(crate) fn close(&mut self) {
+        self.indent -= 1;
+        self.emit("end");
+    }
+
+    pub(crate) fn label(&mut self) -> String {
+        self.next_id += 1;
+        return format!("$L{}", self.next_id);
+    }
+
+    /// Declares a scratch local of the wasm type `value_type`.
+    pub(crate) fn scratch(&mut self, value_type: &'static str) -> String {
+        self.next_id += 1;
+        let name = format!("$s{}", self.next_id);
+        self.locals.push((name.clone(), value_type));
+        return name;
+    }
+
+    pub(crate) fn push_scope(&mut self) {
+        self.context.push_scope();
+        self.scopes.push(HashMap::new());
+        self.defers.push_scope();
+    }
+
+    pub(crate) fn pop_scope(&mut self) {
+        self.context.pop_scope();
+        self.scopes.pop();
+        self.defers.pop_scope();
+    }
+
+    /// Reserves frame memory and returns its offset from `$fp`.
+    pub(crate) fn slot(&mut self, type_specifier: &TypeSpecifier) -> Result<i32> {
+        let size = self.size_of(type_specifier)?.max(1);
+        let align = self.align_of(type_specifier)?;
+        let offset = (self.frame_size + align - 1) / align * align;
+        self.frame_size = offset + size;
+        return Ok(offset);
+    }
+
+    pub(crate) fn declare(&mut self, name: &str, type_specifier: &TypeSpecifier) -> Result<i32> {
+        let offset = self.slot(type_specifier)?;
+        self.context.declare(name, type_specifier.clone());
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_owned(), offset);
+        }
+        return Ok(offset);
+    }
+
+    pub(crate) fn local(&self, name: &str) -> Result<i32> {
+        return self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .copied()
+            .ok_or_else(|| CompilerError::CodeGen(format!("Unknown variable {}", name)));
+    }
+
+    /// Pushes the address of the frame slot at `offset`.
+    pub(crate) fn frame_address(&mut self, offset: i32) {
+        self.emit("local.get $fp");
+        if offset > 0 {
+            self.emit(&format!("i32.const {}", offset));
+            self.emit("i32.add");
+        }
+    }
+
+    pub(crate) fn is_aggregate(&self, type_specifier: &TypeSpecifier) -> bool {
+        return match type_specifier {
+            TypeSpecifier::ArrayType { .. } => true,
+            TypeSpecifier::UserDefine(name) => self.context.is_struct(name.as_ref()),
+            _ => false,
+        };
+    }
+
+    /// The wasm value type representing `type_specifier`, `None` for void.
+    pub(crate) fn value_type(type_specifier: &TypeSpecifier) -> Option<&'static str> {
+        return match type_specifier {
+            TypeSpecifier::Void => None,
+            TypeSpecifier::Usize => Some("i64"),
+            TypeSpecifier::Float => Some("f32"),
+            TypeSpecifier::Double => Some("f64"),
+            _ => Some("i32"),
+        };
+    }
+
+    pub(crate) fn size_of(&self, type_specifier: &TypeSpecifier) -> Result<i32> {
+        return Ok(match type_specifier {
+            TypeSpecifier::Void => 0,
+            TypeSpecifier::Char | TypeSpecifier::Bool => 1,
+            TypeSpecifier::Usize | TypeSpecifier::Double => 8,
+            TypeSpecifier::ArrayType {
+                type_specifier,
+                size,
+                ..
+            } => self.size_of(type_specifier)? * *size as i32,
+            TypeSpecifier::UserDefine(name) if self.context.is_struct(name.as_ref()) => {
+                self.layout(name.as_ref())?.size
+            }
+            _ => 4,
+        });
+    }
+
+    pub(crate) fn align_of(&self, type_specifier: &TypeSpecifier) -> Result<i32> {
+        return Ok(match type_specifier {
+            TypeSpecifier::ArrayType { type_specifier, .. } => self.align_of(type_specifier)?,
+            TypeSpecifier::UserDefine(name) if self.context.is_struct(name.as_ref()) => {
+                self.layout(name.as_ref())?.align
+            }
+            type_specifier => self.size_of(type_specifier)?.max(1),
+        });
+    }
+
+    fn layout(&self, name: &str) -> Result<&StructLayout> {
+        return self.layouts.get(name).ok_or_else(|| {
+            CompilerError::CodeGen(format!("Layout of struct {} is unknown", name))
+        });
+    }
+
+    /// Offset and type of the field `name` of the struct `struct_name`.
+    pub(crate) fn field(&self, struct_name: &str, name: &str) -> Result<(i32, TypeSpecifier)> {
+        return self
+            .layout(struct_name)?
+            .fields
+            .iter()
+            .find(|(field, _, _)| field == name)
+            .map(|(_, offset, type_specifier)| (*offset, type_specifier.clone()))
+            .ok_or_else(|| {
+                CompilerError::CodeGen(format!("Unknown field {}.{}", struct_name, name))
+            });
+    }
+
+    /// Replaces the address on the stack with the value it points to.
+    /// Aggregates are represented by their address.
+    pub(crate) fn load(&mut self, type_specifier: &TypeSpecifier) -> Result<()> {
+        if self.is_aggregate(type_specifier) {
+            return Ok(());
+        }
+        let instruction = match type_specifier {
+            TypeSpecifier::Char | TypeSpecifier::Bool => "i32.load8_u",
+            TypeSpecifier::Usize => "i64.load",
+            TypeSpecifier::Float => "f32.load",
+            TypeSpecifier::Double => "f64.load",
+            _ => "i32.load",
+        };
+        self.emit(instruction);
+        return Ok(());
+    }
+
+    /// Pops a value and the address below it and stores the value, copying
+    /// the pointed memory for aggregates.
+    pub(crate) fn store(&mut self, type_specifier: &TypeSpecifier) -> Result<()> {
+        if self.is_aggregate(type_specifier) {
+            let size = self.size_of(type_specifier)?;
+            self.emit(&format!("i32.const {}", size));
+            self.emit("memory.copy");
+            return Ok(());
+        }
+        let instruction = match type_specifier {
+            TypeSpecifier::Char | TypeSpecifier::Bool => "i32.store8",
+            TypeSpecifier::Usize => "i64.store",
+            TypeSpecifier::Float => "f32.store",
+            TypeSpecifier::Double => "f64.store",
+            _ => "i32.store",
+        };
+        self.emit(instruction);
+        return Ok(());
+    }
+
+    /// Fills the memory at the address on the stack with the default value.
+    pub(crate) fn zero(&mut self, type_specifier: &TypeSpecifier) -> Result<()> {
+        if self.is_aggregate(type_specifier) {
+            let size = self.size_of(type_specifier)?;
+            self.emit("i32.const 0");
+            self.emit(&format!("i32.const {}", size));
+            self.emit("memory.fill");
+            return Ok(());
+        }
+        self.constant(type_specifier, "0");
+        return self.store(type_specifier);
+    }
+
+    pub(crate) fn constant(&mut self, type_specifier: &TypeSpecifier, value: &str) {
+        if let Some(value_type) = Self::value_type(type_specifier) {
+            self.emit(&format!("{}.const {}", value_type, value));
+        }
+    }
+
+    /// Adds a string literal to the data segment and returns its address.
+    pub(crate) fn string(&mut self, bytes: Vec<u8>) -> i32 {
+        if let Some(address) = self.strings.get(&bytes) {
+            return *address;
+        }
+        let address = DATA_START + self.data.len() as i32;
+        self.data.extend_from_slice(&bytes);
+        self.data.push(0);
+        self.strings.insert(bytes, address);
+        return address;
+    }
+
+    fn escape(bytes: &[u8]) -> String {
+        return bytes
+            .iter()
+            .map(|byte| match byte {
+                b' '..=b'~' if *byte != b'"' && *byte != b'\\' => (*byte as char).to_string(),
+                _ => format!("\\{:02x}", byte),
+            })
+            .collect();
+    }
+}
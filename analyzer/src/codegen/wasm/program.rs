@@ -0,0 +1,190 @@
+use crate::{
+    ast::{
+        access_specifier::AccessSpecifier, declarations::Declarations, program::Program,
+        statements::block_stmt::BlockStmt, type_specifier::TypeSpecifier,
+        variable_type::VariableType,
+    },
+    error::{CompilerError, Result},
+};
+
+use super::{StructLayout, ToWat, WatGenerator};
+
+impl Program {
+    /// Returns the function definitions and the names to export.
+    pub(crate) fn functions_to_wat<'a>(
+        &'a self,
+        gen: &mut WatGenerator<'a>,
+    ) -> Result<(Vec<String>, Vec<String>)> {
+        for struct_decl in self.sorted_structs()? {
+            let mut layout = StructLayout {
+                fields: Vec::new(),
+                size: 0,
+                align: 1,
+            };
+            for field in struct_decl.fields.iter() {
+                let type_specifier = &field.field.type_specifier;
+                let align = gen.align_of(type_specifier)?;
+                let offset = (layout.size + align - 1) / align * align;
+                layout.fields.push((
+                    field.field.identifier.to_string(),
+                    offset,
+                    type_specifier.clone(),
+                ));
+                layout.size = offset + gen.size_of(type_specifier)?;
+                layout.align = layout.align.max(align);
+            }
+            layout.size = (layout.size + layout.align - 1) / layout.align * layout.align;
+            gen.layouts.insert(struct_decl.name.to_string(), layout);
+        }
+
+        let mut functions = Vec::new();
+        let mut exports = Vec::new();
+        let mut has_main = false;
+        for decl in self.declarations.iter() {
+            match decl {
+                Declarations::Fn(fn_decl) => {
+                    let name = fn_decl.fn_name.as_ref();
+                    gen.is_main = name == "main";
+                    has_main |= gen.is_main;
+                    if gen.is_main && !fn_decl.params.is_empty() {
+                        return Err(CompilerError::CodeGen(String::from(
+                            "main function cannot take parameters",
+                        )));
+                    }
+                    if gen.is_main || matches!(fn_decl.access_specifier, AccessSpecifier::Public) {
+                        exports.push(name.to_owned());
+                    }
+                    let params: Vec<&VariableType> = fn_decl.params.iter().collect();
+                    functions.push(gen.function(
+                        name,
+                        &params,
+                        &fn_decl.return_type,
+                        &fn_decl.body,
+                    )?);
+                    gen.is_main = false;
+                }
+                Declarations::Impl(impl_decl) => {
+                    let struct_name = impl_decl.name.to_symbol()?;
+                    for member_fn in impl_decl.member_functions.iter() {
+                        let name = WatGenerator::method_name(
+                            struct_name,
+                            member_fn.prototype.name.as_ref(),
+                        );
+                        if matches!(
+                            member_fn.prototype.access_specifier,
+                            AccessSpecifier::Public
+                        ) {
+                            exports.push(name.clone());
+                        }
+                        functions.push(gen.function(
+                            &name,
+                            &member_fn.params(),
+                            &member_fn.prototype.return_type,
+                            &member_fn.body,
+                        )?);
+                    }
+                }
+                _ => {}
+            }
+        }
+        if !has_main {
+            return Err(CompilerError::CodeGen(String::from(
+                "Program has no main function",
+            )));
+        }
+        return Ok((functions, exports));
+    }
+}
+
+impl<'a> WatGenerator<'a> {
+    pub(crate) fn method_name(struct_name: &str, name: &str) -> String {
+        return format!("{}.{}", struct_name, name);
+    }
+
+    /// The wasm result type of a function, `main` always returns an `i32`.
+    pub(crate) fn result_type(&self, return_type: &TypeSpecifier) -> Option<&'static str> {
+        if self.is_main {
+            return Some("i32");
+        }
+        return Self::value_type(return_type);
+    }
+
+    fn function(
+        &mut self,
+        name: &str,
+        params: &[&VariableType],
+        return_type: &TypeSpecifier,
+        body: &'a BlockStmt,
+    ) -> Result<String> {
+        self.code = Vec::new();
+        self.locals = Vec::new();
+        self.indent = 0;
+        self.frame_size = 0;
+        self.return_type = return_type.clone();
+        self.result_slot = None;
+
+        let mut signature = String::new();
+        if self.is_aggregate(return_type) {
+            let slot = self.slot(&TypeSpecifier::Int)?;
+            self.frame_address(slot);
+            self.emit("local.get $result");
+            self.emit("i32.store");
+            self.result_slot = Some(slot);
+            signature += " (param $result i32)";
+        }
+        for param in params.iter() {
+            let value_type = Self::value_type(&param.type_specifier).unwrap_or("i32");
+            signature += &format!(" (param $arg.{} {})", param.identifier, value_type);
+        }
+        if let Some(result_type) = self.result_type(return_type) {
+            signature += &format!(" (result {})", result_type);
+        }
+
+        self.push_scope();
+        let result = self.body(params, body);
+        self.pop_scope();
+        result?;
+
+        if !body.ends_with_return() {
+            self.epilogue();
+            if let Some(result_slot) = self.result_slot {
+                self.frame_address(result_slot);
+                self.emit("i32.load");
+            } else if let Some(result_type) = self.result_type(return_type) {
+                self.emit(&format!("{}.const 0", result_type));
+            }
+        }
+
+        let frame_size = (self.frame_size + 15) / 16 * 16;
+        let mut content = format!("  (func ${}{}\n", name, signature);
+        content += "    (local $fp i32) (local $saved i32)\n";
+        for (local, value_type) in self.locals.iter() {
+            content += &format!("    (local {} {})\n", local, value_type);
+        }
+        content += "    global.get $sp\n    local.tee $saved\n";
+        content += &format!("    i32.const {}\n    i32.sub\n", frame_size);
+        content += "    local.tee $fp\n    global.set $sp\n";
+        for line in self.code.iter() {
+            content += line;
+            content += "\n";
+        }
+        content += "  )\n";
+        return Ok(content);
+    }
+
+    fn body(&mut self, params: &[&VariableType], body: &'a BlockStmt) -> Result<()> {
+        for param in params.iter() {
+            let offset = self.declare(param.identifier.as_ref(), &param.type_specifier)?;
+            self.frame_address(offset);
+            self.emit(&format!("local.get $arg.{}", param.identifier));
+            self.store(&param.type_specifier)?;
+        }
+        return body.to_wat(self);
+    }
+
+    /// Releases the frame of the current function.
+    pub(crate) fn epilogue(&mut self) {
+        self.emit("local.get $saved");
+        self.emit("global.set $sp");
+    }
+}
@@ -0,0 +1,414 @@
+use crate::{
+    ast::{
+        declarations::enum_decl::EnumDeclaration,
+        expressions::Expression,
+        identifier::Identifier,
+        statements::{
+            block_stmt::BlockStmt,
+            break_stmt::BreakStmt,
+            continue_stmt::ContinueStmt,
+            defer_stmt::DeferStmt,
+            do_while_stmt::DoWhileStmt,
+            for_stmt::{ForStmt, VariableInit},
+            if_stmt::IfStmt,
+            let_stmt::LetStmt,
+            match_stmt::{MatchArm, MatchStmt},
+            return_stmt::ReturnStmt,
+            switch_stmt::SwitchStmt,
+            while_stmt::WhileStmt,
+            Statements,
+        },
+        type_specifier::TypeSpecifier,
+    },
+    codegen::{
+        loops::{Jump, LoopTargets},
+        type_context::TypeContext,
+    },
+    error::{CompilerError, Result},
+};
+
+use super::{ToWat, WatGenerator};
+
+impl<'a> ToWat<'a> for Statements {
+    fn to_wat(&'a self, gen: &mut WatGenerator<'a>) -> Result<()> {
+        return match self {
+            Statements::Let(let_stmt) => let_stmt.to_wat(gen),
+            Statements::Const(_) => Ok(()),
+            Statements::Return(return_stmt) => return_stmt.to_wat(gen),
+            Statements::Defer(defer_stmt) => defer_stmt.to_wat(gen),
+            Statements::For(for_stmt) => for_stmt.to_wat(gen),
+            Statements::While(while_stmt) => while_stmt.to_wat(gen),
+            Statements::DoWhile(do_while_stmt) => do_while_stmt.to_wat(gen),
+            Statements::If(if_stmt) => if_stmt.to_wat(gen),
+            Statements::Switch(switch_stmt) => switch_stmt.to_wat(gen),
+            Statements::Match(match_stmt) => match_stmt.to_wat(gen),
+            Statements::Break(break_stmt) => break_stmt.to_wat(gen),
+            Statements::Continue(continue_stmt) => continue_stmt.to_wat(gen),
+            Statements::Block(block_stmt) => block_stmt.to_wat(gen),
+            Statements::Expression(expr) => gen.discard(expr),
+        };
+    }
+}
+
+impl<'a> WatGenerator<'a> {
+    /// Evaluates `expr` for its side effects only.
+    pub(crate) fn discard(&mut self, expr: &'a Expression) -> Result<()> {
+        let type_specifier = self.context.expr_type(expr)?;
+        expr.to_wat(self)?;
+        if type_specifier != TypeSpecifier::Void {
+            self.emit("drop");
+        }
+        return Ok(());
+    }
+
+    /// Branches to `target` when `condition` is false.
+    fn break_unless(&mut self, condition: &'a Expression, target: &str) -> Result<()> {
+        condition.to_wat(self)?;
+        self.emit("i32.eqz");
+        self.emit(&format!("br_if {}", target));
+        return Ok(());
+    }
+
+    /// Lowers the body of a loop labeled `label`, whose `continue`s branch
+    /// to `next` and `break`s to `end`.
+    fn loop_body(
+        &mut self,
+        label: Option<&Identifier>,
+        block: &'a BlockStmt,
+        next: &str,
+        end: &str,
+    ) -> Result<()> {
+        let targets = LoopTargets {
+            next: next.to_owned(),
+            end: end.to_owned(),
+        };
+        self.loops.push(label, self.defers.depth(), targets);
+        let result = block.to_wat(self);
+        self.loops.pop()?;
+        return result;
+    }
+
+    /// Lowers a `break` or `continue` to the loop labeled `label`, which
+    /// runs the deferred expressions of the blocks it leaves first.
+    fn leave_loop(&mut self, label: Option<&Identifier>, jump: Jump) -> Result<()> {
+        let target = self.loops.target(label)?;
+        let defers = self.defers.leaving(target);
+        let target = target.targets.get(jump);
+        for expr in defers {
+            self.discard(expr)?;
+        }
+        self.emit(&format!("br {}", target));
+        return Ok(());
+    }
+}
+
+impl<'a> ToWat<'a> for BlockStmt {
+    fn to_wat(&'a self, gen: &mut WatGenerator<'a>) -> Result<()> {
+        gen.push_scope();
+        let result = self.stmts_to_wat(gen);
+        gen.pop_scope();
+        return result;
+    }
+}
+
+impl BlockStmt {
+    fn stmts_to_wat<'a>(&'a self, gen: &mut WatGenerator<'a>) -> Result<()> {
+        for stmt in self.stmts.iter() {
+            stmt.to_wat(gen)?;
+        }
+        if !self.ends_with_return() {
+            for expr in gen.defers.pending(1) {
+                gen.discard(expr)?;
+            }
+        }
+        return Ok(());
+    }
+}
+
+impl<'a> ToWat<'a> for LetStmt {
+    fn to_wat(&'a self, gen: &mut WatGenerator<'a>) -> Result<()> {
+        let type_specifier = self.type_specifier.as_ref().ok_or_else(|| {
+            CompilerError::CodeGen(format!("Type of {} is unknown", self.identifier))
+        })?;
+        match &self.value {
+            Some(value) => {
+                // The value is evaluated before the variable is in scope.
+                let value_type = WatGenerator::value_type(type_specifier).unwrap_or("i32");
+                let temporary = gen.scratch(value_type);
+                value.to_wat(gen)?;
+                gen.emit(&format!("local.set {}", temporary));
+                let offset = gen.declare(self.identifier.as_ref(), type_specifier)?;
+                gen.frame_address(offset);
+                gen.emit(&format!("local.get {}", temporary));
+                gen.store(type_specifier)?;
+            }
+            None => {
+                let offset = gen.declare(self.identifier.as_ref(), type_specifier)?;
+                gen.frame_address(offset);
+                gen.zero(type_specifier)?;
+            }
+        }
+        return Ok(());
+    }
+}
+
+impl<'a> ToWat<'a> for ReturnStmt {
+    /// The value is computed before the deferred expressions run and stays
+    /// on the operand stack meanwhile.
+    fn to_wat(&'a self, gen: &mut WatGenerator<'a>) -> Result<()> {
+        let return_type = gen.return_type.clone();
+        if let Some(result_slot) = gen.result_slot {
+            gen.frame_address(result_slot);
+            gen.emit("i32.load");
+        }
+        match &self.value {
+            Some(value) => value.to_wat(gen)?,
+            None => {
+                if let Some(result_type) = gen.result_type(&return_type) {
+                    gen.emit(&format!("{}.const 0", result_type));
+                }
+            }
+        }
+        if gen.result_slot.is_some() {
+            gen.store(&return_type)?;
+        }
+
+        for expr in gen.defers.all() {
+            gen.discard(expr)?;
+        }
+        gen.epilogue();
+        if let Some(result_slot) = gen.result_slot {
+            gen.frame_address(result_slot);
+            gen.emit("i32.load");
+        }
+        gen.emit("return");
+        return Ok(());
+    }
+}
+
+impl<'a> ToWat<'a> for DeferStmt {
+    fn to_wat(&'a self, gen: &mut WatGenerator<'a>) -> Result<()> {
+        gen.defers.push(&self.expr);
+        return Ok(());
+    }
+}
+
+impl<'a> ToWat<'a> for BreakStmt {
+    fn to_wat(&'a self, gen: &mut WatGenerator<'a>) -> Result<()> {
+        return gen.leave_loop(self.label.as_ref(), Jump::Break);
+    }
+}
+
+impl<'a> ToWat<'a> for ContinueStmt {
+    fn to_wat(&'a self, gen: &mut WatGenerator<'a>) -> Result<()> {
+        return gen.leave_loop(self.label.as_ref(), Jump::Continue);
+    }
+}
+
+impl<'a> ToWat<'a> for ForStmt {
+    fn to_wat(&'a self, gen: &mut WatGenerator<'a>) -> Result<()> {
+        gen.push_scope();
+        let result = self.loop_to_wat(gen);
+        gen.pop_scope();
+        return result;
+    }
+}
+
+impl ForStmt {
+    fn loop_to_wat<'a>(&'a self, gen: &mut WatGenerator<'a>) -> Result<()> {
+        match &self.init {
+            VariableInit::Let(let_stmt) => let_stmt.to_wat(gen)?,
+            VariableInit::Assign(assign) => {
+                gen.assign(assign)?;
+                gen.emit("drop");
+            }
+        }
+        let end = gen.label();
+        let start = gen.label();
+        let next = gen.label();
+        gen.open(&format!("block {}", end));
+        gen.open(&format!("loop {}", start));
+        gen.break_unless(&self.condition, &end)?;
+        // `continue` leaves this block to reach the increment.
+        gen.open(&format!("block {}", next));
+        gen.loop_body(self.label.as_ref(), &self.block, &next, &end)?;
+        gen.close();
+        gen.discard(&self.increment)?;
+        gen.emit(&format!("br {}", start));
+        gen.close();
+        gen.close();
+        return Ok(());
+    }
+}
+
+impl<'a> ToWat<'a> for WhileStmt {
+    fn to_wat(&'a self, gen: &mut WatGenerator<'a>) -> Result<()> {
+        let end = gen.label();
+        let start = gen.label();
+        gen.open(&format!("block {}", end));
+        gen.open(&format!("loop {}", start));
+        gen.break_unless(&self.condition, &end)?;
+        gen.loop_body(self.label.as_ref(), &self.block, &start, &end)?;
+        gen.emit(&format!("br {}", start));
+        gen.close();
+        gen.close();
+        return Ok(());
+    }
+}
+
+impl<'a> ToWat<'a> for DoWhileStmt {
+    fn to_wat(&'a self, gen: &mut WatGenerator<'a>) -> Result<()> {
+        let end = gen.label();
+        let start = gen.label();
+        let next = gen.label();
+        gen.open(&format!("block {}", end));
+        gen.open(&format!("loop {}", start));
+        // `continue` leaves this block to reach the condition.
+        gen.open(&format!("block {}", next));
+        gen.loop_body(self.label.as_ref(), &self.block, &next, &end)?;
+        gen.close();
+        gen.break_unless(&self.condition, &end)?;
+        gen.emit(&format!("br {}", start));
+        gen.close();
+        gen.close();
+        return Ok(());
+    }
+}
+
+impl<'a> ToWat<'a> for IfStmt {
+    /// Every `else if` nests inside the `else` of the previous branch.
+    fn to_wat(&'a self, gen: &mut WatGenerator<'a>) -> Result<()> {
+        let branches = std::iter::once((&self.condition, &self.block)).chain(
+            self.else_ifs
+                .iter()
+                .map(|else_if| (&else_if.condition, &else_if.block)),
+        );
+        let mut depth = 0;
+        for (condition, block) in branches {
+            if depth > 0 {
+                gen.otherwise();
+            }
+            condition.to_wat(gen)?;
+            gen.open("if");
+            block.to_wat(gen)?;
+            depth += 1;
+        }
+        if let Some(else_stmt) = &self.else_stmt {
+            gen.otherwise();
+            else_stmt.to_wat(gen)?;
+        }
+        for _ in 0..depth {
+            gen.close();
+        }
+        return Ok(());
+    }
+}
+
+impl<'a> ToWat<'a> for SwitchStmt {
+    /// Keeps the value in a scratch local and tests the cases like a chain
+    /// of `else if`s.
+    fn to_wat(&'a self, gen: &mut WatGenerator<'a>) -> Result<()> {
+        let type_specifier = gen.context.expr_type(&self.value)?;
+        let value_type = WatGenerator::value_type(&type_specifier)
+            .ok_or_else(|| CompilerError::CodeGen(String::from("Switch over void")))?;
+        let scratch = gen.scratch(value_type);
+        self.value.to_wat(gen)?;
+        gen.emit(&format!("local.set {}", scratch));
+        let mut depth = 0;
+        for case in self.cases.iter() {
+            if depth > 0 {
+                gen.otherwise();
+            }
+            let value = gen.context.case_value(&case.value)?;
+            gen.emit(&format!("local.get {}", scratch));
+            gen.constant(&type_specifier, &value.to_string());
+            gen.emit(&format!("{}.eq", value_type));
+            gen.open("if");
+            case.block.to_wat(gen)?;
+            depth += 1;
+        }
+        if let Some(default) = &self.default {
+            if depth > 0 {
+                gen.otherwise();
+            }
+            default.to_wat(gen)?;
+        }
+        for _ in 0..depth {
+            gen.close();
+        }
+        return Ok(());
+    }
+}
+
+impl<'a> ToWat<'a> for MatchStmt {
+    /// A tagged value is copied to the frame, where the arms read the
+    /// carried values from. The tag is kept in a scratch local and the arms
+    /// are tested like a chain of `else if`s.
+    fn to_wat(&'a self, gen: &mut WatGenerator<'a>) -> Result<()> {
+        let type_specifier = gen.context.expr_type(&self.value)?;
+        let enum_name = TypeContext::struct_name(&type_specifier)?.to_owned();
+        let tag = gen.scratch("i32");
+        let mut object = 0;
+        if gen.context.is_tagged(&enum_name) {
+            object = gen.slot(&type_specifier)?;
+            gen.frame_address(object);
+            self.value.to_wat(gen)?;
+            gen.store(&type_specifier)?;
+            let (offset, tag_type) = gen.field(&enum_name, EnumDeclaration::TAG)?;
+            gen.frame_address(object + offset);
+            gen.load(&tag_type)?;
+        } else {
+            self.value.to_wat(gen)?;
+        }
+        gen.emit(&format!("local.set {}", tag));
+        let mut depth = 0;
+        for arm in self.arms.iter() {
+            if depth > 0 {
+                gen.otherwise();
+            }
+            let value = gen
+                .context
+                .enum_item(arm.name.as_ref(), arm.item.as_ref())?;
+            gen.emit(&format!("local.get {}", tag));
+            gen.emit(&format!("i32.const {}", value));
+            gen.emit("i32.eq");
+            gen.open("if");
+            gen.push_scope();
+            let result = arm.to_wat_from(object, gen);
+            gen.pop_scope();
+            result?;
+            depth += 1;
+        }
+        if let Some(default) = &self.default {
+            if depth > 0 {
+                gen.otherwise();
+            }
+            default.to_wat(gen)?;
+        }
+        for _ in 0..depth {
+            gen.close();
+        }
+        return Ok(());
+    }
+}
+
+impl MatchArm {
+    /// Binds the values carried in the tagged enum at frame offset `object`,
+    /// then runs the block.
+    fn to_wat_from<'a>(&'a self, object: i32, gen: &mut WatGenerator<'a>) -> Result<()> {
+        let (name, item) = (self.name.as_ref(), self.item.as_ref());
+        for (index, binding) in self.bindings.iter().enumerate() {
+            let Some(binding) = binding else {
+                continue;
+            };
+            let field = EnumDeclaration::payload_field(item, index);
+            let (offset, field_type) = gen.field(name, &field)?;
+            let slot = gen.declare(binding.as_ref(), &field_type)?;
+            gen.frame_address(slot);
+            gen.frame_address(object + offset);
+            gen.load(&field_type)?;
+            gen.store(&field_type)?;
+        }
+        return self.block.to_wat(gen);
+    }
+}
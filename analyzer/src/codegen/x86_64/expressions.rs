@@ -0,0 +1,624 @@
+use crate::{
+    ast::{
+        declarations::enum_decl::EnumDeclaration,
+        expressions::{
+            array_index_expr::ArrayIndexExpression,
+            array_init_expr::ArrayInitExpression,
+            assignment_expr::AssignmentExpression,
+            binary_expr::{BinaryExpression, BinaryOperators},
+            cast_expr::CastExpression,
+            enum_item_expr::EnumItemExpression,
+            fn_call_expr::FnCallExpression,
+            identifier_expression::IdentifierExpression,
+            member_access_expr::MemberAccessExpression,
+            object_init_expr::ObjectInitExpression,
+            primitive_expr::PrimitiveExpression,
+            unary_expr::{UnaryExpression, UnaryExpressionType, UnaryOperators},
+            Expression,
+        },
+        type_specifier::TypeSpecifier,
+    },
+    codegen::type_context::{MemberChain, TypeContext},
+    error::{CompilerError, Result},
+    lexer::token_type::TokenType,
+};
+
+use super::{ToAsm, X86Generator, ARGUMENT_REGISTERS};
+
+/// What a member access left in `%rax`.
+pub(crate) enum Member {
+    Address(TypeSpecifier),
+    Value(TypeSpecifier),
+}
+
+impl<'a> ToAsm<'a> for Expression {
+    fn to_asm(&'a self, gen: &mut X86Generator<'a>) -> Result<()> {
+        return match self {
+            Expression::Binary(binary_expr) => binary_expr.to_asm(gen),
+            Expression::Assignment(assignment_expr) => gen.assign(assignment_expr),
+            Expression::Unary(unary_expr) => unary_expr.to_asm(gen),
+            Expression::ArrayIndex(array_index) => array_index.to_asm(gen),
+            Expression::MemberAccess(member_access) => member_access.to_asm(gen),
+            Expression::FnCall(fn_call_expr) => fn_call_expr.to_asm(gen),
+            Expression::ArrayInit(array_init_expr) => array_init_expr.to_asm(gen),
+            Expression::ObjectInit(object_init_expr) => object_init_expr.to_asm(gen),
+            Expression::Cast(cast_expr) => cast_expr.to_asm(gen),
+            Expression::Identifier(identifier_expr) => identifier_expr.to_asm(gen),
+            Expression::EnumItem(enum_item_expr) => enum_item_expr.to_asm(gen),
+            Expression::Primitive(primitive_expr) => primitive_expr.to_asm(gen),
+        };
+    }
+}
+
+impl<'a> X86Generator<'a> {
+    /// Leaves a pointer to the storage `expr` refers to in `%rax`. Scalars
+    /// that are not stored anywhere are copied to a temporary first.
+    pub(crate) fn address(&mut self, expr: &'a Expression) -> Result<()> {
+        match expr {
+            Expression::Identifier(identifier) => {
+                let offset = self.local(identifier.id.as_ref())?;
+                self.emit(&format!("leaq {}(%rbp), %rax", offset));
+            }
+            Expression::MemberAccess(member_access) => {
+                let object = self.object_address(&member_access.object, &member_access.access)?;
+                if let Member::Value(type_specifier) =
+                    self.member(object, &member_access.property)?
+                {
+                    self.spill(&type_specifier)?;
+                }
+            }
+            Expression::ArrayIndex(array_index) => {
+                self.index_address(array_index)?;
+            }
+            Expression::Unary(UnaryExpression {
+                operator: UnaryOperators::Indirection,
+                value,
+                ..
+            }) => value.to_asm(self)?,
+            _ => {
+                let type_specifier = self.context.expr_type(expr)?;
+                expr.to_asm(self)?;
+                self.spill(&type_specifier)?;
+            }
+        }
+        return Ok(());
+    }
+
+    /// Moves a scalar in `%rax` to a temporary and leaves its address instead.
+    fn spill(&mut self, type_specifier: &TypeSpecifier) -> Result<()> {
+        if self.is_aggregate(type_specifier) {
+            return Ok(());
+        }
+        let slot = self.slot(&TypeSpecifier::Usize)?;
+        self.emit(&format!("movq %rax, {}(%rbp)", slot));
+        self.emit(&format!("leaq {}(%rbp), %rax", slot));
+        return Ok(());
+    }
+
+    pub(crate) fn assign(&mut self, assignment: &'a AssignmentExpression) -> Result<()> {
+        let type_specifier = self.context.expr_type(&assignment.variable)?;
+        assignment.value.to_asm(self)?;
+        self.push("%rax");
+        self.address(&assignment.variable)?;
+        self.emit("movq %rax, %rcx");
+        self.pop("%rax");
+        return self.store(&type_specifier, "(%rcx)");
+    }
+
+    /// Leaves the address of the struct `object` refers to in `%rax` and returns its type.
+    fn object_address(
+        &mut self,
+        object: &'a Expression,
+        access: &TokenType,
+    ) -> Result<TypeSpecifier> {
+        let object_type = self.context.expr_type(object)?;
+        if access != &TokenType::Arrow {
+            self.address(object)?;
+            return Ok(object_type);
+        }
+        object.to_asm(self)?;
+        return match object_type {
+            TypeSpecifier::Pointer(inner) => Ok(*inner),
+            _ => Err(CompilerError::CodeGen(String::from(
+                "Arrow access only can use with pointers",
+            ))),
+        };
+    }
+
+    /// Calls a member function on the struct whose address is in `%rax`.
+    /// Receivers are passed by address either way, by value ones are copied
+    /// by the callee.
+    fn method_call(&mut self, struct_name: &str, fn_call: &'a FnCallExpression) -> Result<Member> {
+        let name = fn_call.fn_name.identifier()?.id.to_string();
+        let signature = self.context.method(struct_name, &name)?.clone();
+        let receiver = match signature.receiver {
+            Some(_) => {
+                let slot = self.slot(&TypeSpecifier::Usize)?;
+                self.emit(&format!("movq %rax, {}(%rbp)", slot));
+                Some(slot)
+            }
+            None => None,
+        };
+        self.call(
+            &X86Generator::method_name(struct_name, &name),
+            receiver,
+            &fn_call.args,
+            &signature.return_type,
+        )?;
+        return Ok(Member::Value(signature.return_type));
+    }
+
+    /// Evaluates the arguments from left to right into temporaries, then
+    /// passes the first six in registers and the rest on the stack.
+    fn call(
+        &mut self,
+        name: &str,
+        receiver: Option<i64>,
+        args: &'a [Expression],
+        return_type: &TypeSpecifier,
+    ) -> Result<()> {
+        let mut slots: Vec<i64> = receiver.into_iter().collect();
+        for arg in args.iter() {
+            let type_specifier = self.context.expr_type(arg)?;
+            arg.to_asm(self)?;
+            if self.is_aggregate(&type_specifier) {
+                let copy = self.slot(&type_specifier)?;
+                self.store(&type_specifier, &format!("{}(%rbp)", copy))?;
+                self.emit(&format!("leaq {}(%rbp), %rax", copy));
+            }
+            let slot = self.slot(&TypeSpecifier::Usize)?;
+            self.emit(&format!("movq %rax, {}(%rbp)", slot));
+            slots.push(slot);
+        }
+
+        let result = match self.is_aggregate(return_type) {
+            true => Some(self.slot(return_type)?),
+            false => None,
+        };
+        let mut registers = ARGUMENT_REGISTERS.iter().skip(result.is_some() as usize);
+        let in_registers = registers.len().min(slots.len());
+        let stack = &slots[in_registers..];
+        let padding = (self.depth + stack.len()) % 2;
+        if padding > 0 {
+            self.emit("subq $8, %rsp");
+        }
+        for slot in stack.iter().rev() {
+            self.emit(&format!("pushq {}(%rbp)", slot));
+        }
+        if let Some(result) = result {
+            self.emit(&format!("leaq {}(%rbp), %rdi", result));
+        }
+        for slot in slots[..in_registers].iter() {
+            let register = registers.next().unwrap_or(&"%rax");
+            self.emit(&format!("movq {}(%rbp), {}", slot, register));
+        }
+        self.emit(&format!("call {}", name));
+        let cleanup = 8 * (stack.len() + padding);
+        if cleanup > 0 {
+            self.emit(&format!("addq ${}, %rsp", cleanup));
+        }
+        if let Some(result) = result {
+            self.emit(&format!("leaq {}(%rbp), %rax", result));
+        }
+        return Ok(());
+    }
+
+    /// Leaves the address of the indexed element in `%rax` and returns its type.
+    fn index_address(&mut self, array_index: &'a ArrayIndexExpression) -> Result<TypeSpecifier> {
+        let element = match self.context.expr_type(&array_index.array)? {
+            TypeSpecifier::ArrayType { type_specifier, .. } => {
+                self.address(&array_index.array)?;
+                *type_specifier
+            }
+            TypeSpecifier::Pointer(type_specifier) => {
+                array_index.array.to_asm(self)?;
+                *type_specifier
+            }
+            _ => {
+                return Err(CompilerError::CodeGen(String::from(
+                    "Expect the array type",
+                )))
+            }
+        };
+        self.element_address(&element, &array_index.index)?;
+
+        let Some(second_index) = &array_index.second_index else {
+            return Ok(element);
+        };
+        let TypeSpecifier::ArrayType { type_specifier, .. } = element else {
+            return Err(CompilerError::CodeGen(String::from(
+                "Expect the two dimensional array type",
+            )));
+        };
+        self.element_address(&type_specifier, second_index)?;
+        return Ok(*type_specifier);
+    }
+
+    /// Moves the base address in `%rax` to the element `index`.
+    fn element_address(&mut self, element: &TypeSpecifier, index: &'a Expression) -> Result<()> {
+        let stride = self.size_of(element)?;
+        self.push("%rax");
+        index.to_asm(self)?;
+        if stride != 1 {
+            self.emit(&format!("imulq ${}, %rax", stride));
+        }
+        self.pop("%rcx");
+        self.emit("addq %rcx, %rax");
+        return Ok(());
+    }
+
+    fn is_signed(type_specifier: &TypeSpecifier) -> bool {
+        return matches!(
+            type_specifier,
+            TypeSpecifier::Int | TypeSpecifier::UserDefine(_)
+        );
+    }
+}
+
+/// The address of the struct is in `%rax`, `Object` is its type.
+impl<'a> MemberChain<'a> for X86Generator<'a> {
+    type Object = TypeSpecifier;
+    type Member = Member;
+
+    fn object_type(&self, object: &TypeSpecifier) -> Result<TypeSpecifier> {
+        return Ok(object.clone());
+    }
+
+    fn field_member(
+        &mut self,
+        _object: TypeSpecifier,
+        struct_name: &str,
+        name: &str,
+    ) -> Result<Member> {
+        let (offset, type_specifier) = self.field(struct_name, name)?;
+        if offset > 0 {
+            self.emit(&format!("addq ${}, %rax", offset));
+        }
+        return Ok(Member::Address(type_specifier));
+    }
+
+    fn method_member(
+        &mut self,
+        _object: TypeSpecifier,
+        struct_name: &str,
+        fn_call: &'a FnCallExpression,
+    ) -> Result<Member> {
+        return self.method_call(struct_name, fn_call);
+    }
+
+    fn member_object(&mut self, member: Member, access: &TokenType) -> Result<TypeSpecifier> {
+        let is_arrow = access == &TokenType::Arrow;
+        let inner = match member {
+            Member::Address(inner) if is_arrow => {
+                self.emit("movq (%rax), %rax");
+                inner
+            }
+            Member::Address(inner) | Member::Value(inner) => inner,
+        };
+        return Ok(match inner {
+            TypeSpecifier::Pointer(pointee) if is_arrow => *pointee,
+            inner => inner,
+        });
+    }
+}
+
+impl<'a> ToAsm<'a> for BinaryExpression {
+    fn to_asm(&'a self, gen: &mut X86Generator<'a>) -> Result<()> {
+        if let BinaryOperators::And | BinaryOperators::Or = self.operator {
+            // The left operand already is the result when it decides.
+            let end = gen.label();
+            self.left.to_asm(gen)?;
+            gen.emit("testq %rax, %rax");
+            if matches!(self.operator, BinaryOperators::And) {
+                gen.emit(&format!("je {}", end));
+            } else {
+                gen.emit(&format!("jne {}", end));
+            }
+            self.right.to_asm(gen)?;
+            gen.place_label(&end);
+            return Ok(());
+        }
+
+        let type_specifier = gen.context.expr_type(&self.left)?;
+        if matches!(type_specifier, TypeSpecifier::Float | TypeSpecifier::Double) {
+            return Err(X86Generator::unsupported_float());
+        }
+        let is_signed = X86Generator::is_signed(&type_specifier);
+
+        self.left.to_asm(gen)?;
+        gen.push("%rax");
+        self.right.to_asm(gen)?;
+        gen.emit("movq %rax, %rcx");
+        gen.pop("%rax");
+
+        let condition = match self.operator {
+            BinaryOperators::Equal => "e",
+            BinaryOperators::NotEqual => "ne",
+            BinaryOperators::GreaterThan if is_signed => "g",
+            BinaryOperators::GreaterThan => "a",
+            BinaryOperators::LessThan if is_signed => "l",
+            BinaryOperators::LessThan => "b",
+            BinaryOperators::GreaterThanOrEqual if is_signed => "ge",
+            BinaryOperators::GreaterThanOrEqual => "ae",
+            BinaryOperators::LessThanOrEqual if is_signed => "le",
+            BinaryOperators::LessThanOrEqual => "be",
+            _ => "",
+        };
+        if !condition.is_empty() {
+            gen.emit("cmpq %rcx, %rax");
+            gen.emit(&format!("set{} %al", condition));
+            gen.emit("movzbq %al, %rax");
+            return Ok(());
+        }
+
+        match self.operator {
+            BinaryOperators::Add => gen.emit("addq %rcx, %rax"),
+            BinaryOperators::Subtract => gen.emit("subq %rcx, %rax"),
+            BinaryOperators::Multiply => gen.emit("imulq %rcx, %rax"),
+            BinaryOperators::Divide | BinaryOperators::Modulo => {
+                if is_signed {
+                    gen.emit("cqto");
+                    gen.emit("idivq %rcx");
+                } else {
+                    gen.emit("xorl %edx, %edx");
+                    gen.emit("divq %rcx");
+                }
+                if matches!(self.operator, BinaryOperators::Modulo) {
+                    gen.emit("movq %rdx, %rax");
+                }
+            }
+            BinaryOperators::BitAnd => gen.emit("andq %rcx, %rax"),
+            BinaryOperators::BitOr => gen.emit("orq %rcx, %rax"),
+            BinaryOperators::BitXor => gen.emit("xorq %rcx, %rax"),
+            BinaryOperators::BitLeft | BinaryOperators::BitRight => {
+                // The amount wraps at the width of the type, not of the register.
+                let mask = TypeContext::shift_mask(&type_specifier);
+                gen.emit(&format!("andl ${}, %ecx", mask));
+                match self.operator {
+                    BinaryOperators::BitLeft => gen.emit("shlq %cl, %rax"),
+                    _ if is_signed => gen.emit("sarq %cl, %rax"),
+                    _ => gen.emit("shrq %cl, %rax"),
+                }
+            }
+            _ => unreachable!("Comparisons are handled above"),
+        }
+        return gen.normalize(&type_specifier);
+    }
+}
+
+impl<'a> ToAsm<'a> for UnaryExpression {
+    fn to_asm(&'a self, gen: &mut X86Generator<'a>) -> Result<()> {
+        let type_specifier = gen.context.expr_type(&self.value)?;
+        match self.operator {
+            UnaryOperators::Address => return gen.address(&self.value),
+            UnaryOperators::Indirection => {
+                let TypeSpecifier::Pointer(pointee) = type_specifier else {
+                    return Err(CompilerError::CodeGen(String::from(
+                        "Expected pointer type",
+                    )));
+                };
+                self.value.to_asm(gen)?;
+                return gen.load(&pointee, "(%rax)");
+            }
+            UnaryOperators::Increment | UnaryOperators::Decrement => {
+                let delta = match &type_specifier {
+                    TypeSpecifier::Pointer(pointee) => gen.size_of(pointee)?,
+                    TypeSpecifier::Float | TypeSpecifier::Double => {
+                        return Err(X86Generator::unsupported_float())
+                    }
+                    _ => 1,
+                };
+                let delta = if matches!(self.operator, UnaryOperators::Increment) {
+                    delta
+                } else {
+                    -delta
+                };
+                let postfix = matches!(self.expression_type, UnaryExpressionType::Postfix);
+                gen.address(&self.value)?;
+                gen.emit("movq %rax, %rcx");
+                gen.load(&type_specifier, "(%rcx)")?;
+                if postfix {
+                    gen.emit("movq %rax, %rdx");
+                }
+                gen.emit(&format!("addq ${}, %rax", delta));
+                gen.normalize(&type_specifier)?;
+                gen.store(&type_specifier, "(%rcx)")?;
+                if postfix {
+                    gen.emit("movq %rdx, %rax");
+                }
+                return Ok(());
+            }
+            _ => {}
+        }
+        if matches!(type_specifier, TypeSpecifier::Float | TypeSpecifier::Double) {
+            return Err(X86Generator::unsupported_float());
+        }
+        self.value.to_asm(gen)?;
+        match self.operator {
+            UnaryOperators::Minus => {
+                gen.emit("negq %rax");
+                gen.normalize(&type_specifier)?;
+            }
+            UnaryOperators::LogicalNot => gen.emit("xorq $1, %rax"),
+            UnaryOperators::BitwiseNot if type_specifier == TypeSpecifier::Bool => {
+                gen.emit("xorq $1, %rax")
+            }
+            UnaryOperators::BitwiseNot => {
+                gen.emit("notq %rax");
+                gen.normalize(&type_specifier)?;
+            }
+            _ => {}
+        }
+        return Ok(());
+    }
+}
+
+impl<'a> ToAsm<'a> for ArrayIndexExpression {
+    fn to_asm(&'a self, gen: &mut X86Generator<'a>) -> Result<()> {
+        let element = gen.index_address(self)?;
+        return gen.load(&element, "(%rax)");
+    }
+}
+
+impl<'a> ToAsm<'a> for MemberAccessExpression {
+    fn to_asm(&'a self, gen: &mut X86Generator<'a>) -> Result<()> {
+        let object = gen.object_address(&self.object, &self.access)?;
+        if let Member::Address(type_specifier) = gen.member(object, &self.property)? {
+            gen.load(&type_specifier, "(%rax)")?;
+        }
+        return Ok(());
+    }
+}
+
+impl<'a> ToAsm<'a> for FnCallExpression {
+    fn to_asm(&'a self, gen: &mut X86Generator<'a>) -> Result<()> {
+        let name = self.fn_name.identifier()?.id.to_string();
+        let return_type = gen.context.fn_call_signature(self)?.return_type.clone();
+        let name = match &self.namespace {
+            Some(namespace) => {
+                let struct_name = namespace.name.identifier()?;
+                X86Generator::method_name(struct_name.id.as_ref(), &name)
+            }
+            None => name,
+        };
+        return gen.call(&name, None, &self.args, &return_type);
+    }
+}
+
+impl<'a> ToAsm<'a> for ArrayInitExpression {
+    fn to_asm(&'a self, gen: &mut X86Generator<'a>) -> Result<()> {
+        let first = self
+            .array
+            .first()
+            .ok_or_else(|| CompilerError::CodeGen(String::from("Empty array init expression")))?;
+        let element = gen.context.expr_type(first)?;
+        let stride = gen.size_of(&element)?;
+        let array = gen.slot(&TypeSpecifier::array(element.clone(), self.size))?;
+        for (index, item) in self.array.iter().enumerate() {
+            item.to_asm(gen)?;
+            let offset = array + index as i64 * stride;
+            gen.store(&element, &format!("{}(%rbp)", offset))?;
+        }
+        gen.emit(&format!("leaq {}(%rbp), %rax", array));
+        return Ok(());
+    }
+}
+
+impl<'a> ToAsm<'a> for ObjectInitExpression {
+    /// Missing fields stay zero.
+    fn to_asm(&'a self, gen: &mut X86Generator<'a>) -> Result<()> {
+        let identifier = self.object.identifier()?.id.clone();
+        let name = identifier.to_string();
+        let type_specifier = TypeSpecifier::UserDefine(identifier);
+        let object = gen.slot(&type_specifier)?;
+        gen.zero(&type_specifier, &format!("{}(%rbp)", object))?;
+        for field in self.fields.iter() {
+            let (offset, field_type) = gen.field(&name, field.name.as_ref())?;
+            field.value.to_asm(gen)?;
+            gen.store(&field_type, &format!("{}(%rbp)", object + offset))?;
+        }
+        gen.emit(&format!("leaq {}(%rbp), %rax", object));
+        return Ok(());
+    }
+}
+
+impl<'a> ToAsm<'a> for CastExpression {
+    /// Scalars are kept extended to 64 bits, so a cast only narrows.
+    fn to_asm(&'a self, gen: &mut X86Generator<'a>) -> Result<()> {
+        let source = gen.context.expr_type(&self.expression)?;
+        if [&source, &self.target_type].iter().any(|type_specifier| {
+            matches!(type_specifier, TypeSpecifier::Float | TypeSpecifier::Double)
+        }) {
+            return Err(X86Generator::unsupported_float());
+        }
+        self.expression.to_asm(gen)?;
+        if let TypeSpecifier::UserDefine(name) = &self.target_type {
+            if gen.context.is_enum(name.as_ref()) {
+                return gen.check_enum(name.as_ref());
+            }
+        }
+        return gen.normalize(&self.target_type);
+    }
+}
+
+impl X86Generator<'_> {
+    /// Traps with `ud2` unless an item of the enum is stored as the value in
+    /// `%rax`.
+    fn check_enum(&mut self, enum_name: &str) -> Result<()> {
+        let valid = self.label();
+        for value in self.context.enum_values(enum_name)? {
+            self.emit(&format!("cmpl ${}, %eax", value));
+            self.emit(&format!("je {}", valid));
+        }
+        self.emit("ud2");
+        self.place_label(&valid);
+        return Ok(());
+    }
+}
+
+impl<'a> ToAsm<'a> for IdentifierExpression {
+    fn to_asm(&'a self, gen: &mut X86Generator<'a>) -> Result<()> {
+        let name = self.id.as_ref();
+        let type_specifier = gen.context.variable(name)?.clone();
+        let offset = gen.local(name)?;
+        return gen.load(&type_specifier, &format!("{}(%rbp)", offset));
+    }
+}
+
+impl<'a> ToAsm<'a> for EnumItemExpression {
+    /// An item of a tagged enum is built like an object init of its layout.
+    fn to_asm(&'a self, gen: &mut X86Generator<'a>) -> Result<()> {
+        let (name, item) = (self.name.as_ref(), self.item.as_ref());
+        let value = gen.context.enum_item(name, item)?;
+        if !gen.context.is_tagged(name) {
+            gen.emit(&format!("movq ${}, %rax", value));
+            return Ok(());
+        }
+        let type_specifier = TypeSpecifier::UserDefine(self.name.clone());
+        let object = gen.slot(&type_specifier)?;
+        gen.zero(&type_specifier, &format!("{}(%rbp)", object))?;
+        let (offset, tag_type) = gen.field(name, EnumDeclaration::TAG)?;
+        gen.emit(&format!("movq ${}, %rax", value));
+        gen.store(&tag_type, &format!("{}(%rbp)", object + offset))?;
+        for (index, arg) in self.args.iter().enumerate() {
+            let field = EnumDeclaration::payload_field(item, index);
+            let (offset, field_type) = gen.field(name, &field)?;
+            arg.to_asm(gen)?;
+            gen.store(&field_type, &format!("{}(%rbp)", object + offset))?;
+        }
+        gen.emit(&format!("leaq {}(%rbp), %rax", object));
+        return Ok(());
+    }
+}
+
+impl<'a> ToAsm<'a> for PrimitiveExpression {
+    fn to_asm(&'a self, gen: &mut X86Generator<'a>) -> Result<()> {
+        let invalid = || CompilerError::CodeGen(format!("Invalid literal {}", self.value));
+        match self.type_specifier {
+            TypeSpecifier::Int => {
+                let value = self.value.parse::<i64>().map_err(|_| invalid())? as i32;
+                gen.emit(&format!("movq ${}, %rax", value));
+            }
+            TypeSpecifier::Usize => {
+                let value: u64 = self.value.parse().map_err(|_| invalid())?;
+                gen.emit(&format!("movabsq ${}, %rax", value));
+            }
+            TypeSpecifier::Bool => {
+                gen.emit(&format!("movq ${}, %rax", (self.value == "true") as u8));
+            }
+            TypeSpecifier::Char => {
+                let value = *self.bytes().first().ok_or_else(invalid)?;
+                gen.emit(&format!("movq ${}, %rax", value));
+            }
+            TypeSpecifier::Str => {
+                let label = gen.string(self.bytes());
+                gen.emit(&format!("leaq {}(%rip), %rax", label));
+            }
+            TypeSpecifier::Float | TypeSpecifier::Double => {
+                return Err(X86Generator::unsupported_float())
+            }
+            _ => return Err(invalid()),
+        }
+        return Ok(());
+    }
+}
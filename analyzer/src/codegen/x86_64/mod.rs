@@ -0,0 +1,303 @@
+pub mod expressions;
+pub mod program;
+pub mod statements;
+
+use std::collections::HashMap;
+
+use crate::{
+    ast::{expressions::Expression, program::Program, type_specifier::TypeSpecifier},
+    error::{CompilerError, Result},
+};
+
+use super::{
+    loops::{Defers, LoopTargets, Loops},
+    type_context::TypeContext,
+};
+
+const INDENT: &str = "    ";
+
+/// Integer argument registers of the System V calling convention.
+const ARGUMENT_REGISTERS: [&str; 6] = ["%rdi", "%rsi", "%rdx", "%rcx", "%r8", "%r9"];
+
+/// Lowers an analyzed program to x86-64 assembly in GAS syntax for the
+/// System V ABI, to be assembled with `as` and linked with `ld`.
+///
+/// Every expression leaves its value in `%rax`, sign extended for `int` and
+/// zero extended for the other scalars. Structs and arrays are handled
+/// through their address: arguments point to a copy the callee copies again,
+/// and results are written through a hidden pointer passed in `%rdi`.
+/// Floating point types are not supported.
+pub struct X86Generator<'a> {
+    program: &'a Program,
+    pub(crate) context: TypeContext,
+    layouts: HashMap<String, StructLayout>,
+    strings: Vec<Vec<u8>>,
+    code: Vec<String>,
+    scopes: Vec<HashMap<String, i64>>,
+    defers: Defers<&'a Expression>,
+    loops: Loops<LoopTargets<String>>,
+    frame_size: i64,
+    depth: usize,
+    next_label: usize,
+    return_type: TypeSpecifier,
+    result_slot: Option<i64>,
+    is_main: bool,
+}
+
+pub(crate) struct StructLayout {
+    fields: Vec<(String, i64, TypeSpecifier)>,
+    size: i64,
+    align: i64,
+}
+
+pub trait ToAsm<'a> {
+    fn to_asm(&'a self, gen: &mut X86Generator<'a>) -> Result<()>;
+}
+
+impl<'a> X86Generator<'a> {
+    pub fn new(program: &'a Program) -> Self {
+        Self {
+            program,
+            context: TypeContext::new(program),
+            layouts: HashMap::new(),
+            strings: Vec::new(),
+            code: Vec::new(),
+            scopes: Vec::new(),
+            defers: Defers::default(),
+            loops: Loops::default(),
+            frame_size: 0,
+            depth: 0,
+            next_label: 0,
+            return_type: TypeSpecifier::Void,
+            result_slot: None,
+            is_main: false,
+        }
+    }
+
+    pub fn generate(mut self) -> Result<String> {
+        let program = self.program;
+        let mut content = String::from("# Generated by the Marble compiler.\n");
+        content += &program.functions_to_asm(&mut self)?;
+        if !self.strings.is_empty() {
+            content += "\n    .section .rodata\n";
+            for (id, bytes) in self.strings.iter().enumerate() {
+                content += &format!(".Lstr{}:\n    .asciz \"{}\"\n", id, Self::escape(bytes));
+            }
+        }
+        return Ok(content);
+    }
+
+    pub(crate) fn emit(&mut self, instruction: &str) {
+        self.code.push(format!("{}{}", INDENT, instruction));
+    }
+
+    pub(crate) fn label(&mut self) -> String {
+        self.next_label += 1;
+        return format!(".L{}", self.next_label);
+    }
+
+    pub(crate) fn place_label(&mut self, label: &str) {
+        self.code.push(format!("{}:", label));
+    }
+
+    pub(crate) fn push(&mut self, register: &str) {
+        self.emit(&format!("pushq {}", register));
+        self.depth += 1;
+    }
+
+    pub(crate) fn pop(&mut self, register: &str) {
+        self.emit(&format!("popq {}", register));
+        self.depth -= 1;
+    }
+
+    pub(crate) fn push_scope(&mut self) {
+        self.context.push_scope();
+        self.scopes.push(HashMap::new());
+        self.defers.push_scope();
+    }
+
+    pub(crate) fn pop_scope(&mut self) {
+        self.context.pop_scope();
+        self.scopes.pop();
+        self.defers.pop_scope();
+    }
+
+    /// Reserves frame memory and returns its offset from `%rbp`.
+    pub(crate) fn slot(&mut self, type_specifier: &TypeSpecifier) -> Result<i64> {
+        let size = self.size_of(type_specifier)?.max(1);
+        let align = self.align_of(type_specifier)?;
+        self.frame_size = (self.frame_size + size + align - 1) / align * align;
+        return Ok(-self.frame_size);
+    }
+
+    pub(crate) fn declare(&mut self, name: &str, type_specifier: &TypeSpecifier) -> Result<i64> {
+        let offset = self.slot(type_specifier)?;
+        self.context.declare(name, type_specifier.clone());
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_owned(), offset);
+        }
+        return Ok(offset);
+    }
+
+    pub(crate) fn local(&self, name: &str) -> Result<i64> {
+        return self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .copied()
+            .ok_or_else(|| CompilerError::CodeGen(format!("Unknown variable {}", name)));
+    }
+
+    pub(crate) fn is_aggregate(&self, type_specifier: &TypeSpecifier) -> bool {
+        return match type_specifier {
+            TypeSpecifier::ArrayType { .. } => true,
+            TypeSpecifier::UserDefine(name) => self.context.is_struct(name.as_ref()),
+            _ => false,
+        };
+    }
+
+    pub(crate) fn size_of(&self, type_specifier: &TypeSpecifier) -> Result<i64> {
+        return Ok(match type_specifier {
+            TypeSpecifier::Void => 0,
+            TypeSpecifier::Char | TypeSpecifier::Bool => 1,
+            TypeSpecifier::Int => 4,
+            TypeSpecifier::Usize | TypeSpecifier::Str | TypeSpecifier::Pointer(_) => 8,
+            TypeSpecifier::Float | TypeSpecifier::Double => return Err(Self::unsupported_float()),
+            TypeSpecifier::ArrayType {
+                type_specifier,
+                size,
+                ..
+            } => self.size_of(type_specifier)? * *size as i64,
+            TypeSpecifier::UserDefine(name) if self.context.is_struct(name.as_ref()) => {
+                self.layout(name.as_ref())?.size
+            }
+            TypeSpecifier::UserDefine(_) => 4,
+        });
+    }
+
+    pub(crate) fn align_of(&self, type_specifier: &TypeSpecifier) -> Result<i64> {
+        return Ok(match type_specifier {
+            TypeSpecifier::ArrayType { type_specifier, .. } => self.align_of(type_specifier)?,
+            TypeSpecifier::UserDefine(name) if self.context.is_struct(name.as_ref()) => {
+                self.layout(name.as_ref())?.align
+            }
+            type_specifier => self.size_of(type_specifier)?.max(1),
+        });
+    }
+
+    fn layout(&self, name: &str) -> Result<&StructLayout> {
+        return self.layouts.get(name).ok_or_else(|| {
+            CompilerError::CodeGen(format!("Layout of struct {} is unknown", name))
+        });
+    }
+
+    /// Offset and type of the field `name` of the struct `struct_name`.
+    pub(crate) fn field(&self, struct_name: &str, name: &str) -> Result<(i64, TypeSpecifier)> {
+        return self
+            .layout(struct_name)?
+            .fields
+            .iter()
+            .find(|(field, _, _)| field == name)
+            .map(|(_, offset, type_specifier)| (*offset, type_specifier.clone()))
+            .ok_or_else(|| {
+                CompilerError::CodeGen(format!("Unknown field {}.{}", struct_name, name))
+            });
+    }
+
+    /// Loads the value stored at `source` into `%rax`. Aggregates are
+    /// represented by their address.
+    pub(crate) fn load(&mut self, type_specifier: &TypeSpecifier, source: &str) -> Result<()> {
+        if self.is_aggregate(type_specifier) {
+            self.emit(&format!("leaq {}, %rax", source));
+            return Ok(());
+        }
+        let instruction = match self.size_of(type_specifier)? {
+            1 => "movzbq",
+            4 => "movslq",
+            _ => "movq",
+        };
+        self.emit(&format!("{} {}, %rax", instruction, source));
+        return Ok(());
+    }
+
+    /// Stores `%rax` to `destination`, copying the pointed memory for aggregates.
+    pub(crate) fn store(
+        &mut self,
+        type_specifier: &TypeSpecifier,
+        destination: &str,
+    ) -> Result<()> {
+        let size = self.size_of(type_specifier)?;
+        if self.is_aggregate(type_specifier) {
+            self.emit(&format!("leaq {}, %rdi", destination));
+            self.emit("movq %rax, %rsi");
+            self.emit(&format!("movq ${}, %rcx", size));
+            self.emit("rep movsb");
+            return Ok(());
+        }
+        match size {
+            1 => self.emit(&format!("movb %al, {}", destination)),
+            4 => self.emit(&format!("movl %eax, {}", destination)),
+            _ => self.emit(&format!("movq %rax, {}", destination)),
+        }
+        return Ok(());
+    }
+
+    /// Fills `size` bytes at `destination` with zeros.
+    pub(crate) fn zero(&mut self, type_specifier: &TypeSpecifier, destination: &str) -> Result<()> {
+        if !self.is_aggregate(type_specifier) {
+            self.emit("xorl %eax, %eax");
+            return self.store(type_specifier, destination);
+        }
+        let size = self.size_of(type_specifier)?;
+        self.emit(&format!("leaq {}, %rdi", destination));
+        self.emit(&format!("movq ${}, %rcx", size));
+        self.emit("xorl %eax, %eax");
+        self.emit("rep stosb");
+        return Ok(());
+    }
+
+    /// Brings a 64-bit result back to the representation of `type_specifier`.
+    pub(crate) fn normalize(&mut self, type_specifier: &TypeSpecifier) -> Result<()> {
+        match type_specifier {
+            TypeSpecifier::Bool => {
+                self.emit("testq %rax, %rax");
+                self.emit("setne %al");
+                self.emit("movzbq %al, %rax");
+            }
+            type_specifier => match self.size_of(type_specifier)? {
+                1 => self.emit("movzbq %al, %rax"),
+                4 => self.emit("movslq %eax, %rax"),
+                _ => {}
+            },
+        }
+        return Ok(());
+    }
+
+    pub(crate) fn string(&mut self, bytes: Vec<u8>) -> String {
+        let id = match self.strings.iter().position(|string| *string == bytes) {
+            Some(id) => id,
+            None => {
+                self.strings.push(bytes);
+                self.strings.len() - 1
+            }
+        };
+        return format!(".Lstr{}", id);
+    }
+
+    fn escape(bytes: &[u8]) -> String {
+        return bytes
+            .iter()
+            .map(|byte| match byte {
+                b' '..=b'~' if *byte != b'"' && *byte != b'\\' => (*byte as char).to_string(),
+                _ => format!("\\{:03o}", byte),
+            })
+            .collect();
+    }
+
+    pub(crate) fn unsupported_float() -> CompilerError {
+        return CompilerError::CodeGen(String::from(
+            "The x86-64 backend does not support floating point types",
+        ));
+    }
+}
@@ -0,0 +1,161 @@
+use crate::{
+    ast::{
+        declarations::Declarations, program::Program, statements::block_stmt::BlockStmt,
+        type_specifier::TypeSpecifier, variable_type::VariableType,
+    },
+    error::{CompilerError, Result},
+};
+
+use super::{StructLayout, ToAsm, X86Generator, ARGUMENT_REGISTERS};
+
+impl Program {
+    pub(crate) fn functions_to_asm<'a>(&'a self, gen: &mut X86Generator<'a>) -> Result<String> {
+        for struct_decl in self.sorted_structs()? {
+            let mut layout = StructLayout {
+                fields: Vec::new(),
+                size: 0,
+                align: 1,
+            };
+            for field in struct_decl.fields.iter() {
+                let type_specifier = &field.field.type_specifier;
+                let align = gen.align_of(type_specifier)?;
+                let offset = (layout.size + align - 1) / align * align;
+                layout.fields.push((
+                    field.field.identifier.to_string(),
+                    offset,
+                    type_specifier.clone(),
+                ));
+                layout.size = offset + gen.size_of(type_specifier)?;
+                layout.align = layout.align.max(align);
+            }
+            layout.size = (layout.size + layout.align - 1) / layout.align * layout.align;
+            gen.layouts.insert(struct_decl.name.to_string(), layout);
+        }
+
+        let mut content = String::from("    .text\n    .globl _start\n_start:\n");
+        content += "    call main\n    movl %eax, %edi\n    movl $60, %eax\n    syscall\n";
+        let mut has_main = false;
+        for decl in self.declarations.iter() {
+            match decl {
+                Declarations::Fn(fn_decl) => {
+                    let name = fn_decl.fn_name.as_ref();
+                    gen.is_main = name == "main";
+                    has_main |= gen.is_main;
+                    if gen.is_main && !fn_decl.params.is_empty() {
+                        return Err(CompilerError::CodeGen(String::from(
+                            "main function cannot take parameters",
+                        )));
+                    }
+                    let params: Vec<&VariableType> = fn_decl.params.iter().collect();
+                    content += &gen.function(name, &params, &fn_decl.return_type, &fn_decl.body)?;
+                    gen.is_main = false;
+                }
+                Declarations::Impl(impl_decl) => {
+                    let struct_name = impl_decl.name.to_symbol()?;
+                    for member_fn in impl_decl.member_functions.iter() {
+                        let name = X86Generator::method_name(
+                            struct_name,
+                            member_fn.prototype.name.as_ref(),
+                        );
+                        content += &gen.function(
+                            &name,
+                            &member_fn.params(),
+                            &member_fn.prototype.return_type,
+                            &member_fn.body,
+                        )?;
+                    }
+                }
+                _ => {}
+            }
+        }
+        if !has_main {
+            return Err(CompilerError::CodeGen(String::from(
+                "Program has no main function",
+            )));
+        }
+        return Ok(content);
+    }
+}
+
+impl<'a> X86Generator<'a> {
+    pub(crate) fn method_name(struct_name: &str, name: &str) -> String {
+        return format!("{}.{}", struct_name, name);
+    }
+
+    fn function(
+        &mut self,
+        name: &str,
+        params: &[&VariableType],
+        return_type: &TypeSpecifier,
+        body: &'a BlockStmt,
+    ) -> Result<String> {
+        self.code = Vec::new();
+        self.frame_size = 0;
+        self.depth = 0;
+        self.return_type = return_type.clone();
+        self.result_slot = None;
+
+        // Incoming registers are saved first, copying aggregates clobbers them.
+        let mut registers = ARGUMENT_REGISTERS.iter();
+        if self.is_aggregate(return_type) {
+            let slot = self.slot(&TypeSpecifier::Usize)?;
+            let register = registers.next().unwrap_or(&"%rdi");
+            self.emit(&format!("movq {}, {}(%rbp)", register, slot));
+            self.result_slot = Some(slot);
+        }
+        let mut sources = Vec::new();
+        let mut stack_offset = 16;
+        for _ in params.iter() {
+            match registers.next() {
+                Some(register) => {
+                    let slot = self.slot(&TypeSpecifier::Usize)?;
+                    self.emit(&format!("movq {}, {}(%rbp)", register, slot));
+                    sources.push(format!("{}(%rbp)", slot));
+                }
+                None => {
+                    sources.push(format!("{}(%rbp)", stack_offset));
+                    stack_offset += 8;
+                }
+            }
+        }
+
+        self.push_scope();
+        let result = self.body(params, &sources, body);
+        self.pop_scope();
+        result?;
+
+        if !body.ends_with_return() {
+            if self.is_main || self.return_type != TypeSpecifier::Void {
+                self.emit("xorl %eax, %eax");
+            }
+            self.emit("leave");
+            self.emit("ret");
+        }
+
+        let frame_size = (self.frame_size + 15) / 16 * 16;
+        let mut content = format!("\n    .globl {}\n{}:\n", name, name);
+        content += "    pushq %rbp\n    movq %rsp, %rbp\n";
+        if frame_size > 0 {
+            content += &format!("    subq ${}, %rsp\n", frame_size);
+        }
+        for line in self.code.iter() {
+            content += line;
+            content += "\n";
+        }
+        return Ok(content);
+    }
+
+    fn body(
+        &mut self,
+        params: &[&VariableType],
+        sources: &[String],
+        body: &'a BlockStmt,
+    ) -> Result<()> {
+        for (param, source) in params.iter().zip(sources.iter()) {
+            let offset = self.declare(param.identifier.as_ref(), &param.type_specifier)?;
+            self.emit(&format!("movq {}, %rax", source));
+            self.store(&param.type_specifier, &format!("{}(%rbp)", offset))?;
+        }
+        return body.to_asm(self);
+    }
+}
@@ -0,0 +1,361 @@
+use crate::{
+    ast::{
+        declarations::enum_decl::EnumDeclaration,
+        expressions::Expression,
+        identifier::Identifier,
+        statements::{
+            block_stmt::BlockStmt,
+            break_stmt::BreakStmt,
+            continue_stmt::ContinueStmt,
+            defer_stmt::DeferStmt,
+            do_while_stmt::DoWhileStmt,
+            for_stmt::{ForStmt, VariableInit},
+            if_stmt::IfStmt,
+            let_stmt::LetStmt,
+            match_stmt::{MatchArm, MatchStmt},
+            return_stmt::ReturnStmt,
+            switch_stmt::SwitchStmt,
+            while_stmt::WhileStmt,
+            Statements,
+        },
+        type_specifier::TypeSpecifier,
+    },
+    codegen::{
+        loops::{Jump, LoopTargets},
+        type_context::TypeContext,
+    },
+    error::{CompilerError, Result},
+};
+
+use super::{ToAsm, X86Generator};
+
+impl<'a> ToAsm<'a> for Statements {
+    fn to_asm(&'a self, gen: &mut X86Generator<'a>) -> Result<()> {
+        return match self {
+            Statements::Let(let_stmt) => let_stmt.to_asm(gen),
+            Statements::Const(_) => Ok(()),
+            Statements::Return(return_stmt) => return_stmt.to_asm(gen),
+            Statements::Defer(defer_stmt) => defer_stmt.to_asm(gen),
+            Statements::For(for_stmt) => for_stmt.to_asm(gen),
+            Statements::While(while_stmt) => while_stmt.to_asm(gen),
+            Statements::DoWhile(do_while_stmt) => do_while_stmt.to_asm(gen),
+            Statements::If(if_stmt) => if_stmt.to_asm(gen),
+            Statements::Switch(switch_stmt) => switch_stmt.to_asm(gen),
+            Statements::Match(match_stmt) => match_stmt.to_asm(gen),
+            Statements::Break(break_stmt) => break_stmt.to_asm(gen),
+            Statements::Continue(continue_stmt) => continue_stmt.to_asm(gen),
+            Statements::Block(block_stmt) => block_stmt.to_asm(gen),
+            Statements::Expression(expr) => expr.to_asm(gen),
+        };
+    }
+}
+
+impl<'a> X86Generator<'a> {
+    /// Jumps to `target` when `condition` is false.
+    fn jump_unless(&mut self, condition: &'a Expression, target: &str) -> Result<()> {
+        condition.to_asm(self)?;
+        self.emit("testq %rax, %rax");
+        self.emit(&format!("je {}", target));
+        return Ok(());
+    }
+
+    /// Emits the body of a loop labeled `label`, whose `continue`s jump to
+    /// `next` and `break`s to `end`.
+    fn loop_body(
+        &mut self,
+        label: Option<&Identifier>,
+        block: &'a BlockStmt,
+        next: &str,
+        end: &str,
+    ) -> Result<()> {
+        let targets = LoopTargets {
+            next: next.to_owned(),
+            end: end.to_owned(),
+        };
+        self.loops.push(label, self.defers.depth(), targets);
+        let result = block.to_asm(self);
+        self.loops.pop()?;
+        return result;
+    }
+
+    /// Emits a `break` or `continue` to the loop labeled `label`, which runs
+    /// the deferred expressions of the blocks it leaves first.
+    fn leave_loop(&mut self, label: Option<&Identifier>, jump: Jump) -> Result<()> {
+        let target = self.loops.target(label)?;
+        let defers = self.defers.leaving(target);
+        let target = target.targets.get(jump);
+        for expr in defers {
+            expr.to_asm(self)?;
+        }
+        self.emit(&format!("jmp {}", target));
+        return Ok(());
+    }
+}
+
+impl<'a> ToAsm<'a> for BlockStmt {
+    fn to_asm(&'a self, gen: &mut X86Generator<'a>) -> Result<()> {
+        gen.push_scope();
+        let result = self.stmts_to_asm(gen);
+        gen.pop_scope();
+        return result;
+    }
+}
+
+impl BlockStmt {
+    fn stmts_to_asm<'a>(&'a self, gen: &mut X86Generator<'a>) -> Result<()> {
+        for stmt in self.stmts.iter() {
+            stmt.to_asm(gen)?;
+        }
+        if !self.ends_with_return() {
+            for expr in gen.defers.pending(1) {
+                expr.to_asm(gen)?;
+            }
+        }
+        return Ok(());
+    }
+}
+
+impl<'a> ToAsm<'a> for LetStmt {
+    fn to_asm(&'a self, gen: &mut X86Generator<'a>) -> Result<()> {
+        let type_specifier = self.type_specifier.as_ref().ok_or_else(|| {
+            CompilerError::CodeGen(format!("Type of {} is unknown", self.identifier))
+        })?;
+        match &self.value {
+            Some(value) => {
+                value.to_asm(gen)?;
+                let offset = gen.declare(self.identifier.as_ref(), type_specifier)?;
+                gen.store(type_specifier, &format!("{}(%rbp)", offset))?;
+            }
+            None => {
+                let offset = gen.declare(self.identifier.as_ref(), type_specifier)?;
+                gen.zero(type_specifier, &format!("{}(%rbp)", offset))?;
+            }
+        }
+        return Ok(());
+    }
+}
+
+impl<'a> ToAsm<'a> for ReturnStmt {
+    /// The value is computed before the deferred expressions run.
+    fn to_asm(&'a self, gen: &mut X86Generator<'a>) -> Result<()> {
+        let return_type = gen.return_type.clone();
+        match &self.value {
+            Some(value) => value.to_asm(gen)?,
+            None => gen.emit("xorl %eax, %eax"),
+        }
+        if let Some(result_slot) = gen.result_slot {
+            gen.emit(&format!("movq {}(%rbp), %rcx", result_slot));
+            gen.store(&return_type, "(%rcx)")?;
+        }
+
+        let has_defers = !gen.defers.is_empty();
+        if has_defers {
+            let slot = gen.slot(&TypeSpecifier::Usize)?;
+            gen.emit(&format!("movq %rax, {}(%rbp)", slot));
+            for expr in gen.defers.all() {
+                expr.to_asm(gen)?;
+            }
+            gen.emit(&format!("movq {}(%rbp), %rax", slot));
+        }
+        if let Some(result_slot) = gen.result_slot {
+            gen.emit(&format!("movq {}(%rbp), %rax", result_slot));
+        }
+        gen.emit("leave");
+        gen.emit("ret");
+        return Ok(());
+    }
+}
+
+impl<'a> ToAsm<'a> for DeferStmt {
+    fn to_asm(&'a self, gen: &mut X86Generator<'a>) -> Result<()> {
+        gen.defers.push(&self.expr);
+        return Ok(());
+    }
+}
+
+impl<'a> ToAsm<'a> for BreakStmt {
+    fn to_asm(&'a self, gen: &mut X86Generator<'a>) -> Result<()> {
+        return gen.leave_loop(self.label.as_ref(), Jump::Break);
+    }
+}
+
+impl<'a> ToAsm<'a> for ContinueStmt {
+    fn to_asm(&'a self, gen: &mut X86Generator<'a>) -> Result<()> {
+        return gen.leave_loop(self.label.as_ref(), Jump::Continue);
+    }
+}
+
+impl<'a> ToAsm<'a> for ForStmt {
+    fn to_asm(&'a self, gen: &mut X86Generator<'a>) -> Result<()> {
+        gen.push_scope();
+        let result = self.loop_to_asm(gen);
+        gen.pop_scope();
+        return result;
+    }
+}
+
+impl ForStmt {
+    fn loop_to_asm<'a>(&'a self, gen: &mut X86Generator<'a>) -> Result<()> {
+        match &self.init {
+            VariableInit::Let(let_stmt) => let_stmt.to_asm(gen)?,
+            VariableInit::Assign(assign) => gen.assign(assign)?,
+        }
+        let start = gen.label();
+        let increment = gen.label();
+        let end = gen.label();
+        gen.place_label(&start);
+        gen.jump_unless(&self.condition, &end)?;
+        gen.loop_body(self.label.as_ref(), &self.block, &increment, &end)?;
+        gen.place_label(&increment);
+        self.increment.to_asm(gen)?;
+        gen.emit(&format!("jmp {}", start));
+        gen.place_label(&end);
+        return Ok(());
+    }
+}
+
+impl<'a> ToAsm<'a> for WhileStmt {
+    fn to_asm(&'a self, gen: &mut X86Generator<'a>) -> Result<()> {
+        let start = gen.label();
+        let end = gen.label();
+        gen.place_label(&start);
+        gen.jump_unless(&self.condition, &end)?;
+        gen.loop_body(self.label.as_ref(), &self.block, &start, &end)?;
+        gen.emit(&format!("jmp {}", start));
+        gen.place_label(&end);
+        return Ok(());
+    }
+}
+
+impl<'a> ToAsm<'a> for DoWhileStmt {
+    fn to_asm(&'a self, gen: &mut X86Generator<'a>) -> Result<()> {
+        let start = gen.label();
+        let condition = gen.label();
+        let end = gen.label();
+        gen.place_label(&start);
+        gen.loop_body(self.label.as_ref(), &self.block, &condition, &end)?;
+        gen.place_label(&condition);
+        gen.jump_unless(&self.condition, &end)?;
+        gen.emit(&format!("jmp {}", start));
+        gen.place_label(&end);
+        return Ok(());
+    }
+}
+
+impl<'a> ToAsm<'a> for IfStmt {
+    fn to_asm(&'a self, gen: &mut X86Generator<'a>) -> Result<()> {
+        let end = gen.label();
+        let branches = std::iter::once((&self.condition, &self.block)).chain(
+            self.else_ifs
+                .iter()
+                .map(|else_if| (&else_if.condition, &else_if.block)),
+        );
+        for (condition, block) in branches {
+            let next = gen.label();
+            gen.jump_unless(condition, &next)?;
+            block.to_asm(gen)?;
+            gen.emit(&format!("jmp {}", end));
+            gen.place_label(&next);
+        }
+        if let Some(else_stmt) = &self.else_stmt {
+            else_stmt.to_asm(gen)?;
+        }
+        gen.place_label(&end);
+        return Ok(());
+    }
+}
+
+impl<'a> ToAsm<'a> for SwitchStmt {
+    /// Compares the value with every case before any case runs, so it only
+    /// needs to live in `%rax`.
+    fn to_asm(&'a self, gen: &mut X86Generator<'a>) -> Result<()> {
+        self.value.to_asm(gen)?;
+        let end = gen.label();
+        let mut labels = Vec::new();
+        for case in self.cases.iter() {
+            let label = gen.label();
+            let value = gen.context.case_value(&case.value)?;
+            gen.emit(&format!("movabsq ${}, %rcx", value));
+            gen.emit("cmpq %rcx, %rax");
+            gen.emit(&format!("je {}", label));
+            labels.push(label);
+        }
+        let default = gen.label();
+        gen.emit(&format!("jmp {}", default));
+        for (case, label) in self.cases.iter().zip(labels.iter()) {
+            gen.place_label(label);
+            case.block.to_asm(gen)?;
+            gen.emit(&format!("jmp {}", end));
+        }
+        gen.place_label(&default);
+        if let Some(block) = &self.default {
+            block.to_asm(gen)?;
+        }
+        gen.place_label(&end);
+        return Ok(());
+    }
+}
+
+impl<'a> ToAsm<'a> for MatchStmt {
+    /// A tagged value is copied to the frame, where the arms read the
+    /// carried values from, and its tag is compared with every arm.
+    fn to_asm(&'a self, gen: &mut X86Generator<'a>) -> Result<()> {
+        let type_specifier = gen.context.expr_type(&self.value)?;
+        let enum_name = TypeContext::struct_name(&type_specifier)?.to_owned();
+        self.value.to_asm(gen)?;
+        let mut object = 0;
+        if gen.context.is_tagged(&enum_name) {
+            object = gen.slot(&type_specifier)?;
+            gen.store(&type_specifier, &format!("{}(%rbp)", object))?;
+            let (offset, tag_type) = gen.field(&enum_name, EnumDeclaration::TAG)?;
+            gen.load(&tag_type, &format!("{}(%rbp)", object + offset))?;
+        }
+        let end = gen.label();
+        let mut labels = Vec::new();
+        for arm in self.arms.iter() {
+            let label = gen.label();
+            let value = gen
+                .context
+                .enum_item(arm.name.as_ref(), arm.item.as_ref())?;
+            gen.emit(&format!("movabsq ${}, %rcx", value));
+            gen.emit("cmpq %rcx, %rax");
+            gen.emit(&format!("je {}", label));
+            labels.push(label);
+        }
+        let default = gen.label();
+        gen.emit(&format!("jmp {}", default));
+        for (arm, label) in self.arms.iter().zip(labels.iter()) {
+            gen.place_label(label);
+            gen.push_scope();
+            let result = arm.to_asm_from(object, gen);
+            gen.pop_scope();
+            result?;
+            gen.emit(&format!("jmp {}", end));
+        }
+        gen.place_label(&default);
+        if let Some(block) = &self.default {
+            block.to_asm(gen)?;
+        }
+        gen.place_label(&end);
+        return Ok(());
+    }
+}
+
+impl MatchArm {
+    /// Binds the values carried in the tagged enum at `object(%rbp)`, then
+    /// runs the block.
+    fn to_asm_from<'a>(&'a self, object: i64, gen: &mut X86Generator<'a>) -> Result<()> {
+        let (name, item) = (self.name.as_ref(), self.item.as_ref());
+        for (index, binding) in self.bindings.iter().enumerate() {
+            let Some(binding) = binding else {
+                continue;
+            };
+            let field = EnumDeclaration::payload_field(item, index);
+            let (offset, field_type) = gen.field(name, &field)?;
+            gen.load(&field_type, &format!("{}(%rbp)", object + offset))?;
+            let slot = gen.declare(binding.as_ref(), &field_type)?;
+            gen.store(&field_type, &format!("{}(%rbp)", slot))?;
+        }
+        return self.block.to_asm(gen);
+    }
+}
@@ -0,0 +1,146 @@
+/// Identifies a kind of diagnostic independently of its wording, so tools
+/// can match on it. Codes are grouped by stage: E0001 lexical, E0100
+/// syntactic, E0200 constants and E0300 semantic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    /// A character that starts no token.
+    UnknownToken,
+    /// A number with more than one decimal point.
+    MalformedNumber,
+    /// A string or char literal that is not closed on its line.
+    UnterminatedLiteral,
+    /// An escape sequence the language does not define.
+    UnknownEscape,
+    /// A `/*` comment that is never closed.
+    UnterminatedComment,
+    /// A token other than the one the grammar requires.
+    UnexpectedToken,
+    /// No expression where one is required.
+    ExpectedExpression,
+    /// No statement where one is required.
+    ExpectedStatement,
+    /// No declaration at the top level of the file.
+    ExpectedDeclaration,
+    /// No type where one is required.
+    ExpectedType,
+    /// A brace, bracket or parenthesis that is never closed.
+    UnclosedDelimiter,
+    /// An array size that is not a non-negative constant integer.
+    InvalidArraySize,
+    /// An expression that cannot be evaluated while compiling.
+    NotConstant,
+    /// A constant declared twice in the same scope.
+    ConstantRedefined,
+    /// An operation that fails while folding a constant.
+    ConstantEvaluation,
+    /// A constant that is assigned, incremented or pointed at.
+    ConstantModified,
+    /// Two types that must be equal are not.
+    TypeMismatch,
+    /// A condition that is not a bool.
+    ConditionNotBool,
+    /// An operand of a type the operator is not defined for.
+    UnsupportedOperand,
+    /// An expression of a kind not allowed where it is used.
+    InvalidExpression,
+    /// A name that is not a variable in scope.
+    UndefinedVariable,
+    /// A call of a function that is not declared.
+    UndefinedFunction,
+    /// A struct name that is not declared.
+    UndefinedStruct,
+    /// A field the struct does not have.
+    UndefinedField,
+    /// A call with more or fewer arguments than parameters.
+    ArgumentCount,
+    /// An object initializer with more or fewer fields than the struct.
+    FieldCount,
+    /// A function with a return type whose body does not end with a return.
+    MissingReturn,
+    /// `.` used on a pointer or `->` on a value.
+    WrongAccessOperator,
+    /// A member access on a value that is not a struct.
+    NotAStruct,
+    /// An index into a value that is not an array or pointer.
+    NotAnArray,
+    /// An index that is negative or not an integer.
+    InvalidIndex,
+    /// A private field or method used outside its struct.
+    PrivateAccess,
+    /// A state the analyzer should never reach.
+    Internal,
+    /// A `break` or `continue` outside of a loop.
+    OutsideLoop,
+    /// A `break` or `continue` naming no loop around it.
+    UndefinedLabel,
+    /// A switch over an enum that handles only some of its items.
+    NonExhaustiveSwitch,
+    /// Two cases of a switch with the same value.
+    DuplicateCase,
+    /// An item the enum does not have.
+    UndefinedItem,
+    /// A name used as an enum that no enum has.
+    UndefinedEnum,
+    /// Two items of an enum with the same value.
+    DuplicateDiscriminant,
+    /// A method receiver that is not its struct, or a method called
+    /// without the value it needs.
+    InvalidReceiver,
+    /// A function without a receiver called on a value.
+    NotAMethod,
+}
+
+impl ErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        return match self {
+            ErrorCode::UnknownToken => "E0001",
+            ErrorCode::MalformedNumber => "E0002",
+            ErrorCode::UnterminatedLiteral => "E0003",
+            ErrorCode::UnknownEscape => "E0004",
+            ErrorCode::UnterminatedComment => "E0005",
+            ErrorCode::UnexpectedToken => "E0100",
+            ErrorCode::ExpectedExpression => "E0101",
+            ErrorCode::ExpectedStatement => "E0102",
+            ErrorCode::ExpectedDeclaration => "E0103",
+            ErrorCode::ExpectedType => "E0104",
+            ErrorCode::UnclosedDelimiter => "E0105",
+            ErrorCode::InvalidArraySize => "E0106",
+            ErrorCode::NotConstant => "E0200",
+            ErrorCode::ConstantRedefined => "E0201",
+            ErrorCode::ConstantEvaluation => "E0202",
+            ErrorCode::ConstantModified => "E0203",
+            ErrorCode::TypeMismatch => "E0300",
+            ErrorCode::ConditionNotBool => "E0301",
+            ErrorCode::UnsupportedOperand => "E0302",
+            ErrorCode::InvalidExpression => "E0303",
+            ErrorCode::UndefinedVariable => "E0304",
+            ErrorCode::UndefinedFunction => "E0305",
+            ErrorCode::UndefinedStruct => "E0306",
+            ErrorCode::UndefinedField => "E0307",
+            ErrorCode::ArgumentCount => "E0308",
+            ErrorCode::FieldCount => "E0309",
+            ErrorCode::MissingReturn => "E0310",
+            ErrorCode::WrongAccessOperator => "E0311",
+            ErrorCode::NotAStruct => "E0312",
+            ErrorCode::NotAnArray => "E0313",
+            ErrorCode::InvalidIndex => "E0314",
+            ErrorCode::PrivateAccess => "E0315",
+            ErrorCode::Internal => "E0316",
+            ErrorCode::OutsideLoop => "E0317",
+            ErrorCode::UndefinedLabel => "E0318",
+            ErrorCode::NonExhaustiveSwitch => "E0319",
+            ErrorCode::DuplicateCase => "E0320",
+            ErrorCode::UndefinedItem => "E0321",
+            ErrorCode::UndefinedEnum => "E0322",
+            ErrorCode::DuplicateDiscriminant => "E0323",
+            ErrorCode::InvalidReceiver => "E0324",
+            ErrorCode::NotAMethod => "E0325",
+        };
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
@@ -0,0 +1,144 @@
+use crate::lexer::token::Span;
+
+use super::Diagnostic;
+
+/// Formats a diagnostic as a single-line JSON object for tools. Lines and
+/// columns start at 1 and the end column is one past the last character.
+/// A diagnostic without a span has `null` positions.
+pub fn render(diagnostic: &Diagnostic, file_name: &str) -> String {
+    let mut content = format!(
+        "{{\"file\":{},\"severity\":{},\"code\":{},\"message\":{},",
+        string(file_name),
+        string(&diagnostic.severity.to_string()),
+        string(diagnostic.code.as_str()),
+        string(&diagnostic.message)
+    );
+    content += position(diagnostic.span.as_ref()).as_str();
+
+    let labels: Vec<String> = diagnostic
+        .labels
+        .iter()
+        .map(|label| {
+            format!(
+                "{{{},\"message\":{}}}",
+                position(Some(&label.span)),
+                string(&label.message)
+            )
+        })
+        .collect();
+    content += format!(",\"labels\":[{}]", labels.join(",")).as_str();
+
+    let notes: Vec<String> = diagnostic.notes.iter().map(|note| string(note)).collect();
+    content += format!(",\"notes\":[{}]", notes.join(",")).as_str();
+
+    let help = match &diagnostic.help {
+        Some(help) => string(help),
+        None => String::from("null"),
+    };
+    content += format!(",\"help\":{}}}", help).as_str();
+    return content;
+}
+
+/// Formats an error that has no diagnostic, such as a code generation
+/// failure, in the same shape with no code and no position.
+pub fn render_message(message: &str, file_name: &str) -> String {
+    return format!(
+        "{{\"file\":{},\"severity\":\"error\",\"code\":null,\"message\":{},{},\"labels\":[],\"notes\":[],\"help\":null}}",
+        string(file_name),
+        string(message),
+        position(None)
+    );
+}
+
+fn position(span: Option<&Span>) -> String {
+    let Some(span) = span else {
+        return String::from("\"line\":null,\"column\":null,\"end_line\":null,\"end_column\":null");
+    };
+    return format!(
+        "\"line\":{},\"column\":{},\"end_line\":{},\"end_column\":{}",
+        span.start().row(),
+        span.start().col(),
+        span.end().row(),
+        span.end().col()
+    );
+}
+
+fn string(value: &str) -> String {
+    let mut content = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => content += "\\\"",
+            '\\' => content += "\\\\",
+            '\n' => content += "\\n",
+            '\r' => content += "\\r",
+            '\t' => content += "\\t",
+            c if (c as u32) < 0x20 => content += format!("\\u{:04x}", c as u32).as_str(),
+            c => content.push(c),
+        }
+    }
+    content.push('"');
+    return content;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        diagnostic::{Diagnostic, ErrorCode},
+        lexer::token::{Position, Span},
+    };
+
+    use super::*;
+
+    fn span(row: usize, col: usize, end_col: usize) -> Span {
+        return Span::new(Position::new(row, col, 0), Position::new(row, end_col, 0));
+    }
+
+    #[test]
+    fn renders_every_field() {
+        let diagnostic = Diagnostic::error(ErrorCode::UnexpectedToken, "Expect ';'")
+            .with_span(&span(3, 5, 6))
+            .with_label(&span(2, 1, 4), "statement starts here")
+            .with_note("statements end with ';'")
+            .with_help("add ';'");
+        assert_eq!(
+            render(&diagnostic, "main.mb"),
+            "{\"file\":\"main.mb\",\"severity\":\"error\",\"code\":\"E0100\",\
+             \"message\":\"Expect ';'\",\
+             \"line\":3,\"column\":5,\"end_line\":3,\"end_column\":6,\
+             \"labels\":[{\"line\":2,\"column\":1,\"end_line\":2,\"end_column\":4,\
+             \"message\":\"statement starts here\"}],\
+             \"notes\":[\"statements end with ';'\"],\"help\":\"add ';'\"}"
+        );
+    }
+
+    #[test]
+    fn renders_missing_fields_as_null_or_empty() {
+        let diagnostic = Diagnostic::error(ErrorCode::UnexpectedToken, "Unexpected end");
+        assert_eq!(
+            render(&diagnostic, "main.mb"),
+            "{\"file\":\"main.mb\",\"severity\":\"error\",\"code\":\"E0100\",\
+             \"message\":\"Unexpected end\",\
+             \"line\":null,\"column\":null,\"end_line\":null,\"end_column\":null,\
+             \"labels\":[],\"notes\":[],\"help\":null}"
+        );
+    }
+
+    #[test]
+    fn renders_a_message_in_the_same_shape() {
+        assert_eq!(
+            render_message("Cannot read main.mb", "main.mb"),
+            "{\"file\":\"main.mb\",\"severity\":\"error\",\"code\":null,\
+             \"message\":\"Cannot read main.mb\",\
+             \"line\":null,\"column\":null,\"end_line\":null,\"end_column\":null,\
+             \"labels\":[],\"notes\":[],\"help\":null}"
+        );
+    }
+
+    #[test]
+    fn escapes_strings() {
+        assert_eq!(
+            string("say \"hi\"\\\n\t\u{1}"),
+            "\"say \\\"hi\\\"\\\\\\n\\t\\u0001\""
+        );
+    }
+}
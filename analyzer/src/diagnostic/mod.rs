@@ -0,0 +1,94 @@
+pub mod code;
+pub mod json;
+pub mod terminal;
+
+use crate::lexer::token::Span;
+
+pub use self::code::ErrorCode;
+
+/// A problem found in the source, kept apart from how it is shown so the
+/// same diagnostic can be printed to a terminal, serialized or sent to an
+/// editor.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub code: ErrorCode,
+    pub severity: Severity,
+    pub message: String,
+    /// The source the diagnostic is about, if it points at any.
+    pub span: Option<Span>,
+    /// Other places that explain the problem.
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+    pub help: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn error(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            severity: Severity::Error,
+            message: message.into(),
+            span: None,
+            labels: Vec::new(),
+            notes: Vec::new(),
+            help: None,
+        }
+    }
+
+    pub fn with_span(mut self, span: &Span) -> Self {
+        self.span = Some(span.clone());
+        return self;
+    }
+
+    pub fn with_label(mut self, span: &Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label {
+            span: span.clone(),
+            message: message.into(),
+        });
+        return self;
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        return self;
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        return self;
+    }
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// The diagnostic on one line, for when the source is not at hand.
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}[{}]: {}", self.severity, self.code, self.message)?;
+        if let Some(span) = &self.span {
+            write!(f, " at {}:{}", span.start().row(), span.start().col())?;
+        }
+        return Ok(());
+    }
+}
+
+impl std::error::Error for Diagnostic {}
@@ -0,0 +1,92 @@
+use crate::lexer::token::Span;
+
+use super::Diagnostic;
+
+/// Formats a diagnostic for a terminal: its location, the line it points
+/// at with the span underlined, the message, then the labelled lines, the
+/// notes and the help.
+pub fn render(diagnostic: &Diagnostic, file_name: &str, source: &str) -> String {
+    let source = source.as_bytes();
+    let Some(span) = &diagnostic.span else {
+        let mut content = format!(
+            "{}: {}[{}]\n\n",
+            file_name, diagnostic.severity, diagnostic.code
+        );
+        content += format!("   |\t{}\n", diagnostic.message).as_str();
+        render_footer(&mut content, diagnostic);
+        return content;
+    };
+
+    let row = span.start().row();
+    let mut content = format!(
+        "{}:{}:{}: {}[{}]\n\n",
+        file_name,
+        row,
+        span.start().col(),
+        diagnostic.severity,
+        diagnostic.code
+    );
+    if row.checked_sub(3).is_some() {
+        content += format!("{:-2} |\n", row - 2).as_str();
+        content += format!("{:-2} |\n", row - 1).as_str();
+    }
+    render_line(&mut content, source, span, '^');
+    content += format!("   |\t{}\n", diagnostic.message).as_str();
+    content += format!("{:-2} |\n", row + 1).as_str();
+
+    for label in diagnostic.labels.iter() {
+        render_line(&mut content, source, &label.span, '-');
+        content += format!("   |\t{}\n", label.message).as_str();
+    }
+    render_footer(&mut content, diagnostic);
+    return content;
+}
+
+fn render_footer(content: &mut String, diagnostic: &Diagnostic) {
+    for note in diagnostic.notes.iter() {
+        *content += format!("   = note: {}\n", note).as_str();
+    }
+    if let Some(help) = &diagnostic.help {
+        *content += format!("   = help: {}\n", help).as_str();
+    }
+}
+
+/// Writes the line of `source` where `span` starts with the span marked
+/// under it.
+fn render_line(content: &mut String, source: &[u8], span: &Span, marker: char) {
+    let start = span.start();
+    let cursor = start.cursor_start().min(source.len());
+    let line_start = source[..cursor]
+        .iter()
+        .rposition(|byte| *byte == b'\n')
+        .map_or(0, |newline| newline + 1);
+    let line_end = source[cursor..]
+        .iter()
+        .position(|byte| *byte == b'\n' || *byte == b'\r')
+        .map_or(source.len(), |end| cursor + end);
+    let prefix = format!("{}-{}:  ", start.row(), start.col());
+    *content += format!(
+        "   |\t{}{}\n",
+        prefix,
+        String::from_utf8_lossy(&source[line_start..line_end])
+    )
+    .as_str();
+
+    let end = if span.end().row() == start.row() {
+        span.end().cursor_start().min(line_end)
+    } else {
+        line_end
+    };
+    // Tabs are kept so the markers line up with the code above them.
+    let indent: String = source[line_start..cursor]
+        .iter()
+        .map(|byte| if *byte == b'\t' { '\t' } else { ' ' })
+        .collect();
+    *content += format!(
+        "   |\t{}{}{}\n",
+        " ".repeat(prefix.len()),
+        indent,
+        marker.to_string().repeat(end.saturating_sub(cursor).max(1))
+    )
+    .as_str();
+}
@@ -1,14 +1,18 @@
 use std::{error::Error, fmt};
 
-use crate::{lexer::lexical_error::LexicalError, parser::syntactic_error::SyntacticError};
+use crate::diagnostic::{json, terminal, Diagnostic};
 
 pub type Result<T> = std::result::Result<T, CompilerError>;
 
 #[derive(Debug)]
 pub enum CompilerError {
-    Lexical(LexicalError),
-    Syntactic(SyntacticError),
-    Semantic(String),
+    Lexical(Box<Diagnostic>),
+    Syntactic(Box<Diagnostic>),
+    Semantic(Box<Diagnostic>),
+    CodeGen(String),
+    Runtime(String),
+    /// Every error a stage recovered from, in source order.
+    Multiple(Vec<CompilerError>),
 }
 
 impl Error for CompilerError {
@@ -16,7 +20,10 @@ impl Error for CompilerError {
         match self {
             CompilerError::Lexical(lexical) => Some(lexical),
             CompilerError::Syntactic(syntactic) => Some(syntactic),
-            CompilerError::Semantic(_semantic) => None,
+            CompilerError::Semantic(semantic) => Some(semantic),
+            CompilerError::CodeGen(_codegen) => None,
+            CompilerError::Runtime(_runtime) => None,
+            CompilerError::Multiple(_errors) => None,
         }
     }
 
@@ -31,24 +38,112 @@ impl fmt::Display for CompilerError {
             CompilerError::Lexical(lexical) => write!(f, "{}", lexical),
             CompilerError::Syntactic(syntactic) => write!(f, "{}", syntactic),
             CompilerError::Semantic(semantic) => write!(f, "{}", semantic),
+            CompilerError::CodeGen(codegen) => write!(f, "{}", codegen),
+            CompilerError::Runtime(runtime) => write!(f, "Runtime error: {}", runtime),
+            CompilerError::Multiple(errors) => {
+                let errors: Vec<String> = errors.iter().map(|err| err.to_string()).collect();
+                write!(f, "{}", errors.join("\n"))
+            }
         }
     }
 }
 
+impl CompilerError {
+    /// Fails with the errors a stage collected while recovering, if any.
+    pub fn from_errors(mut errors: Vec<CompilerError>) -> Result<()> {
+        return match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.remove(0)),
+            _ => Err(CompilerError::Multiple(errors)),
+        };
+    }
+
+    /// The diagnostics of the error, in source order.
+    pub fn diagnostics(&self) -> Vec<&Diagnostic> {
+        return match self {
+            CompilerError::Lexical(diagnostic)
+            | CompilerError::Syntactic(diagnostic)
+            | CompilerError::Semantic(diagnostic) => vec![diagnostic.as_ref()],
+            CompilerError::CodeGen(_) | CompilerError::Runtime(_) => Vec::new(),
+            CompilerError::Multiple(errors) => {
+                errors.iter().flat_map(|err| err.diagnostics()).collect()
+            }
+        };
+    }
+
+    /// Formats the error for a terminal, showing diagnostics with the part
+    /// of `source` they point at.
+    pub fn render(&self, file_name: &str, source: &str) -> String {
+        return match self {
+            CompilerError::Lexical(diagnostic)
+            | CompilerError::Syntactic(diagnostic)
+            | CompilerError::Semantic(diagnostic) => {
+                terminal::render(diagnostic, file_name, source)
+            }
+            CompilerError::Multiple(errors) => {
+                let errors: Vec<String> = errors
+                    .iter()
+                    .map(|err| err.render(file_name, source))
+                    .collect();
+                errors.join("\n")
+            }
+            err => err.to_string(),
+        };
+    }
+
+    /// Formats the error as JSON lines, one object per diagnostic.
+    pub fn render_json(&self, file_name: &str) -> String {
+        return match self {
+            CompilerError::Lexical(diagnostic)
+            | CompilerError::Syntactic(diagnostic)
+            | CompilerError::Semantic(diagnostic) => json::render(diagnostic, file_name),
+            CompilerError::Multiple(errors) => {
+                let errors: Vec<String> = errors
+                    .iter()
+                    .map(|err| err.render_json(file_name))
+                    .collect();
+                errors.join("\n")
+            }
+            err => json::render_message(&err.to_string(), file_name),
+        };
+    }
+}
+
 #[macro_export]
 macro_rules! error_lexer {
-    ($lexer:expr,$msg:expr) => {
-        Err(crate::error::CompilerError::Lexical(
-            crate::lexer::lexical_error::LexicalError::new($lexer, String::from($msg)),
-        ))
+    ($lexer:expr,$code:ident,$msg:expr) => {
+        Err($crate::error::CompilerError::Lexical(Box::new(
+            $crate::diagnostic::Diagnostic::error(
+                $crate::diagnostic::ErrorCode::$code,
+                String::from($msg),
+            )
+            .with_span(&$lexer.span()),
+        )))
     };
 }
 
 #[macro_export]
 macro_rules! error_parser {
-    ($parser:expr,$msg:expr) => {
-        Err(crate::error::CompilerError::Syntactic(
-            crate::parser::syntactic_error::SyntacticError::new($parser, String::from($msg)),
-        ))
+    ($parser:expr,$code:ident,$msg:expr) => {
+        Err($crate::error::CompilerError::Syntactic(Box::new(
+            $crate::diagnostic::Diagnostic::error(
+                $crate::diagnostic::ErrorCode::$code,
+                String::from($msg),
+            )
+            .with_span($parser.current().span()),
+        )))
+    };
+}
+
+#[macro_export]
+macro_rules! error_semantic {
+    ($span:expr,$code:ident,$msg:expr) => {
+        Err($crate::error::CompilerError::Semantic(Box::new(
+            $crate::diagnostic::Diagnostic::error(
+                $crate::diagnostic::ErrorCode::$code,
+                String::from($msg),
+            )
+            .with_span($span),
+        )))
     };
 }
@@ -0,0 +1,167 @@
+use crate::ast::{
+    access_specifier::AccessSpecifier,
+    declarations::{
+        const_decl::ConstDeclaration,
+        enum_decl::EnumDeclaration,
+        fn_decl::FnDeclaration,
+        impl_decl::{ImplDeclaration, MemberFunction},
+        struct_decl::StructDeclaration,
+    },
+    variable_type::VariableType,
+};
+
+use super::{
+    expressions::{type_source, ToSource},
+    Format, Formatter,
+};
+
+impl Format for FnDeclaration {
+    fn format(&self, formatter: &mut Formatter) {
+        let params = params(
+            formatter,
+            &self.params,
+            self.fn_name.span.start().row(),
+            self.body.span.start().cursor_start(),
+        );
+        let header = format!(
+            "{}fn {}({}) -> {} ",
+            access(&self.access_specifier),
+            self.fn_name,
+            params,
+            type_source(&self.return_type)
+        );
+        formatter.block(&header, &self.body);
+        formatter.line("}");
+    }
+}
+
+impl Format for StructDeclaration {
+    fn format(&self, formatter: &mut Formatter) {
+        let header = format!("{}struct {} ", access(&self.access_specifier), self.name);
+        formatter.open(&header, self.name.span.start().row());
+        for field in self.fields.iter() {
+            let span = &field.field.identifier.span;
+            formatter.start(span, false);
+            formatter.line(&format!(
+                "{}{},",
+                access(&field.access_specifier),
+                variable(&field.field)
+            ));
+            formatter.end(span.end().row());
+        }
+        formatter.close(&self.span);
+        formatter.line("}");
+    }
+}
+
+impl Format for EnumDeclaration {
+    fn format(&self, formatter: &mut Formatter) {
+        let header = format!("{}enum {} ", access(&self.access_specifier), self.name);
+        formatter.open(&header, self.name.span.start().row());
+        for item in self.fields.iter() {
+            formatter.start(&item.span, false);
+            let mut name = item.identifier.to_string();
+            if !item.payload.is_empty() {
+                let payload: Vec<String> = item.payload.iter().map(type_source).collect();
+                name += &format!("({})", payload.join(", "));
+            }
+            match &item.value {
+                Some(value) => {
+                    let value = value.to_source(formatter);
+                    formatter.line(&format!("{} = {},", name, value));
+                }
+                None => formatter.line(&format!("{},", name)),
+            }
+            formatter.end(item.span.end().row());
+        }
+        formatter.close(&self.span);
+        formatter.line("}");
+    }
+}
+
+impl Format for ImplDeclaration {
+    fn format(&self, formatter: &mut Formatter) {
+        formatter.open(&format!("impl {} ", self.name), self.span.start().row());
+        for member_fn in self.member_functions.iter() {
+            let span = member_fn.prototype.name.span.to(&member_fn.body.span);
+            formatter.start(&span, true);
+            member_fn.format(formatter);
+            formatter.end(span.end().row());
+        }
+        formatter.close(&self.span);
+        formatter.line("}");
+    }
+}
+
+impl Format for MemberFunction {
+    fn format(&self, formatter: &mut Formatter) {
+        let prototype = &self.prototype;
+        let method = match &prototype.method {
+            Some(method) => format!("({}) ", variable(method)),
+            None => String::new(),
+        };
+        let params = params(
+            formatter,
+            &prototype.params,
+            prototype.name.span.start().row(),
+            self.body.span.start().cursor_start(),
+        );
+        let header = format!(
+            "{}fn {}{}({}) -> {} ",
+            access(&prototype.access_specifier),
+            method,
+            prototype.name,
+            params,
+            type_source(&prototype.return_type)
+        );
+        formatter.block(&header, &self.body);
+        formatter.line("}");
+    }
+}
+
+impl Format for ConstDeclaration {
+    fn format(&self, formatter: &mut Formatter) {
+        let value = self.value.to_source(formatter);
+        formatter.line(&format!(
+            "{}const {}: {} = {};",
+            access(&self.access_specifier),
+            self.identifier,
+            type_source(&self.type_specifier),
+            value
+        ));
+    }
+}
+
+fn access(access_specifier: &AccessSpecifier) -> &'static str {
+    return match access_specifier {
+        AccessSpecifier::Public => "pub ",
+        AccessSpecifier::Private => "",
+    };
+}
+
+fn variable(variable: &VariableType) -> String {
+    return format!(
+        "{}: {}",
+        variable.identifier,
+        type_source(&variable.type_specifier)
+    );
+}
+
+/// The parameters of a function whose name is on `row` and whose header
+/// ends at `offset`, one per line when comments are written among them.
+fn params(formatter: &mut Formatter, params: &[VariableType], row: usize, offset: usize) -> String {
+    if params.is_empty() || !formatter.has_comment_before(offset) {
+        let params: Vec<String> = params.iter().map(variable).collect();
+        return params.join(", ");
+    }
+    return formatter.capture(|formatter| {
+        formatter.open_list("", row);
+        for param in params.iter() {
+            let span = &param.identifier.span;
+            formatter.start(span, false);
+            formatter.line(&format!("{},", variable(param)));
+            formatter.end(span.end().row());
+        }
+        formatter.close_list(offset, "");
+    });
+}
@@ -0,0 +1,309 @@
+use crate::{
+    ast::{
+        expressions::{
+            array_index_expr::ArrayIndexExpression,
+            array_init_expr::ArrayInitExpression,
+            assignment_expr::AssignmentExpression,
+            binary_expr::{BinaryExpression, BinaryOperators, BinaryPrecedence},
+            cast_expr::CastExpression,
+            enum_item_expr::EnumItemExpression,
+            fn_call_expr::FnCallExpression,
+            member_access_expr::MemberAccessExpression,
+            object_init_expr::ObjectInitExpression,
+            primitive_expr::PrimitiveExpression,
+            unary_expr::{UnaryExpression, UnaryExpressionType, UnaryOperators},
+            Expression, Precedence,
+        },
+        type_specifier::TypeSpecifier,
+    },
+    lexer::token_type::TokenType,
+};
+
+use super::Formatter;
+
+/// Renders a node as source. The lines of multi-line nodes are indented from
+/// the level of the line the node starts on, and take the comments written
+/// among them.
+pub trait ToSource {
+    fn to_source(&self, formatter: &mut Formatter) -> String;
+}
+
+impl ToSource for Expression {
+    fn to_source(&self, formatter: &mut Formatter) -> String {
+        return match self {
+            Expression::Binary(binary_expr) => binary_expr.to_source(formatter),
+            Expression::Assignment(assignment_expr) => assignment_expr.to_source(formatter),
+            Expression::Unary(unary_expr) => unary_expr.to_source(formatter),
+            Expression::ArrayIndex(array_index_expr) => array_index_expr.to_source(formatter),
+            Expression::MemberAccess(member_access_expr) => member_access_expr.to_source(formatter),
+            Expression::FnCall(fn_call_expr) => fn_call_expr.to_source(formatter),
+            Expression::ArrayInit(array_init_expr) => array_init_expr.to_source(formatter),
+            Expression::ObjectInit(object_init_expr) => object_init_expr.to_source(formatter),
+            Expression::Identifier(identifier_expr) => identifier_expr.id.to_string(),
+            Expression::EnumItem(enum_item_expr) => enum_item_expr.to_source(formatter),
+            Expression::Primitive(primitive_expr) => primitive_expr.to_source(formatter),
+            Expression::Cast(cast_expr) => cast_expr.to_source(formatter),
+        };
+    }
+}
+
+impl ToSource for BinaryExpression {
+    fn to_source(&self, formatter: &mut Formatter) -> String {
+        let precedence = binary_precedence(&self.operator);
+        // The parser groups operators of the same precedence to the right,
+        // an assignment on the left would take the whole expression, and a
+        // `*` after a cast would make its type a pointer.
+        let left = if matches!(self.left.as_ref(), Expression::Assignment(_))
+            || (matches!(self.operator, BinaryOperators::Multiply) && ends_with_type(&self.left))
+        {
+            format!("({})", self.left.to_source(formatter))
+        } else {
+            operand(&self.left, precedence + 1, formatter)
+        };
+        return format!(
+            "{} {} {}",
+            left,
+            self.operator.to_c_operator(),
+            operand(&self.right, precedence, formatter)
+        );
+    }
+}
+
+impl ToSource for AssignmentExpression {
+    fn to_source(&self, formatter: &mut Formatter) -> String {
+        return format!(
+            "{} = {}",
+            operand(&self.variable, level(Precedence::Unary), formatter),
+            self.value.to_source(formatter)
+        );
+    }
+}
+
+impl ToSource for UnaryExpression {
+    fn to_source(&self, formatter: &mut Formatter) -> String {
+        let value = operand(&self.value, level(Precedence::ArrayIndex), formatter);
+        let operator = match self.operator {
+            UnaryOperators::Plus => "+",
+            UnaryOperators::Minus => "-",
+            UnaryOperators::Increment => "++",
+            UnaryOperators::Decrement => "--",
+            UnaryOperators::LogicalNot => "!",
+            UnaryOperators::BitwiseNot => "~",
+            UnaryOperators::Address => "&",
+            UnaryOperators::Indirection => "*",
+        };
+        return match self.expression_type {
+            UnaryExpressionType::Prefix => format!("{}{}", operator, value),
+            UnaryExpressionType::Postfix => format!("{}{}", value, operator),
+        };
+    }
+}
+
+impl ToSource for ArrayIndexExpression {
+    fn to_source(&self, formatter: &mut Formatter) -> String {
+        let array = if ends_with_type(&self.array) {
+            format!("({})", self.array.to_source(formatter))
+        } else {
+            operand(&self.array, level(Precedence::MemberAccess), formatter)
+        };
+        let mut index = self.index.to_source(formatter);
+        if let Some(second_index) = &self.second_index {
+            index += &format!(", {}", second_index.to_source(formatter));
+        }
+        return format!("{}[{}]", array, index);
+    }
+}
+
+impl ToSource for MemberAccessExpression {
+    fn to_source(&self, formatter: &mut Formatter) -> String {
+        let access = match self.access {
+            TokenType::Arrow => "->",
+            _ => ".",
+        };
+        return format!(
+            "{}{}{}",
+            operand(&self.object, level(Precedence::FunctionCall), formatter),
+            access,
+            operand(&self.property, level(Precedence::MemberAccess), formatter)
+        );
+    }
+}
+
+impl ToSource for FnCallExpression {
+    fn to_source(&self, formatter: &mut Formatter) -> String {
+        let mut content = String::new();
+        if let Some(namespace) = &self.namespace {
+            content += &operand(&namespace.name, level(Precedence::ArrayInit), formatter);
+            content += "::";
+        }
+        content += &operand(&self.fn_name, level(Precedence::ArrayInit), formatter);
+        return format!("{}({})", content, list(&self.args, formatter));
+    }
+}
+
+impl ToSource for EnumItemExpression {
+    fn to_source(&self, formatter: &mut Formatter) -> String {
+        let item = format!("{}::{}", self.name, self.item);
+        if self.args.is_empty() {
+            return item;
+        }
+        return format!("{}({})", item, list(&self.args, formatter));
+    }
+}
+
+impl ToSource for ArrayInitExpression {
+    fn to_source(&self, formatter: &mut Formatter) -> String {
+        return format!("[{}]", list(&self.array, formatter));
+    }
+}
+
+/// Object initializers put each field on a line of its own.
+impl ToSource for ObjectInitExpression {
+    fn to_source(&self, formatter: &mut Formatter) -> String {
+        let object = operand(&self.object, level(Precedence::Cast), formatter);
+        if self.fields.is_empty() {
+            return format!("{} {{}}", object);
+        }
+        let row = self.object.span().end().row();
+        return formatter.capture(|formatter| {
+            formatter.open_list(&format!("{} {{", object), row);
+            for field in self.fields.iter() {
+                let span = field.name.span.to(field.value.span());
+                formatter.start(&span, false);
+                let content = match field.value.as_ref() {
+                    Expression::Identifier(value) if value.id == field.name => {
+                        format!("{},", field.name)
+                    }
+                    value => format!("{}: {},", field.name, value.to_source(formatter)),
+                };
+                formatter.line(&content);
+                formatter.end(span.end().row());
+            }
+            formatter.close_list(self.span.end().cursor_start(), "}");
+        });
+    }
+}
+
+impl ToSource for CastExpression {
+    fn to_source(&self, formatter: &mut Formatter) -> String {
+        return format!(
+            "{} as {}",
+            operand(&self.expression, level(Precedence::Identifier), formatter),
+            type_source(&self.target_type)
+        );
+    }
+}
+
+/// Renders a type with its array sizes as the source wrote them, so that a
+/// constant keeps its name instead of its value.
+pub fn type_source(type_specifier: &TypeSpecifier) -> String {
+    return match type_specifier {
+        TypeSpecifier::Pointer(pointee) => format!("{}*", type_source(pointee)),
+        TypeSpecifier::ArrayType { .. } => {
+            let mut sizes = String::new();
+            let mut item = type_specifier;
+            while let TypeSpecifier::ArrayType {
+                type_specifier,
+                size,
+                written_size,
+            } = item
+            {
+                match &written_size.0 {
+                    Some(written_size) => sizes += &format!("[{}]", written_size),
+                    None => sizes += &format!("[{}]", size),
+                }
+                item = type_specifier;
+            }
+            format!("{}{}", type_source(item), sizes)
+        }
+        _ => type_specifier.to_string(),
+    };
+}
+
+impl ToSource for PrimitiveExpression {
+    fn to_source(&self, _formatter: &mut Formatter) -> String {
+        return match self.type_specifier {
+            TypeSpecifier::Str => format!("\"{}\"", self.value),
+            TypeSpecifier::Char => format!("'{}'", self.value),
+            _ => self.value.clone(),
+        };
+    }
+}
+
+/// Renders `expr` where the parser reads an expression of at least
+/// `minimum` precedence, in parentheses if it binds more loosely.
+fn operand(expr: &Expression, minimum: usize, formatter: &mut Formatter) -> String {
+    let content = expr.to_source(formatter);
+    if precedence(expr) < minimum {
+        return format!("({})", content);
+    }
+    return content;
+}
+
+/// Whether `expr` ends with the type of a cast, which would take a `*` or
+/// `[` written after it.
+fn ends_with_type(expr: &Expression) -> bool {
+    return match expr {
+        Expression::Cast(_) => true,
+        Expression::Binary(binary_expr) => ends_with_type(&binary_expr.right),
+        Expression::Assignment(assignment_expr) => ends_with_type(&assignment_expr.value),
+        Expression::Unary(unary_expr) => {
+            matches!(unary_expr.expression_type, UnaryExpressionType::Prefix)
+                && ends_with_type(&unary_expr.value)
+        }
+        Expression::MemberAccess(member_access_expr) => {
+            ends_with_type(&member_access_expr.property)
+        }
+        _ => false,
+    };
+}
+
+fn list(exprs: &[Expression], formatter: &mut Formatter) -> String {
+    let exprs: Vec<String> = exprs.iter().map(|expr| expr.to_source(formatter)).collect();
+    return exprs.join(", ");
+}
+
+/// Binary operators from `||` to `*` come first on the scale of
+/// precedences, then the other expressions in the order the parser tries
+/// them, up to literals and parenthesized expressions.
+fn precedence(expr: &Expression) -> usize {
+    return match expr {
+        Expression::Binary(binary_expr) => binary_precedence(&binary_expr.operator),
+        Expression::Assignment(_) => level(Precedence::Assignment),
+        Expression::Unary(_) => level(Precedence::Unary),
+        Expression::ArrayIndex(_) => level(Precedence::ArrayIndex),
+        Expression::MemberAccess(_) => level(Precedence::MemberAccess),
+        Expression::FnCall(_) => level(Precedence::FunctionCall),
+        Expression::ArrayInit(_) => level(Precedence::ArrayInit),
+        Expression::ObjectInit(_) => level(Precedence::ObjectInit),
+        Expression::Cast(_) => level(Precedence::Cast),
+        Expression::Identifier(_) | Expression::EnumItem(_) => level(Precedence::Identifier),
+        Expression::Primitive(_) => level(Precedence::Primitive),
+    };
+}
+
+fn level(precedence: Precedence) -> usize {
+    return BinaryPrecedence::Product as usize + precedence as usize;
+}
+
+fn binary_precedence(operator: &BinaryOperators) -> usize {
+    let precedence = match operator {
+        BinaryOperators::Or => BinaryPrecedence::Or,
+        BinaryOperators::And => BinaryPrecedence::And,
+        BinaryOperators::BitOr => BinaryPrecedence::BitOr,
+        BinaryOperators::BitXor => BinaryPrecedence::BitXor,
+        BinaryOperators::BitAnd => BinaryPrecedence::BitAnd,
+        BinaryOperators::BitLeft => BinaryPrecedence::BitLeft,
+        BinaryOperators::BitRight => BinaryPrecedence::BitRight,
+        BinaryOperators::Equal | BinaryOperators::NotEqual => BinaryPrecedence::Equal,
+        BinaryOperators::GreaterThan
+        | BinaryOperators::LessThan
+        | BinaryOperators::GreaterThanOrEqual
+        | BinaryOperators::LessThanOrEqual => BinaryPrecedence::Relational,
+        BinaryOperators::Add | BinaryOperators::Subtract => BinaryPrecedence::Sum,
+        BinaryOperators::Multiply | BinaryOperators::Divide | BinaryOperators::Modulo => {
+            BinaryPrecedence::Product
+        }
+    };
+    return precedence as usize;
+}
@@ -0,0 +1,188 @@
+pub mod declarations;
+pub mod expressions;
+pub mod program;
+pub mod statements;
+
+use crate::{
+    ast::{program::Program, statements::block_stmt::BlockStmt},
+    lexer::token::{Comment, Span},
+};
+
+const INDENT: &str = "    ";
+
+/// Prints a parsed program back as Marble source in the canonical style.
+///
+/// Blocks open on the line of their header, top-level declarations and
+/// methods are separated by one blank line, and struct fields, enum items
+/// and object initializer fields go one per line. Blank lines between
+/// statements are kept, but never more than one in a row. Comments stay on
+/// their own line before, or at the end of the line of, the declaration,
+/// statement, field or parameter they were written next to, and parameters
+/// go one per line when comments are written among them.
+pub struct Formatter<'a> {
+    comments: &'a [Comment],
+    next_comment: usize,
+    output: String,
+    pub(crate) indent: usize,
+    /// The source row where what was written last ends.
+    last_row: usize,
+    /// Whether nothing was written since a block opened, so that no blank
+    /// line follows the brace.
+    fresh: bool,
+}
+
+pub trait Format {
+    fn format(&self, formatter: &mut Formatter);
+}
+
+impl<'a> Formatter<'a> {
+    /// A formatter that puts `comments`, which the parser skipped, back into
+    /// the source.
+    pub fn new(comments: &'a [Comment]) -> Self {
+        Self {
+            comments,
+            next_comment: 0,
+            output: String::new(),
+            indent: 0,
+            last_row: 0,
+            fresh: true,
+        }
+    }
+
+    pub fn format(mut self, program: &Program) -> String {
+        program.format(&mut self);
+        self.comments_before(usize::MAX, false);
+        return self.output;
+    }
+
+    pub(crate) fn line(&mut self, content: &str) {
+        self.output += &INDENT.repeat(self.indent);
+        self.output += content;
+        self.output += "\n";
+        self.fresh = false;
+    }
+
+    /// Writes the comments before the item at `span` on lines of their own
+    /// and a blank line where the source had one. With `blank`, there is
+    /// always a blank line before the item and its comments.
+    pub(crate) fn start(&mut self, span: &Span, blank: bool) {
+        let blank = self.comments_before(span.start().cursor_start(), blank);
+        if blank || self.gap(span.start().row()) {
+            self.output += "\n";
+        }
+    }
+
+    /// Records that the item written last ends on `row`. The comments that
+    /// follow it on that row are added to its last line once the next item
+    /// shows where they stop.
+    pub(crate) fn end(&mut self, row: usize) {
+        self.last_row = row;
+    }
+
+    /// Writes `header` and an opening brace found on `row`, and indents what
+    /// follows. The header takes the comments left on the row of a closing
+    /// brace it continues, as in `} else {`.
+    pub(crate) fn open(&mut self, header: &str, row: usize) {
+        self.line(&format!("{}{{", header));
+        self.trailing(usize::MAX);
+        self.end(row);
+        self.fresh = true;
+        self.indent += 1;
+    }
+
+    /// Writes `content`, which ends the line being written and opens a list
+    /// found on `row`, and indents the items that follow.
+    pub(crate) fn open_list(&mut self, content: &str, row: usize) {
+        self.output += content;
+        self.output += "\n";
+        self.end(row);
+        self.fresh = true;
+        self.indent += 1;
+    }
+
+    /// Writes the comments left before `offset`, where a list ends, stops
+    /// indenting and starts the line of `content` without ending it.
+    pub(crate) fn close_list(&mut self, offset: usize, content: &str) {
+        self.comments_before(offset, false);
+        self.indent -= 1;
+        self.output += &INDENT.repeat(self.indent);
+        self.output += content;
+    }
+
+    /// Runs `write` on an empty output and returns what it wrote, for items
+    /// that span lines but are part of a longer line.
+    pub(crate) fn capture(&mut self, write: impl FnOnce(&mut Self)) -> String {
+        let output = std::mem::take(&mut self.output);
+        write(self);
+        return std::mem::replace(&mut self.output, output);
+    }
+
+    /// Whether a comment is left before `offset`.
+    pub(crate) fn has_comment_before(&self, offset: usize) -> bool {
+        return self
+            .comments
+            .get(self.next_comment)
+            .is_some_and(|comment| comment.span.start().cursor_start() < offset);
+    }
+
+    /// Writes the comments left before the closing brace that ends `span`
+    /// and stops indenting. The caller writes the brace.
+    pub(crate) fn close(&mut self, span: &Span) {
+        self.comments_before(span.end().cursor_start(), false);
+        self.last_row = span.end().row();
+        self.indent -= 1;
+    }
+
+    /// Writes `header` and `block` up to, but not including, its closing
+    /// brace.
+    pub(crate) fn block(&mut self, header: &str, block: &BlockStmt) {
+        self.open(header, block.span.start().row());
+        block.format(self);
+        self.close(&block.span);
+    }
+
+    /// Writes the comments before `offset`, returning whether a forced
+    /// blank line is still owed to what comes next.
+    fn comments_before(&mut self, offset: usize, blank: bool) -> bool {
+        self.trailing(offset);
+        let mut blank = blank && !self.fresh;
+        while let Some(comment) = self.comments.get(self.next_comment) {
+            if comment.span.start().cursor_start() >= offset {
+                break;
+            }
+            self.next_comment += 1;
+            if blank || self.gap(comment.span.start().row()) {
+                self.output += "\n";
+            }
+            blank = false;
+            self.line(&comment.text);
+            self.last_row = comment.span.end().row();
+        }
+        return blank;
+    }
+
+    /// Adds the comments before `offset` on the row where what was written
+    /// last ends to its last line.
+    fn trailing(&mut self, offset: usize) {
+        while let Some(comment) = self.comments.get(self.next_comment) {
+            if self.last_row == 0
+                || comment.span.start().row() != self.last_row
+                || comment.span.start().cursor_start() >= offset
+            {
+                break;
+            }
+            self.next_comment += 1;
+            self.output.pop();
+            self.output += " ";
+            self.output += &comment.text;
+            self.output += "\n";
+            self.last_row = comment.span.end().row();
+        }
+    }
+
+    /// Whether the source left a blank line between what was written last
+    /// and `row`.
+    fn gap(&self, row: usize) -> bool {
+        return !self.fresh && self.last_row != 0 && row > self.last_row + 1;
+    }
+}
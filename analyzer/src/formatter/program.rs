@@ -0,0 +1,26 @@
+use crate::ast::{declarations::Declarations, program::Program};
+
+use super::{Format, Formatter};
+
+impl Format for Program {
+    fn format(&self, formatter: &mut Formatter) {
+        for decl in self.declarations.iter() {
+            let span = decl.span();
+            formatter.start(&span, true);
+            decl.format(formatter);
+            formatter.end(span.end().row());
+        }
+    }
+}
+
+impl Format for Declarations {
+    fn format(&self, formatter: &mut Formatter) {
+        match self {
+            Declarations::Fn(fn_decl) => fn_decl.format(formatter),
+            Declarations::Struct(struct_decl) => struct_decl.format(formatter),
+            Declarations::Enum(enum_decl) => enum_decl.format(formatter),
+            Declarations::Impl(impl_decl) => impl_decl.format(formatter),
+            Declarations::Const(const_decl) => const_decl.format(formatter),
+        }
+    }
+}
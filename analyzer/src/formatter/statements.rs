@@ -0,0 +1,232 @@
+use crate::ast::{
+    identifier::Identifier,
+    statements::{
+        block_stmt::BlockStmt,
+        break_stmt::BreakStmt,
+        continue_stmt::ContinueStmt,
+        defer_stmt::DeferStmt,
+        do_while_stmt::DoWhileStmt,
+        for_stmt::{ForStmt, VariableInit},
+        if_stmt::IfStmt,
+        let_stmt::LetStmt,
+        match_stmt::MatchStmt,
+        return_stmt::ReturnStmt,
+        switch_stmt::SwitchStmt,
+        while_stmt::WhileStmt,
+        Statements,
+    },
+};
+
+use super::{
+    expressions::{type_source, ToSource},
+    Format, Formatter,
+};
+
+impl Format for BlockStmt {
+    fn format(&self, formatter: &mut Formatter) {
+        for stmt in self.stmts.iter() {
+            let span = stmt.span();
+            formatter.start(span, false);
+            stmt.format(formatter);
+            formatter.end(span.end().row());
+        }
+    }
+}
+
+impl Format for Statements {
+    fn format(&self, formatter: &mut Formatter) {
+        match self {
+            Statements::Let(let_stmt) => {
+                let let_stmt = let_stmt.to_source(formatter);
+                formatter.line(&format!("{};", let_stmt));
+            }
+            Statements::Const(const_decl) => const_decl.format(formatter),
+            Statements::Return(return_stmt) => return_stmt.format(formatter),
+            Statements::Defer(defer_stmt) => defer_stmt.format(formatter),
+            Statements::For(for_stmt) => for_stmt.format(formatter),
+            Statements::While(while_stmt) => while_stmt.format(formatter),
+            Statements::DoWhile(do_while_stmt) => do_while_stmt.format(formatter),
+            Statements::If(if_stmt) => if_stmt.format(formatter),
+            Statements::Switch(switch_stmt) => switch_stmt.format(formatter),
+            Statements::Match(match_stmt) => match_stmt.format(formatter),
+            Statements::Break(break_stmt) => break_stmt.format(formatter),
+            Statements::Continue(continue_stmt) => continue_stmt.format(formatter),
+            Statements::Block(block_stmt) => {
+                formatter.block("", block_stmt);
+                formatter.line("}");
+            }
+            Statements::Expression(expr) => {
+                let expr = expr.to_source(formatter);
+                formatter.line(&format!("{};", expr));
+            }
+        }
+    }
+}
+
+/// A `let` without its semicolon, which `for` headers share.
+impl ToSource for LetStmt {
+    fn to_source(&self, formatter: &mut Formatter) -> String {
+        let mut content = format!("let {}", self.identifier);
+        if let Some(type_specifier) = &self.type_specifier {
+            content += &format!(": {}", type_source(type_specifier));
+        }
+        if let Some(value) = &self.value {
+            content += &format!(" = {}", value.to_source(formatter));
+        }
+        return content;
+    }
+}
+
+impl Format for ReturnStmt {
+    fn format(&self, formatter: &mut Formatter) {
+        match &self.value {
+            Some(value) => {
+                let value = value.to_source(formatter);
+                formatter.line(&format!("return {};", value));
+            }
+            None => formatter.line("return;"),
+        }
+    }
+}
+
+impl Format for DeferStmt {
+    fn format(&self, formatter: &mut Formatter) {
+        let expr = self.expr.to_source(formatter);
+        formatter.line(&format!("defer {};", expr));
+    }
+}
+
+impl Format for BreakStmt {
+    fn format(&self, formatter: &mut Formatter) {
+        formatter.line(&format!("break{};", target(&self.label)));
+    }
+}
+
+impl Format for ContinueStmt {
+    fn format(&self, formatter: &mut Formatter) {
+        formatter.line(&format!("continue{};", target(&self.label)));
+    }
+}
+
+impl Format for ForStmt {
+    fn format(&self, formatter: &mut Formatter) {
+        let init = match &self.init {
+            VariableInit::Let(let_stmt) => let_stmt.to_source(formatter),
+            VariableInit::Assign(assignment) => assignment.to_source(formatter),
+        };
+        let header = format!(
+            "{}for ({}; {}; {}) ",
+            label(&self.label),
+            init,
+            self.condition.to_source(formatter),
+            self.increment.to_source(formatter)
+        );
+        formatter.block(&header, &self.block);
+        formatter.line("}");
+    }
+}
+
+impl Format for WhileStmt {
+    fn format(&self, formatter: &mut Formatter) {
+        let condition = self.condition.to_source(formatter);
+        let header = format!("{}while ({}) ", label(&self.label), condition);
+        formatter.block(&header, &self.block);
+        formatter.line("}");
+    }
+}
+
+impl Format for DoWhileStmt {
+    fn format(&self, formatter: &mut Formatter) {
+        formatter.block(&format!("{}do ", label(&self.label)), &self.block);
+        let condition = self.condition.to_source(formatter);
+        formatter.line(&format!("}} while ({});", condition));
+    }
+}
+
+impl Format for IfStmt {
+    fn format(&self, formatter: &mut Formatter) {
+        let condition = self.condition.to_source(formatter);
+        formatter.block(&format!("if ({}) ", condition), &self.block);
+        for else_if in self.else_ifs.iter() {
+            let condition = else_if.condition.to_source(formatter);
+            formatter.block(&format!("}} else if ({}) ", condition), &else_if.block);
+        }
+        if let Some(else_stmt) = &self.else_stmt {
+            formatter.block("} else ", else_stmt);
+        }
+        formatter.line("}");
+    }
+}
+
+impl Format for SwitchStmt {
+    fn format(&self, formatter: &mut Formatter) {
+        let value = self.value.to_source(formatter);
+        let header = format!("switch ({}) ", value);
+        formatter.open(&header, self.value.span().end().row());
+        for case in self.cases.iter() {
+            formatter.start(&case.span, false);
+            let value = case.value.to_source(formatter);
+            formatter.block(&format!("case {}: ", value), &case.block);
+            formatter.line("}");
+            formatter.end(case.span.end().row());
+        }
+        if let Some(default) = &self.default {
+            formatter.start(&default.span, false);
+            formatter.block("default: ", default);
+            formatter.line("}");
+            formatter.end(default.span.end().row());
+        }
+        formatter.close(&self.span);
+        formatter.line("}");
+    }
+}
+
+impl Format for MatchStmt {
+    fn format(&self, formatter: &mut Formatter) {
+        let value = self.value.to_source(formatter);
+        let header = format!("match ({}) ", value);
+        formatter.open(&header, self.value.span().end().row());
+        for arm in self.arms.iter() {
+            formatter.start(&arm.span, false);
+            let mut pattern = format!("{}::{}", arm.name, arm.item);
+            if !arm.bindings.is_empty() {
+                let bindings = arm
+                    .bindings
+                    .iter()
+                    .map(|binding| match binding {
+                        Some(binding) => binding.to_string(),
+                        None => String::from("_"),
+                    })
+                    .collect::<Vec<_>>();
+                pattern += &format!("({})", bindings.join(", "));
+            }
+            formatter.block(&format!("{} => ", pattern), &arm.block);
+            formatter.line("}");
+            formatter.end(arm.span.end().row());
+        }
+        if let Some(default) = &self.default {
+            formatter.start(&default.span, false);
+            formatter.block("_ => ", default);
+            formatter.line("}");
+            formatter.end(default.span.end().row());
+        }
+        formatter.close(&self.span);
+        formatter.line("}");
+    }
+}
+
+/// The `label: ` in front of a loop.
+fn label(label: &Option<Identifier>) -> String {
+    return match label {
+        Some(label) => format!("{}: ", label),
+        None => String::new(),
+    };
+}
+
+/// The ` label` after `break` or `continue`.
+fn target(label: &Option<Identifier>) -> String {
+    return match label {
+        Some(label) => format!(" {}", label),
+        None => String::new(),
+    };
+}
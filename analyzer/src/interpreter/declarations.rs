@@ -0,0 +1,71 @@
+use crate::{
+    ast::{
+        declarations::{fn_decl::FnDeclaration, impl_decl::MemberFunction},
+        statements::block_stmt::BlockStmt,
+        variable_type::VariableType,
+    },
+    error::{CompilerError, Result},
+};
+
+use super::{value::Value, Execute, Flow, Interpreter};
+
+impl<'a> Interpreter<'a> {
+    pub(crate) fn call_fn(
+        &mut self,
+        fn_decl: &'a FnDeclaration,
+        args: Vec<Value>,
+    ) -> Result<Value> {
+        let params: Vec<&VariableType> = fn_decl.params.iter().collect();
+        return self.call(fn_decl.fn_name.as_ref(), &params, args, &fn_decl.body);
+    }
+
+    /// Calls a member function, `receiver` is passed as its first parameter.
+    pub(crate) fn call_member(
+        &mut self,
+        member_fn: &'a MemberFunction,
+        receiver: Option<Value>,
+        mut args: Vec<Value>,
+    ) -> Result<Value> {
+        if let Some(receiver) = receiver {
+            args.insert(0, receiver);
+        }
+        let name = member_fn.prototype.name.as_ref();
+        return self.call(name, &member_fn.params(), args, &member_fn.body);
+    }
+
+    fn call(
+        &mut self,
+        name: &str,
+        params: &[&VariableType],
+        args: Vec<Value>,
+        body: &'a BlockStmt,
+    ) -> Result<Value> {
+        if params.len() != args.len() {
+            return Err(CompilerError::Runtime(format!(
+                "Function {} expects {} arguments but got {}",
+                name,
+                params.len(),
+                args.len()
+            )));
+        }
+
+        self.push_frame()?;
+        let result = self.push_scope().and_then(|_| {
+            for (param, arg) in params.iter().zip(args) {
+                self.declare(param.identifier.as_ref(), arg)?;
+            }
+            return body.execute(self);
+        });
+        self.pop_frame();
+
+        return Ok(match result? {
+            Flow::Return(value) => value,
+            Flow::Normal => Value::Void,
+            Flow::Break(_) | Flow::Continue(_) => {
+                return Err(CompilerError::Runtime(String::from(
+                    "Jump out of a function without a loop",
+                )))
+            }
+        });
+    }
+}
@@ -0,0 +1,552 @@
+use crate::{
+    ast::{
+        declarations::enum_decl::EnumDeclaration,
+        expressions::{
+            array_index_expr::ArrayIndexExpression,
+            array_init_expr::ArrayInitExpression,
+            assignment_expr::AssignmentExpression,
+            binary_expr::{BinaryExpression, BinaryOperators},
+            cast_expr::CastExpression,
+            enum_item_expr::EnumItemExpression,
+            fn_call_expr::FnCallExpression,
+            identifier_expression::IdentifierExpression,
+            member_access_expr::MemberAccessExpression,
+            object_init_expr::ObjectInitExpression,
+            primitive_expr::PrimitiveExpression,
+            unary_expr::{UnaryExpression, UnaryExpressionType, UnaryOperators},
+            Expression,
+        },
+        type_specifier::TypeSpecifier,
+    },
+    error::{CompilerError, Result},
+    lexer::token_type::TokenType,
+};
+
+use super::{
+    value::{Pointer, StructValue, Value},
+    Evaluate, Interpreter,
+};
+
+impl<'a> Evaluate<'a> for Expression {
+    fn evaluate(&'a self, interpreter: &mut Interpreter<'a>) -> Result<Value> {
+        return match self {
+            Expression::Binary(binary_expr) => binary_expr.evaluate(interpreter),
+            Expression::Assignment(assignment_expr) => assignment_expr.evaluate(interpreter),
+            Expression::Unary(unary_expr) => unary_expr.evaluate(interpreter),
+            Expression::ArrayIndex(array_index) => array_index.evaluate(interpreter),
+            Expression::MemberAccess(member_access) => member_access.evaluate(interpreter),
+            Expression::FnCall(fn_call_expr) => fn_call_expr.evaluate(interpreter),
+            Expression::ArrayInit(array_init_expr) => array_init_expr.evaluate(interpreter),
+            Expression::ObjectInit(object_init_expr) => object_init_expr.evaluate(interpreter),
+            Expression::Cast(cast_expr) => cast_expr.evaluate(interpreter),
+            Expression::Identifier(identifier_expr) => identifier_expr.evaluate(interpreter),
+            Expression::EnumItem(enum_item_expr) => enum_item_expr.evaluate(interpreter),
+            Expression::Primitive(primitive_expr) => primitive_expr.evaluate(interpreter),
+        };
+    }
+}
+
+impl<'a> Interpreter<'a> {
+    /// Resolves the storage an expression refers to. Expressions that do not
+    /// name a variable are evaluated into a temporary.
+    pub(crate) fn place(&mut self, expr: &'a Expression) -> Result<Pointer> {
+        return match expr {
+            Expression::Identifier(identifier) => {
+                Ok(Pointer::new(self.variable(identifier.id.as_ref())?))
+            }
+            Expression::MemberAccess(member_access) => {
+                let object = self.object_place(&member_access.object, &member_access.access)?;
+                self.member_place(object, &member_access.property)
+            }
+            Expression::ArrayIndex(array_index) => self.index_place(array_index),
+            Expression::Unary(UnaryExpression {
+                operator: UnaryOperators::Indirection,
+                value,
+                ..
+            }) => value.evaluate(self)?.into_pointer(),
+            _ => Ok(Pointer::new(expr.evaluate(self)?.into_cell())),
+        };
+    }
+
+    fn object_place(&mut self, object: &'a Expression, access: &TokenType) -> Result<Pointer> {
+        if access == &TokenType::Arrow {
+            return object.evaluate(self)?.into_pointer();
+        }
+        return self.place(object);
+    }
+
+    /// Resolves `property` on the struct stored at `object`. Chained accesses
+    /// like `a.b.c` are nested inside the property.
+    fn member_place(&mut self, object: Pointer, property: &'a Expression) -> Result<Pointer> {
+        return match property {
+            Expression::Identifier(identifier) => Ok(object.field(identifier.id.as_ref())),
+            Expression::MemberAccess(member_access) => {
+                let inner = self.member_place(object, &member_access.object)?;
+                let inner = match member_access.access {
+                    TokenType::Arrow => inner.read()?.into_pointer()?,
+                    _ => inner,
+                };
+                self.member_place(inner, &member_access.property)
+            }
+            Expression::FnCall(fn_call) => {
+                let result = self.method_call(object, fn_call)?;
+                Ok(Pointer::new(result.into_cell()))
+            }
+            _ => Err(CompilerError::Runtime(String::from(
+                "Invalid property expression",
+            ))),
+        };
+    }
+
+    /// Calls a member function on `object`, passing it by pointer or by value
+    /// depending on the receiver of the function.
+    fn method_call(&mut self, object: Pointer, fn_call: &'a FnCallExpression) -> Result<Value> {
+        let struct_name = object.inspect(|value| match value {
+            Value::Struct(object) => Ok(object.name.clone()),
+            _ => Err(CompilerError::Runtime(String::from(
+                "Member functions only can be called on structs",
+            ))),
+        })??;
+        let name = fn_call.fn_name.identifier()?.id.as_ref();
+        let member_fn = self.method(&struct_name, name)?;
+
+        let receiver = match &member_fn.prototype.method {
+            Some(method) => match method.type_specifier {
+                TypeSpecifier::Pointer(_) => Some(Value::Pointer(object)),
+                _ => Some(object.read()?),
+            },
+            None => None,
+        };
+        let args = self.args(&fn_call.args)?;
+        return self.call_member(member_fn, receiver, args);
+    }
+
+    fn index_place(&mut self, array_index: &'a ArrayIndexExpression) -> Result<Pointer> {
+        let array = self.place(&array_index.array)?;
+        let index = array_index.index.evaluate(self)?.as_index()?;
+        let element = match array.read_pointer()? {
+            Some(pointer) => pointer.offset(index)?,
+            None => array.index(index)?,
+        };
+        let Some(second_index) = &array_index.second_index else {
+            return Ok(element);
+        };
+        let second_index = second_index.evaluate(self)?.as_index()?;
+        return element.index(second_index);
+    }
+
+    fn args(&mut self, args: &'a [Expression]) -> Result<Vec<Value>> {
+        return args.iter().map(|arg| arg.evaluate(self)).collect();
+    }
+}
+
+impl Pointer {
+    /// Returns the pointed value when it is itself a pointer.
+    fn read_pointer(&self) -> Result<Option<Pointer>> {
+        return self.inspect(|value| match value {
+            Value::Pointer(pointer) => Ok(Some(pointer.clone())),
+            Value::Null => Err(CompilerError::Runtime(String::from(
+                "Null pointer dereference",
+            ))),
+            _ => Ok(None),
+        })?;
+    }
+}
+
+impl<'a> Evaluate<'a> for BinaryExpression {
+    fn evaluate(&'a self, interpreter: &mut Interpreter<'a>) -> Result<Value> {
+        let left = self.left.evaluate(interpreter)?;
+        match self.operator {
+            BinaryOperators::And if !left.as_bool()? => return Ok(Value::Bool(false)),
+            BinaryOperators::Or if left.as_bool()? => return Ok(Value::Bool(true)),
+            _ => {}
+        }
+        let right = self.right.evaluate(interpreter)?;
+        return self.operator.apply(left, right);
+    }
+}
+
+macro_rules! integer_operation {
+    ($operator:expr, $variant:path, $left:expr, $right:expr) => {{
+        let (left, right) = ($left, $right);
+        match $operator {
+            BinaryOperators::Add => $variant(left.wrapping_add(right)),
+            BinaryOperators::Subtract => $variant(left.wrapping_sub(right)),
+            BinaryOperators::Multiply => $variant(left.wrapping_mul(right)),
+            BinaryOperators::Divide | BinaryOperators::Modulo if right == 0 => {
+                return Err(CompilerError::Runtime(String::from("Division by zero")))
+            }
+            BinaryOperators::Divide => $variant(left.wrapping_div(right)),
+            BinaryOperators::Modulo => $variant(left.wrapping_rem(right)),
+            BinaryOperators::BitAnd => $variant(left & right),
+            BinaryOperators::BitOr => $variant(left | right),
+            BinaryOperators::BitXor => $variant(left ^ right),
+            BinaryOperators::BitLeft => $variant(left.wrapping_shl(right as u32)),
+            BinaryOperators::BitRight => $variant(left.wrapping_shr(right as u32)),
+            operator => Value::Bool(operator.compare(left.partial_cmp(&right))?),
+        }
+    }};
+}
+
+macro_rules! float_operation {
+    ($operator:expr, $variant:path, $left:expr, $right:expr) => {{
+        let (left, right) = ($left, $right);
+        match $operator {
+            BinaryOperators::Add => $variant(left + right),
+            BinaryOperators::Subtract => $variant(left - right),
+            BinaryOperators::Multiply => $variant(left * right),
+            BinaryOperators::Divide => $variant(left / right),
+            BinaryOperators::Modulo => $variant(left % right),
+            operator => Value::Bool(operator.compare(left.partial_cmp(&right))?),
+        }
+    }};
+}
+
+impl BinaryOperators {
+    pub fn apply(&self, left: Value, right: Value) -> Result<Value> {
+        return Ok(match (left, right) {
+            (Value::Int(left), Value::Int(right)) => {
+                integer_operation!(self, Value::Int, left, right)
+            }
+            (Value::Usize(left), Value::Usize(right)) => {
+                integer_operation!(self, Value::Usize, left, right)
+            }
+            (Value::Char(left), Value::Char(right)) => {
+                integer_operation!(self, Value::Char, left, right)
+            }
+            (Value::Float(left), Value::Float(right)) => {
+                float_operation!(self, Value::Float, left, right)
+            }
+            (Value::Double(left), Value::Double(right)) => {
+                float_operation!(self, Value::Double, left, right)
+            }
+            (Value::Bool(left), Value::Bool(right)) => match self {
+                BinaryOperators::And | BinaryOperators::BitAnd => Value::Bool(left & right),
+                BinaryOperators::Or | BinaryOperators::BitOr => Value::Bool(left | right),
+                BinaryOperators::BitXor => Value::Bool(left ^ right),
+                operator => Value::Bool(operator.compare(left.partial_cmp(&right))?),
+            },
+            (Value::Str(left), Value::Str(right)) => {
+                Value::Bool(self.compare(left.partial_cmp(&right))?)
+            }
+            (
+                Value::Enum { name, value: left },
+                Value::Enum {
+                    name: right_name,
+                    value: right,
+                },
+            ) if name == right_name => Value::Bool(self.compare(left.partial_cmp(&right))?),
+            _ => {
+                return Err(CompilerError::Runtime(format!(
+                    "Invalid operands for {}",
+                    self.to_c_operator()
+                )))
+            }
+        });
+    }
+
+    fn compare(&self, ordering: Option<std::cmp::Ordering>) -> Result<bool> {
+        let Some(ordering) = ordering else {
+            // Comparisons with NaN are false except `!=`.
+            return Ok(matches!(self, BinaryOperators::NotEqual));
+        };
+        return Ok(match self {
+            BinaryOperators::Equal => ordering.is_eq(),
+            BinaryOperators::NotEqual => ordering.is_ne(),
+            BinaryOperators::GreaterThan => ordering.is_gt(),
+            BinaryOperators::LessThan => ordering.is_lt(),
+            BinaryOperators::GreaterThanOrEqual => ordering.is_ge(),
+            BinaryOperators::LessThanOrEqual => ordering.is_le(),
+            operator => {
+                return Err(CompilerError::Runtime(format!(
+                    "Invalid operands for {}",
+                    operator.to_c_operator()
+                )))
+            }
+        });
+    }
+}
+
+impl<'a> Evaluate<'a> for AssignmentExpression {
+    fn evaluate(&'a self, interpreter: &mut Interpreter<'a>) -> Result<Value> {
+        let variable = interpreter.place(&self.variable)?;
+        let value = self.value.evaluate(interpreter)?;
+        variable.write(value.clone())?;
+        return Ok(value);
+    }
+}
+
+impl<'a> Evaluate<'a> for UnaryExpression {
+    fn evaluate(&'a self, interpreter: &mut Interpreter<'a>) -> Result<Value> {
+        return match &self.operator {
+            UnaryOperators::Address => Ok(Value::Pointer(interpreter.place(&self.value)?)),
+            UnaryOperators::Indirection => self.value.evaluate(interpreter)?.into_pointer()?.read(),
+            UnaryOperators::Increment | UnaryOperators::Decrement => self.step(interpreter),
+            operator => operator.apply(self.value.evaluate(interpreter)?),
+        };
+    }
+}
+
+impl UnaryOperators {
+    /// Applies an operator that only reads its operand.
+    pub fn apply(&self, value: Value) -> Result<Value> {
+        return match self {
+            UnaryOperators::Plus => Ok(value),
+            UnaryOperators::Minus => match value {
+                Value::Int(value) => Ok(Value::Int(value.wrapping_neg())),
+                Value::Usize(value) => Ok(Value::Usize(value.wrapping_neg())),
+                Value::Char(value) => Ok(Value::Char(value.wrapping_neg())),
+                Value::Float(value) => Ok(Value::Float(-value)),
+                Value::Double(value) => Ok(Value::Double(-value)),
+                _ => Err(CompilerError::Runtime(String::from(
+                    "Invalid operand for -",
+                ))),
+            },
+            UnaryOperators::LogicalNot => Ok(Value::Bool(!value.as_bool()?)),
+            UnaryOperators::BitwiseNot => match value {
+                Value::Int(value) => Ok(Value::Int(!value)),
+                Value::Usize(value) => Ok(Value::Usize(!value)),
+                Value::Char(value) => Ok(Value::Char(!value)),
+                Value::Bool(value) => Ok(Value::Bool(!value)),
+                _ => Err(CompilerError::Runtime(String::from(
+                    "Invalid operand for ~",
+                ))),
+            },
+            _ => Err(CompilerError::Runtime(String::from(
+                "Operator needs a variable",
+            ))),
+        };
+    }
+}
+
+impl UnaryExpression {
+    /// Applies `++` or `--`, returning the new value for the prefix form and
+    /// the old one for the postfix form.
+    fn step<'a>(&'a self, interpreter: &mut Interpreter<'a>) -> Result<Value> {
+        let variable = interpreter.place(&self.value)?;
+        let old = variable.read()?;
+        let delta = if matches!(self.operator, UnaryOperators::Increment) {
+            1
+        } else {
+            -1
+        };
+        let new = match &old {
+            Value::Int(value) => Value::Int(value.wrapping_add(delta)),
+            Value::Usize(value) => Value::Usize(value.wrapping_add_signed(delta as i64)),
+            Value::Char(value) => Value::Char(value.wrapping_add_signed(delta as i8)),
+            Value::Float(value) => Value::Float(value + delta as f32),
+            Value::Double(value) => Value::Double(value + delta as f64),
+            Value::Pointer(pointer) => Value::Pointer(pointer.offset(delta as i64)?),
+            _ => {
+                return Err(CompilerError::Runtime(String::from(
+                    "Invalid operand for increment or decrement",
+                )))
+            }
+        };
+        variable.write(new.clone())?;
+        return Ok(match self.expression_type {
+            UnaryExpressionType::Prefix => new,
+            UnaryExpressionType::Postfix => old,
+        });
+    }
+}
+
+impl<'a> Evaluate<'a> for ArrayIndexExpression {
+    fn evaluate(&'a self, interpreter: &mut Interpreter<'a>) -> Result<Value> {
+        return interpreter.index_place(self)?.read();
+    }
+}
+
+impl<'a> Evaluate<'a> for MemberAccessExpression {
+    fn evaluate(&'a self, interpreter: &mut Interpreter<'a>) -> Result<Value> {
+        let object = interpreter.object_place(&self.object, &self.access)?;
+        return interpreter.member_place(object, &self.property)?.read();
+    }
+}
+
+impl<'a> Evaluate<'a> for FnCallExpression {
+    fn evaluate(&'a self, interpreter: &mut Interpreter<'a>) -> Result<Value> {
+        let name = self.fn_name.identifier()?.id.as_ref();
+        let args = interpreter.args(&self.args)?;
+        if let Some(namespace) = &self.namespace {
+            let struct_name = namespace.name.identifier()?.id.as_ref();
+            let member_fn = interpreter.method(struct_name, name)?;
+            return interpreter.call_member(member_fn, None, args);
+        }
+        let fn_decl = interpreter.function(name)?;
+        return interpreter.call_fn(fn_decl, args);
+    }
+}
+
+impl<'a> Evaluate<'a> for ArrayInitExpression {
+    fn evaluate(&'a self, interpreter: &mut Interpreter<'a>) -> Result<Value> {
+        return Ok(Value::Array(interpreter.args(&self.array)?));
+    }
+}
+
+impl<'a> Evaluate<'a> for ObjectInitExpression {
+    fn evaluate(&'a self, interpreter: &mut Interpreter<'a>) -> Result<Value> {
+        let name = self.object.identifier()?.id.to_string();
+        let mut object = match interpreter.zero_value(&TypeSpecifier::UserDefine(
+            self.object.identifier()?.id.clone(),
+        ))? {
+            Value::Struct(object) => object,
+            _ => return Err(CompilerError::Runtime(format!("{} is not a struct", name))),
+        };
+        for field in self.fields.iter() {
+            let value = field.value.evaluate(interpreter)?;
+            object.set(field.name.as_ref(), value)?;
+        }
+        return Ok(Value::Struct(object));
+    }
+}
+
+impl StructValue {
+    pub(crate) fn get(&self, name: &str) -> Result<&Value> {
+        return self
+            .fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value)
+            .ok_or_else(|| {
+                CompilerError::Runtime(format!("Unknown field {}.{}", self.name, name))
+            });
+    }
+
+    fn set(&mut self, name: &str, value: Value) -> Result<()> {
+        let Some((_, field)) = self.fields.iter_mut().find(|(field, _)| field == name) else {
+            return Err(CompilerError::Runtime(format!(
+                "Unknown field {}.{}",
+                self.name, name
+            )));
+        };
+        *field = value;
+        return Ok(());
+    }
+}
+
+impl<'a> Evaluate<'a> for CastExpression {
+    fn evaluate(&'a self, interpreter: &mut Interpreter<'a>) -> Result<Value> {
+        let value = self.expression.evaluate(interpreter)?;
+        if let TypeSpecifier::UserDefine(name) = &self.target_type {
+            if interpreter.context.is_enum(name.as_ref()) {
+                let Value::Int(value) = value else {
+                    return Err(CompilerError::Runtime(format!(
+                        "Cannot cast to the enum {}",
+                        name
+                    )));
+                };
+                if !interpreter.context.is_enum_value(name.as_ref(), value) {
+                    return Err(CompilerError::Runtime(format!(
+                        "{} is not a value of enum {}",
+                        value, name
+                    )));
+                }
+                return Ok(Value::Enum {
+                    name: name.to_string(),
+                    value,
+                });
+            }
+        }
+        return value.cast(&self.target_type);
+    }
+}
+
+impl Value {
+    /// Converts a primitive value with the rules of a C cast.
+    pub fn cast(&self, target_type: &TypeSpecifier) -> Result<Value> {
+        let (integer, float) = match self {
+            Value::Int(value) => (*value as i64, *value as f64),
+            Value::Usize(value) => (*value as i64, *value as f64),
+            Value::Char(value) => (*value as i64, *value as f64),
+            Value::Bool(value) => (*value as i64, *value as i64 as f64),
+            Value::Float(value) => (*value as i64, *value as f64),
+            Value::Double(value) => (*value as i64, *value),
+            Value::Enum { value, .. } => (*value as i64, *value as f64),
+            _ => {
+                return Err(CompilerError::Runtime(String::from(
+                    "Cannot cast the complex type",
+                )))
+            }
+        };
+        return Ok(match target_type {
+            TypeSpecifier::Int => Value::Int(integer as i32),
+            TypeSpecifier::Usize => Value::Usize(integer as u64),
+            TypeSpecifier::Char => Value::Char(integer as u8),
+            TypeSpecifier::Float => Value::Float(float as f32),
+            TypeSpecifier::Double => Value::Double(float),
+            TypeSpecifier::Bool => Value::Bool(float != 0.0),
+            _ => {
+                return Err(CompilerError::Runtime(String::from(
+                    "Cannot cast to the complex type",
+                )))
+            }
+        });
+    }
+}
+
+impl<'a> Evaluate<'a> for IdentifierExpression {
+    fn evaluate(&'a self, interpreter: &mut Interpreter<'a>) -> Result<Value> {
+        let variable = interpreter.variable(self.id.as_ref())?;
+        let value = variable.borrow().clone();
+        return Ok(value);
+    }
+}
+
+impl<'a> Evaluate<'a> for EnumItemExpression {
+    fn evaluate(&'a self, interpreter: &mut Interpreter<'a>) -> Result<Value> {
+        let value = interpreter
+            .context
+            .enum_item(self.name.as_ref(), self.item.as_ref())?;
+        if !interpreter.context.is_tagged(self.name.as_ref()) {
+            return Ok(Value::Enum {
+                name: self.name.to_string(),
+                value,
+            });
+        }
+        let type_specifier = TypeSpecifier::UserDefine(self.name.clone());
+        let Value::Struct(mut object) = interpreter.zero_value(&type_specifier)? else {
+            return Err(CompilerError::Runtime(format!(
+                "{} is not a tagged enum",
+                self.name
+            )));
+        };
+        object.set(EnumDeclaration::TAG, Value::Int(value))?;
+        for (index, arg) in self.args.iter().enumerate() {
+            let field = EnumDeclaration::payload_field(self.item.as_ref(), index);
+            let value = arg.evaluate(interpreter)?;
+            object.set(&field, value)?;
+        }
+        return Ok(Value::Struct(object));
+    }
+}
+
+impl<'a> Evaluate<'a> for PrimitiveExpression {
+    fn evaluate(&'a self, _interpreter: &mut Interpreter<'a>) -> Result<Value> {
+        return self.literal();
+    }
+}
+
+impl PrimitiveExpression {
+    pub fn literal(&self) -> Result<Value> {
+        let invalid = || CompilerError::Runtime(format!("Invalid literal {}", self.value));
+        return Ok(match self.type_specifier {
+            TypeSpecifier::Int => {
+                Value::Int(self.value.parse::<i64>().map_err(|_| invalid())? as i32)
+            }
+            TypeSpecifier::Usize => Value::Usize(self.value.parse().map_err(|_| invalid())?),
+            TypeSpecifier::Float => Value::Float(self.value.parse().map_err(|_| invalid())?),
+            TypeSpecifier::Double => Value::Double(self.value.parse().map_err(|_| invalid())?),
+            TypeSpecifier::Bool => Value::Bool(self.value == "true"),
+            TypeSpecifier::Char => {
+                let bytes = self.bytes();
+                Value::Char(*bytes.first().ok_or_else(invalid)?)
+            }
+            TypeSpecifier::Str => {
+                let bytes = self.bytes();
+                Value::Str(String::from_utf8_lossy(&bytes).into())
+            }
+            _ => return Err(invalid()),
+        });
+    }
+}
@@ -0,0 +1,257 @@
+pub mod declarations;
+pub mod expressions;
+pub mod statements;
+pub mod value;
+
+use std::collections::HashMap;
+
+use crate::{
+    ast::{
+        declarations::{fn_decl::FnDeclaration, impl_decl::MemberFunction, Declarations},
+        expressions::Expression,
+        identifier::Identifier,
+        program::Program,
+        type_specifier::TypeSpecifier,
+    },
+    codegen::type_context::TypeContext,
+    error::{CompilerError, Result},
+    vm::MAX_CALL_DEPTH,
+};
+
+use self::value::{Cell, StructValue, Value};
+
+/// The native stack [`Interpreter::run`] needs to reach [`MAX_CALL_DEPTH`],
+/// since every call of the program recurses through its statements. It is
+/// far more than the main thread has, so the interpreter runs on a thread
+/// of its own.
+pub const STACK_SIZE: usize = 2 << 30;
+
+/// Executes an analyzed program by walking its AST, starting from `main`.
+///
+/// Every variable lives in its own cell so `&` can hand out pointers into
+/// it. `defer` expressions are block scoped like in the C backend.
+pub struct Interpreter<'a> {
+    pub(crate) context: TypeContext,
+    functions: HashMap<&'a str, &'a FnDeclaration>,
+    methods: HashMap<(&'a str, &'a str), &'a MemberFunction>,
+    frames: Vec<Frame<'a>>,
+}
+
+/// The variables and pending `defer` expressions of one function call.
+#[derive(Default)]
+pub(crate) struct Frame<'a> {
+    scopes: Vec<HashMap<String, Cell>>,
+    defers: Vec<Vec<&'a Expression>>,
+}
+
+/// How the execution of a statement ended.
+#[derive(Debug)]
+pub enum Flow {
+    Normal,
+    Return(Value),
+    /// A `break` and the label of the loop it leaves, if it names one.
+    Break(Option<String>),
+    /// A `continue` and the label of the loop it continues, if it names one.
+    Continue(Option<String>),
+}
+
+impl Flow {
+    /// What a loop labeled `label` does once its body ended with this flow:
+    /// `None` to run the next iteration, or how the loop itself ends.
+    pub fn after_iteration(self, label: Option<&Identifier>) -> Option<Flow> {
+        let is_target = |target: &Option<String>| match (target, label) {
+            (None, _) => true,
+            (Some(target), Some(label)) => target == label.as_ref(),
+            (Some(_), None) => false,
+        };
+        return match self {
+            Flow::Normal => None,
+            Flow::Continue(target) if is_target(&target) => None,
+            Flow::Break(target) if is_target(&target) => Some(Flow::Normal),
+            flow => Some(flow),
+        };
+    }
+}
+
+pub trait Execute<'a> {
+    fn execute(&'a self, interpreter: &mut Interpreter<'a>) -> Result<Flow>;
+}
+
+pub trait Evaluate<'a> {
+    fn evaluate(&'a self, interpreter: &mut Interpreter<'a>) -> Result<Value>;
+}
+
+impl<'a> Interpreter<'a> {
+    pub fn new(program: &'a Program) -> Self {
+        let mut functions = HashMap::new();
+        let mut methods = HashMap::new();
+        for decl in program.declarations.iter() {
+            match decl {
+                Declarations::Fn(fn_decl) => {
+                    functions.insert(fn_decl.fn_name.as_ref(), fn_decl);
+                }
+                Declarations::Impl(impl_decl) => {
+                    let Ok(struct_name) = impl_decl.name.to_symbol() else {
+                        continue;
+                    };
+                    for member_fn in impl_decl.member_functions.iter() {
+                        let name = member_fn.prototype.name.as_ref();
+                        methods.insert((struct_name, name), member_fn);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Self {
+            context: TypeContext::new(program),
+            functions,
+            methods,
+            frames: Vec::new(),
+        }
+    }
+
+    /// Runs `main` and returns its result as the exit code.
+    pub fn run(&mut self) -> Result<i32> {
+        let main = self.function("main")?;
+        if !main.params.is_empty() {
+            return Err(CompilerError::Runtime(String::from(
+                "main function cannot take parameters",
+            )));
+        }
+        return match self.call_fn(main, Vec::new())? {
+            Value::Int(code) => Ok(code),
+            Value::Void => Ok(0),
+            _ => Err(CompilerError::Runtime(String::from(
+                "main function must return int or void",
+            ))),
+        };
+    }
+
+    pub(crate) fn function(&self, name: &str) -> Result<&'a FnDeclaration> {
+        return self
+            .functions
+            .get(name)
+            .copied()
+            .ok_or_else(|| CompilerError::Runtime(format!("Unknown function {}", name)));
+    }
+
+    pub(crate) fn method(&self, struct_name: &str, name: &str) -> Result<&'a MemberFunction> {
+        return self
+            .methods
+            .get(&(struct_name, name))
+            .copied()
+            .ok_or_else(|| {
+                CompilerError::Runtime(format!("Unknown function {}::{}", struct_name, name))
+            });
+    }
+
+    pub(crate) fn push_frame(&mut self) -> Result<()> {
+        if self.frames.len() >= MAX_CALL_DEPTH {
+            return Err(CompilerError::Runtime(String::from(
+                "Maximum call depth exceeded",
+            )));
+        }
+        self.frames.push(Frame::default());
+        return Ok(());
+    }
+
+    pub(crate) fn pop_frame(&mut self) {
+        self.frames.pop();
+    }
+
+    fn frame(&mut self) -> Result<&mut Frame<'a>> {
+        return self
+            .frames
+            .last_mut()
+            .ok_or_else(|| CompilerError::Runtime(String::from("No active call frame")));
+    }
+
+    pub(crate) fn push_scope(&mut self) -> Result<()> {
+        let frame = self.frame()?;
+        frame.scopes.push(HashMap::new());
+        frame.defers.push(Vec::new());
+        return Ok(());
+    }
+
+    pub(crate) fn pop_scope(&mut self) -> Result<()> {
+        let frame = self.frame()?;
+        frame.scopes.pop();
+        frame.defers.pop();
+        return Ok(());
+    }
+
+    /// Removes the `defer` expressions of the innermost scope, newest first.
+    pub(crate) fn take_defers(&mut self) -> Result<Vec<&'a Expression>> {
+        let frame = self.frame()?;
+        let mut defers = frame
+            .defers
+            .last_mut()
+            .map(std::mem::take)
+            .unwrap_or_default();
+        defers.reverse();
+        return Ok(defers);
+    }
+
+    pub(crate) fn defer(&mut self, expr: &'a Expression) -> Result<()> {
+        if let Some(scope) = self.frame()?.defers.last_mut() {
+            scope.push(expr);
+        }
+        return Ok(());
+    }
+
+    pub(crate) fn declare(&mut self, name: &str, value: Value) -> Result<()> {
+        if let Some(scope) = self.frame()?.scopes.last_mut() {
+            scope.insert(name.to_owned(), value.into_cell());
+        }
+        return Ok(());
+    }
+
+    pub(crate) fn variable(&mut self, name: &str) -> Result<Cell> {
+        return self
+            .frame()?
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .cloned()
+            .ok_or_else(|| CompilerError::Runtime(format!("Unknown variable {}", name)));
+    }
+
+    /// Default value of variables declared without an initializer.
+    pub(crate) fn zero_value(&self, type_specifier: &TypeSpecifier) -> Result<Value> {
+        return Ok(match type_specifier {
+            TypeSpecifier::Int => Value::Int(0),
+            TypeSpecifier::Usize => Value::Usize(0),
+            TypeSpecifier::Float => Value::Float(0.0),
+            TypeSpecifier::Double => Value::Double(0.0),
+            TypeSpecifier::Char => Value::Char(0),
+            TypeSpecifier::Str => Value::Str("".into()),
+            TypeSpecifier::Bool => Value::Bool(false),
+            TypeSpecifier::Void => Value::Void,
+            TypeSpecifier::Pointer(_) => Value::Null,
+            TypeSpecifier::ArrayType {
+                type_specifier,
+                size,
+                ..
+            } => Value::Array(vec![self.zero_value(type_specifier)?; *size]),
+            TypeSpecifier::UserDefine(name) if self.context.is_enum(name.as_ref()) => Value::Enum {
+                name: name.to_string(),
+                value: 0,
+            },
+            TypeSpecifier::UserDefine(name) => {
+                let layout = self.context.struct_layout(name.as_ref())?;
+                let fields = layout
+                    .fields
+                    .iter()
+                    .map(|(field, type_specifier)| {
+                        return Ok((field.clone(), self.zero_value(type_specifier)?));
+                    })
+                    .collect::<Result<Vec<(String, Value)>>>()?;
+                Value::Struct(StructValue {
+                    name: name.to_string(),
+                    fields,
+                })
+            }
+        });
+    }
+}
@@ -0,0 +1,263 @@
+use crate::{
+    ast::{
+        declarations::enum_decl::EnumDeclaration,
+        statements::{
+            block_stmt::BlockStmt,
+            break_stmt::BreakStmt,
+            continue_stmt::ContinueStmt,
+            defer_stmt::DeferStmt,
+            do_while_stmt::DoWhileStmt,
+            for_stmt::{ForStmt, VariableInit},
+            if_stmt::IfStmt,
+            let_stmt::LetStmt,
+            match_stmt::{MatchArm, MatchStmt},
+            return_stmt::ReturnStmt,
+            switch_stmt::SwitchStmt,
+            while_stmt::WhileStmt,
+            Statements,
+        },
+    },
+    error::{CompilerError, Result},
+};
+
+use super::{value::Value, Evaluate, Execute, Flow, Interpreter};
+
+impl<'a> Execute<'a> for Statements {
+    fn execute(&'a self, interpreter: &mut Interpreter<'a>) -> Result<Flow> {
+        return match self {
+            Statements::Let(let_stmt) => let_stmt.execute(interpreter),
+            Statements::Const(_) => Ok(Flow::Normal),
+            Statements::Return(return_stmt) => return_stmt.execute(interpreter),
+            Statements::Defer(defer_stmt) => defer_stmt.execute(interpreter),
+            Statements::For(for_stmt) => for_stmt.execute(interpreter),
+            Statements::While(while_stmt) => while_stmt.execute(interpreter),
+            Statements::DoWhile(do_while_stmt) => do_while_stmt.execute(interpreter),
+            Statements::If(if_stmt) => if_stmt.execute(interpreter),
+            Statements::Switch(switch_stmt) => switch_stmt.execute(interpreter),
+            Statements::Match(match_stmt) => match_stmt.execute(interpreter),
+            Statements::Break(break_stmt) => break_stmt.execute(interpreter),
+            Statements::Continue(continue_stmt) => continue_stmt.execute(interpreter),
+            Statements::Block(block_stmt) => block_stmt.execute(interpreter),
+            Statements::Expression(expr) => {
+                expr.evaluate(interpreter)?;
+                Ok(Flow::Normal)
+            }
+        };
+    }
+}
+
+impl<'a> Execute<'a> for BlockStmt {
+    /// Runs the statements in a new scope, then the deferred expressions of
+    /// the block. A `return` value is computed before the defers run.
+    fn execute(&'a self, interpreter: &mut Interpreter<'a>) -> Result<Flow> {
+        interpreter.push_scope()?;
+        let result = self.execute_statements(interpreter).and_then(|flow| {
+            for expr in interpreter.take_defers()? {
+                expr.evaluate(interpreter)?;
+            }
+            return Ok(flow);
+        });
+        interpreter.pop_scope()?;
+        return result;
+    }
+}
+
+impl BlockStmt {
+    fn execute_statements<'a>(&'a self, interpreter: &mut Interpreter<'a>) -> Result<Flow> {
+        for stmt in self.stmts.iter() {
+            let flow = stmt.execute(interpreter)?;
+            if !matches!(flow, Flow::Normal) {
+                return Ok(flow);
+            }
+        }
+        return Ok(Flow::Normal);
+    }
+}
+
+impl<'a> Execute<'a> for LetStmt {
+    fn execute(&'a self, interpreter: &mut Interpreter<'a>) -> Result<Flow> {
+        let value = match (&self.value, &self.type_specifier) {
+            (Some(value), _) => value.evaluate(interpreter)?,
+            (None, Some(type_specifier)) => interpreter.zero_value(type_specifier)?,
+            (None, None) => {
+                return Err(CompilerError::Runtime(format!(
+                    "Type of {} is unknown",
+                    self.identifier
+                )))
+            }
+        };
+        interpreter.declare(self.identifier.as_ref(), value)?;
+        return Ok(Flow::Normal);
+    }
+}
+
+impl<'a> Execute<'a> for ReturnStmt {
+    fn execute(&'a self, interpreter: &mut Interpreter<'a>) -> Result<Flow> {
+        let value = match &self.value {
+            Some(value) => value.evaluate(interpreter)?,
+            None => Value::Void,
+        };
+        return Ok(Flow::Return(value));
+    }
+}
+
+impl<'a> Execute<'a> for DeferStmt {
+    fn execute(&'a self, interpreter: &mut Interpreter<'a>) -> Result<Flow> {
+        interpreter.defer(&self.expr)?;
+        return Ok(Flow::Normal);
+    }
+}
+
+impl<'a> Execute<'a> for BreakStmt {
+    fn execute(&'a self, _interpreter: &mut Interpreter<'a>) -> Result<Flow> {
+        return Ok(Flow::Break(self.label.as_ref().map(ToString::to_string)));
+    }
+}
+
+impl<'a> Execute<'a> for ContinueStmt {
+    fn execute(&'a self, _interpreter: &mut Interpreter<'a>) -> Result<Flow> {
+        return Ok(Flow::Continue(self.label.as_ref().map(ToString::to_string)));
+    }
+}
+
+impl<'a> Execute<'a> for ForStmt {
+    fn execute(&'a self, interpreter: &mut Interpreter<'a>) -> Result<Flow> {
+        interpreter.push_scope()?;
+        let result = self.execute_loop(interpreter);
+        interpreter.pop_scope()?;
+        return result;
+    }
+}
+
+impl ForStmt {
+    fn execute_loop<'a>(&'a self, interpreter: &mut Interpreter<'a>) -> Result<Flow> {
+        match &self.init {
+            VariableInit::Let(let_stmt) => {
+                let_stmt.execute(interpreter)?;
+            }
+            VariableInit::Assign(assign) => {
+                assign.evaluate(interpreter)?;
+            }
+        }
+        while self.condition.evaluate(interpreter)?.as_bool()? {
+            let flow = self.block.execute(interpreter)?;
+            if let Some(flow) = flow.after_iteration(self.label.as_ref()) {
+                return Ok(flow);
+            }
+            self.increment.evaluate(interpreter)?;
+        }
+        return Ok(Flow::Normal);
+    }
+}
+
+impl<'a> Execute<'a> for WhileStmt {
+    fn execute(&'a self, interpreter: &mut Interpreter<'a>) -> Result<Flow> {
+        while self.condition.evaluate(interpreter)?.as_bool()? {
+            let flow = self.block.execute(interpreter)?;
+            if let Some(flow) = flow.after_iteration(self.label.as_ref()) {
+                return Ok(flow);
+            }
+        }
+        return Ok(Flow::Normal);
+    }
+}
+
+impl<'a> Execute<'a> for DoWhileStmt {
+    fn execute(&'a self, interpreter: &mut Interpreter<'a>) -> Result<Flow> {
+        loop {
+            let flow = self.block.execute(interpreter)?;
+            if let Some(flow) = flow.after_iteration(self.label.as_ref()) {
+                return Ok(flow);
+            }
+            if !self.condition.evaluate(interpreter)?.as_bool()? {
+                return Ok(Flow::Normal);
+            }
+        }
+    }
+}
+
+impl<'a> Execute<'a> for IfStmt {
+    fn execute(&'a self, interpreter: &mut Interpreter<'a>) -> Result<Flow> {
+        if self.condition.evaluate(interpreter)?.as_bool()? {
+            return self.block.execute(interpreter);
+        }
+        for else_if in self.else_ifs.iter() {
+            if else_if.condition.evaluate(interpreter)?.as_bool()? {
+                return else_if.block.execute(interpreter);
+            }
+        }
+        if let Some(else_stmt) = &self.else_stmt {
+            return else_stmt.execute(interpreter);
+        }
+        return Ok(Flow::Normal);
+    }
+}
+
+impl<'a> Execute<'a> for SwitchStmt {
+    fn execute(&'a self, interpreter: &mut Interpreter<'a>) -> Result<Flow> {
+        let value = match self.value.evaluate(interpreter)? {
+            Value::Enum { value, .. } => value as i64,
+            value => value.as_index()?,
+        };
+        for case in self.cases.iter() {
+            if interpreter.context.case_value(&case.value)? == value {
+                return case.block.execute(interpreter);
+            }
+        }
+        if let Some(default) = &self.default {
+            return default.execute(interpreter);
+        }
+        return Ok(Flow::Normal);
+    }
+}
+
+impl<'a> Execute<'a> for MatchStmt {
+    /// Runs the arm of the item the value holds in a scope with its bindings.
+    fn execute(&'a self, interpreter: &mut Interpreter<'a>) -> Result<Flow> {
+        let value = self.value.evaluate(interpreter)?;
+        let tag = match &value {
+            Value::Enum { value, .. } => *value,
+            Value::Struct(object) => object.get(EnumDeclaration::TAG)?.as_index()? as i32,
+            _ => {
+                return Err(CompilerError::Runtime(String::from(
+                    "Cannot match on the value",
+                )))
+            }
+        };
+        for arm in self.arms.iter() {
+            let item = interpreter
+                .context
+                .enum_item(arm.name.as_ref(), arm.item.as_ref())?;
+            if item != tag {
+                continue;
+            }
+            interpreter.push_scope()?;
+            let result = arm
+                .declare_bindings(&value, interpreter)
+                .and_then(|_| arm.block.execute(interpreter));
+            interpreter.pop_scope()?;
+            return result;
+        }
+        if let Some(default) = &self.default {
+            return default.execute(interpreter);
+        }
+        return Ok(Flow::Normal);
+    }
+}
+
+impl MatchArm {
+    /// Declares the named bindings with the values carried by the item.
+    fn declare_bindings(&self, value: &Value, interpreter: &mut Interpreter) -> Result<()> {
+        let Value::Struct(object) = value else {
+            return Ok(());
+        };
+        for (index, binding) in self.bindings.iter().enumerate() {
+            let Some(binding) = binding else {
+                continue;
+            };
+            let field = EnumDeclaration::payload_field(self.item.as_ref(), index);
+            interpreter.declare(binding.as_ref(), object.get(&field)?.clone())?;
+        }
+        return Ok(());
+    }
+}
@@ -0,0 +1,195 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::error::{CompilerError, Result};
+
+pub type Cell = Rc<RefCell<Value>>;
+
+/// A runtime value. Structs and arrays are stored by value, so copying a
+/// value copies the whole object just like in the generated C code.
+#[derive(Debug, Clone)]
+pub enum Value {
+    Int(i32),
+    Usize(u64),
+    Float(f32),
+    Double(f64),
+    Char(u8),
+    Str(Rc<str>),
+    Bool(bool),
+    Void,
+    Enum { name: String, value: i32 },
+    Struct(StructValue),
+    Array(Vec<Value>),
+    Pointer(Pointer),
+    Null,
+}
+
+#[derive(Debug, Clone)]
+pub struct StructValue {
+    pub name: String,
+    pub fields: Vec<(String, Value)>,
+}
+
+/// Points into a variable: the cell that owns the variable and the path of
+/// fields and indices leading to the pointed value.
+#[derive(Debug, Clone)]
+pub struct Pointer {
+    cell: Cell,
+    path: Vec<PathElement>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathElement {
+    Field(String),
+    Index(usize),
+}
+
+impl Value {
+    pub fn into_cell(self) -> Cell {
+        return Rc::new(RefCell::new(self));
+    }
+
+    pub fn into_pointer(self) -> Result<Pointer> {
+        return match self {
+            Value::Pointer(pointer) => Ok(pointer),
+            Value::Null => Err(CompilerError::Runtime(String::from(
+                "Null pointer dereference",
+            ))),
+            _ => Err(CompilerError::Runtime(String::from(
+                "Expected pointer value",
+            ))),
+        };
+    }
+
+    pub fn as_bool(&self) -> Result<bool> {
+        if let Value::Bool(value) = self {
+            return Ok(*value);
+        }
+        return Err(CompilerError::Runtime(String::from("Expected bool value")));
+    }
+
+    pub fn as_index(&self) -> Result<i64> {
+        return match self {
+            Value::Int(value) => Ok(*value as i64),
+            Value::Usize(value) => Ok(*value as i64),
+            Value::Char(value) => Ok(*value as i64),
+            _ => Err(CompilerError::Runtime(String::from(
+                "Array index must be an integer",
+            ))),
+        };
+    }
+
+    fn child(&self, element: &PathElement) -> Result<&Value> {
+        return match (self, element) {
+            (Value::Struct(object), PathElement::Field(name)) => object
+                .fields
+                .iter()
+                .find(|(field, _)| field == name)
+                .map(|(_, value)| value)
+                .ok_or_else(|| Self::unknown_field(&object.name, name)),
+            (Value::Array(items), PathElement::Index(index)) => {
+                let size = items.len();
+                items
+                    .get(*index)
+                    .ok_or_else(|| Self::out_of_bounds(*index, size))
+            }
+            _ => Err(CompilerError::Runtime(String::from(
+                "Invalid member or index access",
+            ))),
+        };
+    }
+
+    fn child_mut(&mut self, element: &PathElement) -> Result<&mut Value> {
+        return match (self, element) {
+            (Value::Struct(object), PathElement::Field(name)) => {
+                let struct_name = object.name.clone();
+                object
+                    .fields
+                    .iter_mut()
+                    .find(|(field, _)| field == name)
+                    .map(|(_, value)| value)
+                    .ok_or_else(|| Self::unknown_field(&struct_name, name))
+            }
+            (Value::Array(items), PathElement::Index(index)) => {
+                let size = items.len();
+                items
+                    .get_mut(*index)
+                    .ok_or_else(|| Self::out_of_bounds(*index, size))
+            }
+            _ => Err(CompilerError::Runtime(String::from(
+                "Invalid member or index access",
+            ))),
+        };
+    }
+
+    fn unknown_field(struct_name: &str, name: &str) -> CompilerError {
+        return CompilerError::Runtime(format!("Unknown field {}.{}", struct_name, name));
+    }
+
+    fn out_of_bounds(index: usize, size: usize) -> CompilerError {
+        return CompilerError::Runtime(format!(
+            "Index {} is out of bounds for array of size {}",
+            index, size
+        ));
+    }
+}
+
+impl Pointer {
+    pub fn new(cell: Cell) -> Self {
+        Self {
+            cell,
+            path: Vec::new(),
+        }
+    }
+
+    pub fn field(&self, name: &str) -> Self {
+        let mut pointer = self.clone();
+        pointer.path.push(PathElement::Field(name.to_owned()));
+        return pointer;
+    }
+
+    pub fn index(&self, index: i64) -> Result<Self> {
+        let index = usize::try_from(index)
+            .map_err(|_| CompilerError::Runtime(format!("Negative array index {}", index)))?;
+        let mut pointer = self.clone();
+        pointer.path.push(PathElement::Index(index));
+        return Ok(pointer);
+    }
+
+    /// Moves a pointer to an array element `offset` elements further.
+    pub fn offset(&self, offset: i64) -> Result<Self> {
+        if offset == 0 {
+            return Ok(self.clone());
+        }
+        let mut pointer = self.clone();
+        let Some(PathElement::Index(index)) = pointer.path.pop() else {
+            return Err(CompilerError::Runtime(String::from(
+                "Pointer arithmetic is only allowed inside an array",
+            )));
+        };
+        return pointer.index(index as i64 + offset);
+    }
+
+    /// Calls `callback` with the pointed value without copying it.
+    pub fn inspect<T>(&self, callback: impl FnOnce(&Value) -> T) -> Result<T> {
+        let cell = self.cell.borrow();
+        let mut value: &Value = &cell;
+        for element in self.path.iter() {
+            value = value.child(element)?;
+        }
+        return Ok(callback(value));
+    }
+
+    pub fn read(&self) -> Result<Value> {
+        return self.inspect(|value| value.clone());
+    }
+
+    pub fn write(&self, new_value: Value) -> Result<()> {
+        let mut cell = self.cell.borrow_mut();
+        let mut value: &mut Value = &mut cell;
+        for element in self.path.iter() {
+            value = value.child_mut(element)?;
+        }
+        *value = new_value;
+        return Ok(());
+    }
+}
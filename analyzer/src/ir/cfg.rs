@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+
+use super::{Block, BlockId, Function, Terminator};
+
+/// Control-flow graph queries over the blocks of a function.
+impl Function {
+    pub fn predecessors(&self) -> Vec<Vec<BlockId>> {
+        let mut predecessors = vec![Vec::new(); self.blocks.len()];
+        for (id, block) in self.blocks.iter().enumerate() {
+            for successor in block.terminator.successors() {
+                predecessors[successor.0].push(BlockId(id));
+            }
+        }
+        return predecessors;
+    }
+
+    /// The blocks reachable from the entry, each before its successors
+    /// unless the edge closes a loop.
+    pub fn reverse_postorder(&self) -> Vec<BlockId> {
+        let mut visited = vec![false; self.blocks.len()];
+        let mut postorder = Vec::new();
+        if self.blocks.is_empty() {
+            return postorder;
+        }
+        let mut stack = vec![(BlockId(0), 0)];
+        visited[0] = true;
+        while let Some((block, index)) = stack.pop() {
+            let successors = self.block(block).terminator.successors();
+            match successors.get(index) {
+                Some(successor) => {
+                    stack.push((block, index + 1));
+                    if !visited[successor.0] {
+                        visited[successor.0] = true;
+                        stack.push((*successor, 0));
+                    }
+                }
+                None => postorder.push(block),
+            }
+        }
+        postorder.reverse();
+        return postorder;
+    }
+
+    /// Drops the blocks no path from the entry reaches and renumbers the rest.
+    pub fn remove_unreachable_blocks(&mut self) {
+        let order = self.reverse_postorder();
+        if order.len() == self.blocks.len() {
+            return;
+        }
+        let mut reachable = vec![false; self.blocks.len()];
+        for block in order.iter() {
+            reachable[block.0] = true;
+        }
+        let mut renumbered = HashMap::new();
+        let mut blocks = Vec::new();
+        for (id, block) in std::mem::take(&mut self.blocks).into_iter().enumerate() {
+            if reachable[id] {
+                renumbered.insert(BlockId(id), BlockId(blocks.len()));
+                blocks.push(block);
+            }
+        }
+        for block in blocks.iter_mut() {
+            match &mut block.terminator {
+                Terminator::Jump(target) => *target = renumbered[target],
+                Terminator::Branch {
+                    then, otherwise, ..
+                } => {
+                    *then = renumbered[then];
+                    *otherwise = renumbered[otherwise];
+                }
+                Terminator::Return(_) | Terminator::Unreachable => {}
+            }
+            for phi in block.phis.iter_mut() {
+                phi.incoming
+                    .retain(|(block, _)| renumbered.contains_key(block));
+                for (block, _) in phi.incoming.iter_mut() {
+                    *block = renumbered[block];
+                }
+            }
+        }
+        self.blocks = blocks;
+    }
+
+    /// Appends every block to the block jumping to it when that is its only
+    /// predecessor, then drops the emptied blocks.
+    pub fn merge_blocks(&mut self) -> bool {
+        let mut replaced = HashMap::new();
+        let mut changed = false;
+        let mut predecessors = self.predecessors();
+        for id in self.reverse_postorder() {
+            while let Terminator::Jump(next) = self.block(id).terminator {
+                if next == id || next.0 == 0 || predecessors[next.0].len() != 1 {
+                    break;
+                }
+                let merged = std::mem::replace(
+                    self.block_mut(next),
+                    Block {
+                        phis: Vec::new(),
+                        instructions: Vec::new(),
+                        terminator: Terminator::Unreachable,
+                    },
+                );
+                for phi in merged.phis.into_iter() {
+                    if let Some((_, value)) = phi.incoming.into_iter().next() {
+                        replaced.insert(phi.dest, value);
+                    }
+                }
+                for successor in merged.terminator.successors() {
+                    predecessors[successor.0] = predecessors[successor.0]
+                        .iter()
+                        .map(|predecessor| {
+                            if *predecessor == next {
+                                id
+                            } else {
+                                *predecessor
+                            }
+                        })
+                        .collect();
+                    for phi in self.block_mut(successor).phis.iter_mut() {
+                        for (predecessor, _) in phi.incoming.iter_mut() {
+                            if *predecessor == next {
+                                *predecessor = id;
+                            }
+                        }
+                    }
+                }
+                predecessors[next.0].clear();
+                let block = self.block_mut(id);
+                block.instructions.extend(merged.instructions);
+                block.terminator = merged.terminator;
+                changed = true;
+            }
+        }
+        if changed {
+            self.replace_values(&replaced);
+            self.remove_unreachable_blocks();
+        }
+        return changed;
+    }
+
+    /// The immediate dominator of every reachable block, computed with the
+    /// algorithm of Cooper, Harvey and Kennedy. The entry dominates itself.
+    pub fn dominators(&self) -> Vec<Option<BlockId>> {
+        let mut dominators = vec![None; self.blocks.len()];
+        let order = self.reverse_postorder();
+        let Some(entry) = order.first() else {
+            return dominators;
+        };
+        let mut position = vec![usize::MAX; self.blocks.len()];
+        for (index, block) in order.iter().enumerate() {
+            position[block.0] = index;
+        }
+        let predecessors = self.predecessors();
+        dominators[entry.0] = Some(*entry);
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for block in order.iter().skip(1) {
+                let mut dominator: Option<BlockId> = None;
+                for predecessor in predecessors[block.0].iter() {
+                    if dominators[predecessor.0].is_none() {
+                        continue;
+                    }
+                    dominator = Some(match dominator {
+                        None => *predecessor,
+                        Some(dominator) => {
+                            Self::intersect(&dominators, &position, *predecessor, dominator)
+                        }
+                    });
+                }
+                if dominator.is_some() && dominators[block.0] != dominator {
+                    dominators[block.0] = dominator;
+                    changed = true;
+                }
+            }
+        }
+        return dominators;
+    }
+
+    fn intersect(
+        dominators: &[Option<BlockId>],
+        position: &[usize],
+        mut left: BlockId,
+        mut right: BlockId,
+    ) -> BlockId {
+        while left != right {
+            while position[left.0] > position[right.0] {
+                left = dominators[left.0].unwrap_or(left);
+            }
+            while position[right.0] > position[left.0] {
+                right = dominators[right.0].unwrap_or(right);
+            }
+        }
+        return left;
+    }
+
+    /// The children of every block in the dominator tree.
+    pub fn dominator_tree(dominators: &[Option<BlockId>]) -> Vec<Vec<BlockId>> {
+        let mut children = vec![Vec::new(); dominators.len()];
+        for (id, dominator) in dominators.iter().enumerate() {
+            match dominator {
+                Some(dominator) if dominator.0 != id => children[dominator.0].push(BlockId(id)),
+                _ => {}
+            }
+        }
+        return children;
+    }
+
+    /// The blocks where the dominance of every block ends.
+    pub fn dominance_frontiers(&self, dominators: &[Option<BlockId>]) -> Vec<Vec<BlockId>> {
+        let mut frontiers: Vec<Vec<BlockId>> = vec![Vec::new(); self.blocks.len()];
+        for (id, predecessors) in self.predecessors().iter().enumerate() {
+            let Some(dominator) = dominators[id] else {
+                continue;
+            };
+            if predecessors.len() < 2 {
+                continue;
+            }
+            for predecessor in predecessors.iter() {
+                let mut runner = *predecessor;
+                while runner != dominator && dominators[runner.0].is_some() {
+                    if !frontiers[runner.0].contains(&BlockId(id)) {
+                        frontiers[runner.0].push(BlockId(id));
+                    }
+                    match dominators[runner.0] {
+                        Some(next) if next != runner => runner = next,
+                        _ => break,
+                    }
+                }
+            }
+        }
+        return frontiers;
+    }
+}
@@ -0,0 +1,559 @@
+use crate::{
+    ast::{
+        declarations::enum_decl::EnumDeclaration,
+        expressions::{
+            array_index_expr::ArrayIndexExpression,
+            array_init_expr::ArrayInitExpression,
+            assignment_expr::AssignmentExpression,
+            binary_expr::{BinaryExpression, BinaryOperators},
+            cast_expr::CastExpression,
+            enum_item_expr::EnumItemExpression,
+            fn_call_expr::FnCallExpression,
+            identifier_expression::IdentifierExpression,
+            member_access_expr::MemberAccessExpression,
+            object_init_expr::ObjectInitExpression,
+            primitive_expr::PrimitiveExpression,
+            unary_expr::{UnaryExpression, UnaryExpressionType, UnaryOperators},
+            Expression,
+        },
+        type_specifier::TypeSpecifier,
+    },
+    codegen::type_context::MemberChain,
+    error::{CompilerError, Result},
+    ir::{BinaryOp, Constant, Instruction, Phi, UnaryOp, Value},
+    lexer::token_type::TokenType,
+};
+
+use super::{IrBuilder, ToValue};
+
+/// What a member access produced: the address of a field or the result of
+/// a method call.
+pub(crate) enum Member {
+    Address(Value),
+    Value(Value),
+}
+
+impl<'a> ToValue<'a> for Expression {
+    fn to_value(&'a self, builder: &mut IrBuilder<'a>) -> Result<Value> {
+        return match self {
+            Expression::Binary(binary_expr) => binary_expr.to_value(builder),
+            Expression::Assignment(assignment_expr) => assignment_expr.to_value(builder),
+            Expression::Unary(unary_expr) => unary_expr.to_value(builder),
+            Expression::ArrayIndex(array_index) => array_index.to_value(builder),
+            Expression::MemberAccess(member_access) => member_access.to_value(builder),
+            Expression::FnCall(fn_call_expr) => fn_call_expr.to_value(builder),
+            Expression::ArrayInit(array_init_expr) => array_init_expr.to_value(builder),
+            Expression::ObjectInit(object_init_expr) => object_init_expr.to_value(builder),
+            Expression::Cast(cast_expr) => cast_expr.to_value(builder),
+            Expression::Identifier(identifier_expr) => identifier_expr.to_value(builder),
+            Expression::EnumItem(enum_item_expr) => enum_item_expr.to_value(builder),
+            Expression::Primitive(primitive_expr) => primitive_expr.to_value(builder),
+        };
+    }
+}
+
+impl<'a> IrBuilder<'a> {
+    /// Returns a pointer to the storage `expr` refers to. Values that are not
+    /// stored anywhere are copied to a temporary first.
+    pub(crate) fn address(&mut self, expr: &'a Expression) -> Result<Value> {
+        return match expr {
+            Expression::Identifier(identifier) => self.local(identifier.id.as_ref()),
+            Expression::MemberAccess(member_access) => {
+                let object = self.object_address(&member_access.object, &member_access.access)?;
+                match self.member(object, &member_access.property)? {
+                    Member::Address(address) => Ok(address),
+                    Member::Value(value) => Ok(self.spill(value)),
+                }
+            }
+            Expression::ArrayIndex(array_index) => self.index_address(array_index),
+            Expression::Unary(UnaryExpression {
+                operator: UnaryOperators::Indirection,
+                value,
+                ..
+            }) => value.to_value(self),
+            _ => {
+                let value = expr.to_value(self)?;
+                Ok(self.spill(value))
+            }
+        };
+    }
+
+    fn spill(&mut self, value: Value) -> Value {
+        let address = self.alloca(&self.type_of(&value));
+        self.store(address.clone(), value);
+        return address;
+    }
+
+    /// Returns a pointer to the struct `object` refers to.
+    fn object_address(&mut self, object: &'a Expression, access: &TokenType) -> Result<Value> {
+        if access == &TokenType::Arrow {
+            return object.to_value(self);
+        }
+        return self.address(object);
+    }
+
+    /// Calls a member function, passing the receiver by pointer or by value.
+    fn method_call(
+        &mut self,
+        object: Value,
+        struct_name: &str,
+        fn_call: &'a FnCallExpression,
+    ) -> Result<Value> {
+        let name = fn_call.fn_name.identifier()?.id.to_string();
+        let signature = self.context.method(struct_name, &name)?.clone();
+        let mut args = Vec::new();
+        match &signature.receiver {
+            Some(TypeSpecifier::Pointer(_)) => args.push(object),
+            Some(_) => args.push(self.load(object)?),
+            None => {}
+        }
+        for arg in fn_call.args.iter() {
+            args.push(arg.to_value(self)?);
+        }
+        return Ok(self.call(
+            IrBuilder::method_name(struct_name, &name),
+            args,
+            &signature.return_type,
+        ));
+    }
+
+    fn call(&mut self, function: String, args: Vec<Value>, return_type: &TypeSpecifier) -> Value {
+        if return_type == &TypeSpecifier::Void {
+            self.emit(Instruction::Call {
+                dest: None,
+                function,
+                args,
+            });
+            return IrBuilder::void();
+        }
+        return self.define(return_type.clone(), |dest| Instruction::Call {
+            dest: Some(dest),
+            function,
+            args,
+        });
+    }
+
+    fn index_address(&mut self, array_index: &'a ArrayIndexExpression) -> Result<Value> {
+        let base = match self.context.expr_type(&array_index.array)? {
+            TypeSpecifier::ArrayType { .. } => self.address(&array_index.array)?,
+            TypeSpecifier::Pointer(_) => array_index.array.to_value(self)?,
+            _ => {
+                return Err(CompilerError::CodeGen(String::from(
+                    "Expect the array type",
+                )))
+            }
+        };
+        let element = self.element_address(base, &array_index.index)?;
+        return match &array_index.second_index {
+            Some(second_index) => self.element_address(element, second_index),
+            None => Ok(element),
+        };
+    }
+
+    /// Indexes the array or the memory `base` points to.
+    fn element_address(&mut self, base: Value, index: &'a Expression) -> Result<Value> {
+        let index = index.to_value(self)?;
+        let index = self.convert(index, &TypeSpecifier::Usize);
+        let base_type = self.type_of(&base);
+        return Ok(match IrBuilder::pointee(&base_type)? {
+            TypeSpecifier::ArrayType { type_specifier, .. } => {
+                self.define(TypeSpecifier::Pointer(type_specifier), |dest| {
+                    Instruction::Element {
+                        dest,
+                        address: base,
+                        index,
+                    }
+                })
+            }
+            _ => self.define(base_type, |dest| Instruction::Offset {
+                dest,
+                pointer: base,
+                index,
+            }),
+        });
+    }
+
+    /// Converts a value with the rules of a C cast.
+    pub(crate) fn convert(&mut self, value: Value, target: &TypeSpecifier) -> Value {
+        if &self.type_of(&value) == target {
+            return value;
+        }
+        return self.define(target.clone(), |dest| Instruction::Cast { dest, value });
+    }
+
+    /// Short-circuits `a && b` and `a || b` with a phi at the join block.
+    fn logical(&mut self, binary: &'a BinaryExpression) -> Result<Value> {
+        let is_and = matches!(binary.operator, BinaryOperators::And);
+        let right = self.new_block();
+        let end = self.new_block();
+
+        let left = binary.left.to_value(self)?;
+        let left_block = self.block;
+        if is_and {
+            self.branch(left, right, end);
+        } else {
+            self.branch(left, end, right);
+        }
+        self.start_block(right);
+        let value = binary.right.to_value(self)?;
+        let right_block = self.block;
+        self.start_block(end);
+        let dest = self.function.new_temp(TypeSpecifier::Bool);
+        self.function.block_mut(end).phis.push(Phi {
+            dest,
+            incoming: vec![
+                (left_block, Value::Constant(Constant::Bool(!is_and))),
+                (right_block, value),
+            ],
+        });
+        return Ok(Value::Temp(dest));
+    }
+
+    /// Adds one to or subtracts one from the value `address` points to for
+    /// `++` and `--`.
+    fn step(&mut self, address: Value, increment: bool, postfix: bool) -> Result<Value> {
+        let old = self.load(address.clone())?;
+        let type_specifier = self.type_of(&old);
+        let new = match &type_specifier {
+            TypeSpecifier::Pointer(_) => {
+                let index = Value::Constant(Constant::Int(if increment { 1 } else { -1 }));
+                let pointer = old.clone();
+                self.define(type_specifier, |dest| Instruction::Offset {
+                    dest,
+                    pointer,
+                    index,
+                })
+            }
+            _ => {
+                let one = IrBuilder::one(&type_specifier)?;
+                let operator = if increment {
+                    BinaryOp::Add
+                } else {
+                    BinaryOp::Subtract
+                };
+                let left = old.clone();
+                self.define(type_specifier, |dest| Instruction::Binary {
+                    dest,
+                    operator,
+                    left,
+                    right: one,
+                })
+            }
+        };
+        self.store(address, new.clone());
+        return Ok(if postfix { old } else { new });
+    }
+
+    fn one(type_specifier: &TypeSpecifier) -> Result<Value> {
+        let constant = match type_specifier {
+            TypeSpecifier::Int | TypeSpecifier::UserDefine(_) => Constant::Int(1),
+            TypeSpecifier::Usize => Constant::Usize(1),
+            TypeSpecifier::Float => Constant::Float(1.0),
+            TypeSpecifier::Double => Constant::Double(1.0),
+            TypeSpecifier::Char => Constant::Char(1),
+            _ => {
+                return Err(CompilerError::CodeGen(String::from(
+                    "Cannot increment the complex type",
+                )))
+            }
+        };
+        return Ok(Value::Constant(constant));
+    }
+
+    fn binary(
+        &mut self,
+        operator: BinaryOp,
+        left: Value,
+        right: Value,
+        type_specifier: TypeSpecifier,
+    ) -> Value {
+        return self.define(type_specifier, |dest| Instruction::Binary {
+            dest,
+            operator,
+            left,
+            right,
+        });
+    }
+}
+
+impl<'a> ToValue<'a> for BinaryExpression {
+    fn to_value(&'a self, builder: &mut IrBuilder<'a>) -> Result<Value> {
+        let operator = match self.operator {
+            BinaryOperators::And | BinaryOperators::Or => return builder.logical(self),
+            BinaryOperators::Add => BinaryOp::Add,
+            BinaryOperators::Subtract => BinaryOp::Subtract,
+            BinaryOperators::Multiply => BinaryOp::Multiply,
+            BinaryOperators::Divide => BinaryOp::Divide,
+            BinaryOperators::Modulo => BinaryOp::Modulo,
+            BinaryOperators::BitAnd => BinaryOp::BitAnd,
+            BinaryOperators::BitOr => BinaryOp::BitOr,
+            BinaryOperators::BitXor => BinaryOp::BitXor,
+            BinaryOperators::BitLeft => BinaryOp::ShiftLeft,
+            BinaryOperators::BitRight => BinaryOp::ShiftRight,
+            BinaryOperators::Equal => BinaryOp::Equal,
+            BinaryOperators::NotEqual => BinaryOp::NotEqual,
+            BinaryOperators::GreaterThan => BinaryOp::Greater,
+            BinaryOperators::LessThan => BinaryOp::Less,
+            BinaryOperators::GreaterThanOrEqual => BinaryOp::GreaterEqual,
+            BinaryOperators::LessThanOrEqual => BinaryOp::LessEqual,
+        };
+        let left = self.left.to_value(builder)?;
+        let right = self.right.to_value(builder)?;
+        let type_specifier = match operator.is_comparison() {
+            true => TypeSpecifier::Bool,
+            false => builder.type_of(&left),
+        };
+        return Ok(builder.binary(operator, left, right, type_specifier));
+    }
+}
+
+impl<'a> ToValue<'a> for AssignmentExpression {
+    fn to_value(&'a self, builder: &mut IrBuilder<'a>) -> Result<Value> {
+        let value = self.value.to_value(builder)?;
+        let address = builder.address(&self.variable)?;
+        builder.store(address, value.clone());
+        return Ok(value);
+    }
+}
+
+impl<'a> ToValue<'a> for UnaryExpression {
+    fn to_value(&'a self, builder: &mut IrBuilder<'a>) -> Result<Value> {
+        match self.operator {
+            UnaryOperators::Address => return builder.address(&self.value),
+            UnaryOperators::Indirection => {
+                let address = self.value.to_value(builder)?;
+                return builder.load(address);
+            }
+            UnaryOperators::Increment | UnaryOperators::Decrement => {
+                let increment = matches!(self.operator, UnaryOperators::Increment);
+                let address = builder.address(&self.value)?;
+                let postfix = matches!(self.expression_type, UnaryExpressionType::Postfix);
+                return builder.step(address, increment, postfix);
+            }
+            _ => {}
+        }
+        let value = self.value.to_value(builder)?;
+        let type_specifier = builder.type_of(&value);
+        let operator = match self.operator {
+            UnaryOperators::Minus => UnaryOp::Negate,
+            UnaryOperators::LogicalNot => UnaryOp::Not,
+            UnaryOperators::BitwiseNot if type_specifier == TypeSpecifier::Bool => UnaryOp::Not,
+            UnaryOperators::BitwiseNot => UnaryOp::BitNot,
+            _ => return Ok(value),
+        };
+        return Ok(builder.define(type_specifier, |dest| Instruction::Unary {
+            dest,
+            operator,
+            value,
+        }));
+    }
+}
+
+/// The fields of a struct are addressed through a pointer to it.
+impl<'a> MemberChain<'a> for IrBuilder<'a> {
+    type Object = Value;
+    type Member = Member;
+
+    fn object_type(&self, object: &Value) -> Result<TypeSpecifier> {
+        return IrBuilder::pointee(&self.type_of(object));
+    }
+
+    fn field_member(&mut self, object: Value, struct_name: &str, name: &str) -> Result<Member> {
+        let field = self.context.field(struct_name, name)?.clone();
+        return Ok(Member::Address(self.define(
+            TypeSpecifier::Pointer(Box::new(field)),
+            |dest| Instruction::Field {
+                dest,
+                address: object,
+                field: name.to_owned(),
+            },
+        )));
+    }
+
+    fn method_member(
+        &mut self,
+        object: Value,
+        struct_name: &str,
+        fn_call: &'a FnCallExpression,
+    ) -> Result<Member> {
+        return Ok(Member::Value(self.method_call(
+            object,
+            struct_name,
+            fn_call,
+        )?));
+    }
+
+    fn member_object(&mut self, member: Member, access: &TokenType) -> Result<Value> {
+        let is_arrow = access == &TokenType::Arrow;
+        return Ok(match member {
+            Member::Address(address) if is_arrow => self.load(address)?,
+            Member::Value(value) if !is_arrow => self.spill(value),
+            Member::Address(inner) | Member::Value(inner) => inner,
+        });
+    }
+}
+
+impl<'a> ToValue<'a> for ArrayIndexExpression {
+    fn to_value(&'a self, builder: &mut IrBuilder<'a>) -> Result<Value> {
+        let address = builder.index_address(self)?;
+        return builder.load(address);
+    }
+}
+
+impl<'a> ToValue<'a> for MemberAccessExpression {
+    fn to_value(&'a self, builder: &mut IrBuilder<'a>) -> Result<Value> {
+        let object = builder.object_address(&self.object, &self.access)?;
+        return match builder.member(object, &self.property)? {
+            Member::Address(address) => builder.load(address),
+            Member::Value(value) => Ok(value),
+        };
+    }
+}
+
+impl<'a> ToValue<'a> for FnCallExpression {
+    fn to_value(&'a self, builder: &mut IrBuilder<'a>) -> Result<Value> {
+        let name = self.fn_name.identifier()?.id.to_string();
+        let return_type = builder.context.fn_call_signature(self)?.return_type.clone();
+        let mut args = Vec::new();
+        for arg in self.args.iter() {
+            args.push(arg.to_value(builder)?);
+        }
+        let name = match &self.namespace {
+            Some(namespace) => {
+                let struct_name = namespace.name.identifier()?;
+                IrBuilder::method_name(struct_name.id.as_ref(), &name)
+            }
+            None => name,
+        };
+        return Ok(builder.call(name, args, &return_type));
+    }
+}
+
+impl<'a> ToValue<'a> for ArrayInitExpression {
+    /// Stores the items to a temporary array and loads it as a whole.
+    fn to_value(&'a self, builder: &mut IrBuilder<'a>) -> Result<Value> {
+        let items = self
+            .array
+            .iter()
+            .map(|item| item.to_value(builder))
+            .collect::<Result<Vec<Value>>>()?;
+        let element = items
+            .first()
+            .map(|item| builder.type_of(item))
+            .ok_or_else(|| CompilerError::CodeGen(String::from("Empty array init expression")))?;
+        let array = builder.alloca(&TypeSpecifier::array(element.clone(), self.size));
+        for (index, item) in items.into_iter().enumerate() {
+            let address = array.clone();
+            let index = Value::Constant(Constant::Usize(index as u64));
+            let element =
+                builder.define(TypeSpecifier::Pointer(Box::new(element.clone())), |dest| {
+                    Instruction::Element {
+                        dest,
+                        address,
+                        index,
+                    }
+                });
+            builder.store(element, item);
+        }
+        return builder.load(array);
+    }
+}
+
+impl<'a> ToValue<'a> for ObjectInitExpression {
+    /// Missing fields stay zero.
+    fn to_value(&'a self, builder: &mut IrBuilder<'a>) -> Result<Value> {
+        let name = self.object.identifier()?.id.to_string();
+        let type_specifier = TypeSpecifier::UserDefine(self.object.identifier()?.id.clone());
+        let object = builder.alloca(&type_specifier);
+        builder.store(object.clone(), IrBuilder::zero(&type_specifier));
+        for field in self.fields.iter() {
+            let value = field.value.to_value(builder)?;
+            let field_type = builder.context.field(&name, field.name.as_ref())?.clone();
+            let address = object.clone();
+            let field = field.name.to_string();
+            let pointer = builder.define(TypeSpecifier::Pointer(Box::new(field_type)), |dest| {
+                Instruction::Field {
+                    dest,
+                    address,
+                    field,
+                }
+            });
+            builder.store(pointer, value);
+        }
+        return builder.load(object);
+    }
+}
+
+impl<'a> ToValue<'a> for CastExpression {
+    fn to_value(&'a self, builder: &mut IrBuilder<'a>) -> Result<Value> {
+        let value = self.expression.to_value(builder)?;
+        return Ok(builder.convert(value, &self.target_type));
+    }
+}
+
+impl<'a> ToValue<'a> for IdentifierExpression {
+    fn to_value(&'a self, builder: &mut IrBuilder<'a>) -> Result<Value> {
+        let address = builder.local(self.id.as_ref())?;
+        return builder.load(address);
+    }
+}
+
+impl<'a> ToValue<'a> for EnumItemExpression {
+    /// An item of a tagged enum is built like an object init of its layout.
+    fn to_value(&'a self, builder: &mut IrBuilder<'a>) -> Result<Value> {
+        let (name, item) = (self.name.as_ref(), self.item.as_ref());
+        let value = builder.context.enum_item(name, item)?;
+        let tag = Value::Constant(Constant::Int(value));
+        if !builder.context.is_tagged(name) {
+            return Ok(tag);
+        }
+        let type_specifier = TypeSpecifier::UserDefine(self.name.clone());
+        let object = builder.alloca(&type_specifier);
+        builder.store(object.clone(), IrBuilder::zero(&type_specifier));
+        let pointer = builder.field(object.clone(), name, EnumDeclaration::TAG)?;
+        builder.store(pointer, tag);
+        for (index, arg) in self.args.iter().enumerate() {
+            let value = arg.to_value(builder)?;
+            let field = EnumDeclaration::payload_field(item, index);
+            let pointer = builder.field(object.clone(), name, &field)?;
+            builder.store(pointer, value);
+        }
+        return builder.load(object);
+    }
+}
+
+impl IrBuilder<'_> {
+    /// The address of the field `name` of the struct `struct_name` at `address`.
+    pub(crate) fn field(&mut self, address: Value, struct_name: &str, name: &str) -> Result<Value> {
+        let field_type = self.context.field(struct_name, name)?.clone();
+        let field = name.to_owned();
+        return Ok(
+            self.define(TypeSpecifier::Pointer(Box::new(field_type)), |dest| {
+                Instruction::Field {
+                    dest,
+                    address,
+                    field,
+                }
+            }),
+        );
+    }
+}
+
+impl<'a> ToValue<'a> for PrimitiveExpression {
+    fn to_value(&'a self, _builder: &mut IrBuilder<'a>) -> Result<Value> {
+        let invalid = || CompilerError::CodeGen(format!("Invalid literal {}", self.value));
+        let constant = match self.type_specifier {
+            TypeSpecifier::Int => {
+                Constant::Int(self.value.parse::<i64>().map_err(|_| invalid())? as i32)
+            }
+            TypeSpecifier::Usize => Constant::Usize(self.value.parse().map_err(|_| invalid())?),
+            TypeSpecifier::Float => Constant::Float(self.value.parse().map_err(|_| invalid())?),
+            TypeSpecifier::Double => Constant::Double(self.value.parse().map_err(|_| invalid())?),
+            TypeSpecifier::Bool => Constant::Bool(self.value == "true"),
+            TypeSpecifier::Char => Constant::Char(*self.bytes().first().ok_or_else(invalid)?),
+            TypeSpecifier::Str => Constant::Str(self.bytes()),
+            _ => return Err(invalid()),
+        };
+        return Ok(Value::Constant(constant));
+    }
+}
@@ -0,0 +1,194 @@
+pub mod expressions;
+pub mod program;
+pub mod statements;
+
+use std::collections::HashMap;
+
+use crate::{
+    ast::{expressions::Expression, program::Program, type_specifier::TypeSpecifier},
+    codegen::{
+        loops::{Defers, LoopTargets, Loops},
+        type_context::TypeContext,
+    },
+    error::{CompilerError, Result},
+};
+
+use super::{BlockId, Constant, Function, Instruction, Module, Temp, Terminator, Value};
+
+/// Lowers an analyzed program to the IR [`Module`].
+///
+/// Every local gets an alloca in the entry block and is loaded and stored
+/// like in memory, [`Function::construct_ssa`] then promotes the ones whose
+/// address is never taken. `defer` expressions are lowered again at every
+/// exit of their block.
+pub struct IrBuilder<'a> {
+    program: &'a Program,
+    pub(crate) context: TypeContext,
+    function: Function,
+    block: BlockId,
+    terminated: bool,
+    allocas: Vec<Instruction>,
+    scopes: Vec<HashMap<String, Value>>,
+    defers: Defers<&'a Expression>,
+    loops: Loops<LoopTargets<BlockId>>,
+}
+
+pub trait ToIr<'a> {
+    fn to_ir(&'a self, builder: &mut IrBuilder<'a>) -> Result<()>;
+}
+
+pub trait ToValue<'a> {
+    fn to_value(&'a self, builder: &mut IrBuilder<'a>) -> Result<Value>;
+}
+
+impl<'a> IrBuilder<'a> {
+    pub fn new(program: &'a Program) -> Self {
+        Self {
+            program,
+            context: TypeContext::new(program),
+            function: Function::new(String::new(), TypeSpecifier::Void),
+            block: BlockId(0),
+            terminated: false,
+            allocas: Vec::new(),
+            scopes: Vec::new(),
+            defers: Defers::default(),
+            loops: Loops::default(),
+        }
+    }
+
+    pub fn generate(mut self) -> Result<Module> {
+        let program = self.program;
+        return program.to_module(&mut self);
+    }
+
+    /// The value of expressions without one, like calls of void functions.
+    pub(crate) fn void() -> Value {
+        return Value::Constant(Constant::Zero(TypeSpecifier::Void));
+    }
+
+    pub(crate) fn zero(type_specifier: &TypeSpecifier) -> Value {
+        return Value::Constant(Constant::Zero(type_specifier.clone()));
+    }
+
+    /// Appends an instruction to the current block. Code after a terminator
+    /// goes to a new block without predecessors.
+    pub(crate) fn emit(&mut self, instruction: Instruction) {
+        if self.terminated {
+            let block = self.function.new_block();
+            self.start_block(block);
+        }
+        let block = self.block;
+        self.function
+            .block_mut(block)
+            .instructions
+            .push(instruction);
+    }
+
+    /// Emits the instruction `make` builds for a new temporary of `type_specifier`.
+    pub(crate) fn define(
+        &mut self,
+        type_specifier: TypeSpecifier,
+        make: impl FnOnce(Temp) -> Instruction,
+    ) -> Value {
+        let dest = self.function.new_temp(type_specifier);
+        self.emit(make(dest));
+        return Value::Temp(dest);
+    }
+
+    pub(crate) fn terminate(&mut self, terminator: Terminator) {
+        if self.terminated {
+            let block = self.function.new_block();
+            self.start_block(block);
+        }
+        let block = self.block;
+        self.function.block_mut(block).terminator = terminator;
+        self.terminated = true;
+    }
+
+    pub(crate) fn new_block(&mut self) -> BlockId {
+        return self.function.new_block();
+    }
+
+    /// Continues in `block`, falling through to it from an open block.
+    pub(crate) fn start_block(&mut self, block: BlockId) {
+        if !self.terminated {
+            self.function.block_mut(self.block).terminator = Terminator::Jump(block);
+        }
+        self.block = block;
+        self.terminated = false;
+    }
+
+    pub(crate) fn jump(&mut self, target: BlockId) {
+        self.terminate(Terminator::Jump(target));
+    }
+
+    pub(crate) fn branch(&mut self, condition: Value, then: BlockId, otherwise: BlockId) {
+        self.terminate(Terminator::Branch {
+            condition,
+            then,
+            otherwise,
+        });
+    }
+
+    pub(crate) fn push_scope(&mut self) {
+        self.context.push_scope();
+        self.scopes.push(HashMap::new());
+        self.defers.push_scope();
+    }
+
+    pub(crate) fn pop_scope(&mut self) {
+        self.context.pop_scope();
+        self.scopes.pop();
+        self.defers.pop_scope();
+    }
+
+    /// Reserves memory for a value of `type_specifier` in the entry block.
+    pub(crate) fn alloca(&mut self, type_specifier: &TypeSpecifier) -> Value {
+        let dest = self
+            .function
+            .new_temp(TypeSpecifier::Pointer(Box::new(type_specifier.clone())));
+        self.allocas.push(Instruction::Alloca { dest });
+        return Value::Temp(dest);
+    }
+
+    pub(crate) fn declare(&mut self, name: &str, type_specifier: &TypeSpecifier) -> Value {
+        let address = self.alloca(type_specifier);
+        self.context.declare(name, type_specifier.clone());
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_owned(), address.clone());
+        }
+        return address;
+    }
+
+    pub(crate) fn local(&self, name: &str) -> Result<Value> {
+        return self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .cloned()
+            .ok_or_else(|| CompilerError::CodeGen(format!("Unknown variable {}", name)));
+    }
+
+    pub(crate) fn type_of(&self, value: &Value) -> TypeSpecifier {
+        return self.function.type_of(value);
+    }
+
+    pub(crate) fn pointee(type_specifier: &TypeSpecifier) -> Result<TypeSpecifier> {
+        return match type_specifier {
+            TypeSpecifier::Pointer(pointee) => Ok(*pointee.clone()),
+            _ => Err(CompilerError::CodeGen(String::from(
+                "Expected pointer type",
+            ))),
+        };
+    }
+
+    pub(crate) fn load(&mut self, address: Value) -> Result<Value> {
+        let type_specifier = Self::pointee(&self.type_of(&address))?;
+        return Ok(self.define(type_specifier, |dest| Instruction::Load { dest, address }));
+    }
+
+    pub(crate) fn store(&mut self, address: Value, value: Value) {
+        self.emit(Instruction::Store { address, value });
+    }
+}
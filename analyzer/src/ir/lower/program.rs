@@ -0,0 +1,108 @@
+use crate::{
+    ast::{
+        declarations::Declarations, program::Program, statements::block_stmt::BlockStmt,
+        type_specifier::TypeSpecifier, variable_type::VariableType,
+    },
+    error::Result,
+    ir::{Function, Module, StructDef, Terminator},
+};
+
+use super::{IrBuilder, ToIr};
+
+impl Program {
+    pub(crate) fn to_module<'a>(&'a self, builder: &mut IrBuilder<'a>) -> Result<Module> {
+        let mut module = Module::default();
+        for struct_decl in self.sorted_structs()? {
+            module.structs.push(StructDef {
+                name: struct_decl.name.to_string(),
+                fields: struct_decl
+                    .fields
+                    .iter()
+                    .map(|field| {
+                        (
+                            field.field.identifier.to_string(),
+                            field.field.type_specifier.clone(),
+                        )
+                    })
+                    .collect(),
+            });
+        }
+        for decl in self.declarations.iter() {
+            match decl {
+                Declarations::Fn(fn_decl) => {
+                    let params: Vec<&VariableType> = fn_decl.params.iter().collect();
+                    module.functions.push(builder.function(
+                        fn_decl.fn_name.as_ref(),
+                        &params,
+                        &fn_decl.return_type,
+                        &fn_decl.body,
+                    )?);
+                }
+                Declarations::Impl(impl_decl) => {
+                    let struct_name = impl_decl.name.to_symbol()?;
+                    for member_fn in impl_decl.member_functions.iter() {
+                        let name =
+                            IrBuilder::method_name(struct_name, member_fn.prototype.name.as_ref());
+                        module.functions.push(builder.function(
+                            &name,
+                            &member_fn.params(),
+                            &member_fn.prototype.return_type,
+                            &member_fn.body,
+                        )?);
+                    }
+                }
+                _ => {}
+            }
+        }
+        return Ok(module);
+    }
+}
+
+impl<'a> IrBuilder<'a> {
+    pub(crate) fn method_name(struct_name: &str, name: &str) -> String {
+        return format!("{}.{}", struct_name, name);
+    }
+
+    /// Lowers a function and converts it to SSA form. Parameters are copied
+    /// to allocas so they can be assigned like any local.
+    fn function(
+        &mut self,
+        name: &str,
+        params: &[&VariableType],
+        return_type: &TypeSpecifier,
+        body: &'a BlockStmt,
+    ) -> Result<Function> {
+        self.function = Function::new(name.to_owned(), return_type.clone());
+        self.block = self.function.new_block();
+        self.terminated = false;
+        self.allocas = Vec::new();
+
+        self.push_scope();
+        for param in params.iter() {
+            let temp = self.function.new_temp(param.type_specifier.clone());
+            self.function.params.push(temp);
+            let address = self.declare(param.identifier.as_ref(), &param.type_specifier);
+            self.store(address, crate::ir::Value::Temp(temp));
+        }
+        let result = body.to_ir(self);
+        self.pop_scope();
+        result?;
+
+        if !self.terminated {
+            let value = match return_type {
+                TypeSpecifier::Void => None,
+                return_type => Some(IrBuilder::zero(return_type)),
+            };
+            self.terminate(Terminator::Return(value));
+        }
+
+        let mut function = std::mem::replace(
+            &mut self.function,
+            Function::new(String::new(), TypeSpecifier::Void),
+        );
+        let entry = &mut function.blocks[0].instructions;
+        entry.splice(0..0, std::mem::take(&mut self.allocas));
+        function.construct_ssa();
+        return Ok(function);
+    }
+}
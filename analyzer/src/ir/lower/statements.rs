@@ -0,0 +1,356 @@
+use crate::{
+    ast::{
+        declarations::enum_decl::EnumDeclaration,
+        expressions::Expression,
+        identifier::Identifier,
+        statements::{
+            block_stmt::BlockStmt,
+            break_stmt::BreakStmt,
+            continue_stmt::ContinueStmt,
+            defer_stmt::DeferStmt,
+            do_while_stmt::DoWhileStmt,
+            for_stmt::{ForStmt, VariableInit},
+            if_stmt::IfStmt,
+            let_stmt::LetStmt,
+            match_stmt::{MatchArm, MatchStmt},
+            return_stmt::ReturnStmt,
+            switch_stmt::SwitchStmt,
+            while_stmt::WhileStmt,
+            Statements,
+        },
+        type_specifier::TypeSpecifier,
+    },
+    codegen::{
+        loops::{Jump, LoopTargets},
+        type_context::TypeContext,
+    },
+    error::{CompilerError, Result},
+    ir::{BinaryOp, BlockId, Constant, Instruction, Terminator, Value},
+};
+
+use super::{IrBuilder, ToIr, ToValue};
+
+impl<'a> ToIr<'a> for Statements {
+    fn to_ir(&'a self, builder: &mut IrBuilder<'a>) -> Result<()> {
+        return match self {
+            Statements::Let(let_stmt) => let_stmt.to_ir(builder),
+            Statements::Const(_) => Ok(()),
+            Statements::Return(return_stmt) => return_stmt.to_ir(builder),
+            Statements::Defer(defer_stmt) => defer_stmt.to_ir(builder),
+            Statements::For(for_stmt) => for_stmt.to_ir(builder),
+            Statements::While(while_stmt) => while_stmt.to_ir(builder),
+            Statements::DoWhile(do_while_stmt) => do_while_stmt.to_ir(builder),
+            Statements::If(if_stmt) => if_stmt.to_ir(builder),
+            Statements::Switch(switch_stmt) => switch_stmt.to_ir(builder),
+            Statements::Match(match_stmt) => match_stmt.to_ir(builder),
+            Statements::Break(break_stmt) => break_stmt.to_ir(builder),
+            Statements::Continue(continue_stmt) => continue_stmt.to_ir(builder),
+            Statements::Block(block_stmt) => block_stmt.to_ir(builder),
+            Statements::Expression(expr) => expr.to_value(builder).map(|_| ()),
+        };
+    }
+}
+
+impl<'a> IrBuilder<'a> {
+    /// Evaluates `condition` and branches to `then` when it holds.
+    fn condition(
+        &mut self,
+        condition: &'a Expression,
+        then: BlockId,
+        otherwise: BlockId,
+    ) -> Result<()> {
+        let condition = condition.to_value(self)?;
+        self.branch(condition, then, otherwise);
+        return Ok(());
+    }
+
+    /// Lowers the body of a loop labeled `label`, whose `continue`s jump to
+    /// `next` and `break`s to `end`.
+    fn loop_body(
+        &mut self,
+        label: Option<&Identifier>,
+        block: &'a BlockStmt,
+        next: BlockId,
+        end: BlockId,
+    ) -> Result<()> {
+        self.loops
+            .push(label, self.defers.depth(), LoopTargets { next, end });
+        let result = block.to_ir(self);
+        self.loops.pop()?;
+        return result;
+    }
+
+    /// Lowers a `break` or `continue` to the loop labeled `label`, which
+    /// runs the deferred expressions of the blocks it leaves first.
+    fn leave_loop(&mut self, label: Option<&Identifier>, jump: Jump) -> Result<()> {
+        let target = self.loops.target(label)?;
+        let defers = self.defers.leaving(target);
+        let target = target.targets.get(jump);
+        for expr in defers {
+            expr.to_value(self)?;
+        }
+        self.jump(target);
+        return Ok(());
+    }
+}
+
+impl<'a> ToIr<'a> for BlockStmt {
+    fn to_ir(&'a self, builder: &mut IrBuilder<'a>) -> Result<()> {
+        builder.push_scope();
+        let result = self.stmts_to_ir(builder);
+        builder.pop_scope();
+        return result;
+    }
+}
+
+impl BlockStmt {
+    fn stmts_to_ir<'a>(&'a self, builder: &mut IrBuilder<'a>) -> Result<()> {
+        for stmt in self.stmts.iter() {
+            stmt.to_ir(builder)?;
+        }
+        if !self.ends_with_return() {
+            for expr in builder.defers.pending(1) {
+                expr.to_value(builder)?;
+            }
+        }
+        return Ok(());
+    }
+}
+
+impl<'a> ToIr<'a> for LetStmt {
+    fn to_ir(&'a self, builder: &mut IrBuilder<'a>) -> Result<()> {
+        let type_specifier = self.type_specifier.as_ref().ok_or_else(|| {
+            CompilerError::CodeGen(format!("Type of {} is unknown", self.identifier))
+        })?;
+        let value = match &self.value {
+            Some(value) => value.to_value(builder)?,
+            None => IrBuilder::zero(type_specifier),
+        };
+        let address = builder.declare(self.identifier.as_ref(), type_specifier);
+        builder.store(address, value);
+        return Ok(());
+    }
+}
+
+impl<'a> ToIr<'a> for ReturnStmt {
+    /// The value is computed before the deferred expressions run.
+    fn to_ir(&'a self, builder: &mut IrBuilder<'a>) -> Result<()> {
+        let value = match &self.value {
+            Some(value) => Some(value.to_value(builder)?),
+            None => None,
+        };
+        for expr in builder.defers.all() {
+            expr.to_value(builder)?;
+        }
+        let value = value.filter(|value| builder.type_of(value) != TypeSpecifier::Void);
+        builder.terminate(Terminator::Return(value));
+        return Ok(());
+    }
+}
+
+impl<'a> ToIr<'a> for DeferStmt {
+    fn to_ir(&'a self, builder: &mut IrBuilder<'a>) -> Result<()> {
+        builder.defers.push(&self.expr);
+        return Ok(());
+    }
+}
+
+impl<'a> ToIr<'a> for BreakStmt {
+    fn to_ir(&'a self, builder: &mut IrBuilder<'a>) -> Result<()> {
+        return builder.leave_loop(self.label.as_ref(), Jump::Break);
+    }
+}
+
+impl<'a> ToIr<'a> for ContinueStmt {
+    fn to_ir(&'a self, builder: &mut IrBuilder<'a>) -> Result<()> {
+        return builder.leave_loop(self.label.as_ref(), Jump::Continue);
+    }
+}
+
+impl<'a> ToIr<'a> for ForStmt {
+    fn to_ir(&'a self, builder: &mut IrBuilder<'a>) -> Result<()> {
+        builder.push_scope();
+        let result = self.loop_to_ir(builder);
+        builder.pop_scope();
+        return result;
+    }
+}
+
+impl ForStmt {
+    fn loop_to_ir<'a>(&'a self, builder: &mut IrBuilder<'a>) -> Result<()> {
+        match &self.init {
+            VariableInit::Let(let_stmt) => let_stmt.to_ir(builder)?,
+            VariableInit::Assign(assign) => {
+                assign.to_value(builder)?;
+            }
+        }
+        let condition = builder.new_block();
+        let body = builder.new_block();
+        let increment = builder.new_block();
+        let end = builder.new_block();
+        builder.start_block(condition);
+        builder.condition(&self.condition, body, end)?;
+        builder.start_block(body);
+        builder.loop_body(self.label.as_ref(), &self.block, increment, end)?;
+        builder.start_block(increment);
+        self.increment.to_value(builder)?;
+        builder.jump(condition);
+        builder.start_block(end);
+        return Ok(());
+    }
+}
+
+impl<'a> ToIr<'a> for WhileStmt {
+    fn to_ir(&'a self, builder: &mut IrBuilder<'a>) -> Result<()> {
+        let condition = builder.new_block();
+        let body = builder.new_block();
+        let end = builder.new_block();
+        builder.start_block(condition);
+        builder.condition(&self.condition, body, end)?;
+        builder.start_block(body);
+        builder.loop_body(self.label.as_ref(), &self.block, condition, end)?;
+        builder.jump(condition);
+        builder.start_block(end);
+        return Ok(());
+    }
+}
+
+impl<'a> ToIr<'a> for DoWhileStmt {
+    fn to_ir(&'a self, builder: &mut IrBuilder<'a>) -> Result<()> {
+        let body = builder.new_block();
+        let condition = builder.new_block();
+        let end = builder.new_block();
+        builder.start_block(body);
+        builder.loop_body(self.label.as_ref(), &self.block, condition, end)?;
+        builder.start_block(condition);
+        builder.condition(&self.condition, body, end)?;
+        builder.start_block(end);
+        return Ok(());
+    }
+}
+
+impl<'a> ToIr<'a> for IfStmt {
+    fn to_ir(&'a self, builder: &mut IrBuilder<'a>) -> Result<()> {
+        let end = builder.new_block();
+        let branches = std::iter::once((&self.condition, &self.block)).chain(
+            self.else_ifs
+                .iter()
+                .map(|else_if| (&else_if.condition, &else_if.block)),
+        );
+        for (condition, block) in branches {
+            let then = builder.new_block();
+            let next = builder.new_block();
+            builder.condition(condition, then, next)?;
+            builder.start_block(then);
+            block.to_ir(builder)?;
+            builder.jump(end);
+            builder.start_block(next);
+        }
+        if let Some(else_stmt) = &self.else_stmt {
+            else_stmt.to_ir(builder)?;
+        }
+        builder.start_block(end);
+        return Ok(());
+    }
+}
+
+impl<'a> ToIr<'a> for SwitchStmt {
+    /// Tests the cases in order like a chain of `else if`s.
+    fn to_ir(&'a self, builder: &mut IrBuilder<'a>) -> Result<()> {
+        let value = self.value.to_value(builder)?;
+        let end = builder.new_block();
+        for case in self.cases.iter() {
+            let case_value = builder.context.case_value(&case.value)?;
+            let constant = match builder.type_of(&value) {
+                TypeSpecifier::Usize => Constant::Usize(case_value as u64),
+                TypeSpecifier::Char => Constant::Char(case_value as u8),
+                _ => Constant::Int(case_value as i32),
+            };
+            let condition = builder.define(TypeSpecifier::Bool, |dest| Instruction::Binary {
+                dest,
+                operator: BinaryOp::Equal,
+                left: value.clone(),
+                right: Value::Constant(constant),
+            });
+            let then = builder.new_block();
+            let next = builder.new_block();
+            builder.branch(condition, then, next);
+            builder.start_block(then);
+            case.block.to_ir(builder)?;
+            builder.jump(end);
+            builder.start_block(next);
+        }
+        if let Some(default) = &self.default {
+            default.to_ir(builder)?;
+        }
+        builder.start_block(end);
+        return Ok(());
+    }
+}
+
+impl<'a> ToIr<'a> for MatchStmt {
+    /// Compares the tag with the item of every arm like a chain of
+    /// `else if`s. A tagged value is kept in memory for the arms to read the
+    /// carried values from.
+    fn to_ir(&'a self, builder: &mut IrBuilder<'a>) -> Result<()> {
+        let value = self.value.to_value(builder)?;
+        let type_specifier = builder.type_of(&value);
+        let enum_name = TypeContext::struct_name(&type_specifier)?.to_owned();
+        let mut object = None;
+        let mut tag = value.clone();
+        if builder.context.is_tagged(&enum_name) {
+            let address = builder.alloca(&type_specifier);
+            builder.store(address.clone(), value);
+            let pointer = builder.field(address.clone(), &enum_name, EnumDeclaration::TAG)?;
+            tag = builder.load(pointer)?;
+            object = Some(address);
+        }
+        let end = builder.new_block();
+        for arm in self.arms.iter() {
+            let item = builder
+                .context
+                .enum_item(arm.name.as_ref(), arm.item.as_ref())?;
+            let condition = builder.define(TypeSpecifier::Bool, |dest| Instruction::Binary {
+                dest,
+                operator: BinaryOp::Equal,
+                left: tag.clone(),
+                right: Value::Constant(Constant::Int(item)),
+            });
+            let then = builder.new_block();
+            let next = builder.new_block();
+            builder.branch(condition, then, next);
+            builder.start_block(then);
+            builder.push_scope();
+            let result = arm.to_ir_from(object.clone(), builder);
+            builder.pop_scope();
+            result?;
+            builder.jump(end);
+            builder.start_block(next);
+        }
+        if let Some(default) = &self.default {
+            default.to_ir(builder)?;
+        }
+        builder.start_block(end);
+        return Ok(());
+    }
+}
+
+impl MatchArm {
+    /// Binds the values carried in the tagged enum at `object`, then runs
+    /// the block.
+    fn to_ir_from<'a>(&'a self, object: Option<Value>, builder: &mut IrBuilder<'a>) -> Result<()> {
+        let (name, item) = (self.name.as_ref(), self.item.as_ref());
+        for (index, binding) in self.bindings.iter().enumerate() {
+            let (Some(binding), Some(object)) = (binding, &object) else {
+                continue;
+            };
+            let field = EnumDeclaration::payload_field(item, index);
+            let pointer = builder.field(object.clone(), name, &field)?;
+            let value = builder.load(pointer)?;
+            let field_type = builder.type_of(&value);
+            let address = builder.declare(binding.as_ref(), &field_type);
+            builder.store(address, value);
+        }
+        return self.block.to_ir(builder);
+    }
+}
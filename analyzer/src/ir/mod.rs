@@ -0,0 +1,545 @@
+pub mod cfg;
+pub mod lower;
+pub mod opt;
+pub mod ssa;
+
+use std::fmt;
+
+use crate::ast::type_specifier::TypeSpecifier;
+
+/// A program in three-address form: every function is a control-flow graph
+/// of basic blocks whose instructions read values and define at most one
+/// typed temporary. After [`Function::construct_ssa`] every temporary has a
+/// single definition and locals whose address is never taken are kept in
+/// temporaries joined by phi nodes.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Module {
+    pub structs: Vec<StructDef>,
+    pub functions: Vec<Function>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructDef {
+    pub name: String,
+    pub fields: Vec<(String, TypeSpecifier)>,
+}
+
+/// A function whose entry is the first block. Methods are named
+/// `Struct.name` and take their receiver as the first parameter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+    pub name: String,
+    pub params: Vec<Temp>,
+    pub return_type: TypeSpecifier,
+    /// The type of every temporary, indexed by its number.
+    pub temps: Vec<TypeSpecifier>,
+    pub blocks: Vec<Block>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Block {
+    pub phis: Vec<Phi>,
+    pub instructions: Vec<Instruction>,
+    pub terminator: Terminator,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Temp(pub usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BlockId(pub usize);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Temp(Temp),
+    Constant(Constant),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Constant {
+    Int(i32),
+    Usize(u64),
+    Float(f32),
+    Double(f64),
+    Char(u8),
+    Bool(bool),
+    Str(Vec<u8>),
+    /// The zero value of any type: null pointers and zeroed aggregates.
+    Zero(TypeSpecifier),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BinaryOp {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Modulo,
+    BitAnd,
+    BitOr,
+    BitXor,
+    ShiftLeft,
+    ShiftRight,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnaryOp {
+    Negate,
+    Not,
+    BitNot,
+}
+
+/// Joins the values flowing in from the predecessors of its block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Phi {
+    pub dest: Temp,
+    pub incoming: Vec<(BlockId, Value)>,
+}
+
+/// Structs and arrays are values like scalars, memory is only reached
+/// through the addresses `Alloca`, `Field`, `Element` and `Offset` produce.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Instruction {
+    Copy {
+        dest: Temp,
+        value: Value,
+    },
+    /// Comparisons define a `bool`, the other operators the operand type.
+    Binary {
+        dest: Temp,
+        operator: BinaryOp,
+        left: Value,
+        right: Value,
+    },
+    Unary {
+        dest: Temp,
+        operator: UnaryOp,
+        value: Value,
+    },
+    /// Converts `value` to the type of `dest` with the rules of a C cast.
+    Cast {
+        dest: Temp,
+        value: Value,
+    },
+    /// Reserves frame memory for the pointee of the type of `dest`.
+    Alloca {
+        dest: Temp,
+    },
+    Load {
+        dest: Temp,
+        address: Value,
+    },
+    Store {
+        address: Value,
+        value: Value,
+    },
+    /// The address of the field `field` of the struct `address` points to.
+    Field {
+        dest: Temp,
+        address: Value,
+        field: String,
+    },
+    /// The address of the element `index` of the array `address` points to.
+    Element {
+        dest: Temp,
+        address: Value,
+        index: Value,
+    },
+    /// Moves the pointer `pointer` by `index` elements.
+    Offset {
+        dest: Temp,
+        pointer: Value,
+        index: Value,
+    },
+    Call {
+        dest: Option<Temp>,
+        function: String,
+        args: Vec<Value>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Terminator {
+    Jump(BlockId),
+    Branch {
+        condition: Value,
+        then: BlockId,
+        otherwise: BlockId,
+    },
+    Return(Option<Value>),
+    Unreachable,
+}
+
+impl Constant {
+    pub fn type_specifier(&self) -> TypeSpecifier {
+        return match self {
+            Constant::Int(_) => TypeSpecifier::Int,
+            Constant::Usize(_) => TypeSpecifier::Usize,
+            Constant::Float(_) => TypeSpecifier::Float,
+            Constant::Double(_) => TypeSpecifier::Double,
+            Constant::Char(_) => TypeSpecifier::Char,
+            Constant::Bool(_) => TypeSpecifier::Bool,
+            Constant::Str(_) => TypeSpecifier::Str,
+            Constant::Zero(type_specifier) => type_specifier.clone(),
+        };
+    }
+}
+
+impl BinaryOp {
+    pub fn is_comparison(&self) -> bool {
+        return matches!(
+            self,
+            BinaryOp::Equal
+                | BinaryOp::NotEqual
+                | BinaryOp::Less
+                | BinaryOp::LessEqual
+                | BinaryOp::Greater
+                | BinaryOp::GreaterEqual
+        );
+    }
+}
+
+impl Instruction {
+    pub fn dest(&self) -> Option<Temp> {
+        return match self {
+            Instruction::Copy { dest, .. }
+            | Instruction::Binary { dest, .. }
+            | Instruction::Unary { dest, .. }
+            | Instruction::Cast { dest, .. }
+            | Instruction::Alloca { dest }
+            | Instruction::Load { dest, .. }
+            | Instruction::Field { dest, .. }
+            | Instruction::Element { dest, .. }
+            | Instruction::Offset { dest, .. } => Some(*dest),
+            Instruction::Call { dest, .. } => *dest,
+            Instruction::Store { .. } => None,
+        };
+    }
+
+    pub fn operands(&self) -> Vec<&Value> {
+        return match self {
+            Instruction::Copy { value, .. }
+            | Instruction::Unary { value, .. }
+            | Instruction::Cast { value, .. } => vec![value],
+            Instruction::Binary { left, right, .. } => vec![left, right],
+            Instruction::Alloca { .. } => Vec::new(),
+            Instruction::Load { address, .. } | Instruction::Field { address, .. } => {
+                vec![address]
+            }
+            Instruction::Store { address, value } => vec![address, value],
+            Instruction::Element { address, index, .. } => vec![address, index],
+            Instruction::Offset { pointer, index, .. } => vec![pointer, index],
+            Instruction::Call { args, .. } => args.iter().collect(),
+        };
+    }
+
+    pub fn operands_mut(&mut self) -> Vec<&mut Value> {
+        return match self {
+            Instruction::Copy { value, .. }
+            | Instruction::Unary { value, .. }
+            | Instruction::Cast { value, .. } => vec![value],
+            Instruction::Binary { left, right, .. } => vec![left, right],
+            Instruction::Alloca { .. } => Vec::new(),
+            Instruction::Load { address, .. } | Instruction::Field { address, .. } => {
+                vec![address]
+            }
+            Instruction::Store { address, value } => vec![address, value],
+            Instruction::Element { address, index, .. } => vec![address, index],
+            Instruction::Offset { pointer, index, .. } => vec![pointer, index],
+            Instruction::Call { args, .. } => args.iter_mut().collect(),
+        };
+    }
+}
+
+impl Terminator {
+    pub fn successors(&self) -> Vec<BlockId> {
+        return match self {
+            Terminator::Jump(target) => vec![*target],
+            Terminator::Branch {
+                then, otherwise, ..
+            } if then == otherwise => vec![*then],
+            Terminator::Branch {
+                then, otherwise, ..
+            } => vec![*then, *otherwise],
+            Terminator::Return(_) | Terminator::Unreachable => Vec::new(),
+        };
+    }
+
+    pub fn operands(&self) -> Vec<&Value> {
+        return match self {
+            Terminator::Branch { condition, .. } => vec![condition],
+            Terminator::Return(Some(value)) => vec![value],
+            _ => Vec::new(),
+        };
+    }
+
+    pub fn operands_mut(&mut self) -> Vec<&mut Value> {
+        return match self {
+            Terminator::Branch { condition, .. } => vec![condition],
+            Terminator::Return(Some(value)) => vec![value],
+            _ => Vec::new(),
+        };
+    }
+}
+
+impl Function {
+    pub fn new(name: String, return_type: TypeSpecifier) -> Self {
+        return Self {
+            name,
+            params: Vec::new(),
+            return_type,
+            temps: Vec::new(),
+            blocks: Vec::new(),
+        };
+    }
+
+    pub fn new_temp(&mut self, type_specifier: TypeSpecifier) -> Temp {
+        self.temps.push(type_specifier);
+        return Temp(self.temps.len() - 1);
+    }
+
+    pub fn new_block(&mut self) -> BlockId {
+        self.blocks.push(Block {
+            phis: Vec::new(),
+            instructions: Vec::new(),
+            terminator: Terminator::Unreachable,
+        });
+        return BlockId(self.blocks.len() - 1);
+    }
+
+    pub fn block(&self, id: BlockId) -> &Block {
+        return &self.blocks[id.0];
+    }
+
+    pub fn block_mut(&mut self, id: BlockId) -> &mut Block {
+        return &mut self.blocks[id.0];
+    }
+
+    pub fn type_of(&self, value: &Value) -> TypeSpecifier {
+        return match value {
+            Value::Temp(temp) => self.temps[temp.0].clone(),
+            Value::Constant(constant) => constant.type_specifier(),
+        };
+    }
+}
+
+/// The name of a type as the IR listing shows it.
+pub fn type_name(type_specifier: &TypeSpecifier) -> String {
+    return match type_specifier {
+        TypeSpecifier::Int => String::from("int"),
+        TypeSpecifier::Usize => String::from("usize"),
+        TypeSpecifier::Float => String::from("float"),
+        TypeSpecifier::Double => String::from("double"),
+        TypeSpecifier::Char => String::from("char"),
+        TypeSpecifier::Str => String::from("str"),
+        TypeSpecifier::Bool => String::from("bool"),
+        TypeSpecifier::Void => String::from("void"),
+        TypeSpecifier::UserDefine(name) => name.to_string(),
+        TypeSpecifier::Pointer(pointee) => format!("{}*", type_name(pointee)),
+        TypeSpecifier::ArrayType {
+            type_specifier,
+            size,
+            ..
+        } => format!("{}[{}]", type_name(type_specifier), size),
+    };
+}
+
+impl fmt::Display for Temp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "%{}", self.0);
+    }
+}
+
+impl fmt::Display for BlockId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "bb{}", self.0);
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Value::Temp(temp) => write!(f, "{}", temp),
+            Value::Constant(constant) => write!(f, "{}", constant),
+        };
+    }
+}
+
+impl fmt::Display for Constant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Constant::Int(value) => write!(f, "{}", value),
+            Constant::Usize(value) => write!(f, "{}u", value),
+            Constant::Float(value) => write!(f, "{:?}f", value),
+            Constant::Double(value) => write!(f, "{:?}", value),
+            Constant::Char(value) => write!(f, "'{}'", (*value as char).escape_default()),
+            Constant::Bool(value) => write!(f, "{}", value),
+            Constant::Str(bytes) => write!(f, "\"{}\"", bytes.escape_ascii()),
+            Constant::Zero(type_specifier) => write!(f, "zero {}", type_name(type_specifier)),
+        };
+    }
+}
+
+impl fmt::Display for BinaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            BinaryOp::Add => "add",
+            BinaryOp::Subtract => "sub",
+            BinaryOp::Multiply => "mul",
+            BinaryOp::Divide => "div",
+            BinaryOp::Modulo => "rem",
+            BinaryOp::BitAnd => "and",
+            BinaryOp::BitOr => "or",
+            BinaryOp::BitXor => "xor",
+            BinaryOp::ShiftLeft => "shl",
+            BinaryOp::ShiftRight => "shr",
+            BinaryOp::Equal => "eq",
+            BinaryOp::NotEqual => "ne",
+            BinaryOp::Less => "lt",
+            BinaryOp::LessEqual => "le",
+            BinaryOp::Greater => "gt",
+            BinaryOp::GreaterEqual => "ge",
+        };
+        return write!(f, "{}", name);
+    }
+}
+
+impl fmt::Display for UnaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            UnaryOp::Negate => "neg",
+            UnaryOp::Not => "not",
+            UnaryOp::BitNot => "bitnot",
+        };
+        return write!(f, "{}", name);
+    }
+}
+
+impl fmt::Display for Terminator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Terminator::Jump(target) => write!(f, "jump {}", target),
+            Terminator::Branch {
+                condition,
+                then,
+                otherwise,
+            } => write!(f, "branch {}, {}, {}", condition, then, otherwise),
+            Terminator::Return(Some(value)) => write!(f, "ret {}", value),
+            Terminator::Return(None) => write!(f, "ret"),
+            Terminator::Unreachable => write!(f, "unreachable"),
+        };
+    }
+}
+
+impl Function {
+    fn fmt_instruction(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        instruction: &Instruction,
+    ) -> fmt::Result {
+        if let Some(dest) = instruction.dest() {
+            write!(f, "{}: {} = ", dest, type_name(&self.temps[dest.0]))?;
+        }
+        return match instruction {
+            Instruction::Copy { value, .. } => write!(f, "copy {}", value),
+            Instruction::Binary {
+                operator,
+                left,
+                right,
+                ..
+            } => write!(f, "{} {}, {}", operator, left, right),
+            Instruction::Unary {
+                operator, value, ..
+            } => write!(f, "{} {}", operator, value),
+            Instruction::Cast { value, .. } => {
+                write!(f, "cast {} from {}", value, type_name(&self.type_of(value)))
+            }
+            Instruction::Alloca { .. } => write!(f, "alloca"),
+            Instruction::Load { address, .. } => write!(f, "load {}", address),
+            Instruction::Store { address, value } => write!(f, "store {}, {}", address, value),
+            Instruction::Field { address, field, .. } => write!(f, "field {}, {}", address, field),
+            Instruction::Element { address, index, .. } => {
+                write!(f, "element {}, {}", address, index)
+            }
+            Instruction::Offset { pointer, index, .. } => {
+                write!(f, "offset {}, {}", pointer, index)
+            }
+            Instruction::Call { function, args, .. } => {
+                let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+                write!(f, "call {}({})", function, args.join(", "))
+            }
+        };
+    }
+}
+
+impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let params: Vec<String> = self
+            .params
+            .iter()
+            .map(|param| format!("{}: {}", param, type_name(&self.temps[param.0])))
+            .collect();
+        writeln!(
+            f,
+            "fn {}({}) -> {} {{",
+            self.name,
+            params.join(", "),
+            type_name(&self.return_type)
+        )?;
+        for (id, block) in self.blocks.iter().enumerate() {
+            writeln!(f, "{}:", BlockId(id))?;
+            for phi in block.phis.iter() {
+                let incoming: Vec<String> = phi
+                    .incoming
+                    .iter()
+                    .map(|(block, value)| format!("[{}: {}]", block, value))
+                    .collect();
+                writeln!(
+                    f,
+                    "    {}: {} = phi {}",
+                    phi.dest,
+                    type_name(&self.temps[phi.dest.0]),
+                    incoming.join(", ")
+                )?;
+            }
+            for instruction in block.instructions.iter() {
+                write!(f, "    ")?;
+                self.fmt_instruction(f, instruction)?;
+                writeln!(f)?;
+            }
+            writeln!(f, "    {}", block.terminator)?;
+        }
+        return writeln!(f, "}}");
+    }
+}
+
+impl fmt::Display for Module {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for struct_def in self.structs.iter() {
+            let fields: Vec<String> = struct_def
+                .fields
+                .iter()
+                .map(|(name, type_specifier)| format!("{}: {}", name, type_name(type_specifier)))
+                .collect();
+            writeln!(
+                f,
+                "struct {} {{ {} }}\n",
+                struct_def.name,
+                fields.join(", ")
+            )?;
+        }
+        for (index, function) in self.functions.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", function)?;
+        }
+        return Ok(());
+    }
+}
@@ -0,0 +1,261 @@
+use std::collections::HashMap;
+
+use crate::{
+    ast::type_specifier::TypeSpecifier,
+    ir::{BinaryOp, BlockId, Function, Instruction, Temp, Value},
+};
+
+use super::FunctionPass;
+
+/// Reuses the result of an operator or address computation when the same
+/// one with the same operands already ran in a dominating position. Loads
+/// and calls are never merged since memory may change in between.
+pub struct CommonSubexpressionElimination;
+
+/// The computations visible on the path from the entry to the current block.
+struct Available<'a> {
+    expressions: Vec<(&'a Instruction, &'a TypeSpecifier, Temp)>,
+}
+
+impl FunctionPass for CommonSubexpressionElimination {
+    fn name(&self) -> &'static str {
+        return "cse";
+    }
+
+    fn run_function(&self, function: &mut Function) -> bool {
+        let dominators = function.dominators();
+        let tree = Function::dominator_tree(&dominators);
+        let mut replaced = HashMap::new();
+        if !function.blocks.is_empty() {
+            let mut available = Available {
+                expressions: Vec::new(),
+            };
+            available.visit(function, &tree, BlockId(0), &mut replaced);
+        }
+        let changed = !replaced.is_empty();
+        function.substitute(&replaced);
+        return changed;
+    }
+}
+
+impl<'a> Available<'a> {
+    fn visit(
+        &mut self,
+        function: &'a Function,
+        tree: &[Vec<BlockId>],
+        block: BlockId,
+        replaced: &mut HashMap<Temp, Value>,
+    ) {
+        let depth = self.expressions.len();
+        for instruction in function.block(block).instructions.iter() {
+            let Some(dest) = instruction.dest() else {
+                continue;
+            };
+            if !Self::is_pure(instruction) {
+                continue;
+            }
+            let type_specifier = &function.temps[dest.0];
+            let existing = self.expressions.iter().find(|(other, other_type, _)| {
+                return *other_type == type_specifier && Self::same(instruction, other);
+            });
+            match existing {
+                Some((_, _, temp)) => {
+                    replaced.insert(dest, Value::Temp(*temp));
+                }
+                None => self.expressions.push((instruction, type_specifier, dest)),
+            }
+        }
+        for child in tree[block.0].iter() {
+            self.visit(function, tree, *child, replaced);
+        }
+        self.expressions.truncate(depth);
+    }
+
+    fn is_pure(instruction: &Instruction) -> bool {
+        return matches!(
+            instruction,
+            Instruction::Binary { .. }
+                | Instruction::Unary { .. }
+                | Instruction::Cast { .. }
+                | Instruction::Field { .. }
+                | Instruction::Element { .. }
+                | Instruction::Offset { .. }
+        );
+    }
+
+    /// Whether both instructions compute the same value, also with the
+    /// operands of a commutative operator swapped.
+    fn same(instruction: &Instruction, other: &Instruction) -> bool {
+        return match (instruction, other) {
+            (
+                Instruction::Binary {
+                    operator,
+                    left,
+                    right,
+                    ..
+                },
+                Instruction::Binary {
+                    operator: other_operator,
+                    left: other_left,
+                    right: other_right,
+                    ..
+                },
+            ) => {
+                operator == other_operator
+                    && ((left.same(other_left) && right.same(other_right))
+                        || (Self::is_commutative(*operator)
+                            && left.same(other_right)
+                            && right.same(other_left)))
+            }
+            (
+                Instruction::Unary {
+                    operator, value, ..
+                },
+                Instruction::Unary {
+                    operator: other_operator,
+                    value: other_value,
+                    ..
+                },
+            ) => operator == other_operator && value.same(other_value),
+            (
+                Instruction::Field { address, field, .. },
+                Instruction::Field {
+                    address: other_address,
+                    field: other_field,
+                    ..
+                },
+            ) => address.same(other_address) && field == other_field,
+            (Instruction::Cast { .. }, Instruction::Cast { .. })
+            | (Instruction::Element { .. }, Instruction::Element { .. })
+            | (Instruction::Offset { .. }, Instruction::Offset { .. }) => instruction
+                .operands()
+                .into_iter()
+                .zip(other.operands())
+                .all(|(operand, other)| operand.same(other)),
+            _ => false,
+        };
+    }
+
+    fn is_commutative(operator: BinaryOp) -> bool {
+        return matches!(
+            operator,
+            BinaryOp::Add
+                | BinaryOp::Multiply
+                | BinaryOp::BitAnd
+                | BinaryOp::BitOr
+                | BinaryOp::BitXor
+                | BinaryOp::Equal
+                | BinaryOp::NotEqual
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ir::{Constant, Phi, Terminator};
+
+    use super::*;
+
+    fn add(dest: Temp, left: Value, right: Value) -> Instruction {
+        return Instruction::Binary {
+            dest,
+            operator: BinaryOp::Add,
+            left,
+            right,
+        };
+    }
+
+    fn defines(function: &Function, temp: Temp) -> bool {
+        return function.blocks.iter().any(|block| {
+            return block
+                .instructions
+                .iter()
+                .any(|instruction| instruction.dest() == Some(temp));
+        });
+    }
+
+    /// Expressions are reused from the blocks that dominate them, not from
+    /// a sibling branch that may not have run.
+    #[test]
+    fn reuses_only_dominating_expressions() {
+        let mut function = Function::new(String::from("f"), TypeSpecifier::Int);
+        let p = function.new_temp(TypeSpecifier::Int);
+        let c = function.new_temp(TypeSpecifier::Bool);
+        function.params = vec![p, c];
+        let temps: Vec<Temp> = (0..6)
+            .map(|_| function.new_temp(TypeSpecifier::Int))
+            .collect();
+        let [first, again, then_sum, otherwise_sum, joined, after] = temps[..] else {
+            unreachable!();
+        };
+        let (p, one) = (Value::Temp(p), Value::Constant(Constant::Int(1)));
+        let entry = function.new_block();
+        let then = function.new_block();
+        let otherwise = function.new_block();
+        let join = function.new_block();
+
+        function.block_mut(entry).instructions = vec![add(first, p.clone(), one.clone())];
+        function.block_mut(entry).terminator = Terminator::Branch {
+            condition: Value::Temp(c),
+            then,
+            otherwise,
+        };
+        // `again` is `first` with the operands swapped.
+        function.block_mut(then).instructions = vec![
+            add(again, one.clone(), p.clone()),
+            add(then_sum, p.clone(), p.clone()),
+        ];
+        function.block_mut(then).terminator = Terminator::Jump(join);
+        function.block_mut(otherwise).instructions = vec![add(otherwise_sum, p.clone(), p.clone())];
+        function.block_mut(otherwise).terminator = Terminator::Jump(join);
+        function.block_mut(join).phis = vec![Phi {
+            dest: joined,
+            incoming: vec![
+                (then, Value::Temp(again)),
+                (otherwise, Value::Temp(otherwise_sum)),
+            ],
+        }];
+        function.block_mut(join).instructions = vec![add(after, p.clone(), p.clone())];
+        function.block_mut(join).terminator = Terminator::Return(Some(Value::Temp(after)));
+
+        assert!(CommonSubexpressionElimination.run_function(&mut function));
+        assert!(!defines(&function, again), "{}", function);
+        assert_eq!(
+            function.block(join).phis[0].incoming[0],
+            (then, Value::Temp(first))
+        );
+        // Neither branch dominates the other or the join.
+        assert!(defines(&function, then_sum), "{}", function);
+        assert!(defines(&function, otherwise_sum), "{}", function);
+        assert!(defines(&function, after), "{}", function);
+    }
+
+    #[test]
+    fn keeps_loads_and_calls() {
+        let mut function = Function::new(String::from("f"), TypeSpecifier::Int);
+        let address = function.new_temp(TypeSpecifier::Pointer(Box::new(TypeSpecifier::Int)));
+        function.params = vec![address];
+        let temps: Vec<Temp> = (0..4)
+            .map(|_| function.new_temp(TypeSpecifier::Int))
+            .collect();
+        let call = |dest| Instruction::Call {
+            dest: Some(dest),
+            function: String::from("g"),
+            args: Vec::new(),
+        };
+        let load = |dest| Instruction::Load {
+            dest,
+            address: Value::Temp(address),
+        };
+        let entry = function.new_block();
+        function.block_mut(entry).instructions = vec![
+            load(temps[0]),
+            call(temps[1]),
+            load(temps[2]),
+            call(temps[3]),
+        ];
+        let before = function.clone();
+        assert!(!CommonSubexpressionElimination.run_function(&mut function));
+        assert_eq!(function, before);
+    }
+}
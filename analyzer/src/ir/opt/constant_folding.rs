@@ -0,0 +1,359 @@
+use crate::{
+    ast::type_specifier::TypeSpecifier,
+    ir::{BinaryOp, Constant, Function, Instruction, UnaryOp, Value},
+};
+
+use super::FunctionPass;
+
+/// Evaluates operators whose operands are constants and simplifies
+/// algebraic identities like `x + 0`, replacing the instruction with a copy
+/// of the result. Operations whose result depends on the target, like
+/// division by zero or shifts past the width, are left alone.
+pub struct ConstantFolding;
+
+impl FunctionPass for ConstantFolding {
+    fn name(&self) -> &'static str {
+        return "constant-folding";
+    }
+
+    fn run_function(&self, function: &mut Function) -> bool {
+        let mut changed = false;
+        for block in 0..function.blocks.len() {
+            for index in 0..function.blocks[block].instructions.len() {
+                let instruction = &function.blocks[block].instructions[index];
+                let Some(dest) = instruction.dest() else {
+                    continue;
+                };
+                let Some(value) = simplify(function, instruction) else {
+                    continue;
+                };
+                function.blocks[block].instructions[index] = Instruction::Copy { dest, value };
+                changed = true;
+            }
+        }
+        return changed;
+    }
+}
+
+fn simplify(function: &Function, instruction: &Instruction) -> Option<Value> {
+    return match instruction {
+        Instruction::Binary {
+            operator,
+            left,
+            right,
+            ..
+        } => match (left, right) {
+            (Value::Constant(left), Value::Constant(right)) => {
+                fold_binary(*operator, left, right).map(Value::Constant)
+            }
+            _ => identity(*operator, left, right, &function.type_of(left)),
+        },
+        Instruction::Unary {
+            operator,
+            value: Value::Constant(value),
+            ..
+        } => fold_unary(*operator, value).map(Value::Constant),
+        Instruction::Cast { dest, value } => {
+            let target = &function.temps[dest.0];
+            match value {
+                Value::Constant(value) => fold_cast(value, target).map(Value::Constant),
+                value if function.type_of(value) == *target => Some(value.clone()),
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+}
+
+/// Rewrites `x + 0`, `x * 1`, `x - x` and friends on integers. Floats are
+/// skipped since `x + 0.0` is not `x` for `-0.0` and NaN breaks `x - x`.
+fn identity(
+    operator: BinaryOp,
+    left: &Value,
+    right: &Value,
+    type_specifier: &TypeSpecifier,
+) -> Option<Value> {
+    if !matches!(
+        type_specifier,
+        TypeSpecifier::Int | TypeSpecifier::Usize | TypeSpecifier::Char
+    ) {
+        return None;
+    }
+    let is = |value: &Value, number: i64| -> bool {
+        return matches!(value, Value::Constant(constant) if integer(constant) == Some(number));
+    };
+    let zero = || Value::Constant(normalize(&Constant::Zero(type_specifier.clone())));
+    return match operator {
+        BinaryOp::Add | BinaryOp::BitOr | BinaryOp::BitXor if is(left, 0) => Some(right.clone()),
+        BinaryOp::Add
+        | BinaryOp::Subtract
+        | BinaryOp::BitOr
+        | BinaryOp::BitXor
+        | BinaryOp::ShiftLeft
+        | BinaryOp::ShiftRight
+            if is(right, 0) =>
+        {
+            Some(left.clone())
+        }
+        BinaryOp::Multiply if is(left, 1) => Some(right.clone()),
+        BinaryOp::Multiply | BinaryOp::Divide if is(right, 1) => Some(left.clone()),
+        BinaryOp::Multiply | BinaryOp::BitAnd if is(left, 0) || is(right, 0) => Some(zero()),
+        BinaryOp::Subtract | BinaryOp::BitXor if left.same(right) => Some(zero()),
+        BinaryOp::BitAnd | BinaryOp::BitOr if left.same(right) => Some(left.clone()),
+        BinaryOp::Equal | BinaryOp::LessEqual | BinaryOp::GreaterEqual if left.same(right) => {
+            Some(Value::Constant(Constant::Bool(true)))
+        }
+        BinaryOp::NotEqual | BinaryOp::Less | BinaryOp::Greater if left.same(right) => {
+            Some(Value::Constant(Constant::Bool(false)))
+        }
+        _ => None,
+    };
+}
+
+/// The constant with `Zero` of a primitive type spelled out.
+pub fn normalize(constant: &Constant) -> Constant {
+    let Constant::Zero(type_specifier) = constant else {
+        return constant.clone();
+    };
+    return match type_specifier {
+        TypeSpecifier::Int => Constant::Int(0),
+        TypeSpecifier::Usize => Constant::Usize(0),
+        TypeSpecifier::Float => Constant::Float(0.0),
+        TypeSpecifier::Double => Constant::Double(0.0),
+        TypeSpecifier::Char => Constant::Char(0),
+        TypeSpecifier::Bool => Constant::Bool(false),
+        _ => constant.clone(),
+    };
+}
+
+fn integer(constant: &Constant) -> Option<i64> {
+    return match normalize(constant) {
+        Constant::Int(value) => Some(value as i64),
+        Constant::Usize(value) => i64::try_from(value).ok(),
+        Constant::Char(value) => Some(value as i64),
+        _ => None,
+    };
+}
+
+macro_rules! fold_integer {
+    ($operator:expr, $variant:path, $bits:expr, $left:expr, $right:expr) => {{
+        let (left, right) = ($left, $right);
+        match $operator {
+            BinaryOp::Add => $variant(left.wrapping_add(right)),
+            BinaryOp::Subtract => $variant(left.wrapping_sub(right)),
+            BinaryOp::Multiply => $variant(left.wrapping_mul(right)),
+            BinaryOp::Divide => $variant(left.checked_div(right)?),
+            BinaryOp::Modulo => $variant(left.checked_rem(right)?),
+            BinaryOp::BitAnd => $variant(left & right),
+            BinaryOp::BitOr => $variant(left | right),
+            BinaryOp::BitXor => $variant(left ^ right),
+            BinaryOp::ShiftLeft | BinaryOp::ShiftRight if right as u64 >= $bits => return None,
+            BinaryOp::ShiftLeft => $variant(left << right),
+            BinaryOp::ShiftRight => $variant(left >> right),
+            BinaryOp::Equal => Constant::Bool(left == right),
+            BinaryOp::NotEqual => Constant::Bool(left != right),
+            BinaryOp::Less => Constant::Bool(left < right),
+            BinaryOp::LessEqual => Constant::Bool(left <= right),
+            BinaryOp::Greater => Constant::Bool(left > right),
+            BinaryOp::GreaterEqual => Constant::Bool(left >= right),
+        }
+    }};
+}
+
+macro_rules! fold_float {
+    ($operator:expr, $variant:path, $left:expr, $right:expr) => {{
+        let (left, right) = ($left, $right);
+        match $operator {
+            BinaryOp::Add => $variant(left + right),
+            BinaryOp::Subtract => $variant(left - right),
+            BinaryOp::Multiply => $variant(left * right),
+            BinaryOp::Divide => $variant(left / right),
+            BinaryOp::Modulo => $variant(left % right),
+            BinaryOp::Equal => Constant::Bool(left == right),
+            BinaryOp::NotEqual => Constant::Bool(left != right),
+            BinaryOp::Less => Constant::Bool(left < right),
+            BinaryOp::LessEqual => Constant::Bool(left <= right),
+            BinaryOp::Greater => Constant::Bool(left > right),
+            BinaryOp::GreaterEqual => Constant::Bool(left >= right),
+            _ => return None,
+        }
+    }};
+}
+
+/// The result of a binary operator on constants with the semantics of the
+/// interpreter, or `None` when it cannot be computed at compile time.
+pub fn fold_binary(operator: BinaryOp, left: &Constant, right: &Constant) -> Option<Constant> {
+    return Some(match (normalize(left), normalize(right)) {
+        (Constant::Int(left), Constant::Int(right)) => {
+            fold_integer!(operator, Constant::Int, 32, left, right)
+        }
+        (Constant::Usize(left), Constant::Usize(right)) => {
+            fold_integer!(operator, Constant::Usize, 64, left, right)
+        }
+        (Constant::Char(left), Constant::Char(right)) => {
+            fold_integer!(operator, Constant::Char, 8, left, right)
+        }
+        (Constant::Float(left), Constant::Float(right)) => {
+            fold_float!(operator, Constant::Float, left, right)
+        }
+        (Constant::Double(left), Constant::Double(right)) => {
+            fold_float!(operator, Constant::Double, left, right)
+        }
+        (Constant::Bool(left), Constant::Bool(right)) => match operator {
+            BinaryOp::BitAnd => Constant::Bool(left & right),
+            BinaryOp::BitOr => Constant::Bool(left | right),
+            BinaryOp::BitXor | BinaryOp::NotEqual => Constant::Bool(left ^ right),
+            BinaryOp::Equal => Constant::Bool(left == right),
+            _ => return None,
+        },
+        _ => return None,
+    });
+}
+
+pub fn fold_unary(operator: UnaryOp, value: &Constant) -> Option<Constant> {
+    return Some(match (operator, normalize(value)) {
+        (UnaryOp::Negate, Constant::Int(value)) => Constant::Int(value.wrapping_neg()),
+        (UnaryOp::Negate, Constant::Usize(value)) => Constant::Usize(value.wrapping_neg()),
+        (UnaryOp::Negate, Constant::Char(value)) => Constant::Char(value.wrapping_neg()),
+        (UnaryOp::Negate, Constant::Float(value)) => Constant::Float(-value),
+        (UnaryOp::Negate, Constant::Double(value)) => Constant::Double(-value),
+        (UnaryOp::Not, Constant::Bool(value)) => Constant::Bool(!value),
+        (UnaryOp::BitNot, Constant::Int(value)) => Constant::Int(!value),
+        (UnaryOp::BitNot, Constant::Usize(value)) => Constant::Usize(!value),
+        (UnaryOp::BitNot, Constant::Char(value)) => Constant::Char(!value),
+        _ => return None,
+    });
+}
+
+/// Converts a constant with the rules of a C cast. Floats out of the range
+/// of an integer target are left to the target.
+pub fn fold_cast(value: &Constant, target: &TypeSpecifier) -> Option<Constant> {
+    let (integer, float, is_float) = match normalize(value) {
+        Constant::Int(value) => (value as i128, value as f64, false),
+        Constant::Usize(value) => (value as i128, value as f64, false),
+        Constant::Char(value) => (value as i128, value as f64, false),
+        Constant::Bool(value) => (value as i128, value as u8 as f64, false),
+        Constant::Float(value) => (value as i128, value as f64, true),
+        Constant::Double(value) => (value as i128, value, true),
+        _ => return None,
+    };
+    let in_range = |min: f64, max: f64| !is_float || (float > min - 1.0 && float < max + 1.0);
+    return Some(match target {
+        TypeSpecifier::Int if in_range(i32::MIN as f64, i32::MAX as f64) => {
+            Constant::Int(integer as i32)
+        }
+        TypeSpecifier::Usize if in_range(0.0, u64::MAX as f64) => Constant::Usize(integer as u64),
+        TypeSpecifier::Char if in_range(0.0, u8::MAX as f64) => Constant::Char(integer as u8),
+        TypeSpecifier::Float => Constant::Float(float as f32),
+        TypeSpecifier::Double => Constant::Double(float),
+        TypeSpecifier::Bool => Constant::Bool(float != 0.0),
+        _ => return None,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ir::{Block, Temp, Terminator};
+
+    use super::*;
+
+    fn int(value: i32) -> Value {
+        return Value::Constant(Constant::Int(value));
+    }
+
+    fn binary(dest: Temp, operator: BinaryOp, left: Value, right: Value) -> Instruction {
+        return Instruction::Binary {
+            dest,
+            operator,
+            left,
+            right,
+        };
+    }
+
+    /// A function of one block running `instructions` on the parameter
+    /// `%0`, with a temporary of `types` for every other destination.
+    fn function(types: &[TypeSpecifier], instructions: Vec<Instruction>) -> Function {
+        let mut function = Function::new(String::from("f"), TypeSpecifier::Void);
+        let param = function.new_temp(TypeSpecifier::Int);
+        function.params.push(param);
+        for type_specifier in types {
+            function.new_temp(type_specifier.clone());
+        }
+        function.blocks.push(Block {
+            phis: Vec::new(),
+            instructions,
+            terminator: Terminator::Return(None),
+        });
+        return function;
+    }
+
+    fn copied(function: &Function, dest: Temp) -> Option<&Value> {
+        return function.blocks[0]
+            .instructions
+            .iter()
+            .find_map(|instruction| match instruction {
+                Instruction::Copy { dest: copy, value } if *copy == dest => Some(value),
+                _ => None,
+            });
+    }
+
+    #[test]
+    fn folds_operators_on_constants() {
+        let mut function = function(
+            &[TypeSpecifier::Int, TypeSpecifier::Bool, TypeSpecifier::Char],
+            vec![
+                binary(Temp(1), BinaryOp::Multiply, int(6), int(7)),
+                binary(Temp(2), BinaryOp::Less, int(-1), int(1)),
+                Instruction::Cast {
+                    dest: Temp(3),
+                    value: int(300),
+                },
+            ],
+        );
+        assert!(ConstantFolding.run_function(&mut function));
+        assert_eq!(copied(&function, Temp(1)), Some(&int(42)));
+        assert_eq!(
+            copied(&function, Temp(2)),
+            Some(&Value::Constant(Constant::Bool(true)))
+        );
+        assert_eq!(
+            copied(&function, Temp(3)),
+            Some(&Value::Constant(Constant::Char(44)))
+        );
+    }
+
+    #[test]
+    fn simplifies_integer_identities() {
+        let param = Value::Temp(Temp(0));
+        let mut function = function(
+            &[TypeSpecifier::Int, TypeSpecifier::Int, TypeSpecifier::Int],
+            vec![
+                binary(Temp(1), BinaryOp::Add, int(0), param.clone()),
+                binary(Temp(2), BinaryOp::Multiply, param.clone(), int(0)),
+                binary(Temp(3), BinaryOp::Subtract, param.clone(), param.clone()),
+            ],
+        );
+        assert!(ConstantFolding.run_function(&mut function));
+        assert_eq!(copied(&function, Temp(1)), Some(&param));
+        assert_eq!(copied(&function, Temp(2)), Some(&int(0)));
+        assert_eq!(copied(&function, Temp(3)), Some(&int(0)));
+    }
+
+    #[test]
+    fn leaves_operations_the_target_defines() {
+        let mut function = function(
+            &[TypeSpecifier::Int, TypeSpecifier::Int, TypeSpecifier::Int],
+            vec![
+                binary(Temp(1), BinaryOp::Divide, int(1), int(0)),
+                binary(Temp(2), BinaryOp::ShiftLeft, int(1), int(32)),
+                Instruction::Cast {
+                    dest: Temp(3),
+                    value: Value::Constant(Constant::Double(1e20)),
+                },
+            ],
+        );
+        let before = function.clone();
+        assert!(!ConstantFolding.run_function(&mut function));
+        assert_eq!(function, before);
+    }
+}
@@ -0,0 +1,358 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::ir::{BlockId, Constant, Function, Instruction, Temp, Terminator, Value};
+
+use super::{
+    constant_folding::{fold_binary, fold_cast, fold_unary, normalize},
+    FunctionPass,
+};
+
+/// Sparse conditional constant propagation: finds the temporaries that hold
+/// the same constant on every executable path, only following the branches
+/// a constant condition can take, then replaces them with the constant,
+/// turns constant branches into jumps and drops the blocks no longer reached.
+pub struct ConstantPropagation;
+
+/// What is known about the value of a temporary.
+#[derive(Debug, Clone, PartialEq)]
+enum Lattice {
+    /// No definition was executed yet.
+    Unknown,
+    Constant(Constant),
+    /// Different values reach it.
+    Varying,
+}
+
+impl Lattice {
+    fn meet(&self, other: &Lattice) -> Lattice {
+        return match (self, other) {
+            (Lattice::Unknown, other) | (other, Lattice::Unknown) => other.clone(),
+            (Lattice::Constant(left), Lattice::Constant(right)) if left.same(right) => self.clone(),
+            _ => Lattice::Varying,
+        };
+    }
+
+    fn same(&self, other: &Lattice) -> bool {
+        return match (self, other) {
+            (Lattice::Constant(left), Lattice::Constant(right)) => left.same(right),
+            (left, right) => left == right,
+        };
+    }
+}
+
+struct Analysis {
+    values: HashMap<Temp, Lattice>,
+    executable: HashSet<BlockId>,
+    edges: HashSet<(BlockId, BlockId)>,
+}
+
+impl FunctionPass for ConstantPropagation {
+    fn name(&self) -> &'static str {
+        return "constant-propagation";
+    }
+
+    fn run_function(&self, function: &mut Function) -> bool {
+        let analysis = Analysis::solve(function);
+        let mut changed = false;
+
+        let mut replaced = HashMap::new();
+        // Only copies, operators and phis become constants, calls and loads
+        // are always varying.
+        for (temp, lattice) in analysis.values.iter() {
+            let Lattice::Constant(constant) = lattice else {
+                continue;
+            };
+            replaced.insert(*temp, Value::Constant(constant.clone()));
+        }
+        changed |= !replaced.is_empty();
+        function.substitute(&replaced);
+
+        for id in 0..function.blocks.len() {
+            let block = BlockId(id);
+            let Terminator::Branch {
+                then, otherwise, ..
+            } = function.block(block).terminator
+            else {
+                continue;
+            };
+            let taken: Vec<BlockId> = [then, otherwise]
+                .into_iter()
+                .filter(|target| analysis.edges.contains(&(block, *target)))
+                .collect();
+            if !analysis.executable.contains(&block) || taken.len() != 1 {
+                continue;
+            }
+            let target = taken[0];
+            let skipped = if target == then { otherwise } else { then };
+            if skipped != target {
+                for phi in function.block_mut(skipped).phis.iter_mut() {
+                    phi.incoming
+                        .retain(|(predecessor, _)| *predecessor != block);
+                }
+            }
+            function.block_mut(block).terminator = Terminator::Jump(target);
+            changed = true;
+        }
+
+        let blocks = function.blocks.len();
+        function.remove_unreachable_blocks();
+        return changed || blocks != function.blocks.len();
+    }
+}
+
+impl Analysis {
+    /// Iterates over the executable blocks until no value changes. Values
+    /// only ever move from unknown to constant to varying, so this ends.
+    fn solve(function: &Function) -> Analysis {
+        let mut analysis = Analysis {
+            values: HashMap::new(),
+            executable: HashSet::new(),
+            edges: HashSet::new(),
+        };
+        if function.blocks.is_empty() {
+            return analysis;
+        }
+        for param in function.params.iter() {
+            analysis.values.insert(*param, Lattice::Varying);
+        }
+        analysis.executable.insert(BlockId(0));
+        let order = function.reverse_postorder();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for block in order.iter() {
+                if analysis.executable.contains(block) {
+                    changed |= analysis.visit(function, *block);
+                }
+            }
+        }
+        return analysis;
+    }
+
+    fn visit(&mut self, function: &Function, id: BlockId) -> bool {
+        let mut changed = false;
+        let block = function.block(id);
+        for phi in block.phis.iter() {
+            let value = phi
+                .incoming
+                .iter()
+                .filter(|(predecessor, _)| self.edges.contains(&(*predecessor, id)))
+                .fold(Lattice::Unknown, |lattice, (_, value)| {
+                    return lattice.meet(&self.value(value));
+                });
+            changed |= self.set(phi.dest, value);
+        }
+        for instruction in block.instructions.iter() {
+            let Some(dest) = instruction.dest() else {
+                continue;
+            };
+            let value = self.evaluate(function, dest, instruction);
+            changed |= self.set(dest, value);
+        }
+        let targets = match &block.terminator {
+            Terminator::Branch {
+                condition,
+                then,
+                otherwise,
+            } => match self.value(condition) {
+                Lattice::Unknown => Vec::new(),
+                Lattice::Constant(Constant::Bool(true)) => vec![*then],
+                Lattice::Constant(Constant::Bool(false)) => vec![*otherwise],
+                _ => vec![*then, *otherwise],
+            },
+            terminator => terminator.successors(),
+        };
+        for target in targets {
+            changed |= self.edges.insert((id, target));
+            changed |= self.executable.insert(target);
+        }
+        return changed;
+    }
+
+    fn evaluate(&self, function: &Function, dest: Temp, instruction: &Instruction) -> Lattice {
+        let operands: Vec<Lattice> = instruction
+            .operands()
+            .into_iter()
+            .map(|value| self.value(value))
+            .collect();
+        let constants: Vec<&Constant> = operands
+            .iter()
+            .filter_map(|lattice| match lattice {
+                Lattice::Constant(constant) => Some(constant),
+                _ => None,
+            })
+            .collect();
+        let folded = match instruction {
+            Instruction::Copy { .. } => return operands[0].clone(),
+            Instruction::Binary { operator, .. } => match constants[..] {
+                [left, right] => fold_binary(*operator, left, right),
+                _ => None,
+            },
+            Instruction::Unary { operator, .. } => match constants[..] {
+                [value] => fold_unary(*operator, value),
+                _ => None,
+            },
+            Instruction::Cast { .. } => match constants[..] {
+                [value] => fold_cast(value, &function.temps[dest.0]),
+                _ => None,
+            },
+            _ => return Lattice::Varying,
+        };
+        if operands.contains(&Lattice::Varying) {
+            return Lattice::Varying;
+        }
+        if operands.contains(&Lattice::Unknown) {
+            return Lattice::Unknown;
+        }
+        return match folded {
+            Some(constant) => Lattice::Constant(constant),
+            None => Lattice::Varying,
+        };
+    }
+
+    fn value(&self, value: &Value) -> Lattice {
+        return match value {
+            Value::Constant(constant) => Lattice::Constant(normalize(constant)),
+            Value::Temp(temp) => self.values.get(temp).cloned().unwrap_or(Lattice::Unknown),
+        };
+    }
+
+    fn set(&mut self, temp: Temp, value: Lattice) -> bool {
+        let current = self.values.get(&temp).cloned().unwrap_or(Lattice::Unknown);
+        let value = current.meet(&value);
+        if value.same(&current) {
+            return false;
+        }
+        self.values.insert(temp, value);
+        return true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ast::type_specifier::TypeSpecifier,
+        ir::{BinaryOp, Phi},
+    };
+
+    use super::*;
+
+    fn int(value: i32) -> Value {
+        return Value::Constant(Constant::Int(value));
+    }
+
+    fn returned(function: &Function) -> Vec<&Value> {
+        return function
+            .blocks
+            .iter()
+            .filter_map(|block| match &block.terminator {
+                Terminator::Return(Some(value)) => Some(value),
+                _ => None,
+            })
+            .collect();
+    }
+
+    #[test]
+    fn prunes_branches_on_constant_conditions() {
+        // fn f() -> int {
+        //     let x: int = 1;
+        //     if (x == 1) { return 10; } else { return g(); }
+        // }
+        // with the join of both returns in a phi.
+        let mut function = Function::new(String::from("f"), TypeSpecifier::Int);
+        let x = function.new_temp(TypeSpecifier::Int);
+        let condition = function.new_temp(TypeSpecifier::Bool);
+        let called = function.new_temp(TypeSpecifier::Int);
+        let joined = function.new_temp(TypeSpecifier::Int);
+        let entry = function.new_block();
+        let then = function.new_block();
+        let otherwise = function.new_block();
+        let join = function.new_block();
+        function.block_mut(entry).instructions = vec![
+            Instruction::Copy {
+                dest: x,
+                value: int(1),
+            },
+            Instruction::Binary {
+                dest: condition,
+                operator: BinaryOp::Equal,
+                left: Value::Temp(x),
+                right: int(1),
+            },
+        ];
+        function.block_mut(entry).terminator = Terminator::Branch {
+            condition: Value::Temp(condition),
+            then,
+            otherwise,
+        };
+        function.block_mut(then).terminator = Terminator::Jump(join);
+        function.block_mut(otherwise).instructions = vec![Instruction::Call {
+            dest: Some(called),
+            function: String::from("g"),
+            args: Vec::new(),
+        }];
+        function.block_mut(otherwise).terminator = Terminator::Jump(join);
+        function.block_mut(join).phis = vec![Phi {
+            dest: joined,
+            incoming: vec![(then, int(10)), (otherwise, Value::Temp(called))],
+        }];
+        function.block_mut(join).terminator = Terminator::Return(Some(Value::Temp(joined)));
+
+        assert!(ConstantPropagation.run_function(&mut function));
+        assert_eq!(returned(&function), [&int(10)]);
+        assert_eq!(function.blocks.len(), 3, "{}", function);
+        for block in function.blocks.iter() {
+            assert!(!matches!(block.terminator, Terminator::Branch { .. }));
+            assert!(block
+                .instructions
+                .iter()
+                .all(|instruction| !matches!(instruction, Instruction::Call { .. })));
+        }
+    }
+
+    #[test]
+    fn finds_values_that_stay_constant_around_a_loop() {
+        // let i: int = 0;
+        // while (i < n) { i = i * 2; }
+        // return i;
+        let mut function = Function::new(String::from("f"), TypeSpecifier::Int);
+        let n = function.new_temp(TypeSpecifier::Int);
+        function.params.push(n);
+        let i = function.new_temp(TypeSpecifier::Int);
+        let condition = function.new_temp(TypeSpecifier::Bool);
+        let doubled = function.new_temp(TypeSpecifier::Int);
+        let entry = function.new_block();
+        let header = function.new_block();
+        let body = function.new_block();
+        let exit = function.new_block();
+        function.block_mut(entry).terminator = Terminator::Jump(header);
+        function.block_mut(header).phis = vec![Phi {
+            dest: i,
+            incoming: vec![(entry, int(0)), (body, Value::Temp(doubled))],
+        }];
+        function.block_mut(header).instructions = vec![Instruction::Binary {
+            dest: condition,
+            operator: BinaryOp::Less,
+            left: Value::Temp(i),
+            right: Value::Temp(n),
+        }];
+        function.block_mut(header).terminator = Terminator::Branch {
+            condition: Value::Temp(condition),
+            then: body,
+            otherwise: exit,
+        };
+        function.block_mut(body).instructions = vec![Instruction::Binary {
+            dest: doubled,
+            operator: BinaryOp::Multiply,
+            left: Value::Temp(i),
+            right: int(2),
+        }];
+        function.block_mut(body).terminator = Terminator::Jump(header);
+        function.block_mut(exit).terminator = Terminator::Return(Some(Value::Temp(i)));
+
+        assert!(ConstantPropagation.run_function(&mut function));
+        assert_eq!(returned(&function), [&int(0)]);
+        // The loop still runs as long as `n` says.
+        assert_eq!(function.blocks.len(), 4, "{}", function);
+    }
+}
@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+
+use crate::ir::{Function, Instruction, Temp, Value};
+
+use super::FunctionPass;
+
+/// Replaces every use of a copy with the copied value and every phi whose
+/// incoming values are all the same with that value.
+pub struct CopyPropagation;
+
+impl FunctionPass for CopyPropagation {
+    fn name(&self) -> &'static str {
+        return "copy-propagation";
+    }
+
+    fn run_function(&self, function: &mut Function) -> bool {
+        let mut replaced: HashMap<Temp, Value> = HashMap::new();
+        for block in function.blocks.iter() {
+            let phis = block
+                .phis
+                .iter()
+                .filter_map(|phi| Some((phi.dest, phi.trivial_value()?)));
+            let copies = block
+                .instructions
+                .iter()
+                .filter_map(|instruction| match instruction {
+                    Instruction::Copy { dest, value } => Some((*dest, value.clone())),
+                    _ => None,
+                });
+            for (dest, value) in phis.chain(copies) {
+                // Phis that only copy each other around a loop without a
+                // value from outside are left for dead code elimination.
+                if Self::resolve(&replaced, &value) != Value::Temp(dest) {
+                    replaced.insert(dest, value);
+                }
+            }
+        }
+        let changed = !replaced.is_empty();
+        function.substitute(&replaced);
+        return changed;
+    }
+}
+
+impl CopyPropagation {
+    fn resolve(replaced: &HashMap<Temp, Value>, value: &Value) -> Value {
+        let mut value = value.clone();
+        while let Value::Temp(temp) = &value {
+            match replaced.get(temp) {
+                Some(replacement) => value = replacement.clone(),
+                None => break,
+            }
+        }
+        return value;
+    }
+}
@@ -0,0 +1,294 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::ir::{Function, Instruction, Temp, Value};
+
+use super::FunctionPass;
+
+/// Removes unreachable blocks, definitions nothing observable depends on
+/// and stores to frame memory that is never read, then merges the blocks
+/// left as straight-line chains.
+pub struct DeadCodeElimination;
+
+impl FunctionPass for DeadCodeElimination {
+    fn name(&self) -> &'static str {
+        return "dce";
+    }
+
+    fn run_function(&self, function: &mut Function) -> bool {
+        let blocks = function.blocks.len();
+        function.remove_unreachable_blocks();
+        let mut changed = blocks != function.blocks.len();
+        changed |= Self::remove_write_only_allocas(function);
+        changed |= Self::remove_dead_definitions(function);
+        changed |= function.merge_blocks();
+        return changed;
+    }
+}
+
+impl DeadCodeElimination {
+    /// Marks what stores, calls and terminators need and sweeps the rest.
+    /// Phis and instructions only feeding each other are dropped as well.
+    fn remove_dead_definitions(function: &mut Function) -> bool {
+        let mut definitions: HashMap<Temp, Vec<&Value>> = HashMap::new();
+        let mut live = HashSet::new();
+        let mut worklist: Vec<&Value> = Vec::new();
+        for block in function.blocks.iter() {
+            for phi in block.phis.iter() {
+                let incoming = phi.incoming.iter().map(|(_, value)| value).collect();
+                definitions.insert(phi.dest, incoming);
+            }
+            for instruction in block.instructions.iter() {
+                match (instruction.dest(), Self::has_side_effects(instruction)) {
+                    (Some(dest), false) => {
+                        definitions.insert(dest, instruction.operands());
+                    }
+                    _ => worklist.extend(instruction.operands()),
+                }
+            }
+            worklist.extend(block.terminator.operands());
+        }
+        while let Some(value) = worklist.pop() {
+            if let Value::Temp(temp) = value {
+                if live.insert(*temp) {
+                    if let Some(operands) = definitions.get(temp) {
+                        worklist.extend(operands.iter().copied());
+                    }
+                }
+            }
+        }
+
+        let mut changed = false;
+        for block in function.blocks.iter_mut() {
+            let before = block.phis.len() + block.instructions.len();
+            block.phis.retain(|phi| live.contains(&phi.dest));
+            block.instructions.retain(|instruction| {
+                return match instruction.dest() {
+                    Some(dest) if !Self::has_side_effects(instruction) => live.contains(&dest),
+                    _ => true,
+                };
+            });
+            changed |= before != block.phis.len() + block.instructions.len();
+        }
+        return changed;
+    }
+
+    fn has_side_effects(instruction: &Instruction) -> bool {
+        return matches!(
+            instruction,
+            Instruction::Store { .. } | Instruction::Call { .. }
+        );
+    }
+
+    /// Allocas whose address, and the field and element addresses derived
+    /// from it, are only ever stored to. Those stores cannot be observed.
+    fn remove_write_only_allocas(function: &mut Function) -> bool {
+        let mut base: HashMap<Temp, Temp> = HashMap::new();
+        for block in function.blocks.iter() {
+            for instruction in block.instructions.iter() {
+                if let Instruction::Alloca { dest } = instruction {
+                    base.insert(*dest, *dest);
+                }
+            }
+        }
+        // Derived addresses come after their base in a block order that
+        // follows dominance, so one pass in reverse postorder finds them all.
+        for id in function.reverse_postorder() {
+            for instruction in function.block(id).instructions.iter() {
+                let (dest, address) = match instruction {
+                    Instruction::Field { dest, address, .. }
+                    | Instruction::Element { dest, address, .. }
+                    | Instruction::Offset {
+                        dest,
+                        pointer: address,
+                        ..
+                    }
+                    | Instruction::Copy {
+                        dest,
+                        value: address,
+                    } => (dest, address),
+                    _ => continue,
+                };
+                if let Value::Temp(address) = address {
+                    if let Some(alloca) = base.get(address).copied() {
+                        base.insert(*dest, alloca);
+                    }
+                }
+            }
+        }
+
+        let mut read: HashSet<Temp> = HashSet::new();
+        for block in function.blocks.iter() {
+            let phi_uses = block
+                .phis
+                .iter()
+                .flat_map(|phi| phi.incoming.iter().map(|(_, value)| value));
+            let mut uses: Vec<&Value> = phi_uses.chain(block.terminator.operands()).collect();
+            for instruction in block.instructions.iter() {
+                match instruction {
+                    Instruction::Store { value, .. } => uses.push(value),
+                    Instruction::Field { .. }
+                    | Instruction::Element { .. }
+                    | Instruction::Copy { .. } => {}
+                    Instruction::Offset { index, .. } => uses.push(index),
+                    instruction => uses.extend(instruction.operands()),
+                }
+            }
+            for value in uses {
+                if let Value::Temp(temp) = value {
+                    if let Some(alloca) = base.get(temp) {
+                        read.insert(*alloca);
+                    }
+                }
+            }
+        }
+
+        let dead: HashSet<Temp> = base
+            .iter()
+            .filter(|(_, alloca)| !read.contains(alloca))
+            .map(|(temp, _)| *temp)
+            .collect();
+        if dead.is_empty() {
+            return false;
+        }
+        for block in function.blocks.iter_mut() {
+            block.instructions.retain(|instruction| {
+                return match instruction {
+                    Instruction::Store {
+                        address: Value::Temp(address),
+                        ..
+                    } => !dead.contains(address),
+                    instruction => {
+                        !matches!(instruction.dest(), Some(dest) if dead.contains(&dest))
+                    }
+                };
+            });
+        }
+        return true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ast::type_specifier::TypeSpecifier,
+        ir::{Constant, Terminator, UnaryOp},
+    };
+
+    use super::*;
+
+    fn int(value: i32) -> Value {
+        return Value::Constant(Constant::Int(value));
+    }
+
+    fn pointer(type_specifier: TypeSpecifier) -> TypeSpecifier {
+        return TypeSpecifier::Pointer(Box::new(type_specifier));
+    }
+
+    fn defines(function: &Function, temp: Temp) -> bool {
+        return function.blocks.iter().any(|block| {
+            return block
+                .instructions
+                .iter()
+                .any(|instruction| instruction.dest() == Some(temp));
+        });
+    }
+
+    fn stores(function: &Function) -> Vec<&Value> {
+        return function
+            .blocks
+            .iter()
+            .flat_map(|block| block.instructions.iter())
+            .filter_map(|instruction| match instruction {
+                Instruction::Store { value, .. } => Some(value),
+                _ => None,
+            })
+            .collect();
+    }
+
+    #[test]
+    fn removes_allocas_that_are_only_written() {
+        let array = TypeSpecifier::array(TypeSpecifier::Int, 2);
+        let mut function = Function::new(String::from("f"), TypeSpecifier::Int);
+        let scalar = function.new_temp(pointer(TypeSpecifier::Int));
+        let items = function.new_temp(pointer(array));
+        let item = function.new_temp(pointer(TypeSpecifier::Int));
+        let read = function.new_temp(pointer(TypeSpecifier::Int));
+        let value = function.new_temp(TypeSpecifier::Int);
+        let escaping = function.new_temp(pointer(TypeSpecifier::Int));
+        let entry = function.new_block();
+        function.block_mut(entry).instructions = vec![
+            Instruction::Alloca { dest: scalar },
+            Instruction::Alloca { dest: items },
+            Instruction::Alloca { dest: read },
+            Instruction::Alloca { dest: escaping },
+            Instruction::Store {
+                address: Value::Temp(scalar),
+                value: int(1),
+            },
+            Instruction::Element {
+                dest: item,
+                address: Value::Temp(items),
+                index: Value::Constant(Constant::Usize(1)),
+            },
+            Instruction::Store {
+                address: Value::Temp(item),
+                value: int(2),
+            },
+            Instruction::Store {
+                address: Value::Temp(read),
+                value: int(3),
+            },
+            Instruction::Load {
+                dest: value,
+                address: Value::Temp(read),
+            },
+            Instruction::Store {
+                address: Value::Temp(escaping),
+                value: int(4),
+            },
+            Instruction::Call {
+                dest: None,
+                function: String::from("g"),
+                args: vec![Value::Temp(escaping)],
+            },
+        ];
+        function.block_mut(entry).terminator = Terminator::Return(Some(Value::Temp(value)));
+
+        assert!(DeadCodeElimination.run_function(&mut function));
+        assert!(!defines(&function, scalar), "{}", function);
+        assert!(!defines(&function, items), "{}", function);
+        assert!(!defines(&function, item), "{}", function);
+        // The stores through the read and the escaping address remain.
+        assert!(defines(&function, read), "{}", function);
+        assert!(defines(&function, escaping), "{}", function);
+        assert_eq!(stores(&function), [&int(3), &int(4)]);
+    }
+
+    #[test]
+    fn removes_definitions_nothing_uses() {
+        let mut function = Function::new(String::from("f"), TypeSpecifier::Void);
+        let param = function.new_temp(TypeSpecifier::Int);
+        function.params.push(param);
+        let unused = function.new_temp(TypeSpecifier::Int);
+        let result = function.new_temp(TypeSpecifier::Int);
+        let entry = function.new_block();
+        function.block_mut(entry).instructions = vec![
+            Instruction::Unary {
+                dest: unused,
+                operator: UnaryOp::Negate,
+                value: Value::Temp(param),
+            },
+            Instruction::Call {
+                dest: Some(result),
+                function: String::from("g"),
+                args: Vec::new(),
+            },
+        ];
+        function.block_mut(entry).terminator = Terminator::Return(None);
+
+        assert!(DeadCodeElimination.run_function(&mut function));
+        assert!(!defines(&function, unused));
+        // A call may have effects even when its result is unused.
+        assert!(defines(&function, result));
+    }
+}
@@ -0,0 +1,374 @@
+use std::collections::HashMap;
+
+use crate::ir::{Block, BlockId, Function, Instruction, Module, Phi, Temp, Terminator, Value};
+
+use super::Pass;
+
+/// Replaces calls of small functions that do not call themselves with a
+/// copy of their body. The callee's parameters become copies of the
+/// arguments and its returns jump to the rest of the calling block, joined
+/// by a phi when there is more than one.
+pub struct Inlining {
+    /// The largest callee, counted in phis and instructions.
+    pub max_size: usize,
+}
+
+impl Default for Inlining {
+    fn default() -> Self {
+        return Self { max_size: 32 };
+    }
+}
+
+impl Pass for Inlining {
+    fn name(&self) -> &'static str {
+        return "inline";
+    }
+
+    fn run(&self, module: &mut Module) -> bool {
+        let candidates: HashMap<String, Function> = module
+            .functions
+            .iter()
+            .filter(|function| self.is_candidate(function))
+            .map(|function| (function.name.clone(), function.clone()))
+            .collect();
+        let mut changed = false;
+        for function in module.functions.iter_mut() {
+            changed |= Self::inline_calls(function, &candidates);
+        }
+        return changed;
+    }
+}
+
+impl Inlining {
+    fn is_candidate(&self, function: &Function) -> bool {
+        let size: usize = function
+            .blocks
+            .iter()
+            .map(|block| block.phis.len() + block.instructions.len())
+            .sum();
+        let is_recursive = function.blocks.iter().any(|block| {
+            return block
+                .instructions
+                .iter()
+                .any(|instruction| match instruction {
+                    Instruction::Call {
+                        function: callee, ..
+                    } => *callee == function.name,
+                    _ => false,
+                });
+        });
+        return size <= self.max_size && !is_recursive;
+    }
+
+    /// Inlines the calls of the original blocks and of the code after them,
+    /// but not the calls inside inlined bodies, so mutual recursion ends.
+    fn inline_calls(function: &mut Function, candidates: &HashMap<String, Function>) -> bool {
+        let mut changed = false;
+        let mut worklist: Vec<BlockId> = (0..function.blocks.len()).rev().map(BlockId).collect();
+        while let Some(block) = worklist.pop() {
+            let call = function
+                .block(block)
+                .instructions
+                .iter()
+                .enumerate()
+                .find_map(|(index, instruction)| match instruction {
+                    Instruction::Call {
+                        function: callee, ..
+                    } if *callee != function.name => Some((index, candidates.get(callee)?)),
+                    _ => None,
+                });
+            if let Some((index, callee)) = call {
+                let rest = Self::inline(function, block, index, callee);
+                worklist.push(rest);
+                changed = true;
+            }
+        }
+        if changed {
+            function.remove_unreachable_blocks();
+        }
+        return changed;
+    }
+
+    /// Splits `block` at the call at `index`, copies `callee` in between
+    /// and returns the block with the code after the call.
+    fn inline(function: &mut Function, block: BlockId, index: usize, callee: &Function) -> BlockId {
+        let rest = function.new_block();
+        let Instruction::Call { dest, args, .. } =
+            function.block_mut(block).instructions.remove(index)
+        else {
+            unreachable!("inlined instruction is a call");
+        };
+        let after = function.block_mut(block).instructions.split_off(index);
+        let terminator = std::mem::replace(
+            &mut function.block_mut(block).terminator,
+            Terminator::Unreachable,
+        );
+        for successor in terminator.successors() {
+            for phi in function.block_mut(successor).phis.iter_mut() {
+                for (predecessor, _) in phi.incoming.iter_mut() {
+                    if *predecessor == block {
+                        *predecessor = rest;
+                    }
+                }
+            }
+        }
+        function.block_mut(rest).instructions = after;
+        function.block_mut(rest).terminator = terminator;
+
+        let temps = function.temps.len();
+        function.temps.extend(callee.temps.iter().cloned());
+        let temp = |temp: Temp| Temp(temp.0 + temps);
+        let value = |value: &Value| match value {
+            Value::Temp(old) => Value::Temp(temp(*old)),
+            Value::Constant(_) => value.clone(),
+        };
+        let blocks = function.blocks.len();
+        let target = |id: BlockId| BlockId(id.0 + blocks);
+
+        for (param, arg) in callee.params.iter().zip(args) {
+            function
+                .block_mut(block)
+                .instructions
+                .push(Instruction::Copy {
+                    dest: temp(*param),
+                    value: arg,
+                });
+        }
+        function.block_mut(block).terminator = Terminator::Jump(target(BlockId(0)));
+
+        let mut allocas = Vec::new();
+        let mut returns = Vec::new();
+        for (id, callee_block) in callee.blocks.iter().enumerate() {
+            let phis = callee_block
+                .phis
+                .iter()
+                .map(|phi| Phi {
+                    dest: temp(phi.dest),
+                    incoming: phi
+                        .incoming
+                        .iter()
+                        .map(|(predecessor, incoming)| (target(*predecessor), value(incoming)))
+                        .collect(),
+                })
+                .collect();
+            let mut instructions = Vec::new();
+            for instruction in callee_block.instructions.iter() {
+                let mut instruction = Self::rename(instruction, temp);
+                for operand in instruction.operands_mut() {
+                    *operand = value(operand);
+                }
+                match instruction {
+                    Instruction::Alloca { .. } => allocas.push(instruction),
+                    instruction => instructions.push(instruction),
+                }
+            }
+            let terminator = match &callee_block.terminator {
+                Terminator::Jump(to) => Terminator::Jump(target(*to)),
+                Terminator::Branch {
+                    condition,
+                    then,
+                    otherwise,
+                } => Terminator::Branch {
+                    condition: value(condition),
+                    then: target(*then),
+                    otherwise: target(*otherwise),
+                },
+                Terminator::Return(returned) => {
+                    if let Some(returned) = returned {
+                        returns.push((target(BlockId(id)), value(returned)));
+                    }
+                    Terminator::Jump(rest)
+                }
+                Terminator::Unreachable => Terminator::Unreachable,
+            };
+            function.blocks.push(Block {
+                phis,
+                instructions,
+                terminator,
+            });
+        }
+
+        // Frame memory is reserved once on entry, not every time the
+        // inlined body runs.
+        function.blocks[0].instructions.splice(0..0, allocas);
+        if let Some(dest) = dest {
+            match returns.len() {
+                1 => {
+                    let (_, value) = returns.remove(0);
+                    let copy = Instruction::Copy { dest, value };
+                    function.block_mut(rest).instructions.insert(0, copy);
+                }
+                _ => function.block_mut(rest).phis.push(Phi {
+                    dest,
+                    incoming: returns,
+                }),
+            }
+        }
+        return rest;
+    }
+
+    fn rename(instruction: &Instruction, temp: impl Fn(Temp) -> Temp) -> Instruction {
+        let mut instruction = instruction.clone();
+        match &mut instruction {
+            Instruction::Copy { dest, .. }
+            | Instruction::Binary { dest, .. }
+            | Instruction::Unary { dest, .. }
+            | Instruction::Cast { dest, .. }
+            | Instruction::Alloca { dest }
+            | Instruction::Load { dest, .. }
+            | Instruction::Field { dest, .. }
+            | Instruction::Element { dest, .. }
+            | Instruction::Offset { dest, .. }
+            | Instruction::Call {
+                dest: Some(dest), ..
+            } => *dest = temp(*dest),
+            Instruction::Call { dest: None, .. } | Instruction::Store { .. } => {}
+        }
+        return instruction;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ast::type_specifier::TypeSpecifier,
+        ir::{
+            opt::{constant_propagation::ConstantPropagation, FunctionPass},
+            BinaryOp, Constant, UnaryOp,
+        },
+    };
+
+    use super::*;
+
+    fn int(value: i32) -> Value {
+        return Value::Constant(Constant::Int(value));
+    }
+
+    /// fn abs(x: int) -> int {
+    ///     if (x < 0) { return -x; }
+    ///     return x;
+    /// }
+    fn abs() -> Function {
+        let mut function = Function::new(String::from("abs"), TypeSpecifier::Int);
+        let x = function.new_temp(TypeSpecifier::Int);
+        function.params.push(x);
+        let negative = function.new_temp(TypeSpecifier::Bool);
+        let negated = function.new_temp(TypeSpecifier::Int);
+        let entry = function.new_block();
+        let then = function.new_block();
+        let otherwise = function.new_block();
+        function.block_mut(entry).instructions = vec![Instruction::Binary {
+            dest: negative,
+            operator: BinaryOp::Less,
+            left: Value::Temp(x),
+            right: int(0),
+        }];
+        function.block_mut(entry).terminator = Terminator::Branch {
+            condition: Value::Temp(negative),
+            then,
+            otherwise,
+        };
+        function.block_mut(then).instructions = vec![Instruction::Unary {
+            dest: negated,
+            operator: UnaryOp::Negate,
+            value: Value::Temp(x),
+        }];
+        function.block_mut(then).terminator = Terminator::Return(Some(Value::Temp(negated)));
+        function.block_mut(otherwise).terminator = Terminator::Return(Some(Value::Temp(x)));
+        return function;
+    }
+
+    /// fn main() -> int {
+    ///     return abs(-5) + 1;
+    /// }
+    fn main() -> Function {
+        let mut function = Function::new(String::from("main"), TypeSpecifier::Int);
+        let called = function.new_temp(TypeSpecifier::Int);
+        let sum = function.new_temp(TypeSpecifier::Int);
+        let entry = function.new_block();
+        function.block_mut(entry).instructions = vec![
+            Instruction::Call {
+                dest: Some(called),
+                function: String::from("abs"),
+                args: vec![int(-5)],
+            },
+            Instruction::Binary {
+                dest: sum,
+                operator: BinaryOp::Add,
+                left: Value::Temp(called),
+                right: int(1),
+            },
+        ];
+        function.block_mut(entry).terminator = Terminator::Return(Some(Value::Temp(sum)));
+        return function;
+    }
+
+    #[test]
+    fn joins_multiple_returns_in_a_phi() {
+        let mut module = Module {
+            structs: Vec::new(),
+            functions: vec![abs(), main()],
+        };
+        assert!(Inlining::default().run(&mut module));
+        let main = &mut module.functions[1];
+
+        let calls = main
+            .blocks
+            .iter()
+            .flat_map(|block| block.instructions.iter())
+            .filter(|instruction| matches!(instruction, Instruction::Call { .. }));
+        assert_eq!(calls.count(), 0, "{}", main);
+        // The result of the call is a phi of both returns, in the block
+        // holding the code after the call.
+        let predecessors = main.predecessors();
+        let (rest, block) = main
+            .blocks
+            .iter()
+            .enumerate()
+            .find(|(_, block)| !block.phis.is_empty())
+            .unwrap_or_else(|| panic!("no phi in\n{}", main));
+        let phi = &block.phis[0];
+        assert_eq!(phi.dest, Temp(0));
+        assert_eq!(phi.incoming.len(), 2, "{}", main);
+        let mut incoming: Vec<BlockId> = phi.incoming.iter().map(|(block, _)| *block).collect();
+        incoming.sort();
+        assert_eq!(incoming, predecessors[rest]);
+        assert!(matches!(
+            block.instructions[..],
+            [Instruction::Binary {
+                dest: Temp(1),
+                left: Value::Temp(Temp(0)),
+                ..
+            }]
+        ));
+
+        // Only the negating return is taken for -5.
+        ConstantPropagation.run_function(main);
+        let returned: Vec<&Terminator> = main
+            .blocks
+            .iter()
+            .map(|block| &block.terminator)
+            .filter(|terminator| matches!(terminator, Terminator::Return(_)))
+            .collect();
+        assert_eq!(returned, [&Terminator::Return(Some(int(6)))], "{}", main);
+    }
+
+    #[test]
+    fn leaves_recursive_functions() {
+        let mut recursive = abs();
+        recursive.name = String::from("main");
+        let mut module = Module {
+            structs: Vec::new(),
+            functions: vec![main(), recursive],
+        };
+        module.functions[0].name = String::from("abs");
+        module.functions[0].blocks[0].instructions[0] = Instruction::Call {
+            dest: Some(Temp(0)),
+            function: String::from("abs"),
+            args: vec![int(-5)],
+        };
+        let before = module.clone();
+        assert!(!Inlining::default().run(&mut module));
+        assert_eq!(module, before);
+    }
+}
@@ -0,0 +1,220 @@
+pub mod common_subexpression;
+pub mod constant_folding;
+pub mod constant_propagation;
+pub mod copy_propagation;
+pub mod dead_code;
+pub mod inlining;
+
+use std::collections::HashMap;
+
+use crate::error::{CompilerError, Result};
+
+use self::{
+    common_subexpression::CommonSubexpressionElimination, constant_folding::ConstantFolding,
+    constant_propagation::ConstantPropagation, copy_propagation::CopyPropagation,
+    dead_code::DeadCodeElimination, inlining::Inlining,
+};
+
+use super::{Constant, Function, Module, Phi, Temp, Value};
+
+/// A transformation of a module in SSA form that keeps its behavior.
+pub trait Pass {
+    /// The name the pass is enabled and disabled by.
+    fn name(&self) -> &'static str;
+
+    /// Runs the pass once and returns whether anything changed.
+    fn run(&self, module: &mut Module) -> bool;
+}
+
+/// A pass that looks at one function at a time.
+pub trait FunctionPass {
+    fn name(&self) -> &'static str;
+
+    fn run_function(&self, function: &mut Function) -> bool;
+}
+
+impl<T: FunctionPass> Pass for T {
+    fn name(&self) -> &'static str {
+        return FunctionPass::name(self);
+    }
+
+    fn run(&self, module: &mut Module) -> bool {
+        let mut changed = false;
+        for function in module.functions.iter_mut() {
+            changed |= self.run_function(function);
+        }
+        return changed;
+    }
+}
+
+/// Runs a sequence of passes until none of them changes the module.
+pub struct PassManager {
+    passes: Vec<Box<dyn Pass>>,
+    max_rounds: usize,
+}
+
+impl Default for PassManager {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+impl PassManager {
+    /// The names of all passes in the order of the standard pipeline.
+    pub const PASSES: [&'static str; 6] = [
+        "inline",
+        "copy-propagation",
+        "constant-propagation",
+        "constant-folding",
+        "cse",
+        "dce",
+    ];
+
+    /// A pass manager without passes, which leaves modules unchanged.
+    pub fn new() -> Self {
+        return Self {
+            passes: Vec::new(),
+            max_rounds: 8,
+        };
+    }
+
+    /// Every pass in the order of [`PassManager::PASSES`].
+    pub fn standard() -> Self {
+        return Self::with_passes(&Self::PASSES).expect("standard passes exist");
+    }
+
+    /// The named passes, run in the given order.
+    pub fn with_passes(names: &[&str]) -> Result<Self> {
+        let mut manager = Self::new();
+        for name in names.iter() {
+            manager.passes.push(Self::pass(name)?);
+        }
+        return Ok(manager);
+    }
+
+    fn pass(name: &str) -> Result<Box<dyn Pass>> {
+        return Ok(match name {
+            "inline" => Box::new(Inlining::default()),
+            "copy-propagation" => Box::new(CopyPropagation),
+            "constant-propagation" => Box::new(ConstantPropagation),
+            "constant-folding" => Box::new(ConstantFolding),
+            "cse" => Box::new(CommonSubexpressionElimination),
+            "dce" => Box::new(DeadCodeElimination),
+            _ => {
+                return Err(CompilerError::CodeGen(format!(
+                    "Unknown optimization pass {}, expected one of {}",
+                    name,
+                    Self::PASSES.join(", ")
+                )))
+            }
+        });
+    }
+
+    pub fn add(&mut self, pass: Box<dyn Pass>) {
+        self.passes.push(pass);
+    }
+
+    /// Removes the named pass from the pipeline.
+    pub fn disable(&mut self, name: &str) -> Result<()> {
+        Self::pass(name)?;
+        self.passes.retain(|pass| pass.name() != name);
+        return Ok(());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.passes.is_empty();
+    }
+
+    /// Runs the pipeline until it reaches a fixed point or the round limit.
+    pub fn run(&self, module: &mut Module) {
+        for _ in 0..self.max_rounds {
+            let mut changed = false;
+            for pass in self.passes.iter() {
+                changed |= pass.run(module);
+            }
+            if !changed {
+                return;
+            }
+        }
+    }
+}
+
+impl Constant {
+    /// Whether both constants are the same value, comparing floats by their
+    /// bits so `0.0` and `-0.0` differ and NaN equals itself.
+    pub fn same(&self, other: &Constant) -> bool {
+        return match (self, other) {
+            (Constant::Float(left), Constant::Float(right)) => left.to_bits() == right.to_bits(),
+            (Constant::Double(left), Constant::Double(right)) => left.to_bits() == right.to_bits(),
+            (left, right) => left == right,
+        };
+    }
+}
+
+impl Value {
+    pub fn same(&self, other: &Value) -> bool {
+        return match (self, other) {
+            (Value::Temp(left), Value::Temp(right)) => left == right,
+            (Value::Constant(left), Value::Constant(right)) => left.same(right),
+            _ => false,
+        };
+    }
+}
+
+impl Phi {
+    /// The value of a phi whose incoming values are all the same, ignoring
+    /// the phi itself flowing around a loop.
+    pub fn trivial_value(&self) -> Option<Value> {
+        let mut values = self
+            .incoming
+            .iter()
+            .map(|(_, value)| value)
+            .filter(|value| **value != Value::Temp(self.dest));
+        let first = values.next()?;
+        return match values.all(|value| value.same(first)) {
+            true => Some(first.clone()),
+            false => None,
+        };
+    }
+}
+
+impl Function {
+    /// How often every temporary is used by phis, instructions and terminators.
+    pub fn use_counts(&self) -> HashMap<Temp, usize> {
+        let mut counts = HashMap::new();
+        for block in self.blocks.iter() {
+            let uses = block
+                .phis
+                .iter()
+                .flat_map(|phi| phi.incoming.iter().map(|(_, value)| value))
+                .chain(
+                    block
+                        .instructions
+                        .iter()
+                        .flat_map(|instruction| instruction.operands()),
+                )
+                .chain(block.terminator.operands());
+            for value in uses {
+                if let Value::Temp(temp) = value {
+                    *counts.entry(*temp).or_insert(0) += 1;
+                }
+            }
+        }
+        return counts;
+    }
+
+    /// Replaces the temporaries in `replaced` everywhere and drops the
+    /// phis and instructions that defined them.
+    pub(crate) fn substitute(&mut self, replaced: &HashMap<Temp, Value>) {
+        if replaced.is_empty() {
+            return;
+        }
+        for block in self.blocks.iter_mut() {
+            block.phis.retain(|phi| !replaced.contains_key(&phi.dest));
+            block.instructions.retain(|instruction| {
+                return !matches!(instruction.dest(), Some(dest) if replaced.contains_key(&dest));
+            });
+        }
+        self.replace_values(replaced);
+    }
+}
@@ -0,0 +1,582 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::ast::type_specifier::TypeSpecifier;
+
+use super::{BlockId, Constant, Function, Instruction, Phi, Temp, Value};
+
+/// Renaming state: the current value of every promoted variable.
+struct Renamer {
+    variables: HashMap<Temp, TypeSpecifier>,
+    phis: HashMap<Temp, Temp>,
+    stacks: HashMap<Temp, Vec<Value>>,
+    replaced: HashMap<Temp, Value>,
+    children: Vec<Vec<BlockId>>,
+}
+
+impl Function {
+    /// Converts the function to SSA form: allocas only ever loaded and
+    /// stored are replaced by temporaries, with phi nodes placed on the
+    /// dominance frontiers of their stores. A variable read on a path that
+    /// never stored it reads the zero value of its type.
+    pub fn construct_ssa(&mut self) {
+        self.remove_unreachable_blocks();
+        let variables = self.promotable_allocas();
+        if variables.is_empty() {
+            return;
+        }
+        let dominators = self.dominators();
+        let frontiers = self.dominance_frontiers(&dominators);
+        let phis = self.insert_phis(&variables, &frontiers);
+
+        let mut renamer = Renamer {
+            stacks: variables
+                .keys()
+                .map(|variable| (*variable, Vec::new()))
+                .collect(),
+            variables,
+            phis,
+            replaced: HashMap::new(),
+            children: Function::dominator_tree(&dominators),
+        };
+        self.rename(&mut renamer, BlockId(0));
+
+        let Renamer {
+            variables,
+            replaced,
+            ..
+        } = renamer;
+        for block in self.blocks.iter_mut() {
+            block.instructions.retain(|instruction| match instruction {
+                Instruction::Alloca { dest } => !variables.contains_key(dest),
+                Instruction::Load {
+                    address: Value::Temp(address),
+                    ..
+                }
+                | Instruction::Store {
+                    address: Value::Temp(address),
+                    ..
+                } => !variables.contains_key(address),
+                _ => true,
+            });
+        }
+        self.replace_values(&replaced);
+        self.remove_dead_phis();
+    }
+
+    /// Allocas whose address is only used to load and store whole values.
+    fn promotable_allocas(&self) -> HashMap<Temp, TypeSpecifier> {
+        let mut variables = HashMap::new();
+        for block in self.blocks.iter() {
+            for instruction in block.instructions.iter() {
+                if let Instruction::Alloca { dest } = instruction {
+                    if let TypeSpecifier::Pointer(pointee) = &self.temps[dest.0] {
+                        variables.insert(*dest, *pointee.clone());
+                    }
+                }
+            }
+        }
+        for block in self.blocks.iter() {
+            for instruction in block.instructions.iter() {
+                let escaping = match instruction {
+                    Instruction::Load { .. } => Vec::new(),
+                    Instruction::Store { value, .. } => vec![value],
+                    instruction => instruction.operands(),
+                };
+                for value in escaping {
+                    if let Value::Temp(temp) = value {
+                        variables.remove(temp);
+                    }
+                }
+            }
+            for value in block.terminator.operands() {
+                if let Value::Temp(temp) = value {
+                    variables.remove(temp);
+                }
+            }
+            for phi in block.phis.iter() {
+                for (_, value) in phi.incoming.iter() {
+                    if let Value::Temp(temp) = value {
+                        variables.remove(temp);
+                    }
+                }
+            }
+        }
+        return variables;
+    }
+
+    /// Places empty phis on the iterated dominance frontier of the blocks
+    /// storing each variable and returns the variable of every phi.
+    fn insert_phis(
+        &mut self,
+        variables: &HashMap<Temp, TypeSpecifier>,
+        frontiers: &[Vec<BlockId>],
+    ) -> HashMap<Temp, Temp> {
+        let mut phis = HashMap::new();
+        let mut ordered: Vec<&Temp> = variables.keys().collect();
+        ordered.sort();
+        for variable in ordered {
+            let mut definitions: Vec<BlockId> = self
+                .blocks
+                .iter()
+                .enumerate()
+                .filter(|(_, block)| {
+                    block.instructions.iter().any(|instruction| {
+                        matches!(instruction, Instruction::Store {
+                            address: Value::Temp(address),
+                            ..
+                        } if address == variable)
+                    })
+                })
+                .map(|(id, _)| BlockId(id))
+                .collect();
+            let mut has_phi = HashSet::new();
+            let mut defined: HashSet<BlockId> = definitions.iter().copied().collect();
+            while let Some(block) = definitions.pop() {
+                for frontier in frontiers[block.0].iter() {
+                    if !has_phi.insert(*frontier) {
+                        continue;
+                    }
+                    let dest = self.new_temp(variables[variable].clone());
+                    self.block_mut(*frontier).phis.push(Phi {
+                        dest,
+                        incoming: Vec::new(),
+                    });
+                    phis.insert(dest, *variable);
+                    if defined.insert(*frontier) {
+                        definitions.push(*frontier);
+                    }
+                }
+            }
+        }
+        return phis;
+    }
+
+    /// Walks the dominator tree, replacing loads with the reaching store.
+    fn rename(&mut self, renamer: &mut Renamer, block: BlockId) {
+        let mut pushed = Vec::new();
+        for phi in self.block(block).phis.iter() {
+            if let Some(variable) = renamer.phis.get(&phi.dest) {
+                renamer
+                    .stacks
+                    .entry(*variable)
+                    .or_default()
+                    .push(Value::Temp(phi.dest));
+                pushed.push(*variable);
+            }
+        }
+        for instruction in self.block(block).instructions.iter() {
+            match instruction {
+                Instruction::Load {
+                    dest,
+                    address: Value::Temp(address),
+                } if renamer.variables.contains_key(address) => {
+                    let value = renamer.current(address);
+                    renamer.replaced.insert(*dest, value);
+                }
+                Instruction::Store {
+                    address: Value::Temp(address),
+                    value,
+                } if renamer.variables.contains_key(address) => {
+                    let value = renamer.resolve(value);
+                    renamer.stacks.entry(*address).or_default().push(value);
+                    pushed.push(*address);
+                }
+                _ => {}
+            }
+        }
+        for successor in self.block(block).terminator.successors() {
+            let incoming: Vec<(usize, Value)> = self
+                .block(successor)
+                .phis
+                .iter()
+                .enumerate()
+                .filter_map(|(index, phi)| {
+                    let variable = renamer.phis.get(&phi.dest)?;
+                    return Some((index, renamer.current(variable)));
+                })
+                .collect();
+            for (index, value) in incoming {
+                self.block_mut(successor).phis[index]
+                    .incoming
+                    .push((block, value));
+            }
+        }
+        for child in renamer.children[block.0].clone() {
+            self.rename(renamer, child);
+        }
+        for variable in pushed {
+            if let Some(stack) = renamer.stacks.get_mut(&variable) {
+                stack.pop();
+            }
+        }
+    }
+
+    /// Rewrites every use of the temporaries in `replaced`.
+    pub(crate) fn replace_values(&mut self, replaced: &HashMap<Temp, Value>) {
+        if replaced.is_empty() {
+            return;
+        }
+        let resolve = |value: &mut Value| {
+            while let Value::Temp(temp) = value {
+                match replaced.get(temp) {
+                    Some(replacement) => *value = replacement.clone(),
+                    None => break,
+                }
+            }
+        };
+        for block in self.blocks.iter_mut() {
+            for phi in block.phis.iter_mut() {
+                for (_, value) in phi.incoming.iter_mut() {
+                    resolve(value);
+                }
+            }
+            for instruction in block.instructions.iter_mut() {
+                for value in instruction.operands_mut() {
+                    resolve(value);
+                }
+            }
+            for value in block.terminator.operands_mut() {
+                resolve(value);
+            }
+        }
+    }
+
+    /// Removes the phis no instruction needs, also when they only feed
+    /// each other around a loop.
+    pub(crate) fn remove_dead_phis(&mut self) {
+        let mut definitions = HashMap::new();
+        for block in self.blocks.iter() {
+            for phi in block.phis.iter() {
+                definitions.insert(phi.dest, phi);
+            }
+        }
+        let mut live = HashSet::new();
+        let mut worklist = Vec::new();
+        for block in self.blocks.iter() {
+            let uses = block
+                .instructions
+                .iter()
+                .flat_map(|instruction| instruction.operands())
+                .chain(block.terminator.operands());
+            for value in uses {
+                if let Value::Temp(temp) = value {
+                    if definitions.contains_key(temp) && live.insert(*temp) {
+                        worklist.push(*temp);
+                    }
+                }
+            }
+        }
+        while let Some(temp) = worklist.pop() {
+            for (_, value) in definitions[&temp].incoming.iter() {
+                if let Value::Temp(temp) = value {
+                    if definitions.contains_key(temp) && live.insert(*temp) {
+                        worklist.push(*temp);
+                    }
+                }
+            }
+        }
+        for block in self.blocks.iter_mut() {
+            block.phis.retain(|phi| live.contains(&phi.dest));
+        }
+    }
+}
+
+impl Renamer {
+    fn current(&self, variable: &Temp) -> Value {
+        return match self.stacks.get(variable).and_then(|stack| stack.last()) {
+            Some(value) => value.clone(),
+            None => Value::Constant(Constant::Zero(self.variables[variable].clone())),
+        };
+    }
+
+    fn resolve(&self, value: &Value) -> Value {
+        let mut value = value.clone();
+        while let Value::Temp(temp) = &value {
+            match self.replaced.get(temp) {
+                Some(replacement) => value = replacement.clone(),
+                None => break,
+            }
+        }
+        return value;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ast::program::ProgramRef,
+        ir::{lower::IrBuilder, BinaryOp, Module, Terminator},
+        lexer::Lexer,
+        parser::Parser,
+        semantic::SemanticAnalyzer,
+        symbol_table::{SymbolTable, SymbolTableRef},
+    };
+
+    use super::*;
+
+    fn lower_module(source: &str) -> Module {
+        let symbol_table: SymbolTableRef = SymbolTable::new().into();
+        let lexer = Lexer::new(
+            source.to_owned(),
+            String::from("test.mb"),
+            symbol_table.clone(),
+        );
+        let program: ProgramRef = Parser::new(lexer, symbol_table.clone())
+            .and_then(|mut parser| parser.parse())
+            .unwrap_or_else(|err| panic!("{}", err))
+            .into();
+        SemanticAnalyzer::new(symbol_table, program.clone())
+            .analyze()
+            .unwrap_or_else(|err| panic!("{}", err));
+        let module = IrBuilder::new(&program.borrow())
+            .generate()
+            .unwrap_or_else(|err| panic!("{}", err));
+        for function in module.functions.iter() {
+            assert_ssa(function);
+        }
+        return module;
+    }
+
+    /// Lowers `source` next to a `main` and returns its function `f`.
+    fn lower(source: &str) -> Function {
+        let source = format!("{}\nfn main() -> int {{\n    return 0;\n}}\n", source);
+        return lower_module(&source)
+            .functions
+            .into_iter()
+            .find(|function| function.name == "f")
+            .unwrap();
+    }
+
+    /// Every temporary has a single definition and every phi one value for
+    /// each predecessor of its block.
+    fn assert_ssa(function: &Function) {
+        let mut defined = HashSet::new();
+        let phis = function.blocks.iter().flat_map(|block| block.phis.iter());
+        let dests = function
+            .params
+            .iter()
+            .copied()
+            .chain(phis.map(|phi| phi.dest))
+            .chain(
+                function
+                    .blocks
+                    .iter()
+                    .flat_map(|block| block.instructions.iter())
+                    .filter_map(|instruction| instruction.dest()),
+            );
+        for dest in dests {
+            assert!(
+                defined.insert(dest),
+                "{} is defined twice in\n{}",
+                dest,
+                function
+            );
+        }
+
+        let predecessors = function.predecessors();
+        for (id, block) in function.blocks.iter().enumerate() {
+            for phi in block.phis.iter() {
+                let mut incoming: Vec<BlockId> =
+                    phi.incoming.iter().map(|(block, _)| *block).collect();
+                incoming.sort();
+                let mut expected = predecessors[id].clone();
+                expected.sort();
+                assert_eq!(incoming, expected, "{} in\n{}", phi.dest, function);
+            }
+        }
+    }
+
+    fn phis(function: &Function) -> Vec<(BlockId, &Phi)> {
+        return function
+            .blocks
+            .iter()
+            .enumerate()
+            .flat_map(|(id, block)| block.phis.iter().map(move |phi| (BlockId(id), phi)))
+            .collect();
+    }
+
+    fn phi_of(function: &Function, dest: Temp) -> &Phi {
+        return phis(function)
+            .into_iter()
+            .map(|(_, phi)| phi)
+            .find(|phi| phi.dest == dest)
+            .unwrap_or_else(|| panic!("{} is not a phi in\n{}", dest, function));
+    }
+
+    /// The temporary the only `return` of the function returns.
+    fn returned(function: &Function) -> Temp {
+        let values: Vec<&Value> = function
+            .blocks
+            .iter()
+            .filter_map(|block| match &block.terminator {
+                Terminator::Return(Some(value)) => Some(value),
+                _ => None,
+            })
+            .collect();
+        return match values.as_slice() {
+            [Value::Temp(temp)] => *temp,
+            _ => panic!("expected a single return of a temporary in\n{}", function),
+        };
+    }
+
+    fn values(phi: &Phi) -> Vec<&Value> {
+        return phi.incoming.iter().map(|(_, value)| value).collect();
+    }
+
+    /// Checks the values flowing into `phi`, in any order.
+    fn assert_joins(function: &Function, phi: &Phi, expected: &[Value]) {
+        let values = values(phi);
+        assert!(
+            values.len() == expected.len() && expected.iter().all(|value| values.contains(&value)),
+            "{} joins {:?} instead of {:?} in\n{}",
+            phi.dest,
+            values,
+            expected,
+            function
+        );
+    }
+
+    /// The instruction defining `temp`.
+    fn definition(function: &Function, temp: Temp) -> &Instruction {
+        return function
+            .blocks
+            .iter()
+            .flat_map(|block| block.instructions.iter())
+            .find(|instruction| instruction.dest() == Some(temp))
+            .unwrap_or_else(|| panic!("{} has no instruction in\n{}", temp, function));
+    }
+
+    fn int(value: i32) -> Value {
+        return Value::Constant(Constant::Int(value));
+    }
+
+    #[test]
+    fn joins_the_branches_of_an_if() {
+        let function = lower(
+            "fn f(c: bool) -> int {
+                let x: int = 1;
+                if (c) {
+                    x = 2;
+                }
+                return x;
+            }",
+        );
+        assert_eq!(phis(&function).len(), 1, "{}", function);
+        let phi = phi_of(&function, returned(&function));
+        assert_joins(&function, phi, &[int(1), int(2)]);
+    }
+
+    #[test]
+    fn joins_a_while_loop_at_its_condition() {
+        let function = lower(
+            "fn f(n: int) -> int {
+                let i: int = 0;
+                while (i < n) {
+                    i = i + 1;
+                }
+                return i;
+            }",
+        );
+        assert_eq!(phis(&function).len(), 1, "{}", function);
+        let i = returned(&function);
+        let phi = phi_of(&function, i);
+        let next = values(phi)
+            .into_iter()
+            .find_map(|value| match value {
+                Value::Temp(temp) => Some(*temp),
+                Value::Constant(_) => None,
+            })
+            .unwrap();
+        assert_joins(&function, phi, &[int(0), Value::Temp(next)]);
+        assert!(matches!(
+            definition(&function, next),
+            Instruction::Binary { operator: BinaryOp::Add, left: Value::Temp(left), .. }
+                if *left == i
+        ));
+    }
+
+    #[test]
+    fn joins_a_do_while_loop_at_its_body() {
+        let function = lower(
+            "fn f(n: int) -> int {
+                let i: int = 0;
+                do {
+                    i = i + 1;
+                } while (i < n);
+                return i;
+            }",
+        );
+        assert_eq!(phis(&function).len(), 1, "{}", function);
+        let (_, phi) = phis(&function)[0];
+        let next = returned(&function);
+        assert_joins(&function, phi, &[int(0), Value::Temp(next)]);
+        assert!(matches!(
+            definition(&function, next),
+            Instruction::Binary { operator: BinaryOp::Add, left: Value::Temp(left), .. }
+                if *left == phi.dest
+        ));
+    }
+
+    #[test]
+    fn joins_a_break_at_the_loop_exit() {
+        let function = lower(
+            "fn f(n: int) -> int {
+                let x: int = 0;
+                while (n > 0) {
+                    if (n == 5) {
+                        x = 1;
+                        break;
+                    }
+                    n = n - 1;
+                }
+                return x;
+            }",
+        );
+        let x = phi_of(&function, returned(&function));
+        assert_joins(&function, x, &[int(0), int(1)]);
+        // Only `n` changes around the loop.
+        let loop_phis: Vec<_> = phis(&function)
+            .into_iter()
+            .filter(|(_, phi)| phi.dest != x.dest)
+            .collect();
+        assert_eq!(loop_phis.len(), 1, "{}", function);
+        assert!(values(loop_phis[0].1).contains(&&Value::Temp(function.params[0])));
+    }
+
+    #[test]
+    fn joins_a_continue_at_the_loop_condition() {
+        let function = lower(
+            "fn f(n: int) -> int {
+                let s: int = 0;
+                let i: int = 0;
+                while (i < n) {
+                    i = i + 1;
+                    if (i == 2) {
+                        continue;
+                    }
+                    s = s + i;
+                }
+                return s;
+            }",
+        );
+        let s = phi_of(&function, returned(&function));
+        // From the entry, the `continue` and the end of the body, which
+        // are all the predecessors of the condition.
+        assert_eq!(s.incoming.len(), 3, "{}", function);
+        assert!(values(s).contains(&&int(0)));
+        assert!(values(s).contains(&&Value::Temp(s.dest)));
+    }
+
+    #[test]
+    fn defines_every_temp_once() {
+        for source in [
+            include_str!("../../tests/programs/loops.mb"),
+            include_str!("../../tests/programs/do_while.mb"),
+            include_str!("../../tests/programs/match.mb"),
+            include_str!("../../tests/programs/switch.mb"),
+            include_str!("../../tests/programs/pointers.mb"),
+        ] {
+            lower_module(source);
+        }
+    }
+}
@@ -35,5 +35,6 @@ pub fn init_keywords() -> HashMap<String, TokenType> {
     map.insert(String::from("false"), TokenType::False);
     map.insert(String::from("defer"), TokenType::Defer);
     map.insert(String::from("as"), TokenType::As);
+    map.insert(String::from("match"), TokenType::Match);
     return map;
 }
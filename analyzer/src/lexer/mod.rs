@@ -1,14 +1,22 @@
 pub mod keywords;
-pub mod lexical_error;
 pub mod token;
 pub mod token_finder;
 pub mod token_type;
 
 use std::collections::HashMap;
 
-use crate::{error::CompilerError, error_lexer, symbol_table::SymbolTableRef};
+use crate::{
+    diagnostic::{Diagnostic, ErrorCode},
+    error::CompilerError,
+    error_lexer,
+    symbol_table::SymbolTableRef,
+};
 
-use self::{keywords::init_keywords, token::Token, token_type::TokenType};
+use self::{
+    keywords::init_keywords,
+    token::{Comment, Position, Span, Token},
+    token_type::TokenType,
+};
 
 #[allow(dead_code)]
 pub struct Lexer {
@@ -20,6 +28,7 @@ pub struct Lexer {
     file_name: String,
     symbol_table: SymbolTableRef,
     keywords: HashMap<String, TokenType>,
+    comments: Vec<Comment>,
 }
 
 impl Lexer {
@@ -33,13 +42,14 @@ impl Lexer {
             line: 0,
             symbol_table,
             keywords: init_keywords(),
+            comments: Vec::new(),
         }
     }
 
     pub fn next_token(&mut self) -> Result<Token, CompilerError> {
-        self.skip_space();
+        let doc = self.skip_trivia()?;
 
-        match self.current_char() {
+        let token = match self.current_char() {
             0 => Ok(Token::get_eof(self)),
             b'a'..=b'z' | b'A'..=b'Z' | b'_' => Ok(Token::get_id_or_keyword(self)),
             b'0'..=b'9' => Token::get_number(self),
@@ -51,16 +61,113 @@ impl Lexer {
                 } else if let Some(token) = Token::get_delimiters(self) {
                     Ok(token)
                 } else {
-                    return error_lexer!(&self, "Unknown token!");
+                    // Skip the character so lexing can resume after it.
+                    self.set_cursor_start();
+                    self.next_char();
+                    return error_lexer!(self, UnknownToken, "Unknown token!");
                 }
             }
+        };
+        return token.map(|token| token.with_doc(doc));
+    }
+
+    /// Skips whitespace and comments, returning the text of the `///` doc
+    /// comments among them.
+    fn skip_trivia(&mut self) -> Result<Option<String>, CompilerError> {
+        let mut doc: Vec<String> = Vec::new();
+        loop {
+            self.skip_space();
+            if self.current_char() != b'/' {
+                break;
+            }
+            match self.peek_char() {
+                b'/' => {
+                    if let Some(line) = self.skip_line_comment() {
+                        doc.push(line);
+                    }
+                }
+                b'*' => self.skip_block_comment()?,
+                _ => break,
+            }
+        }
+        return Ok(if doc.is_empty() {
+            None
+        } else {
+            Some(doc.join("\n"))
+        });
+    }
+
+    /// Skips and records a `//` comment up to the end of the line. Returns
+    /// its text if it is a `///` doc comment, but not a `////` one.
+    fn skip_line_comment(&mut self) -> Option<String> {
+        self.set_cursor_start();
+        while self.current_char() != b'\n' && self.current_char() != 0 {
+            self.next_char();
+        }
+        let text = String::from_utf8_lossy(&self.content[self.cursor_start..self.cursor])
+            .trim_end()
+            .to_owned();
+        self.comments.push(Comment {
+            text: text.clone(),
+            span: self.span(),
+        });
+        let doc = text.strip_prefix("///")?;
+        if doc.starts_with('/') {
+            return None;
+        }
+        let doc = doc.strip_prefix(' ').unwrap_or(doc);
+        return Some(doc.to_owned());
+    }
+
+    /// Skips and records a `/* */` comment, including the comments nested
+    /// in it.
+    fn skip_block_comment(&mut self) -> Result<(), CompilerError> {
+        self.set_cursor_start();
+        self.next_char();
+        self.next_char();
+        let opening = self.span();
+        let mut depth: usize = 1;
+        while depth > 0 {
+            match (self.current_char(), self.peek_char()) {
+                (0, _) => {
+                    let diagnostic = Diagnostic::error(
+                        ErrorCode::UnterminatedComment,
+                        "Unterminated block comment",
+                    )
+                    .with_span(&opening);
+                    return Err(CompilerError::Lexical(Box::new(diagnostic)));
+                }
+                (b'/', b'*') => {
+                    self.next_char();
+                    depth += 1;
+                }
+                (b'*', b'/') => {
+                    self.next_char();
+                    depth -= 1;
+                }
+                (b'\n', _) => {
+                    self.line_start = self.cursor + 1;
+                    self.line += 1;
+                }
+                _ => {}
+            }
+            self.next_char();
         }
+        let text = String::from_utf8_lossy(&self.content[self.cursor_start..self.cursor]);
+        let text = text.into_owned();
+        // The comment may end on a later line than it starts on.
+        self.set_cursor_start();
+        self.comments.push(Comment {
+            text,
+            span: opening.to(&self.span()),
+        });
+        return Ok(());
     }
 
     fn skip_space(&mut self) {
         while self.current_char().is_ascii_whitespace() {
             if self.current_char() == b'\n' {
-                self.line_start = self.cursor;
+                self.line_start = self.cursor + 1;
                 self.line += 1;
             }
             self.next_char();
@@ -74,6 +181,25 @@ impl Lexer {
         };
     }
 
+    fn peek_char(&self) -> u8 {
+        return if self.cursor + 1 < self.content.len() {
+            self.content[self.cursor + 1]
+        } else {
+            0
+        };
+    }
+
+    /// The comments skipped so far, in the order they appear.
+    pub fn comments(&self) -> &[Comment] {
+        return &self.comments;
+    }
+
+    /// The source text at `span`.
+    pub(crate) fn source(&self, span: &Span) -> String {
+        let range = span.start().cursor_start()..span.end().cursor_start();
+        return String::from_utf8_lossy(&self.content[range]).to_string();
+    }
+
     pub fn file_name(&self) -> &str {
         self.file_name.as_ref()
     }
@@ -86,6 +212,22 @@ impl Lexer {
         self.cursor_start = self.cursor;
     }
 
+    /// The span of the text read since the start of the current token.
+    pub(crate) fn span(&self) -> Span {
+        return Span::new(
+            Position::new(
+                self.line + 1,
+                self.cursor_start - self.line_start + 1,
+                self.cursor_start,
+            ),
+            Position::new(
+                self.line + 1,
+                self.cursor - self.line_start + 1,
+                self.cursor,
+            ),
+        );
+    }
+
     /*
     pub(crate) fn line(&self) -> usize {
         self.line
@@ -103,8 +245,136 @@ impl Lexer {
         self.line_start
     }
     */
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ast::declarations::Declarations, parser::Parser, symbol_table::SymbolTable};
+
+    use super::*;
 
-    pub(crate) fn content(&self) -> &[u8] {
-        self.content.as_ref()
+    fn lexer(source: &str) -> Lexer {
+        return Lexer::new(
+            source.to_owned(),
+            String::from("test.mb"),
+            SymbolTable::new().into(),
+        );
+    }
+
+    /// The text and line of every token up to the end of the file.
+    fn tokens(lexer: &mut Lexer) -> Vec<(String, usize)> {
+        let mut tokens = Vec::new();
+        loop {
+            let token = lexer.next_token().unwrap_or_else(|err| panic!("{}", err));
+            if token.token_type() == &TokenType::Eof {
+                return tokens;
+            }
+            tokens.push((token.text().to_owned(), token.span().start().row()));
+        }
+    }
+
+    #[test]
+    fn skips_and_records_comments() {
+        let mut lexer = lexer("a // line\n/* block */ b /* x */ c");
+        let tokens = tokens(&mut lexer);
+        assert_eq!(
+            tokens,
+            [
+                (String::from("a"), 1),
+                (String::from("b"), 2),
+                (String::from("c"), 2)
+            ]
+        );
+        let comments: Vec<&str> = lexer
+            .comments()
+            .iter()
+            .map(|comment| comment.text.as_str())
+            .collect();
+        assert_eq!(comments, ["// line", "/* block */", "/* x */"]);
+    }
+
+    #[test]
+    fn nests_block_comments() {
+        let mut lexer = lexer("a /* outer /* inner */\n still outer */ b\nc");
+        assert_eq!(
+            tokens(&mut lexer),
+            [
+                (String::from("a"), 1),
+                (String::from("b"), 2),
+                (String::from("c"), 3)
+            ]
+        );
+        assert_eq!(
+            lexer.comments()[0].text,
+            "/* outer /* inner */\n still outer */"
+        );
+        assert_eq!(lexer.comments()[0].span.end().row(), 2);
+    }
+
+    #[test]
+    fn reports_unterminated_block_comments_where_they_open() {
+        let mut lexer = lexer("a\n/* outer /* inner */\nb");
+        lexer.next_token().unwrap();
+        let Err(CompilerError::Lexical(diagnostic)) = lexer.next_token() else {
+            panic!("the comment is closed");
+        };
+        assert_eq!(diagnostic.code, ErrorCode::UnterminatedComment);
+        let span = diagnostic.span.unwrap();
+        assert_eq!((span.start().row(), span.start().col()), (2, 1));
+    }
+
+    #[test]
+    fn attaches_doc_comments_to_the_next_token() {
+        let mut lexer = lexer("/// First line.\n///Second line.\n//// Not doc.\n// Plain.\na b");
+        let a = lexer.next_token().unwrap();
+        assert_eq!(
+            a.doc().map(String::as_str),
+            Some("First line.\nSecond line.")
+        );
+        assert_eq!(lexer.next_token().unwrap().doc(), None);
+    }
+
+    #[test]
+    fn attaches_doc_comments_to_declarations() {
+        let symbol_table: SymbolTableRef = SymbolTable::new().into();
+        let lexer = Lexer::new(
+            String::from(
+                "/// A point.
+                struct Point {
+                    x: int,
+                }
+
+                impl Point {
+                    /// The x coordinate.
+                    fn x(self: Point*) -> int {
+                        return self->x;
+                    }
+                }
+
+                // Not doc.
+                fn main() -> int {
+                    return 0;
+                }",
+            ),
+            String::from("test.mb"),
+            symbol_table.clone(),
+        );
+        let program = Parser::new(lexer, symbol_table)
+            .unwrap()
+            .parse()
+            .unwrap();
+        let docs: Vec<Option<&str>> = program
+            .declarations
+            .iter()
+            .map(|declaration| match declaration {
+                Declarations::Struct(struct_decl) => struct_decl.doc.as_deref(),
+                Declarations::Impl(impl_decl) => {
+                    impl_decl.member_functions[0].prototype.doc.as_deref()
+                }
+                Declarations::Fn(fn_decl) => fn_decl.doc.as_deref(),
+                declaration => panic!("unexpected {:?}", declaration),
+            })
+            .collect();
+        assert_eq!(docs, [Some("A point."), Some("The x coordinate."), None]);
     }
 }
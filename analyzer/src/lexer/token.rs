@@ -5,15 +5,25 @@ pub struct Token {
     token_type: TokenType,
     span: Span,
     text: String,
+    /// The `///` comments right before the token, one line each.
+    doc: Option<String>,
 }
 
+/// A `//` or `/* */` comment, which the parser skips but tools such as
+/// the formatter put back.
 #[derive(Debug, Clone, PartialEq)]
+pub struct Comment {
+    pub text: String,
+    pub span: Span,
+}
+
+#[derive(Clone, PartialEq, Default)]
 pub struct Span {
     start: Position,
     end: Position,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Default)]
 pub struct Position {
     row: usize,
     col: usize,
@@ -26,9 +36,19 @@ impl Token {
             token_type,
             span,
             text,
+            doc: None,
         }
     }
 
+    pub(crate) fn with_doc(mut self, doc: Option<String>) -> Self {
+        self.doc = doc;
+        return self;
+    }
+
+    pub fn doc(&self) -> Option<&String> {
+        self.doc.as_ref()
+    }
+
     pub fn token_type(&self) -> &TokenType {
         &self.token_type
     }
@@ -54,6 +74,21 @@ impl Span {
     pub fn end(&self) -> &Position {
         &self.end
     }
+
+    /// The span from the start of this one to the end of `end`.
+    pub fn to(&self, end: &Span) -> Span {
+        return Span::new(self.start.clone(), end.end.clone());
+    }
+}
+
+impl std::fmt::Debug for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return write!(
+            f,
+            "{}:{}..{}:{}",
+            self.start.row, self.start.col, self.end.row, self.end.col
+        );
+    }
 }
 
 impl Position {
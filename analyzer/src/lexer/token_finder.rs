@@ -10,18 +10,7 @@ impl Token {
     fn from_lexer(lexer: &Lexer, token_type: TokenType) -> Self {
         return Self::new(
             token_type,
-            Span::new(
-                Position::new(
-                    lexer.line + 1,
-                    lexer.cursor_start - lexer.line_start,
-                    lexer.cursor_start,
-                ),
-                Position::new(
-                    lexer.line + 1,
-                    lexer.cursor - lexer.line_start,
-                    lexer.cursor,
-                ),
-            ),
+            lexer.span(),
             String::from_utf8_lossy(&lexer.content[lexer.cursor_start..lexer.cursor]).to_string(),
         );
     }
@@ -56,21 +45,11 @@ impl Token {
         }
         let text =
             String::from_utf8_lossy(&lexer.content[lexer.cursor_start..lexer.cursor]).to_string();
-        let start = Position::new(
-            lexer.line + 1,
-            lexer.cursor_start - lexer.line_start,
-            lexer.cursor_start,
-        );
-        let end = Position::new(
-            lexer.line + 1,
-            lexer.cursor - lexer.line_start,
-            lexer.cursor,
-        );
         match lexer.keywords.get(&text) {
-            Some(token_type) => Self::new(token_type.to_owned(), Span::new(start, end), text),
+            Some(token_type) => Self::new(token_type.to_owned(), lexer.span(), text),
             None => {
                 // lexer.symbol_table.borrow_mut().insert_symbol(&text);
-                Self::new(TokenType::Identifier, Span::new(start, end), text)
+                Self::new(TokenType::Identifier, lexer.span(), text)
             }
         }
     }
@@ -86,10 +65,10 @@ impl Token {
             lexer.next_char();
         }
         if count > 1 {
-            return error_lexer!(lexer, "'.' occurs more than once!");
+            return error_lexer!(lexer, MalformedNumber, "'.' occurs more than once!");
         }
 
-        return Ok(Self::from_lexer(&lexer, TokenType::Number));
+        return Ok(Self::from_lexer(lexer, TokenType::Number));
     }
 
     pub(crate) fn get_string(lexer: &mut Lexer) -> Result<Self> {
@@ -98,16 +77,20 @@ impl Token {
 
         while lexer.current_char() != b'"' {
             if lexer.cursor >= lexer.content.len() {
-                return error_lexer!(lexer, "\" not closed");
+                return error_lexer!(lexer, UnterminatedLiteral, "\" not closed");
             }
             Self::is_escape_char(lexer)?;
             if lexer.current_char() == b'\n' {
-                return error_lexer!(lexer, "String should not be contain new line");
+                return error_lexer!(
+                    lexer,
+                    UnterminatedLiteral,
+                    "String should not be contain new line"
+                );
             }
             lexer.next_char();
         }
 
-        let token = Self::from_lexer(&lexer, TokenType::String);
+        let token = Self::from_lexer(lexer, TokenType::String);
 
         lexer.next_char();
         return Ok(token);
@@ -118,7 +101,7 @@ impl Token {
         lexer.cursor_start = lexer.cursor;
         Self::is_escape_char(lexer)?;
         lexer.next_char();
-        let token = Self::from_lexer(&lexer, TokenType::Char);
+        let token = Self::from_lexer(lexer, TokenType::Char);
         lexer.next_char();
         return Ok(token);
     }
@@ -127,27 +110,17 @@ impl Token {
         if lexer.current_char() == b'\\' {
             lexer.next_char();
             if !Self::check_escape(&lexer.current_char()) {
-                return error_lexer!(lexer, "Unknown escape sequence");
+                return error_lexer!(lexer, UnknownEscape, "Unknown escape sequence");
             }
         }
         return Ok(());
     }
 
     pub(crate) fn check_escape(ch: &u8) -> bool {
-        match ch {
-            b'a' => true,
-            b'b' => true,
-            b'f' => true,
-            b'n' => true,
-            b'r' => true,
-            b't' => true,
-            b'v' => true,
-            b'\\' => true,
-            b'\'' => true,
-            b'"' => true,
-            b'0' => true,
-            _ => false,
-        }
+        return matches!(
+            ch,
+            b'a' | b'b' | b'f' | b'n' | b'r' | b't' | b'v' | b'\\' | b'\'' | b'"' | b'0'
+        );
     }
 
     pub(crate) fn get_delimiters(lexer: &mut Lexer) -> Option<Self> {
@@ -161,7 +134,13 @@ impl Token {
             b',' => TokenType::Comma,
             b'.' => TokenType::Dot,
             b';' => TokenType::Semicolon,
-            b':' => TokenType::Colon,
+            b':' => {
+                if Self::check_next_op(lexer, b':') {
+                    TokenType::DoubleColon
+                } else {
+                    TokenType::Colon
+                }
+            }
             _ => {
                 return None;
             }
@@ -196,6 +175,8 @@ impl Token {
             b'=' => {
                 if Self::check_next_op(lexer, b'=') {
                     TokenType::Equal
+                } else if Self::check_next_op(lexer, b'>') {
+                    TokenType::FatArrow
                 } else {
                     TokenType::Assign
                 }
@@ -249,7 +230,7 @@ impl Token {
     }
 
     fn check_next_op(lexer: &mut Lexer, txt: u8) -> bool {
-        if lexer.cursor > lexer.content.len() {
+        if lexer.cursor + 1 >= lexer.content.len() {
             return false;
         }
         if lexer.content[lexer.cursor + 1] != txt {
@@ -68,6 +68,7 @@ pub enum TokenType {
     False,
     Defer,
     As,
+    Match,
 
     // keywords end
     Number,
@@ -75,9 +76,11 @@ pub enum TokenType {
     Char,
     Semicolon,
     Colon,
+    DoubleColon,
     Comma,
     Dot,
     Arrow,
+    FatArrow,
     Eof,
 }
 
@@ -96,9 +99,11 @@ impl Display for TokenType {
             TokenType::Char => "Char",
             TokenType::Semicolon => "Semicolon ';'",
             TokenType::Colon => "Colon ':'",
+            TokenType::DoubleColon => "Double Colon '::'",
             TokenType::Comma => "Comma ','",
             TokenType::Dot => "Dot '.'",
             TokenType::Arrow => "Arrow '->'",
+            TokenType::FatArrow => "Fat Arrow '=>'",
             TokenType::Eof => "Eof",
             TokenType::Assign => "Assign '='",
             TokenType::Plus => "Plus '+'",
@@ -153,6 +158,7 @@ impl Display for TokenType {
             TokenType::True => "True Keyword",
             TokenType::False => "False Keyword",
             TokenType::As => "As Keyword",
+            TokenType::Match => "Match Keyword",
             TokenType::BitLeft => "BitLeft '<<'",
             TokenType::BitRight => "BitRight '>>'",
         };
@@ -1,9 +1,17 @@
+#![allow(clippy::needless_return)]
+
 pub mod ast;
+pub mod codegen;
+pub mod diagnostic;
 pub mod error;
+pub mod formatter;
+pub mod interpreter;
+pub mod ir;
 pub mod lexer;
 pub mod parser;
 pub mod semantic;
 pub mod symbol_table;
+pub mod vm;
 
 #[macro_export]
 macro_rules! debug_current_token {
@@ -1,10 +1,14 @@
-pub mod syntactic_error;
-
 use crate::{
-    ast::{program::Program, AstParse},
-    error::Result,
+    ast::{expressions::Expression, program::Program, AstParse},
+    error::{CompilerError, Result},
     error_parser,
-    lexer::{token::Token, token_type::TokenType, Lexer},
+    interpreter::value::Value,
+    lexer::{
+        token::{Comment, Span, Token},
+        token_type::TokenType,
+        Lexer,
+    },
+    semantic::constant::{ConstEvaluate, ConstantScopes},
     symbol_table::SymbolTableRef,
 };
 
@@ -15,36 +19,78 @@ pub struct Parser {
     current: Token,
     next: Token,
     symbol_table: SymbolTableRef,
+    constants: ConstantScopes,
+    errors: Vec<CompilerError>,
 }
 
 #[allow(dead_code)]
 impl Parser {
     pub fn new(mut lexer: Lexer, symbol_table: SymbolTableRef) -> Result<Self> {
+        let mut errors = Vec::new();
         Ok(Self {
             previous: None,
-            current: lexer.next_token()?,
-            next: lexer.next_token()?,
+            current: Self::lex(&mut lexer, &mut errors),
+            next: Self::lex(&mut lexer, &mut errors),
             lexer,
             symbol_table,
+            constants: ConstantScopes::new(),
+            errors,
         })
     }
 
+    /// Parses the whole file, recovering from errors so that all of them
+    /// are reported at once.
     pub fn parse(&mut self) -> Result<Program> {
-        return Program::parse(self);
+        let (program, errors) = self.parse_partial()?;
+        CompilerError::from_errors(errors)?;
+        return Ok(program);
+    }
+
+    /// Parses the whole file like `parse`, but hands back the declarations
+    /// that parsed cleanly along with the errors, for tools that work on
+    /// code being edited.
+    pub fn parse_partial(&mut self) -> Result<(Program, Vec<CompilerError>)> {
+        let program = Program::parse(self)?;
+        return Ok((program, std::mem::take(&mut self.errors)));
     }
 
     pub(crate) fn next_token(&mut self) -> Result<()> {
+        let token = Self::lex(&mut self.lexer, &mut self.errors);
         self.previous = Some(std::mem::replace(
             &mut self.current,
-            std::mem::replace(&mut self.next, self.lexer.next_token()?),
+            std::mem::replace(&mut self.next, token),
         ));
         return Ok(());
     }
 
+    /// Reads the next valid token. Invalid input is reported and skipped.
+    fn lex(lexer: &mut Lexer, errors: &mut Vec<CompilerError>) -> Token {
+        loop {
+            match lexer.next_token() {
+                Ok(token) => return token,
+                Err(err) => errors.push(err),
+            }
+        }
+    }
+
+    /// Records an error the parser recovered from.
+    pub(crate) fn report(&mut self, err: CompilerError) {
+        self.errors.push(err);
+    }
+
+    /// Runs `parse` with its own scope of constants.
+    pub(crate) fn scope<T>(&mut self, parse: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        self.constants.push();
+        let result = parse(self);
+        self.constants.pop();
+        return result;
+    }
+
     pub(crate) fn expect(&mut self, token_type: TokenType) -> Result<&Token> {
         if self.current.token_type() != &token_type {
             return error_parser!(
                 self,
+                UnexpectedToken,
                 &format!("Expect {token_type} found {}", self.current.token_type())
             );
         }
@@ -60,6 +106,32 @@ impl Parser {
         return self.symbol_table.clone();
     }
 
+    pub(crate) fn constants(&mut self) -> &mut ConstantScopes {
+        return &mut self.constants;
+    }
+
+    /// Folds an expression that must be known while parsing, such as an
+    /// array size.
+    pub(crate) fn evaluate_constant(&self, expr: &Expression) -> Result<Value> {
+        return expr.evaluate(&self.constants);
+    }
+
+    /// The span from `start` to the end of the current token, which is the
+    /// last token of the node just parsed.
+    pub(crate) fn span_from(&self, start: &Span) -> Span {
+        return start.to(self.current.span());
+    }
+
+    /// The source text at `span`.
+    pub(crate) fn source(&self, span: &Span) -> String {
+        return self.lexer.source(span);
+    }
+
+    /// The comments of the source read so far.
+    pub fn comments(&self) -> &[Comment] {
+        return self.lexer.comments();
+    }
+
     pub fn previous(&self) -> Option<&Token> {
         self.previous.as_ref()
     }
@@ -72,3 +144,124 @@ impl Parser {
         &self.next
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ast::declarations::Declarations,
+        diagnostic::Diagnostic,
+        semantic::SemanticAnalyzer,
+        symbol_table::{SymbolTable, SymbolTableRef},
+    };
+
+    use super::*;
+
+    fn parser(source: &str) -> Parser {
+        let symbol_table: SymbolTableRef = SymbolTable::new().into();
+        let lexer = Lexer::new(
+            source.to_owned(),
+            String::from("test.mb"),
+            symbol_table.clone(),
+        );
+        return Parser::new(lexer, symbol_table).unwrap();
+    }
+
+    /// The line of every diagnostic in `errors`, which must all have one.
+    fn lines(errors: Vec<CompilerError>) -> Vec<usize> {
+        return errors
+            .iter()
+            .map(|err| {
+                let diagnostic: &Diagnostic = match err {
+                    CompilerError::Lexical(diagnostic)
+                    | CompilerError::Syntactic(diagnostic)
+                    | CompilerError::Semantic(diagnostic) => diagnostic,
+                    err => panic!("{} has no diagnostic", err),
+                };
+                diagnostic.span.as_ref().unwrap().start().row()
+            })
+            .collect();
+    }
+
+    fn errors(result: Result<impl std::fmt::Debug>) -> Vec<CompilerError> {
+        return match result.unwrap_err() {
+            CompilerError::Multiple(errors) => errors,
+            err => vec![err],
+        };
+    }
+
+    #[test]
+    fn recovers_at_the_next_statement() {
+        let result = parser(
+            "fn main() -> int {
+                let a: int = ;
+                let b: int = 2;
+                b = 3 +;
+                return b;
+            }",
+        )
+        .parse();
+        assert_eq!(lines(errors(result)), [2, 4]);
+    }
+
+    #[test]
+    fn recovers_at_the_next_declaration() {
+        let (program, errors) = parser(
+            "fn broken( -> int {
+                return 1;
+            }
+            struct Point {
+                x: int,
+            }
+            fn main() -> int {
+                return 0
+            }
+            fn last() -> int {
+                return 2;
+            }",
+        )
+        .parse_partial()
+        .unwrap();
+        assert_eq!(lines(errors), [1, 9]);
+        let names: Vec<&str> = program
+            .declarations
+            .iter()
+            .filter_map(|declaration| match declaration {
+                Declarations::Fn(fn_decl) => Some(fn_decl.fn_name.as_ref()),
+                Declarations::Struct(struct_decl) => Some(struct_decl.name.as_ref()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(names, ["Point", "main", "last"]);
+    }
+
+    #[test]
+    fn reports_unknown_tokens_with_the_syntax_errors() {
+        let result = parser(
+            "fn main() -> int {
+                let a: int = 1 $ 2;
+                let b: int = @;
+                return a;
+            }",
+        )
+        .parse();
+        assert_eq!(lines(errors(result)), [2, 2, 3, 3]);
+    }
+
+    #[test]
+    fn analysis_goes_on_after_a_failed_declaration() {
+        let mut parser = parser(
+            "fn first() -> int {
+                return missing;
+            }
+            fn second() -> bool {
+                return 1;
+            }
+            fn main() -> int {
+                return first();
+            }",
+        );
+        let program = parser.parse().unwrap().into();
+        let result = SemanticAnalyzer::new(parser.symbol_table.clone(), program).analyze();
+        assert_eq!(lines(errors(result)), [2, 5]);
+    }
+}
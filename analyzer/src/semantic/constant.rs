@@ -0,0 +1,414 @@
+use std::collections::HashMap;
+
+use crate::{
+    ast::{
+        expressions::{
+            binary_expr::{BinaryExpression, BinaryOperators},
+            cast_expr::CastExpression,
+            identifier_expression::IdentifierExpression,
+            primitive_expr::PrimitiveExpression,
+            unary_expr::{UnaryExpression, UnaryExpressionType, UnaryOperators},
+            Expression,
+        },
+        identifier::Identifier,
+        type_specifier::TypeSpecifier,
+    },
+    diagnostic::{Diagnostic, ErrorCode},
+    error::{CompilerError, Result},
+    error_semantic,
+    interpreter::value::Value,
+    lexer::token::Span,
+};
+
+/// The constants visible at a point of the program, innermost scope last.
+/// A name bound to `None` is a variable hiding a constant of an outer scope.
+#[derive(Debug)]
+pub struct ConstantScopes {
+    scopes: Vec<HashMap<String, Option<Value>>>,
+}
+
+/// Evaluates an expression at compile time with the same rules the
+/// interpreter applies at runtime.
+pub trait ConstEvaluate {
+    fn evaluate(&self, constants: &ConstantScopes) -> Result<Value>;
+}
+
+impl Default for ConstantScopes {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+impl ConstantScopes {
+    pub fn new() -> Self {
+        Self {
+            scopes: vec![HashMap::new()],
+        }
+    }
+
+    pub fn push(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    pub fn pop(&mut self) {
+        self.scopes.pop();
+    }
+
+    pub fn declare_constant(&mut self, identifier: &Identifier, value: Value) -> Result<()> {
+        let Some(scope) = self.scopes.last_mut() else {
+            return Err(CompilerError::Semantic(Box::new(Diagnostic::error(
+                ErrorCode::Internal,
+                "Missing scope",
+            ))));
+        };
+        if let Some(Some(_)) = scope.get(identifier.as_ref()) {
+            return error_semantic!(
+                &identifier.span,
+                ConstantRedefined,
+                format!("Constant {} is already defined", identifier)
+            );
+        }
+        scope.insert(identifier.id.clone(), Some(value));
+        return Ok(());
+    }
+
+    pub fn declare_variable(&mut self, name: &str) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_owned(), None);
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        return self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))?
+            .as_ref();
+    }
+}
+
+/// The type of a value a constant can hold.
+pub fn type_specifier(value: &Value) -> Result<TypeSpecifier> {
+    return Ok(match value {
+        Value::Int(_) => TypeSpecifier::Int,
+        Value::Usize(_) => TypeSpecifier::Usize,
+        Value::Float(_) => TypeSpecifier::Float,
+        Value::Double(_) => TypeSpecifier::Double,
+        Value::Char(_) => TypeSpecifier::Char,
+        Value::Str(_) => TypeSpecifier::Str,
+        Value::Bool(_) => TypeSpecifier::Bool,
+        _ => {
+            return Err(CompilerError::Semantic(Box::new(Diagnostic::error(
+                ErrorCode::NotConstant,
+                "Expression is not a constant",
+            ))))
+        }
+    });
+}
+
+/// Builds the literal expression that replaces a use of a constant.
+/// Negative numbers become a negation since literals have no sign. Every
+/// node of the literal takes the span of the use it replaces.
+pub fn literal(value: &Value, span: &Span) -> Result<Expression> {
+    let primitive = |type_specifier, value| {
+        return Expression::Primitive(PrimitiveExpression::new(
+            type_specifier,
+            value,
+            span.clone(),
+        ));
+    };
+    let negate = |expression| {
+        return Expression::Unary(UnaryExpression::new(
+            UnaryOperators::Minus,
+            Box::new(expression),
+            UnaryExpressionType::Prefix,
+            span.clone(),
+        ));
+    };
+    return Ok(match value {
+        Value::Int(value) if *value < 0 => negate(primitive(
+            TypeSpecifier::Int,
+            value.unsigned_abs().to_string(),
+        )),
+        Value::Int(value) => primitive(TypeSpecifier::Int, value.to_string()),
+        Value::Usize(value) => primitive(TypeSpecifier::Usize, value.to_string()),
+        Value::Float(value) if !value.is_finite() => return not_finite(span),
+        Value::Float(value) if value.is_sign_negative() => {
+            negate(primitive(TypeSpecifier::Float, format!("{:?}", -value)))
+        }
+        Value::Float(value) => primitive(TypeSpecifier::Float, format!("{:?}", value)),
+        Value::Double(value) if !value.is_finite() => return not_finite(span),
+        Value::Double(value) if value.is_sign_negative() => {
+            negate(primitive(TypeSpecifier::Double, format!("{:?}", -value)))
+        }
+        Value::Double(value) => primitive(TypeSpecifier::Double, format!("{:?}", value)),
+        Value::Char(value) => match escape(*value as char, b'\'') {
+            Some(text) => primitive(TypeSpecifier::Char, text),
+            None => Expression::Cast(CastExpression::new(
+                TypeSpecifier::Char,
+                Box::new(primitive(TypeSpecifier::Int, value.to_string())),
+                span.clone(),
+            )),
+        },
+        Value::Str(value) => {
+            let Some(text) = value
+                .chars()
+                .map(|char| escape(char, b'"'))
+                .collect::<Option<String>>()
+            else {
+                return error_semantic!(
+                    span,
+                    NotConstant,
+                    "String constant has a character without an escape sequence"
+                );
+            };
+            primitive(TypeSpecifier::Str, text)
+        }
+        Value::Bool(value) => primitive(TypeSpecifier::Bool, value.to_string()),
+        _ => return not_constant(span),
+    });
+}
+
+/// Spells a character inside a char or str literal closed by `quote`.
+fn escape(char: char, quote: u8) -> Option<String> {
+    return Some(match char {
+        '\x07' => String::from("\\a"),
+        '\x08' => String::from("\\b"),
+        '\x0c' => String::from("\\f"),
+        '\n' => String::from("\\n"),
+        '\r' => String::from("\\r"),
+        '\t' => String::from("\\t"),
+        '\x0b' => String::from("\\v"),
+        '\0' => String::from("\\0"),
+        '\\' => String::from("\\\\"),
+        char if char as u32 == quote as u32 => format!("\\{}", char),
+        char if char.is_ascii_control() || (quote == b'\'' && !char.is_ascii()) => return None,
+        char => char.to_string(),
+    });
+}
+
+fn not_constant<T>(span: &Span) -> Result<T> {
+    return error_semantic!(span, NotConstant, "Expression is not a constant");
+}
+
+fn not_finite<T>(span: &Span) -> Result<T> {
+    return error_semantic!(span, NotConstant, "Constant is not a finite number");
+}
+
+/// The interpreter reports invalid operations as runtime errors, which are
+/// compile errors when they happen while folding the constant at `span`.
+fn semantic(span: &Span) -> impl Fn(CompilerError) -> CompilerError + '_ {
+    return move |error| match error {
+        CompilerError::Runtime(message) => CompilerError::Semantic(Box::new(
+            Diagnostic::error(ErrorCode::ConstantEvaluation, message).with_span(span),
+        )),
+        error => error,
+    };
+}
+
+impl ConstEvaluate for Expression {
+    fn evaluate(&self, constants: &ConstantScopes) -> Result<Value> {
+        return match self {
+            Expression::Binary(binary_expr) => binary_expr.evaluate(constants),
+            Expression::Unary(unary_expr) => unary_expr.evaluate(constants),
+            Expression::Cast(cast_expr) => cast_expr.evaluate(constants),
+            Expression::Identifier(identifier_expr) => identifier_expr.evaluate(constants),
+            Expression::Primitive(primitive_expr) => primitive_expr.evaluate(constants),
+            _ => not_constant(self.span()),
+        };
+    }
+}
+
+impl ConstEvaluate for BinaryExpression {
+    fn evaluate(&self, constants: &ConstantScopes) -> Result<Value> {
+        let left = self.left.evaluate(constants)?;
+        match self.operator {
+            BinaryOperators::And if !left.as_bool().map_err(semantic(&self.span))? => {
+                return Ok(Value::Bool(false))
+            }
+            BinaryOperators::Or if left.as_bool().map_err(semantic(&self.span))? => {
+                return Ok(Value::Bool(true))
+            }
+            _ => {}
+        }
+        let right = self.right.evaluate(constants)?;
+        return self
+            .operator
+            .apply(left, right)
+            .map_err(semantic(&self.span));
+    }
+}
+
+impl ConstEvaluate for UnaryExpression {
+    fn evaluate(&self, constants: &ConstantScopes) -> Result<Value> {
+        return match self.operator {
+            UnaryOperators::Plus
+            | UnaryOperators::Minus
+            | UnaryOperators::LogicalNot
+            | UnaryOperators::BitwiseNot => {
+                let value = self.value.evaluate(constants)?;
+                self.operator.apply(value).map_err(semantic(&self.span))
+            }
+            _ => not_constant(&self.span),
+        };
+    }
+}
+
+impl ConstEvaluate for CastExpression {
+    fn evaluate(&self, constants: &ConstantScopes) -> Result<Value> {
+        let value = self.expression.evaluate(constants)?;
+        return value.cast(&self.target_type).map_err(semantic(&self.span));
+    }
+}
+
+impl ConstEvaluate for IdentifierExpression {
+    fn evaluate(&self, constants: &ConstantScopes) -> Result<Value> {
+        return match constants.get(self.id.as_ref()) {
+            Some(value) => Ok(value.clone()),
+            None => error_semantic!(
+                &self.span,
+                NotConstant,
+                format!("{} is not a constant", self.id)
+            ),
+        };
+    }
+}
+
+impl ConstEvaluate for PrimitiveExpression {
+    fn evaluate(&self, _constants: &ConstantScopes) -> Result<Value> {
+        return self.literal().map_err(semantic(&self.span));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ast::{declarations::Declarations, program::Program, type_specifier::TypeSpecifier},
+        lexer::Lexer,
+        parser::Parser,
+        symbol_table::{SymbolTable, SymbolTableRef},
+    };
+
+    use super::*;
+
+    fn parse(source: &str) -> Result<Program> {
+        let symbol_table: SymbolTableRef = SymbolTable::new().into();
+        let lexer = Lexer::new(
+            source.to_owned(),
+            String::from("test.mb"),
+            symbol_table.clone(),
+        );
+        return Parser::new(lexer, symbol_table)?.parse();
+    }
+
+    /// The values of the constants `source` declares, as the interpreter
+    /// prints them.
+    fn constants(source: &str) -> Vec<String> {
+        let program = parse(source).unwrap_or_else(|err| panic!("{}", err));
+        return program
+            .declarations
+            .iter()
+            .filter_map(|declaration| match declaration {
+                Declarations::Const(const_decl) => Some(format!("{:?}", const_decl.constant)),
+                _ => None,
+            })
+            .collect();
+    }
+
+    /// The codes of the errors parsing `source` reports.
+    fn error_codes(source: &str) -> Vec<ErrorCode> {
+        let errors = match parse(source) {
+            Ok(_) => panic!("{} was accepted", source),
+            Err(CompilerError::Multiple(errors)) => errors,
+            Err(err) => vec![err],
+        };
+        return errors
+            .into_iter()
+            .map(|err| match err {
+                CompilerError::Semantic(diagnostic) => diagnostic.code,
+                err => panic!("{}", err),
+            })
+            .collect();
+    }
+
+    #[test]
+    fn evaluates_constant_expressions() {
+        assert_eq!(
+            constants(
+                "const A: int = 2 + 3 * 4;
+                const B: int = -A / 4;
+                const C: usize = (A as usize) << 2 as usize;
+                const D: bool = A > 10 && !(B == 0);
+                const E: char = 'a' + 1 as char;"
+            ),
+            ["Int(14)", "Int(-3)", "Usize(56)", "Bool(true)", "Char(98)"]
+        );
+    }
+
+    #[test]
+    fn sizes_arrays_with_constants() {
+        let program = parse(
+            "const N: int = 3;
+            fn main() -> int {
+                let a: int[N * 2] = [0, 0, 0, 0, 0, 0];
+                return 0;
+            }",
+        )
+        .unwrap();
+        let Declarations::Fn(main) = &program.declarations[1] else {
+            panic!("main is not a function");
+        };
+        assert!(format!("{:?}", main.body).contains("size: 6"));
+    }
+
+    /// A constant can only use the ones declared before it, so a cycle is a
+    /// use of a constant that is not declared yet.
+    #[test]
+    fn rejects_cycles() {
+        assert_eq!(
+            error_codes("const A: int = B; const B: int = A;"),
+            [ErrorCode::NotConstant, ErrorCode::NotConstant]
+        );
+        assert_eq!(
+            error_codes("const A: int = A + 1;"),
+            [ErrorCode::NotConstant]
+        );
+    }
+
+    #[test]
+    fn rejects_variables() {
+        assert_eq!(
+            error_codes("fn main() -> int { let n: int = 1; let a: int[n] = [0]; return 0; }"),
+            [ErrorCode::NotConstant]
+        );
+    }
+
+    #[test]
+    fn rejects_division_by_zero() {
+        assert_eq!(
+            error_codes("const A: int = 1 / 0;"),
+            [ErrorCode::ConstantEvaluation]
+        );
+        assert_eq!(
+            error_codes("const A: int = 0; const B: int = 7 % A;"),
+            [ErrorCode::ConstantEvaluation]
+        );
+    }
+
+    #[test]
+    fn rejects_redefinitions() {
+        assert_eq!(
+            error_codes("const A: int = 1; const A: int = 2;"),
+            [ErrorCode::ConstantRedefined]
+        );
+    }
+
+    #[test]
+    fn literal_spells_negative_numbers_as_negations() {
+        let span = Span::default();
+        let literal = literal(&Value::Int(-5), &span).unwrap();
+        assert!(matches!(literal, Expression::Unary(_)));
+        assert_eq!(type_specifier(&Value::Int(-5)).unwrap(), TypeSpecifier::Int);
+    }
+}
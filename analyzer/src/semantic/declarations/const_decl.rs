@@ -0,0 +1,26 @@
+use crate::{
+    ast::{declarations::const_decl::ConstDeclaration, type_specifier::TypeSpecifier},
+    error::Result,
+    semantic::AstAnalyze,
+    symbol_table::{
+        symbol::{SymbolNode, SymbolNodeRef},
+        ToSymbol,
+    },
+};
+
+/// The value was folded and checked while parsing, so a local constant only
+/// has to become visible to the statements after it.
+impl AstAnalyze for ConstDeclaration {
+    fn analyze(&mut self, parent: SymbolNodeRef, _root: SymbolNodeRef) -> Result<TypeSpecifier> {
+        self.to_symbol(parent)?;
+        return Ok(TypeSpecifier::Void);
+    }
+}
+
+impl ToSymbol for ConstDeclaration {
+    fn to_symbol(&self, root: SymbolNodeRef) -> Result<()> {
+        let const_symbol = SymbolNode::from((self, root.clone()));
+        root.borrow_mut().append(const_symbol.into());
+        return Ok(());
+    }
+}
@@ -2,7 +2,9 @@ use crate::{
     ast::{
         declarations::fn_decl::FnDeclaration, statements::Statements, type_specifier::TypeSpecifier,
     },
+    diagnostic::{Diagnostic, ErrorCode},
     error::{CompilerError, Result},
+    error_semantic,
     semantic::AstAnalyze,
     symbol_table::{
         symbol::{
@@ -17,7 +19,12 @@ impl AstAnalyze for FnDeclaration {
         let fn_symbol: SymbolNodeRef = parent
             .iter()
             .function(self.fn_name.as_ref())
-            .ok_or_else(|| CompilerError::Semantic(String::from("Function not found")))?
+            .ok_or_else(|| {
+                CompilerError::Semantic(Box::new(
+                    Diagnostic::error(ErrorCode::UndefinedFunction, "Function not found")
+                        .with_span(&self.fn_name.span),
+                ))
+            })?
             .find();
 
         self.body.analyze(fn_symbol, root)?;
@@ -26,18 +33,21 @@ impl AstAnalyze for FnDeclaration {
             return Ok(TypeSpecifier::Void);
         }
 
-        let return_stmt = self
-            .body
-            .stmts
-            .last()
-            .ok_or_else(|| CompilerError::Semantic(String::from("Statement expected")))?;
+        let return_stmt = self.body.stmts.last().ok_or_else(|| {
+            CompilerError::Semantic(Box::new(
+                Diagnostic::error(ErrorCode::MissingReturn, "Statement expected")
+                    .with_span(&self.body.span),
+            ))
+        })?;
 
         if let Statements::Return(_) = return_stmt {
             return Ok(TypeSpecifier::Void);
         }
-        return Err(CompilerError::Semantic(String::from(
-            "Return Statement Expected",
-        )));
+        return error_semantic!(
+            return_stmt.span(),
+            MissingReturn,
+            "Return Statement Expected"
+        );
     }
 }
 
@@ -49,7 +59,7 @@ impl ToSymbol for FnDeclaration {
                 param,
                 Access::Local,
                 fn_symbol.clone(),
-                Box::new(|var| NodeTypes::Variable(var)) as NodeCallBack,
+                Box::new(NodeTypes::Variable) as NodeCallBack,
             ));
             fn_symbol.borrow_mut().append(param_symbol.into());
         }
@@ -6,7 +6,9 @@ use crate::{
         statements::Statements,
         type_specifier::TypeSpecifier,
     },
+    diagnostic::{Diagnostic, ErrorCode},
     error::{CompilerError, Result},
+    error_semantic,
     semantic::AstAnalyze,
     symbol_table::{
         symbol::{
@@ -21,11 +23,11 @@ use crate::{
 
 impl AstAnalyze for ImplDeclaration {
     fn analyze(&mut self, _parent: SymbolNodeRef, root: SymbolNodeRef) -> Result<TypeSpecifier> {
-        let struct_name = self.name.to_symbol()?;
+        let struct_name = self.struct_name()?;
         let struct_sym = root
             .iter()
             .struct_sym(struct_name)
-            .ok_or(CompilerError::Semantic("Struct not found".to_owned()))?
+            .ok_or_else(|| self.undefined_struct())?
             .find();
 
         for member_fn in self.member_functions.iter_mut() {
@@ -36,42 +38,105 @@ impl AstAnalyze for ImplDeclaration {
     }
 }
 
+impl ImplDeclaration {
+    /// `impl` takes the name of a struct, not a pointer or array type.
+    fn struct_name(&self) -> Result<&str> {
+        let Ok(name) = self.name.to_symbol() else {
+            return error_semantic!(
+                &self.span,
+                NotAStruct,
+                format!("Cannot implement methods for `{}`", self.name)
+            );
+        };
+        return Ok(name);
+    }
+
+    fn undefined_struct(&self) -> CompilerError {
+        let mut diagnostic = Diagnostic::error(ErrorCode::UndefinedStruct, "Struct not found");
+        if let TypeSpecifier::UserDefine(identifier) = &self.name {
+            diagnostic = diagnostic.with_span(&identifier.span);
+        }
+        return CompilerError::Semantic(Box::new(diagnostic));
+    }
+}
+
 impl AstAnalyze for MemberFunction {
     fn analyze(&mut self, parent: SymbolNodeRef, root: SymbolNodeRef) -> Result<TypeSpecifier> {
         let fn_node: SymbolNodeRef = parent
             .iter()
             .function(self.prototype.name.as_ref())
-            .ok_or_else(|| CompilerError::Semantic("Function not found".to_owned()))?
+            .ok_or_else(|| {
+                CompilerError::Semantic(Box::new(
+                    Diagnostic::error(ErrorCode::UndefinedFunction, "Function not found")
+                        .with_span(&self.prototype.name.span),
+                ))
+            })?
             .find();
 
+        self.check_receiver(&parent.borrow().data.name)?;
         self.body.analyze(fn_node, root)?;
 
         if self.prototype.return_type == TypeSpecifier::Void {
             return Ok(TypeSpecifier::Void);
         }
 
-        let return_stmt = self
-            .body
-            .stmts
-            .last()
-            .ok_or_else(|| CompilerError::Semantic(String::from("Statement expected")))?;
+        let return_stmt = self.body.stmts.last().ok_or_else(|| {
+            CompilerError::Semantic(Box::new(
+                Diagnostic::error(ErrorCode::MissingReturn, "Statement expected")
+                    .with_span(&self.body.span),
+            ))
+        })?;
 
         if let Statements::Return(_) = return_stmt {
             return Ok(TypeSpecifier::Void);
         }
-        return Err(CompilerError::Semantic(String::from(
-            "Return Statement Expected",
+        return error_semantic!(
+            return_stmt.span(),
+            MissingReturn,
+            "Return Statement Expected"
+        );
+    }
+}
+
+impl MemberFunction {
+    /// A method can only be called on its own struct, by value or through a
+    /// pointer.
+    fn check_receiver(&self, struct_name: &str) -> Result<()> {
+        let Some(receiver) = &self.prototype.method else {
+            return Ok(());
+        };
+        let target = match &receiver.type_specifier {
+            TypeSpecifier::Pointer(pointer) => pointer.as_ref(),
+            type_specifier => type_specifier,
+        };
+        if let TypeSpecifier::UserDefine(identifier) = target {
+            if identifier.as_ref() == struct_name {
+                return Ok(());
+            }
+        }
+        return Err(CompilerError::Semantic(Box::new(
+            Diagnostic::error(
+                ErrorCode::InvalidReceiver,
+                format!(
+                    "The receiver of `{}` must be `{}` or `{}*`, found `{}`",
+                    self.prototype.name.as_ref(),
+                    struct_name,
+                    struct_name,
+                    receiver.type_specifier
+                ),
+            )
+            .with_span(&receiver.identifier.span),
         )));
     }
 }
 
 impl ToSymbol for ImplDeclaration {
     fn to_symbol(&self, root: SymbolNodeRef) -> Result<()> {
-        let struct_name = self.name.to_symbol()?;
+        let struct_name = self.struct_name()?;
         let struct_sym = root
             .iter()
             .struct_sym(struct_name)
-            .ok_or(CompilerError::Semantic("Struct not found".to_owned()))?
+            .ok_or_else(|| self.undefined_struct())?
             .find();
 
         for member_fn in self.member_functions.iter() {
@@ -84,11 +149,12 @@ impl ToSymbol for ImplDeclaration {
 impl ToSymbol for MemberFunction {
     fn to_symbol(&self, root: SymbolNodeRef) -> Result<()> {
         let member_data = SymbolData::from((
-            self.prototype.name.as_ref(),
+            &self.prototype.name,
             &self.prototype.access_specifier,
             &self.prototype.return_type,
             &self.prototype.params,
-        ));
+        ))
+        .with_receiver(self.prototype.method.as_ref());
         let member_symbol: SymbolNodeRef =
             SymbolNode::new(member_data, Some(root.clone()), HashMap::new()).into();
 
@@ -97,7 +163,7 @@ impl ToSymbol for MemberFunction {
                 method,
                 Access::Local,
                 member_symbol.clone(),
-                Box::new(|var| NodeTypes::Variable(var)) as NodeCallBack,
+                Box::new(NodeTypes::Variable) as NodeCallBack,
             ));
             member_symbol.borrow_mut().append(node.into());
         }
@@ -107,7 +173,7 @@ impl ToSymbol for MemberFunction {
                 param,
                 Access::Local,
                 member_symbol.clone(),
-                Box::new(|var| NodeTypes::Variable(var)) as NodeCallBack,
+                Box::new(NodeTypes::Variable) as NodeCallBack,
             ));
             member_symbol.borrow_mut().append(param_symbol.into());
         }
@@ -1,3 +1,4 @@
+pub mod const_decl;
 pub mod enum_decl;
 pub mod fn_decl;
 pub mod impl_decl;
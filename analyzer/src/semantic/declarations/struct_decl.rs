@@ -24,7 +24,7 @@ impl ToSymbol for StructField {
             &self.field,
             Access::from(&self.access_specifier),
             root.clone(),
-            Box::new(|var| NodeTypes::StructField(var)) as NodeCallBack,
+            Box::new(NodeTypes::StructField) as NodeCallBack,
         ));
         root.borrow_mut().append(field.into());
         return Ok(());
@@ -7,7 +7,8 @@ use crate::{
         },
         type_specifier::TypeSpecifier,
     },
-    error::{CompilerError, Result},
+    error::Result,
+    error_semantic,
     semantic::AstAnalyze,
     symbol_table::symbol::SymbolNodeRef,
 };
@@ -26,9 +27,11 @@ impl AstAnalyze for ArrayIndexExpression {
         );
 
         if !result {
-            return Err(CompilerError::Semantic(String::from(
-                "Array type must be Identifier, Member Access or Function Call expression",
-            )));
+            return error_semantic!(
+                self.array.span(),
+                InvalidExpression,
+                "Array type must be Identifier, Member Access or Function Call expression"
+            );
         }
         let expr_type = self.array.analyze(parent, root)?;
 
@@ -45,9 +48,7 @@ impl AstAnalyze for ArrayIndexExpression {
                 }
             }
             TypeSpecifier::Pointer(type_specifier) => Ok(type_specifier.as_ref().clone()),
-            _ => Err(CompilerError::Semantic(String::from(
-                "Expect the array type",
-            ))),
+            _ => error_semantic!(self.array.span(), NotAnArray, "Expect the array type"),
         };
     }
 }
@@ -60,17 +61,17 @@ impl ArrayIndexExpression {
     ) -> Result<()> {
         let result = matches!(index, Expression::ArrayInit(_) | Expression::ObjectInit(_));
         if result {
-            return Err(CompilerError::Semantic(String::from(
-                "Index cannot be an object or array init expression",
-            )));
+            return error_semantic!(
+                index.span(),
+                InvalidExpression,
+                "Index cannot be an object or array init expression"
+            );
         }
 
         if let Expression::Unary(unary_expr) = index {
             if let UnaryExpressionType::Prefix = unary_expr.expression_type {
                 if let UnaryOperators::Minus = unary_expr.operator {
-                    return Err(CompilerError::Semantic(String::from(
-                        "Index cannot be negative",
-                    )));
+                    return error_semantic!(index.span(), InvalidIndex, "Index cannot be negative");
                 }
             }
         }
@@ -81,8 +82,6 @@ impl ArrayIndexExpression {
             return Ok(());
         }
 
-        return Err(CompilerError::Semantic(String::from(
-            "Array index type must be usize",
-        )));
+        return error_semantic!(index.span(), InvalidIndex, "Array index type must be usize");
     }
 }
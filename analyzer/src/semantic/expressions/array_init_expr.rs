@@ -1,6 +1,7 @@
 use crate::{
     ast::{expressions::array_init_expr::ArrayInitExpression, type_specifier::TypeSpecifier},
-    error::{CompilerError, Result},
+    error::Result,
+    error_semantic,
     semantic::AstAnalyze,
     symbol_table::symbol::SymbolNodeRef,
 };
@@ -11,15 +12,14 @@ impl AstAnalyze for ArrayInitExpression {
         for i in 1..self.array.len() {
             let type_specifier = self.array[i].analyze(parent.clone(), root.clone())?;
             if expr_type != type_specifier {
-                return Err(CompilerError::Semantic(String::from(
-                    "Array item types must be same!",
-                )));
+                return error_semantic!(
+                    self.array[i].span(),
+                    TypeMismatch,
+                    "Array item types must be same!"
+                );
             }
         }
 
-        return Ok(TypeSpecifier::ArrayType {
-            type_specifier: Box::new(expr_type),
-            size: self.size,
-        });
+        return Ok(TypeSpecifier::array(expr_type, self.size));
     }
 }
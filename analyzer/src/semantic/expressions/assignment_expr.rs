@@ -3,7 +3,8 @@ use crate::{
         expressions::{assignment_expr::AssignmentExpression, Expression},
         type_specifier::TypeSpecifier,
     },
-    error::{CompilerError, Result},
+    error::Result,
+    error_semantic,
     semantic::AstAnalyze,
     symbol_table::symbol::SymbolNodeRef,
 };
@@ -12,16 +13,20 @@ impl AstAnalyze for AssignmentExpression {
     fn analyze(&mut self, parent: SymbolNodeRef, root: SymbolNodeRef) -> Result<TypeSpecifier> {
         let left = self.analyze_left(parent.clone(), root.clone())?;
         if let TypeSpecifier::ArrayType { .. } = &left {
-            return Err(CompilerError::Semantic(String::from(
-                "Cannot assign to array",
-            )));
+            return error_semantic!(
+                self.variable.span(),
+                InvalidExpression,
+                "Cannot assign to array"
+            );
         }
         let right = self.value.analyze(parent, root)?;
 
         if left != right {
-            return Err(CompilerError::Semantic(String::from(
-                "Left and Right types are not matched",
-            )));
+            return error_semantic!(
+                &self.span,
+                TypeMismatch,
+                "Left and Right types are not matched"
+            );
         }
         return Ok(left);
     }
@@ -43,11 +48,14 @@ impl AssignmentExpression {
         );
 
         if result {
-            return Err(CompilerError::Semantic(String::from(
-                "Left hand side expression is not valid",
-            )));
+            return error_semantic!(
+                self.variable.span(),
+                InvalidExpression,
+                "Left hand side expression is not valid"
+            );
         }
 
+        self.variable.check_not_constant(parent.clone())?;
         return self.variable.analyze(parent, root);
     }
 }
@@ -3,26 +3,53 @@ use crate::{
         expressions::binary_expr::{BinaryExpression, BinaryOperators},
         type_specifier::TypeSpecifier,
     },
+    diagnostic::{Diagnostic, ErrorCode},
     error::{CompilerError, Result},
+    error_semantic,
     semantic::AstAnalyze,
-    symbol_table::symbol::SymbolNodeRef,
+    symbol_table::symbol::{iter::ToIter, SymbolNodeRef},
 };
 
 impl AstAnalyze for BinaryExpression {
     fn analyze(&mut self, parent: SymbolNodeRef, root: SymbolNodeRef) -> Result<TypeSpecifier> {
         let left_type = self.left.analyze(parent.clone(), root.clone())?;
-        let right_type = self.right.analyze(parent, root)?;
+        let right_type = self.right.analyze(parent, root.clone())?;
 
         if left_type != right_type {
-            return Err(CompilerError::Semantic(String::from(
-                "Left and Right hand-side must be the same type",
+            return Err(CompilerError::Semantic(Box::new(
+                Diagnostic::error(
+                    ErrorCode::TypeMismatch,
+                    "Left and Right hand-side must be the same type",
+                )
+                .with_span(&self.span)
+                .with_label(self.left.span(), format!("this is `{}`", left_type))
+                .with_label(self.right.span(), format!("this is `{}`", right_type)),
             )));
         }
 
+        if Self::is_enum(&left_type, root) {
+            if !matches!(
+                self.operator,
+                BinaryOperators::Equal | BinaryOperators::NotEqual
+            ) {
+                return error_semantic!(
+                    &self.span,
+                    UnsupportedOperand,
+                    format!(
+                        "Values of enum {} can only be compared with == and !=",
+                        left_type
+                    )
+                );
+            }
+            return Ok(TypeSpecifier::Bool);
+        }
+
         if !left_type.is_primitive() {
-            return Err(CompilerError::Semantic(String::from(
-                "Cannot apply binary operation to complex type",
-            )));
+            return error_semantic!(
+                &self.span,
+                UnsupportedOperand,
+                "Cannot apply binary operation to complex type"
+            );
         }
 
         return Ok(match self.operator {
@@ -38,3 +65,13 @@ impl AstAnalyze for BinaryExpression {
         });
     }
 }
+
+impl BinaryExpression {
+    fn is_enum(type_specifier: &TypeSpecifier, root: SymbolNodeRef) -> bool {
+        return matches!(
+            type_specifier,
+            TypeSpecifier::UserDefine(name)
+                if root.iter().enum_sym(name.as_ref()).is_some_and(|sym| !sym.is_tagged())
+        );
+    }
+}
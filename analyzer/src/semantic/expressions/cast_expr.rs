@@ -1,18 +1,84 @@
 use crate::{
     ast::{expressions::cast_expr::CastExpression, type_specifier::TypeSpecifier},
-    error::{CompilerError, Result},
-    semantic::AstAnalyze,
-    symbol_table::symbol::SymbolNodeRef,
+    error::Result,
+    error_semantic,
+    interpreter::value::Value,
+    semantic::{
+        constant::{ConstEvaluate, ConstantScopes},
+        AstAnalyze,
+    },
+    symbol_table::symbol::{iter::ToIter, SymbolNodeRef},
 };
 
 impl AstAnalyze for CastExpression {
     fn analyze(&mut self, parent: SymbolNodeRef, root: SymbolNodeRef) -> Result<TypeSpecifier> {
-        let type_specifier = self.expression.analyze(parent, root)?;
+        let type_specifier = self.expression.analyze(parent, root.clone())?;
+        let source_enum = Self::enum_name(&type_specifier, root.clone());
+        let target_enum = Self::enum_name(&self.target_type, root.clone());
+        if source_enum.is_some() || target_enum.is_some() {
+            let other = match source_enum {
+                Some(_) => &self.target_type,
+                None => &type_specifier,
+            };
+            if other != &TypeSpecifier::Int {
+                return error_semantic!(
+                    &self.span,
+                    UnsupportedOperand,
+                    "Enums can only be cast to and from int"
+                );
+            }
+            if let Some(name) = target_enum {
+                self.check_enum_value(&name, root)?;
+            }
+            return Ok(self.target_type.clone());
+        }
         if !type_specifier.is_primitive() {
-            return Err(CompilerError::Semantic(String::from(
-                "Cannot cast the complex type",
-            )));
+            return error_semantic!(
+                self.expression.span(),
+                UnsupportedOperand,
+                "Cannot cast the complex type"
+            );
+        }
+        if let TypeSpecifier::UserDefine(_) = self.target_type {
+            return error_semantic!(
+                &self.span,
+                UnsupportedOperand,
+                "Cannot cast to the complex type"
+            );
         }
         return Ok(self.target_type.clone());
     }
 }
+
+impl CastExpression {
+    fn enum_name(type_specifier: &TypeSpecifier, root: SymbolNodeRef) -> Option<String> {
+        return match type_specifier {
+            TypeSpecifier::UserDefine(name)
+                if root
+                    .iter()
+                    .enum_sym(name.as_ref())
+                    .is_some_and(|sym| !sym.is_tagged()) =>
+            {
+                Some(name.to_string())
+            }
+            _ => None,
+        };
+    }
+
+    /// A constant cast to an enum must be the value of one of its items.
+    /// Other values are checked when the program runs.
+    fn check_enum_value(&self, name: &str, root: SymbolNodeRef) -> Result<()> {
+        let Ok(Value::Int(value)) = self.expression.evaluate(&ConstantScopes::new()) else {
+            return Ok(());
+        };
+        let enum_sym = root.iter().enum_sym(name);
+        if enum_sym.and_then(|sym| sym.enum_value(value)).is_none() {
+            return error_semantic!(
+                self.expression.span(),
+                UndefinedItem,
+                format!("Enum {} has no item with value {}", name, value)
+            );
+        }
+        return Ok(());
+    }
+}
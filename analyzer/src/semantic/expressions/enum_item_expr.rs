@@ -0,0 +1,61 @@
+use crate::{
+    ast::{expressions::enum_item_expr::EnumItemExpression, type_specifier::TypeSpecifier},
+    diagnostic::{Diagnostic, ErrorCode},
+    error::{CompilerError, Result},
+    error_semantic,
+    semantic::AstAnalyze,
+    symbol_table::symbol::{iter::ToIter, SymbolNodeRef},
+};
+
+impl AstAnalyze for EnumItemExpression {
+    fn analyze(&mut self, parent: SymbolNodeRef, root: SymbolNodeRef) -> Result<TypeSpecifier> {
+        let enum_sym = root.iter().enum_sym(self.name.as_ref()).ok_or_else(|| {
+            CompilerError::Semantic(Box::new(
+                Diagnostic::error(ErrorCode::UndefinedEnum, "Cannot find the enum")
+                    .with_span(&self.name.span),
+            ))
+        })?;
+        let Some(item) = enum_sym.enum_item(self.item.as_ref()) else {
+            return error_semantic!(
+                &self.item.span,
+                UndefinedItem,
+                format!("Enum {} has no item {}", self.name, self.item)
+            );
+        };
+        let payload = item
+            .find()
+            .borrow()
+            .data
+            .node_type
+            .enum_item()
+            .payload
+            .clone();
+        if payload.len() != self.args.len() {
+            return error_semantic!(
+                &self.span,
+                ArgumentCount,
+                format!(
+                    "{}::{} carries {} values but {} were given",
+                    self.name,
+                    self.item,
+                    payload.len(),
+                    self.args.len()
+                )
+            );
+        }
+        for (arg, type_specifier) in self.args.iter_mut().zip(payload.iter()) {
+            let arg_type = arg.analyze(parent.clone(), root.clone())?;
+            if &arg_type != type_specifier {
+                return error_semantic!(
+                    arg.span(),
+                    TypeMismatch,
+                    format!(
+                        "Expect a value of type {} but found {}",
+                        type_specifier, arg_type
+                    )
+                );
+            }
+        }
+        return Ok(TypeSpecifier::UserDefine(self.name.clone()));
+    }
+}
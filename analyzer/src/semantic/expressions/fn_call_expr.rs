@@ -1,6 +1,14 @@
 use crate::{
-    ast::{expressions::fn_call_expr::FnCallExpression, type_specifier::TypeSpecifier},
+    ast::{
+        expressions::{
+            enum_item_expr::EnumItemExpression, fn_call_expr::FnCallExpression,
+            member_access_expr::MemberAccessExpression, Expression,
+        },
+        type_specifier::TypeSpecifier,
+    },
+    diagnostic::{Diagnostic, ErrorCode},
     error::{CompilerError, Result},
+    error_semantic,
     semantic::AstAnalyze,
     symbol_table::symbol::{iter::ToIter, node::FunctionNode, SymbolNodeRef},
 };
@@ -8,10 +16,14 @@ use crate::{
 impl AstAnalyze for FnCallExpression {
     fn analyze(&mut self, parent: SymbolNodeRef, root: SymbolNodeRef) -> Result<TypeSpecifier> {
         let fn_symbol = self.fn_function(root.clone())?;
+        if self.namespace.is_some() {
+            MemberAccessExpression::check_access_specifier(&fn_symbol, parent.clone(), &self.span)?;
+        }
 
         let binding = fn_symbol.borrow();
         let fn_node = binding.data.node_type.function();
 
+        self.check_receiver(fn_node)?;
         self.check_params(fn_node)?;
         self.check_params_type(fn_node, parent, root)?;
 
@@ -20,36 +32,80 @@ impl AstAnalyze for FnCallExpression {
 }
 
 impl FnCallExpression {
+    /// `Name::item(values)` builds an item of the enum `Name` when there is
+    /// one, which the parser cannot know yet. The arguments move to the item.
+    pub(crate) fn enum_item(&mut self, root: SymbolNodeRef) -> Option<EnumItemExpression> {
+        let namespace = self.namespace.as_ref()?;
+        let Expression::Identifier(name) = namespace.name.as_ref() else {
+            return None;
+        };
+        root.iter().enum_sym(name.id.as_ref())?;
+        let item = self.fn_name.identifier().ok()?;
+        return Some(EnumItemExpression::new(
+            name.id.clone(),
+            item.id.clone(),
+            std::mem::take(&mut self.args),
+            self.span.clone(),
+        ));
+    }
+
     fn fn_function(&self, root: SymbolNodeRef) -> Result<SymbolNodeRef> {
         let fn_name = self.fn_name.identifier()?;
 
         let mut iter = root.iter();
         if let Some(namespace) = &self.namespace {
             let struct_name = namespace.name.identifier()?;
-            iter = iter
-                .struct_sym(struct_name.id.as_ref())
-                .ok_or_else(|| CompilerError::Semantic(String::from("Cannot find the struct")))?;
+            iter = iter.struct_sym(struct_name.id.as_ref()).ok_or_else(|| {
+                CompilerError::Semantic(Box::new(
+                    Diagnostic::error(ErrorCode::UndefinedStruct, "Cannot find the struct")
+                        .with_span(&struct_name.span),
+                ))
+            })?;
         }
 
         let fn_symbol = iter
             .function(fn_name.id.as_ref())
-            .ok_or_else(|| CompilerError::Semantic("Cannot find the function".to_owned()))?
+            .ok_or_else(|| {
+                CompilerError::Semantic(Box::new(
+                    Diagnostic::error(ErrorCode::UndefinedFunction, "Cannot find the function")
+                        .with_span(&fn_name.span),
+                ))
+            })?
             .find();
 
         return Ok(fn_symbol);
     }
 
-    fn check_params(&self, fn_node: &FunctionNode) -> Result<()> {
+    /// `Name::method()` has no value to pass as the receiver.
+    fn check_receiver(&self, fn_node: &FunctionNode) -> Result<()> {
+        if fn_node.receiver.is_none() {
+            return Ok(());
+        }
+        let fn_name = &self.fn_name.identifier()?.id;
+        return Err(CompilerError::Semantic(Box::new(
+            Diagnostic::error(
+                ErrorCode::InvalidReceiver,
+                format!(
+                    "`{}` takes a receiver and must be called on a value",
+                    fn_name
+                ),
+            )
+            .with_span(&self.span)
+            .with_help(format!("call it as `value.{}(...)`", fn_name)),
+        )));
+    }
+
+    pub(crate) fn check_params(&self, fn_node: &FunctionNode) -> Result<()> {
         if fn_node.params.len() < self.args.len() {
-            return Err(CompilerError::Semantic(String::from("Too many parameter")));
+            return error_semantic!(&self.span, ArgumentCount, "Too many parameter");
         }
         if fn_node.params.len() > self.args.len() {
-            return Err(CompilerError::Semantic(String::from("Missing parameter")));
+            return error_semantic!(&self.span, ArgumentCount, "Missing parameter");
         }
         return Ok(());
     }
 
-    fn check_params_type(
+    pub(crate) fn check_params_type(
         &mut self,
         fn_node: &FunctionNode,
         parent: SymbolNodeRef,
@@ -58,9 +114,11 @@ impl FnCallExpression {
         for i in 0..self.args.len() {
             let param_type = self.args[i].analyze(parent.clone(), root.clone())?;
             if param_type != fn_node.params[i] {
-                return Err(CompilerError::Semantic(String::from(
-                    "Parameter expression type does not match",
-                )));
+                return error_semantic!(
+                    self.args[i].span(),
+                    TypeMismatch,
+                    "Parameter expression type does not match"
+                );
             }
         }
 
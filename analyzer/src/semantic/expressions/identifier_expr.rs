@@ -2,12 +2,15 @@ use crate::{
     ast::{
         expressions::identifier_expression::IdentifierExpression, type_specifier::TypeSpecifier,
     },
+    diagnostic::{Diagnostic, ErrorCode},
     error::{CompilerError, Result},
     semantic::AstAnalyze,
-    symbol_table::symbol::{iter::ToIter, SymbolNodeRef},
+    symbol_table::symbol::{iter::ToIter, node::ConstantNode, SymbolNodeRef},
 };
 
 impl AstAnalyze for IdentifierExpression {
+    // `root` is only passed on while the outer scopes are searched
+    #[allow(clippy::only_used_in_recursion)]
     fn analyze(&mut self, parent: SymbolNodeRef, root: SymbolNodeRef) -> Result<TypeSpecifier> {
         let node_iter = parent.iter();
         let node = node_iter.variable(self.id.as_ref());
@@ -22,10 +25,37 @@ impl AstAnalyze for IdentifierExpression {
             None => {
                 let parent: SymbolNodeRef = node_iter
                     .parent()
-                    .ok_or_else(|| CompilerError::Semantic("Cannot find the variable".to_owned()))?
+                    .ok_or_else(|| {
+                        CompilerError::Semantic(Box::new(
+                            Diagnostic::error(
+                                ErrorCode::UndefinedVariable,
+                                "Cannot find the variable",
+                            )
+                            .with_span(&self.span),
+                        ))
+                    })?
                     .find();
                 return self.analyze(parent, root);
             }
         }
     }
 }
+
+impl IdentifierExpression {
+    /// The constant the identifier names, unless a variable of an inner
+    /// scope hides it.
+    pub(crate) fn constant(&self, parent: SymbolNodeRef) -> Option<ConstantNode> {
+        let mut node_iter = parent.iter();
+        loop {
+            if node_iter.variable(self.id.as_ref()).is_some() {
+                return None;
+            }
+            if let Some(constant) = node_iter.constant(self.id.as_ref()) {
+                let constant = constant.find();
+                let node_type = &constant.borrow().data.node_type;
+                return Some(node_type.constant().clone());
+            }
+            node_iter = node_iter.parent()?;
+        }
+    }
+}
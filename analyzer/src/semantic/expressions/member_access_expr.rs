@@ -3,13 +3,15 @@ use crate::{
         access_specifier::AccessSpecifier,
         expressions::{
             fn_call_expr::FnCallExpression, identifier_expression::IdentifierExpression,
-            member_access_expr::MemberAccessExpression, Expression,
+            member_access_expr::MemberAccessExpression, unary_expr::UnaryOperators, Expression,
         },
         identifier::Identifier,
         type_specifier::TypeSpecifier,
     },
+    diagnostic::{Diagnostic, ErrorCode},
     error::{CompilerError, Result},
-    lexer::token_type::TokenType,
+    error_semantic,
+    lexer::{token::Span, token_type::TokenType},
     semantic::AstAnalyze,
     symbol_table::symbol::{
         data::Access,
@@ -22,17 +24,93 @@ use crate::{
 impl AstAnalyze for MemberAccessExpression {
     fn analyze(&mut self, parent: SymbolNodeRef, root: SymbolNodeRef) -> Result<TypeSpecifier> {
         self.check_obj()?;
-        let object = self.object.analyze(parent.clone(), root.clone())?;
-        let struct_name = match object {
-            TypeSpecifier::UserDefine(identifier) => self.check_user_define(identifier)?,
-            TypeSpecifier::Pointer(pointer) => self.check_pointer(*pointer)?,
-            _ => {
-                return Err(CompilerError::Semantic(String::from(
-                    "Member access only can use with user define type",
-                )))
+        let object = Object {
+            type_specifier: self.object.analyze(parent.clone(), root.clone())?,
+            is_place: Object::is_place(&self.object),
+            span: self.object.span().clone(),
+        };
+        return Self::analyze_property(
+            &object,
+            &self.access,
+            &mut self.property,
+            &self.span,
+            parent,
+            root,
+        );
+    }
+}
+
+/// The value a property is looked up on: its type, whether it is stored
+/// somewhere a method can take its address and where it is written.
+struct Object {
+    type_specifier: TypeSpecifier,
+    is_place: bool,
+    span: Span,
+}
+
+impl Object {
+    fn is_place(expression: &Expression) -> bool {
+        return match expression {
+            Expression::Identifier(_) | Expression::ArrayIndex(_) => true,
+            Expression::Unary(unary) => matches!(unary.operator, UnaryOperators::Indirection),
+            _ => false,
+        };
+    }
+
+    fn struct_name(&self, access: &TokenType, span: &Span) -> Result<Identifier> {
+        return match &self.type_specifier {
+            TypeSpecifier::UserDefine(identifier) => {
+                Self::check_user_define(identifier, access, span)
             }
+            TypeSpecifier::Pointer(pointer) => self.check_pointer(pointer, access, span),
+            _ => error_semantic!(
+                &self.span,
+                NotAStruct,
+                "Member access only can use with user define type"
+            ),
         };
-        return self.check_property(struct_name.as_ref(), parent, root);
+    }
+
+    fn check_user_define(id: &Identifier, access: &TokenType, span: &Span) -> Result<Identifier> {
+        if &TokenType::Dot == access {
+            return Ok(id.clone());
+        }
+        return Err(CompilerError::Semantic(Box::new(
+            Diagnostic::error(
+                ErrorCode::WrongAccessOperator,
+                "Use dot('.') operator to access member",
+            )
+            .with_span(span)
+            .with_help("`->` is only for pointers to structs"),
+        )));
+    }
+
+    fn check_pointer(
+        &self,
+        type_specifier: &TypeSpecifier,
+        access: &TokenType,
+        span: &Span,
+    ) -> Result<Identifier> {
+        let TypeSpecifier::UserDefine(identifier) = type_specifier else {
+            return error_semantic!(
+                &self.span,
+                NotAStruct,
+                "Member access only can use with user define type"
+            );
+        };
+
+        if &TokenType::Arrow == access {
+            return Ok(identifier.clone());
+        }
+
+        return Err(CompilerError::Semantic(Box::new(
+            Diagnostic::error(
+                ErrorCode::WrongAccessOperator,
+                "Use arrow('->') operator to access member with pointer type",
+            )
+            .with_span(span)
+            .with_help("`.` is only for struct values"),
+        )));
     }
 }
 
@@ -47,86 +125,144 @@ impl MemberAccessExpression {
                 | Expression::MemberAccess(_)
         );
         if result {
-            return Err(CompilerError::Semantic(String::from(
-                "Invalid object expression",
-            )));
+            return error_semantic!(
+                self.object.span(),
+                InvalidExpression,
+                "Invalid object expression"
+            );
         }
         return Ok(());
     }
 
-    fn check_user_define(&self, id: Identifier) -> Result<Identifier> {
-        if TokenType::Dot == self.access {
-            return Ok(id);
-        }
-        return Err(CompilerError::Semantic(String::from(
-            "Use dot('.') operator to access member",
-        )));
-    }
-
-    fn check_pointer(&self, type_specifier: TypeSpecifier) -> Result<Identifier> {
-        let TypeSpecifier::UserDefine(identifier) = type_specifier else {
-          return Err(CompilerError::Semantic(String::from("Member access only can use with user define type")));
-        };
-
-        if TokenType::Arrow == self.access {
-            return Ok(identifier);
-        }
-
-        return Err(CompilerError::Semantic(String::from(
-            "Use arrow('->') operator to access member with pointer type",
-        )));
-    }
-
-    fn check_property(
-        &mut self,
-        name: &str,
+    fn analyze_property(
+        object: &Object,
+        access: &TokenType,
+        property: &mut Expression,
+        span: &Span,
         parent: SymbolNodeRef,
         root: SymbolNodeRef,
     ) -> Result<TypeSpecifier> {
+        if let Expression::MemberAccess(member_access) = property {
+            return Self::analyze_member_access(object, access, member_access, parent, root);
+        }
+
+        let struct_name = object.struct_name(access, span)?;
         let iter = root
             .iter()
-            .struct_sym(name)
-            .ok_or_else(|| CompilerError::Semantic(String::from("Cannot find the struct")))?;
+            .struct_sym(struct_name.as_ref())
+            .ok_or_else(|| {
+                CompilerError::Semantic(Box::new(
+                    Diagnostic::error(ErrorCode::UndefinedStruct, "Cannot find the struct")
+                        .with_span(span),
+                ))
+            })?;
 
-        let result = matches!(
-            self.property.as_ref(),
-            Expression::Identifier(_) | Expression::FnCall(_) | Expression::MemberAccess(_)
-        );
-
-        if !result {
-            return Err(CompilerError::Semantic(String::from(
-                "Invalid property expression",
-            )));
-        }
-
-        match self.property.as_mut() {
-            Expression::FnCall(fn_call) => Self::analyze_fn(fn_call, parent, iter),
+        match property {
+            Expression::FnCall(fn_call) => {
+                Self::analyze_fn(fn_call, object, access, &struct_name, parent, root, iter)
+            }
             Expression::Identifier(identifier) => {
                 Self::analyze_identifier(identifier, parent, iter)
             }
-            Expression::MemberAccess(member_access) => {
-                Self::analyze_member_access(member_access, iter)
-            }
-            _ => Err(CompilerError::Semantic(String::from(
-                "Invalid property expression",
-            ))),
+            _ => error_semantic!(
+                property.span(),
+                InvalidExpression,
+                "Invalid property expression"
+            ),
         }
     }
 
+    /// `a.b.c` is `a.(b.c)`, so `b` is looked up on `a` first and `c` on
+    /// the result.
+    fn analyze_member_access(
+        object: &Object,
+        access: &TokenType,
+        member_access: &mut MemberAccessExpression,
+        parent: SymbolNodeRef,
+        root: SymbolNodeRef,
+    ) -> Result<TypeSpecifier> {
+        let is_field = matches!(member_access.object.as_ref(), Expression::Identifier(_));
+        let inner = Object {
+            type_specifier: Self::analyze_property(
+                object,
+                access,
+                &mut member_access.object,
+                &member_access.span,
+                parent.clone(),
+                root.clone(),
+            )?,
+            is_place: is_field && (object.is_place || access == &TokenType::Arrow),
+            span: member_access.object.span().clone(),
+        };
+        return Self::analyze_property(
+            &inner,
+            &member_access.access,
+            &mut member_access.property,
+            &member_access.span,
+            parent,
+            root,
+        );
+    }
+
+    /// Methods are called with the object as their receiver, taking its
+    /// address when the receiver is a pointer and dereferencing a pointer
+    /// when it is a value.
     fn analyze_fn(
         fn_call: &mut FnCallExpression,
+        object: &Object,
+        access: &TokenType,
+        struct_name: &Identifier,
         parent: SymbolNodeRef,
+        root: SymbolNodeRef,
         iter: SymbolIterator,
     ) -> Result<TypeSpecifier> {
+        let fn_name = fn_call.fn_name.identifier()?.id.clone();
         let node = iter
-            .function(fn_call.fn_name.identifier()?.id.as_ref())
-            .ok_or_else(|| CompilerError::Semantic(String::from("Cannot find the function")))?
+            .function(fn_name.as_ref())
+            .ok_or_else(|| {
+                CompilerError::Semantic(Box::new(
+                    Diagnostic::error(
+                        ErrorCode::UndefinedFunction,
+                        format!("`{}` has no method `{}`", struct_name, fn_name),
+                    )
+                    .with_span(&fn_name.span),
+                ))
+            })?
             .find();
 
-        Self::check_access_specifier(&node.borrow().data.access, parent.clone())?;
-        // It is in the struct scope now
-        let root = iter.find();
-        return fn_call.analyze(parent, root);
+        Self::check_access_specifier(&node, parent.clone(), &fn_call.span)?;
+
+        let binding = node.borrow();
+        let fn_node = binding.data.node_type.function();
+        let Some(receiver) = &fn_node.receiver else {
+            return Err(CompilerError::Semantic(Box::new(
+                Diagnostic::error(
+                    ErrorCode::NotAMethod,
+                    format!("`{}::{}` has no receiver", struct_name, fn_name),
+                )
+                .with_span(&fn_call.span)
+                .with_help(format!("call it as `{}::{}(...)`", struct_name, fn_name)),
+            )));
+        };
+
+        let takes_pointer = matches!(receiver, TypeSpecifier::Pointer(_));
+        if takes_pointer && access == &TokenType::Dot && !object.is_place {
+            return Err(CompilerError::Semantic(Box::new(
+                Diagnostic::error(
+                    ErrorCode::InvalidReceiver,
+                    format!(
+                        "`{}` takes `{}` and cannot be called on a temporary value",
+                        fn_name, receiver
+                    ),
+                )
+                .with_span(&object.span)
+                .with_help("store the value in a variable first"),
+            )));
+        }
+
+        fn_call.check_params(fn_node)?;
+        fn_call.check_params_type(fn_node, parent, root)?;
+        return Ok(fn_node.return_type.clone());
     }
 
     fn analyze_identifier(
@@ -136,41 +272,70 @@ impl MemberAccessExpression {
     ) -> Result<TypeSpecifier> {
         let node = iter
             .struct_field(identifier.id.as_ref())
-            .ok_or_else(|| CompilerError::Semantic(String::from("Cannot find the struct field")))?
+            .ok_or_else(|| {
+                CompilerError::Semantic(Box::new(
+                    Diagnostic::error(ErrorCode::UndefinedField, "Cannot find the struct field")
+                        .with_span(&identifier.span),
+                ))
+            })?
             .find();
 
         let binding = node.borrow();
 
-        Self::check_access_specifier(&binding.data.access, parent)?;
+        Self::check_access_specifier(&node, parent, &identifier.span)?;
 
         let type_specifier = &binding.data.node_type.variable().type_specifier;
         return Ok(type_specifier.clone());
     }
 
-    fn analyze_member_access(
-        _member_access: &mut MemberAccessExpression,
-        _iter: SymbolIterator,
-    ) -> Result<TypeSpecifier> {
-        todo!()
-        // return Ok(TypeSpecifier::Void);
-    }
-
-    fn check_access_specifier(access: &Access, parent: SymbolNodeRef) -> Result<()> {
-        let Access::AccessSpecifier(access_specifier) = access else { unreachable!("Something went wrong!") };
-
-        if let AccessSpecifier::Public = access_specifier {
+    /// Private fields and methods can only be used inside the `impl` blocks
+    /// of their own struct.
+    pub(crate) fn check_access_specifier(
+        member: &SymbolNodeRef,
+        scope: SymbolNodeRef,
+        span: &Span,
+    ) -> Result<()> {
+        let Some(struct_node) = member.iter().parent() else {
+            return error_semantic!(span, Internal, "Struct member outside of a struct");
+        };
+        let struct_node = struct_node.find();
+        if Self::is_accessible(member, &struct_node, &scope) {
             return Ok(());
         }
-        // Parent always will be a function or method because it can only be called from those
-        // If parent's parent is Global then it cannot access the property
-        // If parent's parent is Struct then it can access the private property
-        let node = unsafe { parent.iter().parent().unwrap_unchecked() }.find();
-        let binding = node.borrow();
 
-        if let NodeTypes::Global = binding.data.node_type {
-            return Err(CompilerError::Semantic(String::from("Property is private")));
-        }
+        let binding = member.borrow();
+        let kind = match binding.data.node_type {
+            NodeTypes::Function(_) => "Method",
+            _ => "Field",
+        };
+        let name = &binding.data.name;
+        let struct_name = &struct_node.borrow().data.name;
+        return Err(CompilerError::Semantic(Box::new(
+            Diagnostic::error(
+                ErrorCode::PrivateAccess,
+                format!("{} `{}` of `{}` is private", kind, name, struct_name),
+            )
+            .with_span(span)
+            .with_label(
+                &binding.data.span,
+                format!("`{}` is declared here without `pub`", name),
+            )
+            .with_help(format!("only `impl {}` blocks can use it", struct_name)),
+        )));
+    }
 
-        return Ok(());
+    /// Whether `member`, a field or method of `struct_node`, can be used
+    /// from `scope`: public ones anywhere and private ones only inside the
+    /// `impl` blocks of the struct.
+    pub fn is_accessible(
+        member: &SymbolNodeRef,
+        struct_node: &SymbolNodeRef,
+        scope: &SymbolNodeRef,
+    ) -> bool {
+        let is_private = matches!(
+            member.borrow().data.access,
+            Access::AccessSpecifier(AccessSpecifier::Private)
+        );
+        return !is_private || scope.iter().is_within(struct_node);
     }
 }
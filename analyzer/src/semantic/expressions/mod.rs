@@ -3,6 +3,7 @@ pub mod array_init_expr;
 pub mod assignment_expr;
 pub mod binary_expr;
 pub mod cast_expr;
+pub mod enum_item_expr;
 pub mod fn_call_expr;
 pub mod identifier_expr;
 pub mod member_access_expr;
@@ -15,14 +16,29 @@ use crate::{
         expressions::{identifier_expression::IdentifierExpression, Expression},
         type_specifier::TypeSpecifier,
     },
+    diagnostic::{Diagnostic, ErrorCode},
     error::{CompilerError, Result},
+    error_semantic,
     symbol_table::symbol::SymbolNodeRef,
 };
 
+use super::constant;
+
 use super::AstAnalyze;
 
 impl AstAnalyze for Expression {
     fn analyze(&mut self, parent: SymbolNodeRef, root: SymbolNodeRef) -> Result<TypeSpecifier> {
+        if let Expression::Identifier(identifier_expr) = self {
+            if let Some(constant) = identifier_expr.constant(parent.clone()) {
+                *self = constant::literal(&constant.value, &identifier_expr.span)?;
+                return Ok(constant.type_specifier);
+            }
+        }
+        if let Expression::FnCall(fn_call_expr) = self {
+            if let Some(enum_item) = fn_call_expr.enum_item(root.clone()) {
+                *self = Expression::EnumItem(enum_item);
+            }
+        }
         return match self {
             Expression::Binary(binary_expr) => binary_expr.analyze(parent, root),
             Expression::Assignment(assignment_expr) => assignment_expr.analyze(parent, root),
@@ -34,18 +50,40 @@ impl AstAnalyze for Expression {
             Expression::ObjectInit(object_init_expr) => object_init_expr.analyze(parent, root),
             Expression::Cast(cast_expr) => cast_expr.analyze(parent, root),
             Expression::Identifier(identifier_expr) => identifier_expr.analyze(parent, root),
+            Expression::EnumItem(enum_item_expr) => enum_item_expr.analyze(parent, root),
             Expression::Primitive(primitive_expr) => primitive_expr.analyze(parent, root),
         };
     }
 }
 
 impl Expression {
-    fn identifier(&self) -> Result<&IdentifierExpression> {
+    /// Fails when the expression names a constant, which has no storage to
+    /// write to or point at.
+    pub(crate) fn check_not_constant(&self, parent: SymbolNodeRef) -> Result<()> {
+        if let Expression::Identifier(identifier) = self {
+            if identifier.constant(parent).is_some() {
+                let message = format!(
+                    "Cannot modify or take the address of constant {}",
+                    identifier.id
+                );
+                return Err(CompilerError::Semantic(Box::new(
+                    Diagnostic::error(ErrorCode::ConstantModified, message)
+                        .with_span(&identifier.span)
+                        .with_help("declare a variable with `let` to get a mutable copy"),
+                )));
+            }
+        }
+        return Ok(());
+    }
+
+    pub(crate) fn identifier(&self) -> Result<&IdentifierExpression> {
         if let Expression::Identifier(identifier) = self {
             return Ok(identifier);
         }
-        return Err(CompilerError::Semantic(String::from(
-            "Expected Identifier Expression",
-        )));
+        return error_semantic!(
+            self.span(),
+            InvalidExpression,
+            "Expected Identifier Expression"
+        );
     }
 }
@@ -1,9 +1,14 @@
 use crate::{
     ast::{
-        expressions::object_init_expr::{Field, ObjectInitExpression},
+        expressions::{
+            member_access_expr::MemberAccessExpression,
+            object_init_expr::{Field, ObjectInitExpression},
+        },
         type_specifier::TypeSpecifier,
     },
+    diagnostic::{Diagnostic, ErrorCode},
     error::{CompilerError, Result},
+    error_semantic,
     semantic::AstAnalyze,
     symbol_table::symbol::{
         iter::{IterNodeTypes, ToIter},
@@ -18,16 +23,21 @@ impl AstAnalyze for ObjectInitExpression {
         let iter = root
             .iter()
             .struct_sym(struct_name.id.as_ref())
-            .ok_or_else(|| CompilerError::Semantic(String::from("Cannot find the struct")))?;
+            .ok_or_else(|| {
+                CompilerError::Semantic(Box::new(
+                    Diagnostic::error(ErrorCode::UndefinedStruct, "Cannot find the struct")
+                        .with_span(&struct_name.span),
+                ))
+            })?;
 
         let len = iter.count(IterNodeTypes::StructField);
         let node = iter.find();
 
         if self.fields.len() > len {
-            return Err(CompilerError::Semantic(String::from("Too many fields")));
+            return error_semantic!(&self.span, FieldCount, "Too many fields");
         }
         if self.fields.len() < len {
-            return Err(CompilerError::Semantic(String::from("Missing fields")));
+            return error_semantic!(&self.span, FieldCount, "Missing fields");
         }
 
         for field in self.fields.iter_mut() {
@@ -46,12 +56,16 @@ impl AstAnalyze for Field {
             .iter()
             .struct_field(self.name.as_ref())
             .ok_or_else(|| {
-                CompilerError::Semantic(format!(
-                    "Cannot find the struct field named {}",
-                    self.name.as_ref()
+                CompilerError::Semantic(Box::new(
+                    Diagnostic::error(
+                        ErrorCode::UndefinedField,
+                        format!("Cannot find the struct field named {}", self.name.as_ref()),
+                    )
+                    .with_span(&self.name.span),
                 ))
             })?
             .find();
+        MemberAccessExpression::check_access_specifier(&field_node, root.clone(), &self.name.span)?;
 
         //Since root is the parent
         //Since the real root is lost and parent is the struct which scope is global. It's parent is the root that is needed.
@@ -63,9 +77,11 @@ impl AstAnalyze for Field {
         let variable = binding.data.node_type.variable();
 
         if variable.type_specifier != type_specifier {
-            return Err(CompilerError::Semantic(String::from(
-                "Struct type and expression types do not matches",
-            )));
+            return error_semantic!(
+                self.value.span(),
+                TypeMismatch,
+                "Struct type and expression types do not matches"
+            );
         }
 
         return Ok(type_specifier);
@@ -6,7 +6,8 @@ use crate::{
         },
         type_specifier::TypeSpecifier,
     },
-    error::{CompilerError, Result},
+    error::Result,
+    error_semantic,
     semantic::AstAnalyze,
     symbol_table::symbol::SymbolNodeRef,
 };
@@ -52,10 +53,9 @@ impl UnaryExpression {
                 | Expression::Unary(_)
         );
         if !result {
-            return Err(CompilerError::Semantic(String::from(
-                "Unexpected expression",
-            )));
+            return error_semantic!(&self.span, InvalidExpression, "Unexpected expression");
         }
+        self.value.check_not_constant(parent.clone())?;
         let expr_type = self.value.analyze(parent, root)?;
         self.check_type(&expr_type)?;
         return Ok(expr_type);
@@ -71,9 +71,7 @@ impl UnaryExpression {
             Expression::ArrayInit(_) | Expression::ObjectInit(_)
         );
         if result {
-            return Err(CompilerError::Semantic(String::from(
-                "Unexpected expression",
-            )));
+            return error_semantic!(&self.span, InvalidExpression, "Unexpected expression");
         }
 
         let expr_type = self.value.analyze(parent, root)?;
@@ -87,9 +85,7 @@ impl UnaryExpression {
             Expression::ArrayInit(_) | Expression::ObjectInit(_)
         );
         if result {
-            return Err(CompilerError::Semantic(String::from(
-                "Unexpected expression",
-            )));
+            return error_semantic!(&self.span, InvalidExpression, "Unexpected expression");
         }
 
         let expr_type = self.value.analyze(parent, root)?;
@@ -97,18 +93,22 @@ impl UnaryExpression {
         if let UnaryOperators::BitwiseNot = self.operator {
             return match expr_type {
                 TypeSpecifier::Int | TypeSpecifier::Usize => Ok(expr_type),
-                _ => Err(CompilerError::Semantic(String::from(
-                    "Type not supported for the operation",
-                ))),
+                _ => error_semantic!(
+                    &self.span,
+                    UnsupportedOperand,
+                    "Type not supported for the operation"
+                ),
             };
         }
 
         if let UnaryOperators::LogicalNot = self.operator {
             let TypeSpecifier::Bool = expr_type else {
-            return Err(CompilerError::Semantic(String::from(
-                    "Type not supported for the operation",
-                )));
-          };
+                return error_semantic!(
+                    &self.span,
+                    UnsupportedOperand,
+                    "Type not supported for the operation"
+                );
+            };
         }
         return Ok(expr_type);
     }
@@ -127,11 +127,10 @@ impl UnaryExpression {
         );
 
         if !result {
-            return Err(CompilerError::Semantic(String::from(
-                "Unexpected expression",
-            )));
+            return error_semantic!(&self.span, InvalidExpression, "Unexpected expression");
         }
 
+        self.value.check_not_constant(parent.clone())?;
         let expr_type = self.value.analyze(parent, root)?;
         return Ok(TypeSpecifier::Pointer(Box::new(expr_type)));
     }
@@ -149,17 +148,13 @@ impl UnaryExpression {
                 | Expression::Unary(_)
         );
         if !result {
-            return Err(CompilerError::Semantic(String::from(
-                "Unexpected expression",
-            )));
+            return error_semantic!(&self.span, InvalidExpression, "Unexpected expression");
         }
         let expr_type = self.value.analyze(parent, root)?;
         if let TypeSpecifier::Pointer(type_specifier) = expr_type {
             return Ok(*type_specifier);
         }
-        return Err(CompilerError::Semantic(String::from(
-            "Expected pointer type",
-        )));
+        return error_semantic!(&self.span, UnsupportedOperand, "Expected pointer type");
     }
 
     fn check_type(&self, expr_type: &TypeSpecifier) -> Result<()> {
@@ -172,9 +167,11 @@ impl UnaryExpression {
         );
 
         if !result {
-            return Err(CompilerError::Semantic(String::from(
-                "Value is not supported for the operator",
-            )));
+            return error_semantic!(
+                &self.span,
+                UnsupportedOperand,
+                "Value is not supported for the operator"
+            );
         }
 
         return Ok(());
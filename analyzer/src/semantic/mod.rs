@@ -1,3 +1,4 @@
+pub mod constant;
 pub mod declarations;
 pub mod expressions;
 pub mod program;
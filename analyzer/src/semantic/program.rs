@@ -1,16 +1,21 @@
 use crate::{
     ast::{program::Program, type_specifier::TypeSpecifier},
-    error::Result,
+    error::{CompilerError, Result},
     symbol_table::symbol::SymbolNodeRef,
 };
 
 use super::AstAnalyze;
 
 impl AstAnalyze for Program {
+    /// Declarations are checked independently, so a failed one does not
+    /// hide the errors of the rest.
     fn analyze(&mut self, parent: SymbolNodeRef, root: SymbolNodeRef) -> Result<TypeSpecifier> {
-        for decl in self.declarations.iter_mut() {
-            decl.analyze(parent.clone(), root.clone())?;
-        }
+        let errors = self
+            .declarations
+            .iter_mut()
+            .filter_map(|decl| decl.analyze(parent.clone(), root.clone()).err())
+            .collect();
+        CompilerError::from_errors(errors)?;
         return Ok(TypeSpecifier::Void);
     }
 }
@@ -0,0 +1,52 @@
+use crate::{
+    ast::{
+        identifier::Identifier, statements::break_stmt::BreakStmt, type_specifier::TypeSpecifier,
+    },
+    error::Result,
+    error_semantic,
+    lexer::token::Span,
+    semantic::AstAnalyze,
+    symbol_table::symbol::{node::NodeTypes, SymbolNodeRef},
+};
+
+impl AstAnalyze for BreakStmt {
+    fn analyze(&mut self, parent: SymbolNodeRef, _root: SymbolNodeRef) -> Result<TypeSpecifier> {
+        check_loop(parent, self.label.as_ref(), &self.span, "break")?;
+        return Ok(TypeSpecifier::Void);
+    }
+}
+
+/// Checks that a `break` or `continue` is inside a loop of its function, and
+/// inside one with its label if it names one.
+pub(crate) fn check_loop(
+    parent: SymbolNodeRef,
+    label: Option<&Identifier>,
+    span: &Span,
+    keyword: &str,
+) -> Result<()> {
+    let mut node = Some(parent);
+    while let Some(current) = node {
+        let binding = current.borrow();
+        match &binding.data.node_type {
+            NodeTypes::Loop(loop_label) => match label {
+                Some(label) if loop_label.as_deref() != Some(label.as_ref()) => {}
+                _ => return Ok(()),
+            },
+            NodeTypes::Function(_) => break,
+            _ => {}
+        }
+        node = binding.parent.clone();
+    }
+    return match label {
+        Some(label) => error_semantic!(
+            &label.span,
+            UndefinedLabel,
+            format!("No loop labeled `{}` around this `{}`", label, keyword)
+        ),
+        None => error_semantic!(
+            span,
+            OutsideLoop,
+            format!("`{}` outside of a loop", keyword)
+        ),
+    };
+}
@@ -0,0 +1,15 @@
+use crate::{
+    ast::{statements::continue_stmt::ContinueStmt, type_specifier::TypeSpecifier},
+    error::Result,
+    semantic::AstAnalyze,
+    symbol_table::symbol::SymbolNodeRef,
+};
+
+use super::break_stmt::check_loop;
+
+impl AstAnalyze for ContinueStmt {
+    fn analyze(&mut self, parent: SymbolNodeRef, _root: SymbolNodeRef) -> Result<TypeSpecifier> {
+        check_loop(parent, self.label.as_ref(), &self.span, "continue")?;
+        return Ok(TypeSpecifier::Void);
+    }
+}
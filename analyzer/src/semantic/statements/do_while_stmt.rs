@@ -0,0 +1,46 @@
+use std::collections::HashMap;
+
+use crate::{
+    ast::{statements::do_while_stmt::DoWhileStmt, type_specifier::TypeSpecifier},
+    error::Result,
+    error_semantic,
+    semantic::AstAnalyze,
+    symbol_table::symbol::{
+        data::{Access, SymbolData},
+        node::NodeTypes,
+        SymbolNode, SymbolNodeRef,
+    },
+};
+
+impl AstAnalyze for DoWhileStmt {
+    fn analyze(&mut self, parent: SymbolNodeRef, root: SymbolNodeRef) -> Result<TypeSpecifier> {
+        let do_while_node: SymbolNodeRef = self.create_symbol(parent.clone())?;
+        self.block.analyze(do_while_node, root.clone())?;
+        let condition_type = self.condition.analyze(parent, root)?;
+
+        if condition_type != TypeSpecifier::Bool {
+            return error_semantic!(
+                self.condition.span(),
+                ConditionNotBool,
+                "Condition type must be boolean"
+            );
+        }
+        return Ok(TypeSpecifier::Void);
+    }
+}
+
+impl DoWhileStmt {
+    fn create_symbol(&self, parent: SymbolNodeRef) -> Result<SymbolNodeRef> {
+        let data = SymbolData::new(
+            "do_while_1".to_owned(),
+            Access::Local,
+            NodeTypes::Loop(self.label.as_ref().map(ToString::to_string)),
+        );
+        let node: SymbolNodeRef =
+            SymbolNode::new(data, Some(parent.clone()), HashMap::new()).into();
+
+        parent.borrow_mut().append(node.clone());
+
+        return Ok(node);
+    }
+}
@@ -5,7 +5,8 @@ use crate::{
         statements::for_stmt::{ForStmt, VariableInit},
         type_specifier::TypeSpecifier,
     },
-    error::{CompilerError, Result},
+    error::Result,
+    error_semantic,
     semantic::AstAnalyze,
     symbol_table::symbol::{
         data::{Access, SymbolData},
@@ -29,9 +30,11 @@ impl AstAnalyze for ForStmt {
         let condition_type = self.condition.analyze(for_node.clone(), root.clone())?;
 
         if condition_type != TypeSpecifier::Bool {
-            return Err(CompilerError::Semantic(String::from(
-                "Condition type must be boolean",
-            )));
+            return error_semantic!(
+                self.condition.span(),
+                ConditionNotBool,
+                "Condition type must be boolean"
+            );
         }
         // TODO Warn
         self.increment.analyze(for_node.clone(), root.clone())?;
@@ -44,7 +47,11 @@ impl AstAnalyze for ForStmt {
 
 impl ForStmt {
     fn create_symbol(&mut self, parent: SymbolNodeRef) -> Result<SymbolNodeRef> {
-        let data = SymbolData::new("for_1".to_owned(), Access::Local, NodeTypes::Block);
+        let data = SymbolData::new(
+            "for_1".to_owned(),
+            Access::Local,
+            NodeTypes::Loop(self.label.as_ref().map(ToString::to_string)),
+        );
         let symbol: SymbolNodeRef =
             SymbolNode::new(data, Some(parent.clone()), HashMap::new()).into();
 
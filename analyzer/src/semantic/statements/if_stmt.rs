@@ -5,7 +5,8 @@ use crate::{
         statements::if_stmt::{ElseIfStmt, IfStmt},
         type_specifier::TypeSpecifier,
     },
-    error::{CompilerError, Result},
+    error::Result,
+    error_semantic,
     semantic::AstAnalyze,
     symbol_table::symbol::{
         data::{Access, SymbolData},
@@ -19,9 +20,11 @@ impl AstAnalyze for IfStmt {
         let condition_type = self.condition.analyze(parent.clone(), root.clone())?;
 
         if condition_type != TypeSpecifier::Bool {
-            return Err(CompilerError::Semantic(String::from(
-                "Condition type must be boolean",
-            )));
+            return error_semantic!(
+                self.condition.span(),
+                ConditionNotBool,
+                "Condition type must be boolean"
+            );
         }
 
         let if_node: SymbolNodeRef = Self::create_symbol("if_1", parent.clone())?;
@@ -45,9 +48,11 @@ impl AstAnalyze for ElseIfStmt {
         let condition_type = self.condition.analyze(parent.clone(), root.clone())?;
 
         if condition_type != TypeSpecifier::Bool {
-            return Err(CompilerError::Semantic(String::from(
-                "Condition type must be boolean",
-            )));
+            return error_semantic!(
+                self.condition.span(),
+                ConditionNotBool,
+                "Condition type must be boolean"
+            );
         }
 
         let else_if_node = IfStmt::create_symbol("else_if_1", parent)?;
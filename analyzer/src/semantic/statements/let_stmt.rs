@@ -1,6 +1,7 @@
 use crate::{
     ast::{statements::let_stmt::LetStmt, type_specifier::TypeSpecifier},
-    error::{CompilerError, Result},
+    error::Result,
+    error_semantic,
     semantic::AstAnalyze,
     symbol_table::{
         symbol::{SymbolNode, SymbolNodeRef},
@@ -15,7 +16,7 @@ impl AstAnalyze for LetStmt {
             match &self.type_specifier {
                 Some(type_info) => {
                     if &type_specifier != type_info {
-                        return Err(CompilerError::Semantic(String::from("Miss matched types")));
+                        return error_semantic!(value.span(), TypeMismatch, "Miss matched types");
                     }
                 }
                 _ => {
@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+
+use crate::{
+    ast::{
+        statements::{
+            match_stmt::{MatchArm, MatchStmt},
+            switch_stmt::SwitchStmt,
+        },
+        type_specifier::TypeSpecifier,
+    },
+    diagnostic::{Diagnostic, ErrorCode},
+    error::{CompilerError, Result},
+    error_semantic,
+    semantic::AstAnalyze,
+    symbol_table::symbol::{
+        data::{Access, SymbolData},
+        iter::ToIter,
+        node::NodeTypes,
+        SymbolNode, SymbolNodeRef,
+    },
+};
+
+impl AstAnalyze for MatchStmt {
+    fn analyze(&mut self, parent: SymbolNodeRef, root: SymbolNodeRef) -> Result<TypeSpecifier> {
+        let value_type = self.value.analyze(parent.clone(), root.clone())?;
+        let enum_name = match &value_type {
+            TypeSpecifier::UserDefine(name) if root.iter().enum_sym(name.as_ref()).is_some() => {
+                name.to_string()
+            }
+            _ => {
+                return error_semantic!(
+                    self.value.span(),
+                    UnsupportedOperand,
+                    format!("Cannot match on a value of type {}", value_type)
+                )
+            }
+        };
+
+        let mut handled: Vec<String> = Vec::new();
+        for arm in self.arms.iter_mut() {
+            if arm.name.as_ref() != enum_name {
+                return error_semantic!(
+                    &arm.name.span,
+                    TypeMismatch,
+                    format!("Pattern of enum {} in a match over {}", arm.name, enum_name)
+                );
+            }
+            if handled.contains(&arm.item.id) {
+                return error_semantic!(
+                    &arm.item.span,
+                    DuplicateCase,
+                    format!("{}::{} is already handled", arm.name, arm.item)
+                );
+            }
+            handled.push(arm.item.to_string());
+            let arm_node = Self::create_symbol("arm_1", parent.clone())?;
+            arm.bind(arm_node.clone(), root.clone())?;
+            arm.block.analyze(arm_node, root.clone())?;
+        }
+
+        if let Some(default) = self.default.as_mut() {
+            let default_node = Self::create_symbol("default_1", parent)?;
+            default.analyze(default_node, root)?;
+            return Ok(TypeSpecifier::Void);
+        }
+        let missing: Vec<String> = SwitchStmt::enum_items(root, &enum_name)
+            .into_iter()
+            .filter(|item| !handled.contains(item))
+            .collect();
+        if !missing.is_empty() {
+            return Err(CompilerError::Semantic(Box::new(
+                Diagnostic::error(
+                    ErrorCode::NonExhaustiveSwitch,
+                    format!(
+                        "Match over {} does not handle {}",
+                        enum_name,
+                        missing.join(", ")
+                    ),
+                )
+                .with_span(&self.span)
+                .with_help("add the missing arms or a `_` arm"),
+            )));
+        }
+        return Ok(TypeSpecifier::Void);
+    }
+}
+
+impl MatchStmt {
+    fn create_symbol(name: &str, parent: SymbolNodeRef) -> Result<SymbolNodeRef> {
+        let data = SymbolData::new(name.to_owned(), Access::Local, NodeTypes::Block);
+        let symbol: SymbolNodeRef =
+            SymbolNode::new(data, Some(parent.clone()), HashMap::new()).into();
+        parent.borrow_mut().append(symbol.clone());
+        return Ok(symbol);
+    }
+}
+
+impl MatchArm {
+    /// Declares the bindings of the pattern as variables of the arm, typed
+    /// by the values the item carries.
+    fn bind(&self, arm_node: SymbolNodeRef, root: SymbolNodeRef) -> Result<()> {
+        let item = root
+            .iter()
+            .enum_sym(self.name.as_ref())
+            .and_then(|enum_sym| enum_sym.enum_item(self.item.as_ref()));
+        let Some(item) = item else {
+            return error_semantic!(
+                &self.item.span,
+                UndefinedItem,
+                format!("Enum {} has no item {}", self.name, self.item)
+            );
+        };
+        let payload = item
+            .find()
+            .borrow()
+            .data
+            .node_type
+            .enum_item()
+            .payload
+            .clone();
+        if payload.len() != self.bindings.len() {
+            return error_semantic!(
+                &self.span,
+                ArgumentCount,
+                format!(
+                    "{}::{} carries {} values but the pattern binds {}",
+                    self.name,
+                    self.item,
+                    payload.len(),
+                    self.bindings.len()
+                )
+            );
+        }
+        for (binding, type_specifier) in self.bindings.iter().zip(payload.iter()) {
+            let Some(binding) = binding else {
+                continue;
+            };
+            let symbol = SymbolNode::from((binding, type_specifier, arm_node.clone()));
+            arm_node.borrow_mut().append(symbol.into());
+        }
+        return Ok(());
+    }
+}
@@ -1,9 +1,14 @@
 pub mod block_stmt;
+pub mod break_stmt;
+pub mod continue_stmt;
 pub mod defer_stmt;
+pub mod do_while_stmt;
 pub mod for_stmt;
 pub mod if_stmt;
 pub mod let_stmt;
+pub mod match_stmt;
 pub mod return_stmt;
+pub mod switch_stmt;
 pub mod while_stmt;
 
 use crate::{
@@ -18,11 +23,17 @@ impl AstAnalyze for Statements {
     fn analyze(&mut self, parent: SymbolNodeRef, root: SymbolNodeRef) -> Result<TypeSpecifier> {
         match self {
             Statements::Let(let_stmt) => let_stmt.analyze(parent, root),
+            Statements::Const(const_decl) => const_decl.analyze(parent, root),
             Statements::Return(return_stmt) => return_stmt.analyze(parent, root),
             Statements::Defer(defer_stmt) => defer_stmt.analyze(parent, root),
             Statements::For(for_stmt) => for_stmt.analyze(parent, root),
             Statements::While(while_stmt) => while_stmt.analyze(parent, root),
+            Statements::DoWhile(do_while_stmt) => do_while_stmt.analyze(parent, root),
             Statements::If(if_stmt) => if_stmt.analyze(parent, root),
+            Statements::Switch(switch_stmt) => switch_stmt.analyze(parent, root),
+            Statements::Match(match_stmt) => match_stmt.analyze(parent, root),
+            Statements::Break(break_stmt) => break_stmt.analyze(parent, root),
+            Statements::Continue(continue_stmt) => continue_stmt.analyze(parent, root),
             Statements::Block(block) => block.analyze(parent, root),
             Statements::Expression(expression) => expression.analyze(parent, root),
         }
@@ -1,32 +1,53 @@
 use crate::{
     ast::{statements::return_stmt::ReturnStmt, type_specifier::TypeSpecifier},
+    diagnostic::{Diagnostic, ErrorCode},
     error::{CompilerError, Result},
+    error_semantic,
     semantic::AstAnalyze,
     symbol_table::symbol::{iter::ToIter, node::NodeTypes, SymbolNodeRef},
 };
 
 impl AstAnalyze for ReturnStmt {
     fn analyze(&mut self, parent: SymbolNodeRef, root: SymbolNodeRef) -> Result<TypeSpecifier> {
-        let parent_binding = parent.clone();
-        let binding = parent_binding.borrow();
-        let fn_node = &binding.data.node_type;
-
-        let NodeTypes::Function(fn_node) = fn_node else {
-        let parent:SymbolNodeRef = parent.iter().parent().ok_or_else(|| CompilerError::Semantic(String::from("Cannot find the parent")))?.find();
-            return self.analyze(parent,root);
-        };
+        let return_type = self.return_type(parent.clone())?;
 
+        // The value sees the variables of the blocks around the `return`.
         if let Some(value) = self.value.as_mut() {
             let type_specifier = value.analyze(parent, root)?;
-            if type_specifier == fn_node.return_type {
+            if type_specifier == return_type {
                 return Ok(TypeSpecifier::Void);
             }
-        } else if fn_node.return_type == TypeSpecifier::Void {
+        } else if return_type == TypeSpecifier::Void {
             return Ok(TypeSpecifier::Void);
         }
 
-        return Err(CompilerError::Semantic(String::from(
-            "Return value and return type of function does not match",
-        )));
+        return error_semantic!(
+            &self.span,
+            TypeMismatch,
+            "Return value and return type of function does not match"
+        );
+    }
+}
+
+impl ReturnStmt {
+    /// The return type of the function the statement is in.
+    fn return_type(&self, parent: SymbolNodeRef) -> Result<TypeSpecifier> {
+        let mut node = parent;
+        loop {
+            if let NodeTypes::Function(fn_node) = &node.borrow().data.node_type {
+                return Ok(fn_node.return_type.clone());
+            }
+            let next = node
+                .iter()
+                .parent()
+                .ok_or_else(|| {
+                    CompilerError::Semantic(Box::new(
+                        Diagnostic::error(ErrorCode::Internal, "Cannot find the parent")
+                            .with_span(&self.span),
+                    ))
+                })?
+                .find();
+            node = next;
+        }
     }
 }
@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+
+use crate::{
+    ast::{
+        expressions::Expression, statements::switch_stmt::SwitchStmt, type_specifier::TypeSpecifier,
+    },
+    diagnostic::{Diagnostic, ErrorCode},
+    error::{CompilerError, Result},
+    error_semantic,
+    semantic::{
+        constant::{self, ConstEvaluate, ConstantScopes},
+        AstAnalyze,
+    },
+    symbol_table::symbol::{
+        data::{Access, SymbolData},
+        iter::ToIter,
+        node::NodeTypes,
+        SymbolNode, SymbolNodeRef,
+    },
+};
+
+impl AstAnalyze for SwitchStmt {
+    fn analyze(&mut self, parent: SymbolNodeRef, root: SymbolNodeRef) -> Result<TypeSpecifier> {
+        let value_type = self.value.analyze(parent.clone(), root.clone())?;
+        let enum_items = match &value_type {
+            TypeSpecifier::Int | TypeSpecifier::Usize | TypeSpecifier::Char => None,
+            TypeSpecifier::UserDefine(name)
+                if root
+                    .iter()
+                    .enum_sym(name.as_ref())
+                    .is_some_and(|sym| !sym.is_tagged()) =>
+            {
+                Some(Self::enum_items(root.clone(), name.as_ref()))
+            }
+            TypeSpecifier::UserDefine(name) if root.iter().enum_sym(name.as_ref()).is_some() => {
+                return Err(CompilerError::Semantic(Box::new(
+                    Diagnostic::error(
+                        ErrorCode::UnsupportedOperand,
+                        format!("Cannot switch on {}, whose items carry values", value_type),
+                    )
+                    .with_span(self.value.span())
+                    .with_help("use `match` to look at the values"),
+                )))
+            }
+            _ => {
+                return error_semantic!(
+                    self.value.span(),
+                    UnsupportedOperand,
+                    format!("Cannot switch on a value of type {}", value_type)
+                )
+            }
+        };
+
+        let mut handled: Vec<String> = Vec::new();
+        for case in self.cases.iter_mut() {
+            let key = Self::case_key(&mut case.value, &value_type, parent.clone(), root.clone())?;
+            if handled.contains(&key) {
+                return error_semantic!(
+                    &case.value.span(),
+                    DuplicateCase,
+                    format!("Case {} is already handled", key)
+                );
+            }
+            handled.push(key);
+            let case_node = Self::create_symbol("case_1", parent.clone())?;
+            case.block.analyze(case_node, root.clone())?;
+        }
+
+        if let Some(default) = self.default.as_mut() {
+            let default_node = Self::create_symbol("default_1", parent)?;
+            default.analyze(default_node, root)?;
+        } else if let Some(items) = enum_items {
+            let missing: Vec<String> = items
+                .into_iter()
+                .filter(|item| !handled.contains(item))
+                .collect();
+            if !missing.is_empty() {
+                return Err(CompilerError::Semantic(Box::new(
+                    Diagnostic::error(
+                        ErrorCode::NonExhaustiveSwitch,
+                        format!(
+                            "Switch over {} does not handle {}",
+                            value_type,
+                            missing.join(", ")
+                        ),
+                    )
+                    .with_span(&self.span)
+                    .with_help("add the missing cases or a `default`"),
+                )));
+            }
+        }
+        return Ok(TypeSpecifier::Void);
+    }
+}
+
+impl SwitchStmt {
+    /// What a case matches, checked against the type switched over: the
+    /// item for an enum, the constant otherwise, which becomes a literal.
+    fn case_key(
+        value: &mut Expression,
+        value_type: &TypeSpecifier,
+        parent: SymbolNodeRef,
+        root: SymbolNodeRef,
+    ) -> Result<String> {
+        let case_type = value.analyze(parent, root)?;
+        if &case_type != value_type {
+            return error_semantic!(
+                value.span(),
+                TypeMismatch,
+                format!("Case of type {} in a switch over {}", case_type, value_type)
+            );
+        }
+        if let Expression::EnumItem(enum_item) = value {
+            return Ok(enum_item.item.to_string());
+        }
+        let constant = value.evaluate(&ConstantScopes::new())?;
+        *value = constant::literal(&constant, value.span())?;
+        return Ok(constant.as_index()?.to_string());
+    }
+
+    /// The items of the enum `name`, in the order they are declared.
+    pub(crate) fn enum_items(root: SymbolNodeRef, name: &str) -> Vec<String> {
+        let Some(enum_node) = root.iter().enum_sym(name) else {
+            return Vec::new();
+        };
+        let enum_node = enum_node.find();
+        let enum_node = enum_node.borrow();
+        let mut items: Vec<_> = enum_node
+            .children
+            .values()
+            .map(|item| {
+                let item = item.borrow();
+                (
+                    item.data.span.start().cursor_start(),
+                    item.data.name.clone(),
+                )
+            })
+            .collect();
+        items.sort();
+        return items.into_iter().map(|(_, name)| name).collect();
+    }
+
+    fn create_symbol(name: &str, parent: SymbolNodeRef) -> Result<SymbolNodeRef> {
+        let data = SymbolData::new(name.to_owned(), Access::Local, NodeTypes::Block);
+        let symbol: SymbolNodeRef =
+            SymbolNode::new(data, Some(parent.clone()), HashMap::new()).into();
+        parent.borrow_mut().append(symbol.clone());
+        return Ok(symbol);
+    }
+}
@@ -2,7 +2,8 @@ use std::collections::HashMap;
 
 use crate::{
     ast::{statements::while_stmt::WhileStmt, type_specifier::TypeSpecifier},
-    error::{CompilerError, Result},
+    error::Result,
+    error_semantic,
     semantic::AstAnalyze,
     symbol_table::symbol::{
         data::{Access, SymbolData},
@@ -17,9 +18,11 @@ impl AstAnalyze for WhileStmt {
         let condition_type = self.condition.analyze(parent, root.clone())?;
 
         if condition_type != TypeSpecifier::Bool {
-            return Err(CompilerError::Semantic(String::from(
-                "Condition type must be boolean",
-            )));
+            return error_semantic!(
+                self.condition.span(),
+                ConditionNotBool,
+                "Condition type must be boolean"
+            );
         }
 
         self.block.analyze(while_node, root)?;
@@ -29,7 +32,11 @@ impl AstAnalyze for WhileStmt {
 
 impl WhileStmt {
     fn create_symbol(&self, parent: SymbolNodeRef) -> Result<SymbolNodeRef> {
-        let data = SymbolData::new("while_1".to_owned(), Access::Local, NodeTypes::Block);
+        let data = SymbolData::new(
+            "while_1".to_owned(),
+            Access::Local,
+            NodeTypes::Loop(self.label.as_ref().map(ToString::to_string)),
+        );
         let node: SymbolNodeRef =
             SymbolNode::new(data, Some(parent.clone()), HashMap::new()).into();
 
@@ -28,6 +28,12 @@ impl Display for SymbolTable {
     }
 }
 
+impl Default for SymbolTable {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
 impl SymbolTable {
     pub fn new() -> Self {
         let data = SymbolData::new(
@@ -44,9 +50,9 @@ impl SymbolTable {
     }
 }
 
-impl Into<SymbolTableRef> for SymbolTable {
-    fn into(self) -> SymbolTableRef {
-        return Rc::new(RefCell::new(self));
+impl From<SymbolTable> for SymbolTableRef {
+    fn from(value: SymbolTable) -> Self {
+        return Rc::new(RefCell::new(value));
     }
 }
 
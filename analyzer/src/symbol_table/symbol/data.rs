@@ -1,7 +1,11 @@
 use std::fmt::Display;
 
-use crate::ast::{
-    access_specifier::AccessSpecifier, type_specifier::TypeSpecifier, variable_type::VariableType,
+use crate::{
+    ast::{
+        access_specifier::AccessSpecifier, identifier::Identifier, type_specifier::TypeSpecifier,
+        variable_type::VariableType,
+    },
+    lexer::token::Span,
 };
 
 use super::node::{FunctionNode, NodeTypes};
@@ -11,6 +15,8 @@ pub struct SymbolData {
     pub name: String,
     pub access: Access,
     pub node_type: NodeTypes,
+    /// Where the symbol is defined, empty for scopes.
+    pub span: Span,
 }
 
 #[derive(Debug)]
@@ -25,14 +31,35 @@ impl SymbolData {
             name,
             access,
             node_type,
+            span: Span::default(),
         }
     }
+
+    pub fn with_span(mut self, span: &Span) -> Self {
+        self.span = span.clone();
+        return self;
+    }
+
+    /// Records the receiver of a method, only meaningful for functions.
+    pub fn with_receiver(mut self, receiver: Option<&VariableType>) -> Self {
+        if let NodeTypes::Function(function) = &mut self.node_type {
+            function.receiver = receiver.map(|receiver| receiver.type_specifier.clone());
+        }
+        return self;
+    }
 }
 
-impl From<(&str, &AccessSpecifier, &TypeSpecifier, &Vec<VariableType>)> for SymbolData {
+impl
+    From<(
+        &Identifier,
+        &AccessSpecifier,
+        &TypeSpecifier,
+        &Vec<VariableType>,
+    )> for SymbolData
+{
     fn from(
         (name, access_specifier, return_type, params): (
-            &str,
+            &Identifier,
             &AccessSpecifier,
             &TypeSpecifier,
             &Vec<VariableType>,
@@ -46,10 +73,11 @@ impl From<(&str, &AccessSpecifier, &TypeSpecifier, &Vec<VariableType>)> for Symb
                 .collect(),
         );
         return SymbolData::new(
-            name.to_owned(),
+            name.to_string(),
             Access::from(access_specifier),
             NodeTypes::Function(func_node),
-        );
+        )
+        .with_span(&name.span);
     }
 }
 
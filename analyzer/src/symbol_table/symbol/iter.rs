@@ -1,3 +1,5 @@
+use std::rc::Rc;
+
 use super::{node::NodeTypes, SymbolNodeRef};
 
 pub struct SymbolIterator {
@@ -8,8 +10,10 @@ pub struct SymbolIterator {
 pub enum IterNodeTypes {
     Global,
     Variable,
+    Constant,
     Function,
     Block,
+    Loop,
     Struct,
     StructField,
     Enum,
@@ -20,13 +24,15 @@ impl From<&NodeTypes> for IterNodeTypes {
     fn from(value: &NodeTypes) -> Self {
         match value {
             NodeTypes::Variable(_) => IterNodeTypes::Variable,
+            NodeTypes::Constant(_) => IterNodeTypes::Constant,
             NodeTypes::Function(_) => IterNodeTypes::Function,
             NodeTypes::Block => IterNodeTypes::Block,
+            NodeTypes::Loop(_) => IterNodeTypes::Loop,
             NodeTypes::Global => IterNodeTypes::Global,
             NodeTypes::Struct => IterNodeTypes::Struct,
             NodeTypes::StructField(_) => IterNodeTypes::StructField,
             NodeTypes::Enum => IterNodeTypes::Enum,
-            NodeTypes::EnumItem => IterNodeTypes::EnumItem,
+            NodeTypes::EnumItem(_) => IterNodeTypes::EnumItem,
         }
     }
 }
@@ -53,6 +59,18 @@ impl SymbolIterator {
         return None;
     }
 
+    /// Whether this symbol is `scope` or nested somewhere inside it.
+    pub fn is_within(&self, scope: &SymbolNodeRef) -> bool {
+        let mut iter = Some(Self::new(self.node.clone()));
+        while let Some(current) = iter {
+            if Rc::ptr_eq(&current.node, scope) {
+                return true;
+            }
+            iter = current.parent();
+        }
+        return false;
+    }
+
     pub fn function(&self, name: &str) -> Option<Self> {
         let root = self.node.borrow();
         let node = root.children.get(name)?;
@@ -89,6 +107,15 @@ impl SymbolIterator {
         return None;
     }
 
+    pub fn constant(&self, name: &str) -> Option<SymbolIterator> {
+        let root = self.node.borrow();
+        let node = root.children.get(name)?;
+        if let NodeTypes::Constant { .. } = node.borrow().data.node_type {
+            return Some(Self::new(node.clone()));
+        }
+        return None;
+    }
+
     pub fn struct_field(&self, name: &str) -> Option<SymbolIterator> {
         let root = self.node.borrow();
         let node = root.children.get(name)?;
@@ -98,6 +125,32 @@ impl SymbolIterator {
         return None;
     }
 
+    pub fn enum_item(&self, name: &str) -> Option<SymbolIterator> {
+        let root = self.node.borrow();
+        let node = root.children.get(name)?;
+        if let NodeTypes::EnumItem(_) = node.borrow().data.node_type {
+            return Some(Self::new(node.clone()));
+        }
+        return None;
+    }
+
+    /// The item of an enum stored as `value`.
+    pub fn enum_value(&self, value: i32) -> Option<SymbolIterator> {
+        let root = self.node.borrow();
+        let node = root.children.values().find(|node| {
+            matches!(&node.borrow().data.node_type, NodeTypes::EnumItem(item) if item.value == value)
+        })?;
+        return Some(Self::new(node.clone()));
+    }
+
+    /// Whether the items of an enum carry values, which makes it a tagged
+    /// union that only `match` can look into.
+    pub fn is_tagged(&self) -> bool {
+        return self.node.borrow().children.values().any(|node| {
+            matches!(&node.borrow().data.node_type, NodeTypes::EnumItem(item) if !item.payload.is_empty())
+        });
+    }
+
     pub fn count(&self, filter: IterNodeTypes) -> usize {
         return self
             .node
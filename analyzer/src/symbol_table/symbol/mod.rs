@@ -6,7 +6,10 @@ use crate::{
     ast::{
         access_specifier::AccessSpecifier,
         declarations::{
-            enum_decl::EnumDeclaration, fn_decl::FnDeclaration, struct_decl::StructDeclaration,
+            const_decl::ConstDeclaration,
+            enum_decl::{EnumDeclaration, EnumField},
+            fn_decl::FnDeclaration,
+            struct_decl::StructDeclaration,
         },
         identifier::Identifier,
         type_specifier::TypeSpecifier,
@@ -18,7 +21,7 @@ use crate::{
 use self::{
     data::SymbolData,
     iter::{SymbolIterator, ToIter},
-    node::VariableNode,
+    node::{ConstantNode, EnumItemNode, VariableNode},
 };
 use std::{cell::RefCell, collections::HashMap, fmt::Display, rc::Rc};
 
@@ -65,7 +68,7 @@ impl SymbolNode {
             writeln!(f, "{}Children: {{", indent)?;
         }
 
-        for (_, value) in &self.children {
+        for value in self.children.values() {
             value.borrow().dfs(f, depth + 3)?;
         }
 
@@ -91,16 +94,16 @@ impl Display for SymbolNode {
     }
 }
 
-impl Into<SymbolNodeRef> for SymbolNode {
-    fn into(self) -> SymbolNodeRef {
-        return Rc::new(RefCell::new(self));
+impl From<SymbolNode> for SymbolNodeRef {
+    fn from(value: SymbolNode) -> Self {
+        return Rc::new(RefCell::new(value));
     }
 }
 
 impl From<(&FnDeclaration, SymbolNodeRef)> for SymbolNode {
     fn from((fn_decl, parent): (&FnDeclaration, SymbolNodeRef)) -> Self {
         let data = SymbolData::from((
-            fn_decl.fn_name.as_ref(),
+            &fn_decl.fn_name,
             &fn_decl.access_specifier,
             &fn_decl.return_type,
             &fn_decl.params,
@@ -115,7 +118,8 @@ impl From<(&StructDeclaration, SymbolNodeRef)> for SymbolNode {
             struct_decl.name.to_string(),
             Access::from(&struct_decl.access_specifier),
             NodeTypes::Struct,
-        );
+        )
+        .with_span(&struct_decl.name.span);
         return SymbolNode::new(data, Some(parent), HashMap::new());
     }
 }
@@ -126,19 +130,40 @@ impl From<(&EnumDeclaration, SymbolNodeRef)> for SymbolNode {
             enum_decl.name.to_string(),
             Access::from(&enum_decl.access_specifier),
             NodeTypes::Enum,
+        )
+        .with_span(&enum_decl.name.span);
+        return SymbolNode::new(data, Some(parent), HashMap::new());
+    }
+}
+
+impl From<(&ConstDeclaration, SymbolNodeRef)> for SymbolNode {
+    fn from((const_decl, parent): (&ConstDeclaration, SymbolNodeRef)) -> Self {
+        let constant_node = ConstantNode::new(
+            const_decl.type_specifier.clone(),
+            const_decl.constant.clone(),
         );
+        let data = SymbolData::new(
+            const_decl.identifier.to_string(),
+            Access::from(&const_decl.access_specifier),
+            NodeTypes::Constant(constant_node),
+        )
+        .with_span(&const_decl.identifier.span);
         return SymbolNode::new(data, Some(parent), HashMap::new());
     }
 }
 
 //Enum Field
-impl From<(&Identifier, SymbolNodeRef)> for SymbolNode {
-    fn from((enum_field, parent): (&Identifier, SymbolNodeRef)) -> Self {
+impl From<(&EnumField, SymbolNodeRef)> for SymbolNode {
+    fn from((enum_field, parent): (&EnumField, SymbolNodeRef)) -> Self {
         let data = SymbolData::new(
-            enum_field.to_string(),
+            enum_field.identifier.to_string(),
             Access::from(AccessSpecifier::Public),
-            NodeTypes::EnumItem,
-        );
+            NodeTypes::EnumItem(EnumItemNode::new(
+                enum_field.discriminant,
+                enum_field.payload.clone(),
+            )),
+        )
+        .with_span(&enum_field.identifier.span);
         return SymbolNode::new(data, Some(parent), HashMap::new());
     }
 }
@@ -155,7 +180,8 @@ impl From<(&VariableType, Access, SymbolNodeRef, NodeCallBack)> for SymbolNode {
     ) -> Self {
         let variable_node = VariableNode::new(variable_type.type_specifier.clone());
         let node_type = node_callback(variable_node);
-        let data = SymbolData::new(variable_type.identifier.to_string(), access, node_type);
+        let data = SymbolData::new(variable_type.identifier.to_string(), access, node_type)
+            .with_span(&variable_type.identifier.span);
         return SymbolNode::new(data, Some(parent), HashMap::new());
     }
 }
@@ -167,7 +193,8 @@ impl From<(&Identifier, &TypeSpecifier, SymbolNodeRef)> for SymbolNode {
             name.to_string(),
             Access::Local,
             NodeTypes::Variable(variable_node),
-        );
+        )
+        .with_span(&name.span);
         return SymbolNode::new(data, Some(parent), HashMap::new());
     }
 }
@@ -1,15 +1,18 @@
-use crate::ast::type_specifier::TypeSpecifier;
+use crate::{ast::type_specifier::TypeSpecifier, interpreter::value::Value};
 
 #[derive(Debug)]
 pub enum NodeTypes {
     Variable(VariableNode),
+    Constant(ConstantNode),
     Function(FunctionNode),
     Block,
+    /// A `for` or `while` loop and its label.
+    Loop(Option<String>),
     Global,
     Struct,
     StructField(VariableNode),
     Enum,
-    EnumItem,
+    EnumItem(EnumItemNode),
 }
 
 impl NodeTypes {
@@ -20,12 +23,26 @@ impl NodeTypes {
         };
     }
 
+    pub fn constant(&self) -> &ConstantNode {
+        return match self {
+            NodeTypes::Constant(constant) => constant,
+            _ => unreachable!("Something went wrong"),
+        };
+    }
+
     pub fn function(&self) -> &FunctionNode {
         return match self {
             NodeTypes::Function(function) => function,
             _ => unreachable!("Something went wrong"),
         };
     }
+
+    pub fn enum_item(&self) -> &EnumItemNode {
+        return match self {
+            NodeTypes::EnumItem(item) => item,
+            _ => unreachable!("Something went wrong"),
+        };
+    }
 }
 
 #[derive(Debug)]
@@ -33,10 +50,27 @@ pub struct VariableNode {
     pub type_specifier: TypeSpecifier,
 }
 
+#[derive(Debug, Clone)]
+pub struct ConstantNode {
+    pub type_specifier: TypeSpecifier,
+    pub value: Value,
+}
+
 #[derive(Debug)]
 pub struct FunctionNode {
     pub return_type: TypeSpecifier,
     pub params: Vec<TypeSpecifier>,
+    /// The type of the value a method is called on, `None` for functions
+    /// and methods without one.
+    pub receiver: Option<TypeSpecifier>,
+}
+
+/// An item of an enum, the number it is stored as and the types of the
+/// values it carries.
+#[derive(Debug)]
+pub struct EnumItemNode {
+    pub value: i32,
+    pub payload: Vec<TypeSpecifier>,
 }
 
 impl VariableNode {
@@ -45,11 +79,27 @@ impl VariableNode {
     }
 }
 
+impl ConstantNode {
+    pub fn new(type_specifier: TypeSpecifier, value: Value) -> Self {
+        Self {
+            type_specifier,
+            value,
+        }
+    }
+}
+
+impl EnumItemNode {
+    pub fn new(value: i32, payload: Vec<TypeSpecifier>) -> Self {
+        Self { value, payload }
+    }
+}
+
 impl FunctionNode {
     pub fn new(return_type: TypeSpecifier, params: Vec<TypeSpecifier>) -> Self {
         Self {
             return_type,
             params,
+            receiver: None,
         }
     }
 }
@@ -0,0 +1,298 @@
+pub mod value;
+
+use crate::{
+    codegen::bytecode::module::{Function, Instruction, Module},
+    error::{CompilerError, Result},
+};
+
+use self::value::Value;
+
+/// The deepest a program may recurse, on the VM as in the interpreter.
+pub const MAX_CALL_DEPTH: usize = 65536;
+
+/// Runs a bytecode [`Module`].
+///
+/// Operands live on `stack` while every call gets a window of `memory` for
+/// its locals, so pointers are plain indices into `memory`.
+pub struct VirtualMachine<'a> {
+    module: &'a Module,
+    stack: Vec<Value>,
+    memory: Vec<Value>,
+    frames: Vec<Frame>,
+}
+
+struct Frame {
+    function: usize,
+    ip: usize,
+    base: usize,
+    stack_base: usize,
+}
+
+impl<'a> VirtualMachine<'a> {
+    pub fn new(module: &'a Module) -> Self {
+        Self {
+            module,
+            stack: Vec::new(),
+            memory: Vec::new(),
+            frames: Vec::new(),
+        }
+    }
+
+    /// Runs the entry function and returns its result as the exit code.
+    pub fn run(&mut self) -> Result<i32> {
+        self.call(self.module.entry)?;
+        loop {
+            let frame = self.frames.last_mut().ok_or_else(Self::corrupted)?;
+            let instruction = *self.module.functions[frame.function]
+                .code
+                .get(frame.ip)
+                .ok_or_else(|| CompilerError::Runtime(String::from("Missing return")))?;
+            frame.ip += 1;
+
+            if let Instruction::Return = instruction {
+                if let Some(code) = self.ret()? {
+                    return Ok(code);
+                }
+                continue;
+            }
+            self.execute(instruction)?;
+        }
+    }
+
+    fn execute(&mut self, instruction: Instruction) -> Result<()> {
+        match instruction {
+            Instruction::Const(id) => {
+                let constant = self
+                    .module
+                    .constants
+                    .get(id as usize)
+                    .ok_or_else(Self::corrupted)?;
+                self.stack.push(Value::from(constant));
+            }
+            Instruction::Pop(size) => {
+                let length = self.split(size)?;
+                self.stack.truncate(length);
+            }
+            Instruction::Dup(size) => {
+                let start = self.split(size)?;
+                self.stack.extend_from_within(start..);
+            }
+            Instruction::LoadLocal { slot, size } => {
+                let address = self.frame()?.base + slot as usize;
+                self.load(address, size)?;
+            }
+            Instruction::StoreLocal { slot, size } => {
+                let address = self.frame()?.base + slot as usize;
+                self.store(address, size)?;
+            }
+            Instruction::LocalAddr(slot) => {
+                let address = self.frame()?.base + slot as usize;
+                self.stack.push(Value::Pointer(address));
+            }
+            Instruction::Load(size) => {
+                let address = self.pop()?.as_pointer()?;
+                self.load(address, size)?;
+            }
+            Instruction::Store(size) => {
+                let address = self.pop()?.as_pointer()?;
+                self.store(address, size)?;
+            }
+            Instruction::Offset(offset) => {
+                let address = self.pop()?.as_pointer()?;
+                self.stack.push(Value::Pointer(address + offset as usize));
+            }
+            Instruction::Index { stride, length } => {
+                let index = self.pop()?.as_index()?;
+                let address = self.pop()?.as_pointer()?;
+                if let Some(length) = length {
+                    if index < 0 || index >= length as i64 {
+                        return Err(CompilerError::Runtime(format!(
+                            "Index {} is out of bounds for array of size {}",
+                            index, length
+                        )));
+                    }
+                }
+                let address = address as i64 + index * stride as i64;
+                let address = usize::try_from(address)
+                    .map_err(|_| CompilerError::Runtime(String::from("Invalid pointer")))?;
+                self.stack.push(Value::Pointer(address));
+            }
+            Instruction::Add
+            | Instruction::Subtract
+            | Instruction::Multiply
+            | Instruction::Divide
+            | Instruction::Modulo
+            | Instruction::Equal
+            | Instruction::NotEqual
+            | Instruction::Less
+            | Instruction::LessEqual
+            | Instruction::Greater
+            | Instruction::GreaterEqual
+            | Instruction::BitAnd
+            | Instruction::BitOr
+            | Instruction::BitXor
+            | Instruction::ShiftLeft
+            | Instruction::ShiftRight => {
+                let right = self.pop()?;
+                let left = self.pop()?;
+                self.stack.push(left.binary(&instruction, right)?);
+            }
+            Instruction::Negate => {
+                let value = self.pop()?.negate()?;
+                self.stack.push(value);
+            }
+            Instruction::Not => {
+                let value = !self.pop()?.as_bool()?;
+                self.stack.push(Value::Bool(value));
+            }
+            Instruction::BitNot => {
+                let value = self.pop()?.bit_not()?;
+                self.stack.push(value);
+            }
+            Instruction::Step { delta, postfix } => {
+                let address = self.pop()?.as_pointer()?;
+                let slot = self.slot(address)?;
+                let old = slot.clone();
+                let new = old.step(delta)?;
+                *slot = new.clone();
+                self.stack.push(if postfix { old } else { new });
+            }
+            Instruction::Cast(primitive) => {
+                let value = self.pop()?.cast(primitive)?;
+                self.stack.push(value);
+            }
+            Instruction::CheckEnum(id) => {
+                let layout = self
+                    .module
+                    .enums
+                    .get(id as usize)
+                    .ok_or_else(Self::corrupted)?;
+                let value = self.pop()?.as_index()? as i32;
+                if !layout.values.contains(&value) {
+                    return Err(CompilerError::Runtime(format!(
+                        "{} is not a value of enum {}",
+                        value, layout.name
+                    )));
+                }
+                self.stack.push(Value::Int(value));
+            }
+            Instruction::Jump(target) => self.frame()?.ip = target as usize,
+            Instruction::JumpIfFalse(target) => {
+                if !self.pop()?.as_bool()? {
+                    self.frame()?.ip = target as usize;
+                }
+            }
+            Instruction::Call(id) => self.call(id)?,
+            Instruction::Return => unreachable!("Return is handled by the run loop"),
+        }
+        return Ok(());
+    }
+
+    /// Moves the arguments on the stack into a new frame.
+    fn call(&mut self, id: u32) -> Result<()> {
+        if self.frames.len() >= MAX_CALL_DEPTH {
+            return Err(CompilerError::Runtime(String::from(
+                "Maximum call depth exceeded",
+            )));
+        }
+        let function = self.function(id)?;
+        let params = self.split(function.params_size)?;
+        let base = self.memory.len();
+        self.memory
+            .resize(base + function.locals_size as usize, Value::Null);
+        for (slot, value) in self.stack.drain(params..).enumerate() {
+            let slot = self
+                .memory
+                .get_mut(base + slot)
+                .ok_or_else(Self::corrupted)?;
+            *slot = value;
+        }
+        self.frames.push(Frame {
+            function: id as usize,
+            ip: 0,
+            base,
+            stack_base: params,
+        });
+        return Ok(());
+    }
+
+    /// Leaves the current function, returns the exit code when it was the
+    /// entry function.
+    fn ret(&mut self) -> Result<Option<i32>> {
+        let frame = self.frames.pop().ok_or_else(Self::corrupted)?;
+        let function = self.function(frame.function as u32)?;
+        let start = self.split(function.return_size)?;
+        let values = self.stack.split_off(start);
+        self.stack.truncate(frame.stack_base);
+        self.stack.extend(values);
+        self.memory.truncate(frame.base);
+
+        if !self.frames.is_empty() {
+            return Ok(None);
+        }
+        return match self.stack.pop() {
+            Some(Value::Int(code)) => Ok(Some(code)),
+            None => Ok(Some(0)),
+            Some(_) => Err(CompilerError::Runtime(String::from(
+                "main function must return int or void",
+            ))),
+        };
+    }
+
+    fn function(&self, id: u32) -> Result<&'a Function> {
+        return self
+            .module
+            .functions
+            .get(id as usize)
+            .ok_or_else(Self::corrupted);
+    }
+
+    fn frame(&mut self) -> Result<&mut Frame> {
+        return self.frames.last_mut().ok_or_else(Self::corrupted);
+    }
+
+    fn pop(&mut self) -> Result<Value> {
+        return self.stack.pop().ok_or_else(Self::corrupted);
+    }
+
+    /// Returns the stack length without the top `size` slots.
+    fn split(&self, size: u32) -> Result<usize> {
+        return self
+            .stack
+            .len()
+            .checked_sub(size as usize)
+            .ok_or_else(Self::corrupted);
+    }
+
+    fn slot(&mut self, address: usize) -> Result<&mut Value> {
+        return self
+            .memory
+            .get_mut(address)
+            .ok_or_else(|| CompilerError::Runtime(String::from("Invalid pointer")));
+    }
+
+    fn load(&mut self, address: usize, size: u32) -> Result<()> {
+        let values = self
+            .memory
+            .get(address..address + size as usize)
+            .ok_or_else(|| CompilerError::Runtime(String::from("Invalid pointer")))?;
+        self.stack.extend_from_slice(values);
+        return Ok(());
+    }
+
+    fn store(&mut self, address: usize, size: u32) -> Result<()> {
+        let start = self.split(size)?;
+        let slots = self
+            .memory
+            .get_mut(address..address + size as usize)
+            .ok_or_else(|| CompilerError::Runtime(String::from("Invalid pointer")))?;
+        for (slot, value) in slots.iter_mut().zip(self.stack.drain(start..)) {
+            *slot = value;
+        }
+        return Ok(());
+    }
+
+    fn corrupted() -> CompilerError {
+        return CompilerError::Runtime(String::from("Corrupted bytecode"));
+    }
+}
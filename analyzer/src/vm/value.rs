@@ -0,0 +1,221 @@
+use std::rc::Rc;
+
+use crate::{
+    codegen::bytecode::module::{Constant, Instruction, Primitive},
+    error::{CompilerError, Result},
+};
+
+/// The content of one stack or memory slot. Pointers are memory addresses.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i32),
+    Usize(u64),
+    Float(f32),
+    Double(f64),
+    Char(u8),
+    Bool(bool),
+    Str(Rc<str>),
+    Pointer(usize),
+    Null,
+}
+
+macro_rules! integer_operation {
+    ($instruction:expr, $variant:path, $left:expr, $right:expr) => {{
+        let (left, right) = ($left, $right);
+        match $instruction {
+            Instruction::Add => $variant(left.wrapping_add(right)),
+            Instruction::Subtract => $variant(left.wrapping_sub(right)),
+            Instruction::Multiply => $variant(left.wrapping_mul(right)),
+            Instruction::Divide | Instruction::Modulo if right == 0 => {
+                return Err(CompilerError::Runtime(String::from("Division by zero")))
+            }
+            Instruction::Divide => $variant(left.wrapping_div(right)),
+            Instruction::Modulo => $variant(left.wrapping_rem(right)),
+            Instruction::BitAnd => $variant(left & right),
+            Instruction::BitOr => $variant(left | right),
+            Instruction::BitXor => $variant(left ^ right),
+            Instruction::ShiftLeft => $variant(left.wrapping_shl(right as u32)),
+            Instruction::ShiftRight => $variant(left.wrapping_shr(right as u32)),
+            instruction => Value::Bool(compare(instruction, left.partial_cmp(&right))?),
+        }
+    }};
+}
+
+macro_rules! float_operation {
+    ($instruction:expr, $variant:path, $left:expr, $right:expr) => {{
+        let (left, right) = ($left, $right);
+        match $instruction {
+            Instruction::Add => $variant(left + right),
+            Instruction::Subtract => $variant(left - right),
+            Instruction::Multiply => $variant(left * right),
+            Instruction::Divide => $variant(left / right),
+            Instruction::Modulo => $variant(left % right),
+            instruction => Value::Bool(compare(instruction, left.partial_cmp(&right))?),
+        }
+    }};
+}
+
+impl Value {
+    pub fn as_bool(&self) -> Result<bool> {
+        if let Value::Bool(value) = self {
+            return Ok(*value);
+        }
+        return Err(CompilerError::Runtime(String::from("Expected bool value")));
+    }
+
+    pub fn as_index(&self) -> Result<i64> {
+        return match self {
+            Value::Int(value) => Ok(*value as i64),
+            Value::Usize(value) => Ok(*value as i64),
+            Value::Char(value) => Ok(*value as i64),
+            _ => Err(CompilerError::Runtime(String::from(
+                "Array index must be an integer",
+            ))),
+        };
+    }
+
+    pub fn as_pointer(&self) -> Result<usize> {
+        return match self {
+            Value::Pointer(address) => Ok(*address),
+            Value::Null => Err(CompilerError::Runtime(String::from(
+                "Null pointer dereference",
+            ))),
+            _ => Err(CompilerError::Runtime(String::from(
+                "Expected pointer value",
+            ))),
+        };
+    }
+
+    /// Applies a binary operator instruction.
+    pub fn binary(self, instruction: &Instruction, right: Value) -> Result<Value> {
+        return Ok(match (self, right) {
+            (Value::Int(left), Value::Int(right)) => {
+                integer_operation!(instruction, Value::Int, left, right)
+            }
+            (Value::Usize(left), Value::Usize(right)) => {
+                integer_operation!(instruction, Value::Usize, left, right)
+            }
+            (Value::Char(left), Value::Char(right)) => {
+                integer_operation!(instruction, Value::Char, left, right)
+            }
+            (Value::Float(left), Value::Float(right)) => {
+                float_operation!(instruction, Value::Float, left, right)
+            }
+            (Value::Double(left), Value::Double(right)) => {
+                float_operation!(instruction, Value::Double, left, right)
+            }
+            (Value::Bool(left), Value::Bool(right)) => match instruction {
+                Instruction::BitAnd => Value::Bool(left & right),
+                Instruction::BitOr => Value::Bool(left | right),
+                Instruction::BitXor => Value::Bool(left ^ right),
+                instruction => Value::Bool(compare(instruction, left.partial_cmp(&right))?),
+            },
+            (Value::Str(left), Value::Str(right)) => {
+                Value::Bool(compare(instruction, left.partial_cmp(&right))?)
+            }
+            _ => return Err(invalid_operands(instruction)),
+        });
+    }
+
+    pub fn negate(self) -> Result<Value> {
+        return Ok(match self {
+            Value::Int(value) => Value::Int(value.wrapping_neg()),
+            Value::Usize(value) => Value::Usize(value.wrapping_neg()),
+            Value::Char(value) => Value::Char(value.wrapping_neg()),
+            Value::Float(value) => Value::Float(-value),
+            Value::Double(value) => Value::Double(-value),
+            _ => return Err(invalid_operands(&Instruction::Negate)),
+        });
+    }
+
+    pub fn bit_not(self) -> Result<Value> {
+        return Ok(match self {
+            Value::Int(value) => Value::Int(!value),
+            Value::Usize(value) => Value::Usize(!value),
+            Value::Char(value) => Value::Char(!value),
+            Value::Bool(value) => Value::Bool(!value),
+            _ => return Err(invalid_operands(&Instruction::BitNot)),
+        });
+    }
+
+    /// Adds `delta` for `++` and `--`. Pointers move by `delta` slots.
+    pub fn step(&self, delta: i32) -> Result<Value> {
+        return Ok(match self {
+            Value::Int(value) => Value::Int(value.wrapping_add(delta)),
+            Value::Usize(value) => Value::Usize(value.wrapping_add_signed(delta as i64)),
+            Value::Char(value) => Value::Char(value.wrapping_add_signed(delta as i8)),
+            Value::Float(value) => Value::Float(value + delta as f32),
+            Value::Double(value) => Value::Double(value + delta as f64),
+            Value::Pointer(address) => Value::Pointer(
+                address
+                    .checked_add_signed(delta as isize)
+                    .ok_or_else(|| CompilerError::Runtime(String::from("Invalid pointer")))?,
+            ),
+            _ => {
+                return Err(CompilerError::Runtime(String::from(
+                    "Invalid operand for increment or decrement",
+                )))
+            }
+        });
+    }
+
+    /// Converts a primitive value with the rules of a C cast.
+    pub fn cast(&self, primitive: Primitive) -> Result<Value> {
+        let (integer, float) = match self {
+            Value::Int(value) => (*value as i64, *value as f64),
+            Value::Usize(value) => (*value as i64, *value as f64),
+            Value::Char(value) => (*value as i64, *value as f64),
+            Value::Bool(value) => (*value as i64, *value as i64 as f64),
+            Value::Float(value) => (*value as i64, *value as f64),
+            Value::Double(value) => (*value as i64, *value),
+            _ => {
+                return Err(CompilerError::Runtime(String::from(
+                    "Cannot cast the complex type",
+                )))
+            }
+        };
+        return Ok(match primitive {
+            Primitive::Int => Value::Int(integer as i32),
+            Primitive::Usize => Value::Usize(integer as u64),
+            Primitive::Char => Value::Char(integer as u8),
+            Primitive::Float => Value::Float(float as f32),
+            Primitive::Double => Value::Double(float),
+            Primitive::Bool => Value::Bool(float != 0.0),
+        });
+    }
+}
+
+impl From<&Constant> for Value {
+    fn from(constant: &Constant) -> Self {
+        return match constant {
+            Constant::Int(value) => Value::Int(*value),
+            Constant::Usize(value) => Value::Usize(*value),
+            Constant::Float(value) => Value::Float(*value),
+            Constant::Double(value) => Value::Double(*value),
+            Constant::Char(value) => Value::Char(*value),
+            Constant::Bool(value) => Value::Bool(*value),
+            Constant::Str(value) => Value::Str(value.as_str().into()),
+            Constant::Null => Value::Null,
+        };
+    }
+}
+
+fn compare(instruction: &Instruction, ordering: Option<std::cmp::Ordering>) -> Result<bool> {
+    let Some(ordering) = ordering else {
+        // Comparisons with NaN are false except `!=`.
+        return Ok(matches!(instruction, Instruction::NotEqual));
+    };
+    return Ok(match instruction {
+        Instruction::Equal => ordering.is_eq(),
+        Instruction::NotEqual => ordering.is_ne(),
+        Instruction::Less => ordering.is_lt(),
+        Instruction::LessEqual => ordering.is_le(),
+        Instruction::Greater => ordering.is_gt(),
+        Instruction::GreaterEqual => ordering.is_ge(),
+        instruction => return Err(invalid_operands(instruction)),
+    });
+}
+
+fn invalid_operands(instruction: &Instruction) -> CompilerError {
+    return CompilerError::Runtime(format!("Invalid operands for {:?}", instruction));
+}
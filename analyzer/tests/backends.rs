@@ -0,0 +1,314 @@
+//! Runs every program in `tests/programs` on each backend and checks that
+//! they all end the way the first line of the program says: `// exit: N`
+//! for the exit status, or `// exit: trap` for a program that must stop
+//! with a runtime error. Backends whose tools are not installed are skipped.
+
+#![allow(clippy::needless_return)]
+
+use std::{
+    fs, panic,
+    path::{Path, PathBuf},
+    process::{self, Command, Stdio},
+    thread,
+};
+
+use analyzer::{
+    ast::program::ProgramRef,
+    codegen::{
+        bytecode::{module::Module, BytecodeGenerator},
+        c::CGenerator,
+        llvm::LlvmGenerator,
+        wasm::WatGenerator,
+        x86_64::X86Generator,
+    },
+    error::Result,
+    interpreter::{self, Interpreter},
+    ir::opt::PassManager,
+    lexer::Lexer,
+    parser::Parser,
+    semantic::SemanticAnalyzer,
+    symbol_table::{SymbolTable, SymbolTableRef},
+    vm::VirtualMachine,
+};
+
+/// How a program ends. Exit statuses are compared modulo 256, as a process
+/// reports them.
+#[derive(Debug, PartialEq, Eq)]
+enum Outcome {
+    Exit(i32),
+    Trap,
+}
+
+impl Outcome {
+    fn exit(code: i32) -> Self {
+        return Outcome::Exit(code.rem_euclid(256));
+    }
+
+    fn from_run(result: Result<i32>) -> Self {
+        return match result {
+            Ok(code) => Outcome::exit(code),
+            Err(_) => Outcome::Trap,
+        };
+    }
+}
+
+struct Program {
+    name: String,
+    source: String,
+    expected: Outcome,
+}
+
+impl Program {
+    fn analyze(&self) -> ProgramRef {
+        let symbol_table: SymbolTableRef = SymbolTable::new().into();
+        let lexer = Lexer::new(self.source.clone(), self.name.clone(), symbol_table.clone());
+        let mut parser = Parser::new(lexer, symbol_table.clone())
+            .unwrap_or_else(|err| panic!("{}: {}", self.name, err));
+        let program: ProgramRef = parser
+            .parse()
+            .unwrap_or_else(|err| panic!("{}: {}", self.name, err))
+            .into();
+        SemanticAnalyzer::new(symbol_table, program.clone())
+            .analyze()
+            .unwrap_or_else(|err| panic!("{}: {}", self.name, err));
+        return program;
+    }
+
+    fn check(&self, backend: &str, outcome: Outcome) {
+        assert_eq!(
+            outcome, self.expected,
+            "{} on the {} backend",
+            self.name, backend
+        );
+    }
+}
+
+fn programs() -> Vec<Program> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/programs");
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "mb"))
+        .collect();
+    paths.sort();
+    return paths
+        .into_iter()
+        .map(|path| {
+            let name = path.file_name().unwrap().to_string_lossy().to_string();
+            let source = fs::read_to_string(&path).unwrap();
+            let expected = match source
+                .lines()
+                .next()
+                .and_then(|line| line.strip_prefix("// exit: "))
+            {
+                Some("trap") => Outcome::Trap,
+                Some(code) => Outcome::exit(code.parse().unwrap()),
+                None => panic!("{} does not start with `// exit: `", name),
+            };
+            Program {
+                name,
+                source,
+                expected,
+            }
+        })
+        .collect();
+}
+
+/// Whether `tool` can be started, to skip the backends that need it.
+fn installed(tool: &str) -> bool {
+    let found = Command::new(tool).arg("--version").output().is_ok();
+    if !found {
+        eprintln!("{} is not installed, skipping", tool);
+    }
+    return found;
+}
+
+/// A fresh directory for the files of one program on one backend.
+fn scratch(backend: &str, program: &Program) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "marble-{}-{}-{}",
+        backend,
+        process::id(),
+        program.name.trim_end_matches(".mb")
+    ));
+    fs::create_dir_all(&dir).unwrap();
+    return dir;
+}
+
+/// Runs a compiled program. Being killed by a signal, like the `abort` of
+/// a failed check, is a trap.
+fn execute(command: &mut Command) -> Outcome {
+    let status = command.stderr(Stdio::null()).status().unwrap();
+    return match status.code() {
+        Some(code) => Outcome::exit(code),
+        None => Outcome::Trap,
+    };
+}
+
+/// Runs `command` and panics with its output when it fails.
+fn tool(command: &mut Command) {
+    let output = command.output().unwrap();
+    assert!(
+        output.status.success(),
+        "{:?} failed:\n{}",
+        command,
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn interpreter() {
+    let runner = thread::Builder::new().stack_size(interpreter::STACK_SIZE);
+    let handle = runner.spawn(|| {
+        for program in programs() {
+            let analyzed = program.analyze();
+            let result = Interpreter::new(&analyzed.borrow()).run();
+            program.check("interpreter", Outcome::from_run(result));
+        }
+    });
+    if let Err(panic) = handle.unwrap().join() {
+        panic::resume_unwind(panic);
+    }
+}
+
+#[test]
+fn vm() {
+    for program in programs() {
+        let module = BytecodeGenerator::new(&program.analyze().borrow())
+            .generate()
+            .unwrap();
+        let result = VirtualMachine::new(&module).run();
+        program.check("vm", Outcome::from_run(result));
+    }
+}
+
+#[test]
+fn bytecode_file() {
+    for program in programs() {
+        let bytes = BytecodeGenerator::new(&program.analyze().borrow())
+            .generate()
+            .unwrap()
+            .encode();
+        let module = Module::decode(&bytes).unwrap();
+        let result = VirtualMachine::new(&module).run();
+        program.check(".mbc", Outcome::from_run(result));
+    }
+}
+
+#[test]
+fn c() {
+    if !installed("gcc") {
+        return;
+    }
+    for program in programs() {
+        let dir = scratch("c", &program);
+        let code = CGenerator::new(program.analyze()).generate().unwrap();
+        fs::write(dir.join("main.c"), code).unwrap();
+        tool(
+            Command::new("gcc")
+                .arg("-w")
+                .arg(dir.join("main.c"))
+                .arg("-o")
+                .arg(dir.join("main")),
+        );
+        program.check("c", execute(&mut Command::new(dir.join("main"))));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
+
+#[test]
+fn llvm() {
+    if !installed("lli") {
+        return;
+    }
+    let passes = PassManager::standard();
+    for program in programs() {
+        let dir = scratch("llvm", &program);
+        let analyzed = program.analyze();
+        let code = LlvmGenerator::new(&analyzed.borrow()).generate().unwrap();
+        fs::write(dir.join("main.ll"), code).unwrap();
+        program.check(
+            "llvm",
+            execute(Command::new("lli").arg(dir.join("main.ll"))),
+        );
+        let code = LlvmGenerator::new(&analyzed.borrow())
+            .with_passes(&passes)
+            .generate()
+            .unwrap();
+        fs::write(dir.join("main.ll"), code).unwrap();
+        program.check(
+            "llvm -O",
+            execute(Command::new("lli").arg(dir.join("main.ll"))),
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
+
+#[test]
+fn x86_64() {
+    if !cfg!(all(target_arch = "x86_64", target_os = "linux"))
+        || !installed("as")
+        || !installed("ld")
+    {
+        return;
+    }
+    for program in programs() {
+        let dir = scratch("x86_64", &program);
+        let code = X86Generator::new(&program.analyze().borrow())
+            .generate()
+            .unwrap();
+        fs::write(dir.join("main.s"), code).unwrap();
+        tool(
+            Command::new("as")
+                .arg("-o")
+                .arg(dir.join("main.o"))
+                .arg(dir.join("main.s")),
+        );
+        tool(
+            Command::new("ld")
+                .arg("-o")
+                .arg(dir.join("main"))
+                .arg(dir.join("main.o")),
+        );
+        program.check("x86-64", execute(&mut Command::new(dir.join("main"))));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
+
+/// Instantiates the module and prints what `main` returns, or `trap`.
+const RUN_WASM: &str = "WebAssembly.instantiate(require('fs').readFileSync(process.argv[1]))
+    .then((module) => console.log(module.instance.exports.main()))
+    .catch(() => console.log('trap'));";
+
+#[test]
+fn wasm() {
+    if !installed("wat2wasm") || !installed("node") {
+        return;
+    }
+    for program in programs() {
+        let dir = scratch("wasm", &program);
+        let code = WatGenerator::new(&program.analyze().borrow())
+            .generate()
+            .unwrap();
+        fs::write(dir.join("main.wat"), code).unwrap();
+        tool(
+            Command::new("wat2wasm")
+                .arg(dir.join("main.wat"))
+                .arg("-o")
+                .arg(dir.join("main.wasm")),
+        );
+        let output = Command::new("node")
+            .arg("-e")
+            .arg(RUN_WASM)
+            .arg(dir.join("main.wasm"))
+            .output()
+            .unwrap();
+        let result = String::from_utf8_lossy(&output.stdout).trim().to_owned();
+        let outcome = match result.as_str() {
+            "trap" => Outcome::Trap,
+            code => Outcome::exit(code.parse().unwrap()),
+        };
+        program.check("wasm", outcome);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
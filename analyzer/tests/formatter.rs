@@ -0,0 +1,64 @@
+//! Formats `tests/formatter/*.mb` and compares the result with the
+//! `.expected.mb` file next to it, and checks that formatting is idempotent
+//! on those and on every program in `tests/programs`.
+
+#![allow(clippy::needless_return)]
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use analyzer::{
+    formatter::Formatter,
+    lexer::Lexer,
+    parser::Parser,
+    symbol_table::{SymbolTable, SymbolTableRef},
+};
+
+fn format(name: &str, source: &str) -> String {
+    let symbol_table: SymbolTableRef = SymbolTable::new().into();
+    let lexer = Lexer::new(source.to_owned(), name.to_owned(), symbol_table.clone());
+    let mut parser =
+        Parser::new(lexer, symbol_table).unwrap_or_else(|err| panic!("{}: {}", name, err));
+    let program = parser
+        .parse()
+        .unwrap_or_else(|err| panic!("{}: {}", name, err));
+    return Formatter::new(parser.comments()).format(&program);
+}
+
+/// The `.mb` files of `tests/<dir>`, without the expected outputs.
+fn sources(dir: &str) -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join(dir);
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            let name = path.to_string_lossy();
+            name.ends_with(".mb") && !name.ends_with(".expected.mb")
+        })
+        .collect();
+    paths.sort();
+    return paths;
+}
+
+#[test]
+fn matches_expected_output() {
+    for path in sources("formatter") {
+        let name = path.display().to_string();
+        let expected = fs::read_to_string(path.with_extension("expected.mb")).unwrap();
+        let formatted = format(&name, &fs::read_to_string(&path).unwrap());
+        assert_eq!(formatted, expected, "{}", name);
+    }
+}
+
+#[test]
+fn is_idempotent() {
+    for path in sources("formatter").into_iter().chain(sources("programs")) {
+        let name = path.display().to_string();
+        let formatted = format(&name, &fs::read_to_string(&path).unwrap());
+        assert_eq!(format(&name, &formatted), formatted, "{}", name);
+    }
+}
@@ -0,0 +1,170 @@
+use crate::compiler::{Emit, ErrorFormat};
+
+pub const USAGE: &str = "Usage: compiler <command> [options] <file>...
+
+Commands:
+    check <file>...          Parse and analyze the files
+    build <file>...          Compile the files to the --emit targets
+    run <file>               Run a program with the interpreter, or a .mbc file on the VM
+    fmt <file>...            Rewrite the files in the canonical style
+    dump-tokens <file>...    Print the tokens of the files
+    dump-ast <file>...       Print the syntax tree of the files
+    dump-symbols <file>...   Print the symbol table of the files after analysis
+    help                     Print this message
+
+Options:
+    --emit=<kind>[,<kind>]   What build writes: c, llvm, asm, ir, wat or bytecode (default c)
+    -o <path>                Where build writes its output, - for stdout (one file and
+                             one kind only)
+    --vm                     Make run compile to bytecode and use the virtual machine
+    --check                  Make fmt list the files it would change instead of writing them
+    -O                       Run every IR optimization pass (build --emit=ir or llvm)
+    --passes=<a>,<b>         Run only the listed IR optimization passes
+    --no-<pass>              Skip an IR optimization pass
+    --error-format=<format>  Report errors as human (default) or json
+    -h, --help               Print this message
+
+Exit status is 0 on success, 1 when compilation or the program fails and
+2 when the command line is invalid. run exits with the program's own status
+and fmt --check fails when a file is not formatted.
+
+The optimization passes change what --emit=ir and --emit=llvm write, LLVM
+IR is generated from the optimized IR. The other targets are generated
+from the syntax tree, so the optimization options are rejected with them.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Check,
+    Build,
+    Run,
+    Fmt,
+    DumpTokens,
+    DumpAst,
+    DumpSymbols,
+    Help,
+}
+
+/// A parsed command line.
+#[derive(Debug)]
+pub struct Cli {
+    pub command: Command,
+    pub files: Vec<String>,
+    pub emit: Vec<Emit>,
+    pub output: Option<String>,
+    pub vm: bool,
+    pub check: bool,
+    pub error_format: ErrorFormat,
+    /// The optimization options, kept in order for the pass manager.
+    pub passes: Vec<String>,
+}
+
+impl Cli {
+    /// Parses the arguments that follow the program name, failing with a
+    /// message for the user when they are invalid.
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let Some(command) = args.first() else {
+            return Err(String::from("Missing command"));
+        };
+        let command = match command.as_str() {
+            "check" => Command::Check,
+            "build" => Command::Build,
+            "run" => Command::Run,
+            "fmt" => Command::Fmt,
+            "dump-tokens" => Command::DumpTokens,
+            "dump-ast" => Command::DumpAst,
+            "dump-symbols" => Command::DumpSymbols,
+            "help" | "-h" | "--help" => Command::Help,
+            command => return Err(format!("Unknown command {}", command)),
+        };
+
+        let mut cli = Cli {
+            command,
+            files: Vec::new(),
+            emit: Vec::new(),
+            output: None,
+            vm: false,
+            check: false,
+            error_format: ErrorFormat::Human,
+            passes: Vec::new(),
+        };
+        let mut args = args[1..].iter();
+        while let Some(arg) = args.next() {
+            if arg == "-h" || arg == "--help" {
+                cli.command = Command::Help;
+            } else if arg == "-o" {
+                let Some(output) = args.next() else {
+                    return Err(String::from("Missing path after -o"));
+                };
+                cli.output = Some(output.clone());
+            } else if arg == "--vm" {
+                cli.vm = true;
+            } else if arg == "--check" {
+                cli.check = true;
+            } else if let Some(kinds) = arg.strip_prefix("--emit=") {
+                for kind in kinds.split(',') {
+                    let Some(emit) = Emit::from_name(kind) else {
+                        return Err(format!(
+                            "Unknown emit kind {}, expected one of c, llvm, asm, ir, wat, bytecode",
+                            kind
+                        ));
+                    };
+                    cli.emit.push(emit);
+                }
+            } else if let Some(name) = arg.strip_prefix("--error-format=") {
+                let Some(format) = ErrorFormat::from_name(name) else {
+                    return Err(format!(
+                        "Unknown error format {}, expected human or json",
+                        name
+                    ));
+                };
+                cli.error_format = format;
+            } else if arg == "-O" || arg.starts_with("--passes=") || arg.starts_with("--no-") {
+                cli.passes.push(arg.clone());
+            } else if arg.starts_with('-') {
+                return Err(format!("Unknown option {}", arg));
+            } else {
+                cli.files.push(arg.clone());
+            }
+        }
+        cli.validate()?;
+        return Ok(cli);
+    }
+
+    fn validate(&mut self) -> Result<(), String> {
+        if self.command == Command::Help {
+            return Ok(());
+        }
+        if self.files.is_empty() {
+            return Err(String::from("Missing input file"));
+        }
+        if self.command == Command::Run && self.files.len() > 1 {
+            return Err(String::from("run takes a single file"));
+        }
+        if self.output.is_some() && self.command != Command::Build {
+            return Err(String::from("-o can only be used with build"));
+        }
+        if self.check && self.command != Command::Fmt {
+            return Err(String::from("--check can only be used with fmt"));
+        }
+        if !self.emit.is_empty() && self.command != Command::Build {
+            return Err(String::from("--emit can only be used with build"));
+        }
+        if self.emit.is_empty() {
+            self.emit.push(Emit::C);
+        }
+        if let Some(option) = self.passes.first() {
+            if self.command != Command::Build || !self.emit.iter().all(Emit::is_optimized) {
+                return Err(format!(
+                    "{} can only be used with build --emit=ir or --emit=llvm",
+                    option
+                ));
+            }
+        }
+        if self.output.is_some() && (self.files.len() > 1 || self.emit.len() > 1) {
+            return Err(String::from(
+                "-o needs a single input file and a single emit kind",
+            ));
+        }
+        return Ok(());
+    }
+}
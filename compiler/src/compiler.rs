@@ -1,49 +1,302 @@
-use std::fs;
+use std::{
+    fs,
+    io::{self, Write},
+    path::Path,
+};
 
 use analyzer::{
     ast::program::ProgramRef,
-    error::Result,
-    lexer::Lexer,
+    codegen::{
+        bytecode::{module::Module, BytecodeGenerator},
+        c::CGenerator,
+        llvm::LlvmGenerator,
+        wasm::WatGenerator,
+        x86_64::X86Generator,
+    },
+    diagnostic::json,
+    error::{CompilerError, Result},
+    formatter::Formatter,
+    interpreter::Interpreter,
+    ir::{lower::IrBuilder, opt::PassManager},
+    lexer::{token::Token, token_type::TokenType, Lexer},
     parser::Parser,
     semantic::SemanticAnalyzer,
     symbol_table::{SymbolTable, SymbolTableRef},
+    vm::VirtualMachine,
 };
 
+/// How errors are reported: rendered for a terminal on stdout, or as one
+/// JSON object per line on stderr for tools.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorFormat {
+    Human,
+    Json,
+}
+
+impl ErrorFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        return match name {
+            "human" => Some(ErrorFormat::Human),
+            "json" => Some(ErrorFormat::Json),
+            _ => None,
+        };
+    }
+}
+
+/// An output `build` can write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Emit {
+    C,
+    Llvm,
+    Asm,
+    Ir,
+    Wat,
+    Bytecode,
+}
+
+impl Emit {
+    pub fn from_name(name: &str) -> Option<Self> {
+        return match name {
+            "c" => Some(Emit::C),
+            "llvm" => Some(Emit::Llvm),
+            "asm" => Some(Emit::Asm),
+            "ir" => Some(Emit::Ir),
+            "wat" => Some(Emit::Wat),
+            "bytecode" => Some(Emit::Bytecode),
+            _ => None,
+        };
+    }
+
+    /// What the output is, for messages.
+    pub fn description(&self) -> &'static str {
+        return match self {
+            Emit::C => "C source",
+            Emit::Llvm => "LLVM IR",
+            Emit::Asm => "Assembly",
+            Emit::Ir => "IR",
+            Emit::Wat => "WebAssembly text",
+            Emit::Bytecode => "Bytecode",
+        };
+    }
+
+    /// The extension of the file written next to the source by default.
+    pub fn extension(&self) -> &'static str {
+        return match self {
+            Emit::C => "c",
+            Emit::Llvm => "ll",
+            Emit::Asm => "s",
+            Emit::Ir => "ir",
+            Emit::Wat => "wat",
+            Emit::Bytecode => "mbc",
+        };
+    }
+
+    /// Whether the target is generated from the IR and so goes through the
+    /// optimization passes.
+    pub fn is_optimized(&self) -> bool {
+        return matches!(self, Emit::Ir | Emit::Llvm);
+    }
+}
+
 pub struct Compiler {
     file_name: String,
+    source: String,
+    error_format: ErrorFormat,
     symbol_table: SymbolTableRef,
 }
 
 impl Compiler {
-    pub fn new(file_name: String) -> Self {
-        return Compiler {
-            file_name,
+    /// Reads the source file the compiler works on.
+    pub fn open(file_name: &str, error_format: ErrorFormat) -> io::Result<Self> {
+        let source = fs::read_to_string(file_name)?;
+        return Ok(Compiler {
+            file_name: file_name.to_owned(),
+            source,
+            error_format,
             symbol_table: SymbolTable::new().into(),
-        };
+        });
     }
 
-    pub fn analyze(&self) -> Result<()> {
-        let program: ProgramRef = self.parse_program()?;
-        self.analyze_program(program.clone())?;
+    pub fn symbol_table(&self) -> SymbolTableRef {
+        return self.symbol_table.clone();
+    }
 
-        println!("{:#?}", program);
-        println!("{}", self.symbol_table.borrow());
-        return Ok(());
+    /// Lexes the whole file, carrying on past unknown tokens so that all of
+    /// them are reported.
+    pub fn tokens(&self) -> Result<Vec<Token>> {
+        let mut lexer = self.lexer();
+        let mut tokens = Vec::new();
+        let mut errors = Vec::new();
+        loop {
+            match lexer.next_token() {
+                Ok(token) if token.token_type() == &TokenType::Eof => break,
+                Ok(token) => tokens.push(token),
+                Err(err) => errors.push(err),
+            }
+        }
+        CompilerError::from_errors(errors)?;
+        return Ok(tokens);
     }
 
-    fn parse_program(&self) -> Result<ProgramRef> {
-        let content = self.read_file();
-        let lexer = Lexer::new(content, self.file_name.clone(), self.symbol_table.clone());
-        let mut parser = Parser::new(lexer, self.symbol_table.clone())?;
+    pub fn parse(&self) -> Result<ProgramRef> {
+        let mut parser = Parser::new(self.lexer(), self.symbol_table.clone())?;
         return Ok(parser.parse()?.into());
     }
 
-    fn analyze_program(&self, program: ProgramRef) -> Result<()> {
-        let mut semantic_analyzer = SemanticAnalyzer::new(self.symbol_table.clone(), program);
-        return semantic_analyzer.analyze();
+    /// The source in the canonical style. Only parsing is needed, so a file
+    /// with semantic errors can still be formatted.
+    pub fn format(&self) -> Result<String> {
+        let mut parser = Parser::new(self.lexer(), self.symbol_table.clone())?;
+        let program = parser.parse()?;
+        return Ok(Formatter::new(parser.comments()).format(&program));
+    }
+
+    /// The first line, counting from 1, where the source differs from
+    /// `formatted`.
+    pub fn first_difference(&self, formatted: &str) -> Option<usize> {
+        if self.source == formatted {
+            return None;
+        }
+        let line = self
+            .source
+            .lines()
+            .zip(formatted.lines())
+            .position(|(line, formatted)| line != formatted)
+            .unwrap_or_else(|| self.source.lines().count().min(formatted.lines().count()));
+        return Some(line + 1);
+    }
+
+    /// Replaces the source file with `content`.
+    pub fn rewrite(&self, content: &str) -> Result<()> {
+        return fs::write(&self.file_name, content).map_err(|err| {
+            CompilerError::CodeGen(format!("Cannot write {}: {}", self.file_name, err))
+        });
+    }
+
+    pub fn file_name(&self) -> &str {
+        return &self.file_name;
+    }
+
+    pub fn analyze(&self) -> Result<ProgramRef> {
+        let program = self.parse()?;
+        let mut semantic_analyzer =
+            SemanticAnalyzer::new(self.symbol_table.clone(), program.clone());
+        semantic_analyzer.analyze()?;
+        return Ok(program);
+    }
+
+    /// Compiles an analyzed program to `emit`. The IR and LLVM IR go through
+    /// `passes`, the other targets are generated from the syntax tree.
+    pub fn generate(
+        &self,
+        program: ProgramRef,
+        emit: Emit,
+        passes: &PassManager,
+    ) -> Result<Vec<u8>> {
+        return Ok(match emit {
+            Emit::C => CGenerator::new(program).generate()?.into_bytes(),
+            Emit::Llvm => LlvmGenerator::new(&program.borrow())
+                .with_passes(passes)
+                .generate()?
+                .into_bytes(),
+            Emit::Asm => X86Generator::new(&program.borrow())
+                .generate()?
+                .into_bytes(),
+            Emit::Ir => {
+                let mut module = IrBuilder::new(&program.borrow()).generate()?;
+                passes.run(&mut module);
+                module.to_string().into_bytes()
+            }
+            Emit::Wat => WatGenerator::new(&program.borrow())
+                .generate()?
+                .into_bytes(),
+            Emit::Bytecode => BytecodeGenerator::new(&program.borrow())
+                .generate()?
+                .encode(),
+        });
+    }
+
+    /// Writes `content` to `output`, to stdout when it is `-`, or next to
+    /// the source file with the extension of `emit`, and returns the path
+    /// written unless it was stdout.
+    pub fn write(
+        &self,
+        emit: Emit,
+        output: Option<&str>,
+        content: &[u8],
+    ) -> Result<Option<String>> {
+        let output = match output {
+            Some("-") => {
+                io::stdout().write_all(content).map_err(|err| {
+                    CompilerError::CodeGen(format!("Cannot write to stdout: {}", err))
+                })?;
+                return Ok(None);
+            }
+            Some(output) => output.to_owned(),
+            None => Path::new(&self.file_name)
+                .with_extension(emit.extension())
+                .to_string_lossy()
+                .to_string(),
+        };
+        fs::write(&output, content)
+            .map_err(|err| CompilerError::CodeGen(format!("Cannot write {}: {}", output, err)))?;
+        return Ok(Some(output));
+    }
+
+    /// Runs the analyzed program with the interpreter and returns its exit code.
+    pub fn run(&self, program: ProgramRef) -> Result<i32> {
+        let program = program.borrow();
+        return Interpreter::new(&program).run();
+    }
+
+    /// Compiles the analyzed program to bytecode and runs it on the virtual machine.
+    pub fn run_vm(&self, program: ProgramRef) -> Result<i32> {
+        let module = BytecodeGenerator::new(&program.borrow()).generate()?;
+        return VirtualMachine::new(&module).run();
+    }
+
+    /// Loads a `.mbc` file and runs it on the virtual machine.
+    pub fn run_bytecode(path: &str) -> Result<i32> {
+        let bytes = fs::read(path)
+            .map_err(|err| CompilerError::Runtime(format!("Cannot read {}: {}", path, err)))?;
+        let module = Module::decode(&bytes)?;
+        return VirtualMachine::new(&module).run();
+    }
+
+    /// Prints an error to stderr in the chosen error format.
+    pub fn report(&self, err: &CompilerError) {
+        Self::report_for(self.error_format, &self.file_name, &self.source, err);
+    }
+
+    /// Prints an error about `file_name`, whose text is `source`, to stderr
+    /// in `error_format`.
+    pub fn report_for(
+        error_format: ErrorFormat,
+        file_name: &str,
+        source: &str,
+        err: &CompilerError,
+    ) {
+        match error_format {
+            ErrorFormat::Human => eprintln!("{}", err.render(file_name, source)),
+            ErrorFormat::Json => eprintln!("{}", err.render_json(file_name)),
+        }
+    }
+
+    /// Prints to stderr in `error_format` that `file_name` could not be
+    /// opened, as there is no source to point into.
+    pub fn report_unreadable(error_format: ErrorFormat, file_name: &str, err: &io::Error) {
+        let message = format!("Cannot read {}: {}", file_name, err);
+        match error_format {
+            ErrorFormat::Human => eprintln!("{}", message),
+            ErrorFormat::Json => eprintln!("{}", json::render_message(&message, file_name)),
+        }
     }
 
-    fn read_file(&self) -> String {
-        return fs::read_to_string(&self.file_name).expect("File not found");
+    fn lexer(&self) -> Lexer {
+        return Lexer::new(
+            self.source.clone(),
+            self.file_name.clone(),
+            self.symbol_table.clone(),
+        );
     }
 }
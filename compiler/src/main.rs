@@ -1,10 +1,166 @@
+#![allow(clippy::needless_return)]
+
+use std::{panic, process::exit, thread};
+
+use analyzer::{error::Result, interpreter, ir::opt::PassManager};
+use cli::{Cli, Command, USAGE};
 use compiler::Compiler;
 
+pub mod cli;
 pub mod compiler;
 
+/// Exit status when compilation or the program fails.
+const EXIT_FAILURE: i32 = 1;
+/// Exit status when the command line is invalid.
+const EXIT_USAGE: i32 = 2;
+
 fn main() {
-    let compiler = Compiler::new("main.mb".to_owned());
-    if let Err(err) = compiler.analyze() {
-        println!("{}", err);
+    // The interpreter recurses with the program it runs, which takes a far
+    // larger stack than the main thread has. Without room for one, it still
+    // runs here, only less deep.
+    let runner = thread::Builder::new()
+        .stack_size(interpreter::STACK_SIZE)
+        .spawn(run);
+    match runner {
+        Ok(handle) => {
+            if let Err(panic) = handle.join() {
+                panic::resume_unwind(panic);
+            }
+        }
+        Err(_) => run(),
+    }
+}
+
+/// Parses the command line and runs it on every file.
+fn run() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let cli = match Cli::parse(&args) {
+        Ok(cli) => cli,
+        Err(message) => {
+            eprintln!("{}\n\n{}", message, USAGE);
+            exit(EXIT_USAGE);
+        }
+    };
+    if cli.command == Command::Help {
+        println!("{}", USAGE);
+        return;
+    }
+    let passes = match passes(&cli.passes) {
+        Ok(passes) => passes,
+        Err(err) => {
+            eprintln!("{}", err);
+            exit(EXIT_USAGE);
+        }
+    };
+
+    if cli.command == Command::Run && cli.files[0].ends_with(".mbc") {
+        match Compiler::run_bytecode(&cli.files[0]) {
+            Ok(code) => exit(code),
+            Err(err) => {
+                // A bytecode file has no source to point into.
+                Compiler::report_for(cli.error_format, &cli.files[0], "", &err);
+                exit(EXIT_FAILURE);
+            }
+        }
+    }
+
+    let mut failed = false;
+    for file in cli.files.iter() {
+        let compiler = match Compiler::open(file, cli.error_format) {
+            Ok(compiler) => compiler,
+            Err(err) => {
+                Compiler::report_unreadable(cli.error_format, file, &err);
+                failed = true;
+                continue;
+            }
+        };
+        match execute(&compiler, &cli, &passes) {
+            Ok(Some(code)) if cli.command == Command::Run => exit(code),
+            Ok(Some(_)) => failed = true,
+            Ok(None) => {}
+            Err(err) => {
+                compiler.report(&err);
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        exit(EXIT_FAILURE);
+    }
+}
+
+/// Runs the command on one file. Returns the program's exit status for `run`,
+/// and a failure for a file `fmt --check` finds unformatted.
+fn execute(compiler: &Compiler, cli: &Cli, passes: &PassManager) -> Result<Option<i32>> {
+    match cli.command {
+        Command::Check => {
+            compiler.analyze()?;
+        }
+        Command::Build => {
+            let program = compiler.analyze()?;
+            for emit in cli.emit.iter() {
+                let content = compiler.generate(program.clone(), *emit, passes)?;
+                let output = compiler.write(*emit, cli.output.as_deref(), &content)?;
+                if let Some(output) = output {
+                    println!("{} written to {}", emit.description(), output);
+                }
+            }
+        }
+        Command::Run => {
+            let program = compiler.analyze()?;
+            let code = if cli.vm {
+                compiler.run_vm(program)?
+            } else {
+                compiler.run(program)?
+            };
+            return Ok(Some(code));
+        }
+        Command::Fmt => {
+            let formatted = compiler.format()?;
+            let Some(line) = compiler.first_difference(&formatted) else {
+                return Ok(None);
+            };
+            if cli.check {
+                println!("{}:{}: not formatted", compiler.file_name(), line);
+                return Ok(Some(EXIT_FAILURE));
+            }
+            compiler.rewrite(&formatted)?;
+            println!("Formatted {}", compiler.file_name());
+        }
+        Command::DumpTokens => {
+            for token in compiler.tokens()? {
+                println!(
+                    "{:?}\t{}\t{}",
+                    token.span(),
+                    token.token_type(),
+                    token.text()
+                );
+            }
+        }
+        Command::DumpAst => println!("{:#?}", compiler.parse()?),
+        Command::DumpSymbols => {
+            compiler.analyze()?;
+            println!("{}", compiler.symbol_table().borrow());
+        }
+        Command::Help => {}
+    }
+    return Ok(None);
+}
+
+/// The optimization passes of IR and LLVM output: none by default, all of
+/// them with `-O` or the ones listed in `--passes=a,b`, minus any disabled
+/// by `--no-<pass>`.
+fn passes(args: &[String]) -> Result<PassManager> {
+    let mut passes = PassManager::new();
+    for arg in args.iter() {
+        if arg == "-O" {
+            passes = PassManager::standard();
+        } else if let Some(names) = arg.strip_prefix("--passes=") {
+            let names: Vec<&str> = names.split(',').collect();
+            passes = PassManager::with_passes(&names)?;
+        } else if let Some(name) = arg.strip_prefix("--no-") {
+            passes.disable(name)?;
+        }
     }
+    return Ok(passes);
 }
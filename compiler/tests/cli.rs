@@ -0,0 +1,108 @@
+//! Runs the compiler binary and checks what it reports and how it exits.
+
+#![allow(clippy::needless_return)]
+
+use std::{
+    fs,
+    path::PathBuf,
+    process::{self, Command, Output},
+};
+
+fn compiler(args: &[&str]) -> Output {
+    return Command::new(env!("CARGO_BIN_EXE_compiler"))
+        .args(args)
+        .output()
+        .unwrap();
+}
+
+/// Writes `source` to a file of its own for one test.
+fn source(name: &str, source: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("marble-cli-{}-{}.mb", process::id(), name));
+    fs::write(&path, source).unwrap();
+    return path;
+}
+
+#[test]
+fn valid_program_exits_with_0() {
+    let path = source("valid", "fn main() -> int {\n    return 0;\n}\n");
+    let output = compiler(&["check", path.to_str().unwrap()]);
+    fs::remove_file(&path).unwrap();
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn invalid_program_exits_with_1() {
+    let path = source("invalid", "fn main() -> int {\n    return missing;\n}\n");
+    let output = compiler(&["check", path.to_str().unwrap()]);
+    fs::remove_file(&path).unwrap();
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn invalid_command_line_exits_with_2() {
+    assert_eq!(compiler(&["check"]).status.code(), Some(2));
+    assert_eq!(
+        compiler(&["check", "--unknown", "main.mb"]).status.code(),
+        Some(2)
+    );
+    assert_eq!(compiler(&["unknown", "main.mb"]).status.code(), Some(2));
+}
+
+#[test]
+fn run_exits_with_the_status_of_main() {
+    let path = source("run", "fn main() -> int {\n    return 42;\n}\n");
+    let interpreted = compiler(&["run", path.to_str().unwrap()]);
+    let on_vm = compiler(&["run", "--vm", path.to_str().unwrap()]);
+    fs::remove_file(&path).unwrap();
+    assert_eq!(interpreted.status.code(), Some(42));
+    assert_eq!(on_vm.status.code(), Some(42));
+}
+
+#[test]
+fn unreadable_file_is_reported_as_json() {
+    let output = compiler(&["check", "--error-format=json", "missing.mb"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(
+        stderr.starts_with(
+            "{\"file\":\"missing.mb\",\"severity\":\"error\",\"code\":null,\
+             \"message\":\"Cannot read missing.mb: "
+        ),
+        "{}",
+        stderr
+    );
+    assert!(
+        stderr.ends_with(
+            "\"line\":null,\"column\":null,\"end_line\":null,\"end_column\":null,\
+             \"labels\":[],\"notes\":[],\"help\":null}\n"
+        ),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn optimization_options_need_an_optimized_target() {
+    let path = source("optimize", "fn main() -> int {\n    return 0;\n}\n");
+    let file = path.to_str().unwrap();
+    let rejected = [
+        compiler(&["build", "-O", "-o", "-", file]),
+        compiler(&["build", "--emit=ir,c", "--no-dce", file]),
+        compiler(&["run", "--passes=dce", file]),
+    ];
+    let ir = compiler(&["build", "--emit=ir", "-O", "-o", "-", file]);
+    let llvm = compiler(&[
+        "build",
+        "--emit=llvm",
+        "--passes=inline,dce",
+        "-o",
+        "-",
+        file,
+    ]);
+    fs::remove_file(&path).unwrap();
+    for output in rejected {
+        assert_eq!(output.status.code(), Some(2));
+    }
+    assert_eq!(ir.status.code(), Some(0));
+    assert_eq!(llvm.status.code(), Some(0));
+}
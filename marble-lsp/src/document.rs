@@ -0,0 +1,126 @@
+use analyzer::{
+    ast::program::ProgramRef,
+    diagnostic::{Diagnostic, ErrorCode},
+    error::CompilerError,
+    lexer::{
+        token::{Position, Span},
+        Lexer,
+    },
+    parser::Parser,
+    semantic::SemanticAnalyzer,
+    symbol_table::{SymbolTable, SymbolTableRef},
+};
+
+use crate::{index::Index, json::Value};
+
+/// An open file and what the analyzer found in it.
+pub struct Document {
+    pub text: String,
+    pub diagnostics: Vec<Diagnostic>,
+    /// The declarations that parsed, even when others did not.
+    pub program: Option<ProgramRef>,
+    pub symbol_table: SymbolTableRef,
+    pub index: Index,
+    line_starts: Vec<usize>,
+}
+
+impl Document {
+    /// Lexes, parses and analyzes `text`. Semantic analysis only runs once
+    /// the file parses, since errors in a partial program are misleading.
+    pub fn new(file_name: &str, text: String) -> Self {
+        let symbol_table: SymbolTableRef = SymbolTable::new().into();
+        let lexer = Lexer::new(text.clone(), file_name.to_owned(), symbol_table.clone());
+        let mut errors = Vec::new();
+        let mut program = None;
+        match Parser::new(lexer, symbol_table.clone()).and_then(|mut parser| parser.parse_partial())
+        {
+            Ok((parsed, parse_errors)) => {
+                let parsed: ProgramRef = parsed.into();
+                if parse_errors.is_empty() {
+                    let mut analyzer = SemanticAnalyzer::new(symbol_table.clone(), parsed.clone());
+                    if let Err(err) = analyzer.analyze() {
+                        errors.push(err);
+                    }
+                } else {
+                    errors = parse_errors;
+                }
+                program = Some(parsed);
+            }
+            Err(err) => errors.push(err),
+        }
+
+        let diagnostics = errors.iter().flat_map(Self::diagnostics).collect();
+        let index = match &program {
+            Some(program) => Index::new(&program.borrow(), &symbol_table),
+            None => Index::default(),
+        };
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        return Document {
+            text,
+            diagnostics,
+            program,
+            symbol_table,
+            index,
+            line_starts,
+        };
+    }
+
+    fn diagnostics(err: &CompilerError) -> Vec<Diagnostic> {
+        let diagnostics: Vec<Diagnostic> = err.diagnostics().into_iter().cloned().collect();
+        if diagnostics.is_empty() {
+            return vec![Diagnostic::error(ErrorCode::Internal, err.to_string())];
+        }
+        return diagnostics;
+    }
+
+    /// The text of a 1-based line, without its line break.
+    pub fn line(&self, row: usize) -> &str {
+        let Some(start) = row.checked_sub(1).and_then(|i| self.line_starts.get(i)) else {
+            return "";
+        };
+        let end = self
+            .line_starts
+            .get(row)
+            .map(|end| end - 1)
+            .unwrap_or(self.text.len());
+        return self.text[*start..end].trim_end_matches('\r');
+    }
+
+    /// Converts an LSP position, with 0-based lines and UTF-16 characters,
+    /// to the 1-based row and byte column spans use.
+    pub fn position(&self, position: &Value) -> Option<(usize, usize)> {
+        let row = position.get("line").as_usize()? + 1;
+        let character = position.get("character").as_usize()?;
+        let mut units = 0;
+        let mut col = 1;
+        for c in self.line(row).chars() {
+            if units >= character {
+                break;
+            }
+            units += c.len_utf16();
+            col += c.len_utf8();
+        }
+        return Some((row, col));
+    }
+
+    pub fn lsp_position(&self, position: &Position) -> Value {
+        let row = position.row().max(1);
+        let col = position.col().max(1);
+        let line = self.line(row);
+        let prefix = line.get(..col - 1).unwrap_or(line);
+        let character: usize = prefix.chars().map(char::len_utf16).sum();
+        return Value::object(vec![
+            ("line", Value::from(row - 1)),
+            ("character", Value::from(character)),
+        ]);
+    }
+
+    pub fn range(&self, span: &Span) -> Value {
+        return Value::object(vec![
+            ("start", self.lsp_position(span.start())),
+            ("end", self.lsp_position(span.end())),
+        ]);
+    }
+}
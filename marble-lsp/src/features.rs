@@ -0,0 +1,450 @@
+use analyzer::{
+    ast::{
+        declarations::Declarations, expressions::member_access_expr::MemberAccessExpression,
+        identifier::Identifier, type_specifier::TypeSpecifier,
+    },
+    codegen::type_context::TypeContext,
+    diagnostic::{Diagnostic, Severity},
+    lexer::token::Span,
+    symbol_table::symbol::{node::NodeTypes, SymbolNodeRef},
+};
+
+use crate::{document::Document, index::contains, json::Value};
+
+// Kinds from the protocol's SymbolKind and CompletionItemKind.
+const SYMBOL_CLASS: usize = 5;
+const SYMBOL_METHOD: usize = 6;
+const SYMBOL_FIELD: usize = 8;
+const SYMBOL_ENUM: usize = 10;
+const SYMBOL_FUNCTION: usize = 12;
+const SYMBOL_CONSTANT: usize = 14;
+const SYMBOL_ENUM_MEMBER: usize = 22;
+const SYMBOL_STRUCT: usize = 23;
+const COMPLETION_METHOD: usize = 2;
+const COMPLETION_FIELD: usize = 5;
+
+/// The parameters of a `textDocument/publishDiagnostics` notification.
+pub fn diagnostics(uri: &str, document: &Document) -> Value {
+    let diagnostics = document
+        .diagnostics
+        .iter()
+        .map(|diagnostic| self::diagnostic(uri, document, diagnostic))
+        .collect();
+    return Value::object(vec![
+        ("uri", Value::from(uri)),
+        ("diagnostics", Value::Array(diagnostics)),
+    ]);
+}
+
+fn diagnostic(uri: &str, document: &Document, diagnostic: &Diagnostic) -> Value {
+    let span = diagnostic.span.clone().unwrap_or_default();
+    let mut message = diagnostic.message.clone();
+    for note in diagnostic.notes.iter() {
+        message += format!("\nnote: {}", note).as_str();
+    }
+    if let Some(help) = &diagnostic.help {
+        message += format!("\nhelp: {}", help).as_str();
+    }
+    let severity: usize = match diagnostic.severity {
+        Severity::Error => 1,
+        Severity::Warning => 2,
+    };
+    let related = diagnostic
+        .labels
+        .iter()
+        .map(|label| {
+            Value::object(vec![
+                ("location", location(uri, document, &label.span)),
+                ("message", Value::from(label.message.as_str())),
+            ])
+        })
+        .collect();
+    return Value::object(vec![
+        ("range", document.range(&span)),
+        ("severity", Value::from(severity)),
+        ("code", Value::from(diagnostic.code.as_str())),
+        ("source", Value::from("marble")),
+        ("message", Value::from(message)),
+        ("relatedInformation", Value::Array(related)),
+    ]);
+}
+
+/// The declaration of the name under the cursor and its doc comment.
+pub fn hover(document: &Document, position: (usize, usize)) -> Value {
+    let Some(occurrence) = document.index.occurrence_at(position) else {
+        return Value::Null;
+    };
+    let mut contents = format!("```marble\n{}\n```", occurrence.signature);
+    if let Some(doc) = &occurrence.doc {
+        contents += format!("\n\n{}", doc).as_str();
+    }
+    return Value::object(vec![
+        (
+            "contents",
+            Value::object(vec![
+                ("kind", Value::from("markdown")),
+                ("value", Value::from(contents)),
+            ]),
+        ),
+        ("range", document.range(&occurrence.span)),
+    ]);
+}
+
+/// Where the name under the cursor is defined.
+pub fn definition(uri: &str, document: &Document, position: (usize, usize)) -> Value {
+    return document
+        .index
+        .occurrence_at(position)
+        .and_then(|occurrence| occurrence.definition.as_ref())
+        .map(|definition| location(uri, document, definition))
+        .unwrap_or(Value::Null);
+}
+
+fn location(uri: &str, document: &Document, span: &Span) -> Value {
+    return Value::object(vec![
+        ("uri", Value::from(uri)),
+        ("range", document.range(span)),
+    ]);
+}
+
+/// The functions, structs, enums, constants and impl methods of the file.
+pub fn document_symbols(document: &Document) -> Value {
+    let Some(program) = &document.program else {
+        return Value::Array(Vec::new());
+    };
+    let mut symbols = Vec::new();
+    for decl in program.borrow().declarations.iter() {
+        match decl {
+            Declarations::Fn(fn_decl) => symbols.push(symbol(
+                document,
+                &fn_decl.fn_name,
+                &fn_decl.fn_name.span.to(&fn_decl.body.span),
+                SYMBOL_FUNCTION,
+                format!("-> {}", fn_decl.return_type),
+                Vec::new(),
+            )),
+            Declarations::Struct(struct_decl) => {
+                let fields = struct_decl
+                    .fields
+                    .iter()
+                    .map(|field| {
+                        let field = &field.field;
+                        symbol(
+                            document,
+                            &field.identifier,
+                            &field.identifier.span,
+                            SYMBOL_FIELD,
+                            field.type_specifier.to_string(),
+                            Vec::new(),
+                        )
+                    })
+                    .collect();
+                symbols.push(symbol(
+                    document,
+                    &struct_decl.name,
+                    &struct_decl.name.span,
+                    SYMBOL_STRUCT,
+                    String::new(),
+                    fields,
+                ));
+            }
+            Declarations::Enum(enum_decl) => {
+                let items = enum_decl
+                    .fields
+                    .iter()
+                    .map(|item| {
+                        symbol(
+                            document,
+                            &item.identifier,
+                            &item.span,
+                            SYMBOL_ENUM_MEMBER,
+                            String::new(),
+                            Vec::new(),
+                        )
+                    })
+                    .collect();
+                symbols.push(symbol(
+                    document,
+                    &enum_decl.name,
+                    &enum_decl.name.span,
+                    SYMBOL_ENUM,
+                    String::new(),
+                    items,
+                ));
+            }
+            Declarations::Impl(impl_decl) => {
+                let TypeSpecifier::UserDefine(name) = &impl_decl.name else {
+                    continue;
+                };
+                let mut span = name.span.clone();
+                let methods = impl_decl
+                    .member_functions
+                    .iter()
+                    .map(|member_fn| {
+                        let prototype = &member_fn.prototype;
+                        let method_span = prototype.name.span.to(&member_fn.body.span);
+                        span = name.span.to(&method_span);
+                        symbol(
+                            document,
+                            &prototype.name,
+                            &method_span,
+                            SYMBOL_METHOD,
+                            format!("-> {}", prototype.return_type),
+                            Vec::new(),
+                        )
+                    })
+                    .collect();
+                let impl_name = Identifier::new(format!("impl {}", name), name.span.clone());
+                symbols.push(symbol(
+                    document,
+                    &impl_name,
+                    &span,
+                    SYMBOL_CLASS,
+                    String::new(),
+                    methods,
+                ));
+            }
+            Declarations::Const(const_decl) => symbols.push(symbol(
+                document,
+                &const_decl.identifier,
+                &const_decl.identifier.span,
+                SYMBOL_CONSTANT,
+                const_decl.type_specifier.to_string(),
+                Vec::new(),
+            )),
+        }
+    }
+    return Value::Array(symbols);
+}
+
+fn symbol(
+    document: &Document,
+    name: &Identifier,
+    span: &Span,
+    kind: usize,
+    detail: String,
+    children: Vec<Value>,
+) -> Value {
+    return Value::object(vec![
+        ("name", Value::from(name.to_string())),
+        ("detail", Value::from(detail)),
+        ("kind", Value::from(kind)),
+        ("range", document.range(span)),
+        ("selectionRange", document.range(&name.span)),
+        ("children", Value::Array(children)),
+    ]);
+}
+
+/// The fields and methods of the struct before a `.` or `->` that ends
+/// right before the cursor, apart from the part of the name already typed.
+/// Member functions without a receiver are called as `Struct::name()`
+/// instead, so they are left out, and so are private members outside the
+/// `impl` blocks of their struct.
+pub fn completion(document: &Document, (row, col): (usize, usize)) -> Value {
+    let line = document.line(row);
+    let prefix = line.get(..col - 1).unwrap_or(line);
+    let receiver = prefix.trim_end_matches(|c: char| c.is_ascii_alphanumeric() || c == '_');
+    let receiver = match receiver.strip_suffix("->") {
+        Some(receiver) => receiver,
+        None => match receiver.strip_suffix('.') {
+            Some(receiver) => receiver,
+            None => return Value::Array(Vec::new()),
+        },
+    };
+    let Some(type_specifier) = receiver_type(document, receiver, (row, col)) else {
+        return Value::Array(Vec::new());
+    };
+    let Ok(struct_name) = TypeContext::struct_name(&type_specifier) else {
+        return Value::Array(Vec::new());
+    };
+
+    let root = document.symbol_table.borrow().root();
+    let scope = scope_at(document, &root, (row, col));
+    let root = root.borrow();
+    let Some(struct_node) = root.children.get(struct_name) else {
+        return Value::Array(Vec::new());
+    };
+    let mut items: Vec<(String, usize, String)> = struct_node
+        .borrow()
+        .children
+        .values()
+        .filter_map(|member| {
+            if !MemberAccessExpression::is_accessible(member, struct_node, &scope) {
+                return None;
+            }
+            let member = member.borrow();
+            let name = member.data.name.clone();
+            return match &member.data.node_type {
+                NodeTypes::StructField(field) => {
+                    Some((name, COMPLETION_FIELD, field.type_specifier.to_string()))
+                }
+                NodeTypes::Function(method) if method.receiver.is_some() => {
+                    let params: Vec<String> = method
+                        .params
+                        .iter()
+                        .map(|param| param.to_string())
+                        .collect();
+                    let detail = format!("fn({}) -> {}", params.join(", "), method.return_type);
+                    Some((name, COMPLETION_METHOD, detail))
+                }
+                _ => None,
+            };
+        })
+        .collect();
+    items.sort();
+    let items = items
+        .into_iter()
+        .map(|(label, kind, detail)| {
+            Value::object(vec![
+                ("label", Value::from(label)),
+                ("kind", Value::from(kind)),
+                ("detail", Value::from(detail)),
+            ])
+        })
+        .collect();
+    return Value::Array(items);
+}
+
+/// The symbol the code at `position` is analyzed in: the struct of the
+/// `impl` block around it, or the root outside of them.
+fn scope_at(document: &Document, root: &SymbolNodeRef, position: (usize, usize)) -> SymbolNodeRef {
+    let Some(program) = &document.program else {
+        return root.clone();
+    };
+    for decl in program.borrow().declarations.iter() {
+        let Declarations::Impl(impl_decl) = decl else {
+            continue;
+        };
+        if !contains(&impl_decl.span, position) {
+            continue;
+        }
+        let Ok(name) = impl_decl.name.to_symbol() else {
+            continue;
+        };
+        if let Some(struct_node) = root.borrow().children.get(name) {
+            return struct_node.clone();
+        }
+    }
+    return root.clone();
+}
+
+/// What follows a name in a receiver like `a.b()[0]`.
+#[derive(Debug, PartialEq)]
+enum Postfix {
+    Call,
+    Index,
+}
+
+/// Resolves the type of a receiver such as `p`, `self->next` or
+/// `make().points[1]` written before the cursor.
+fn receiver_type(
+    document: &Document,
+    receiver: &str,
+    position: (usize, usize),
+) -> Option<TypeSpecifier> {
+    let chain = receiver_chain(receiver)?;
+    let (first, postfixes) = chain.first()?;
+    let root = document.symbol_table.borrow().root();
+
+    let mut type_specifier = if postfixes.first() == Some(&Postfix::Call) {
+        let root = root.borrow();
+        let function = root.children.get(first)?.borrow();
+        match &function.data.node_type {
+            NodeTypes::Function(function) => function.return_type.clone(),
+            _ => return None,
+        }
+    } else {
+        document
+            .index
+            .local_at(first, position)?
+            .type_specifier
+            .clone()?
+    };
+    type_specifier = elements(type_specifier, postfixes)?;
+
+    for (name, postfixes) in chain.iter().skip(1) {
+        let struct_name = TypeContext::struct_name(&type_specifier).ok()?.to_owned();
+        let root = root.borrow();
+        let struct_node = root.children.get(&struct_name)?.borrow();
+        let member = struct_node.children.get(name)?.borrow();
+        type_specifier = match &member.data.node_type {
+            NodeTypes::StructField(field) => field.type_specifier.clone(),
+            NodeTypes::Function(method) => method.return_type.clone(),
+            _ => return None,
+        };
+        type_specifier = elements(type_specifier, postfixes)?;
+    }
+    return Some(type_specifier);
+}
+
+/// Applies the indexing in `postfixes` to a value of `type_specifier`.
+fn elements(mut type_specifier: TypeSpecifier, postfixes: &[Postfix]) -> Option<TypeSpecifier> {
+    for postfix in postfixes.iter() {
+        if postfix == &Postfix::Index {
+            type_specifier = match type_specifier {
+                TypeSpecifier::ArrayType { type_specifier, .. } => *type_specifier,
+                TypeSpecifier::Pointer(type_specifier) => *type_specifier,
+                _ => return None,
+            };
+        }
+    }
+    return Some(type_specifier);
+}
+
+/// Splits a receiver into its names and what follows each of them.
+fn receiver_chain(receiver: &str) -> Option<Vec<(String, Vec<Postfix>)>> {
+    let mut rest = receiver.trim_end();
+    let mut chain = Vec::new();
+    loop {
+        let mut postfixes = Vec::new();
+        loop {
+            if let Some(inner) = rest.strip_suffix(')') {
+                rest = skip_group(inner, b'(', b')')?;
+                postfixes.push(Postfix::Call);
+            } else if let Some(inner) = rest.strip_suffix(']') {
+                rest = skip_group(inner, b'[', b']')?;
+                postfixes.push(Postfix::Index);
+            } else {
+                break;
+            }
+        }
+        postfixes.reverse();
+
+        let start = rest
+            .rfind(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        let name = &rest[start..];
+        if name.is_empty() {
+            return None;
+        }
+        chain.push((name.to_owned(), postfixes));
+        rest = &rest[..start];
+        rest = match rest.strip_suffix("->") {
+            Some(rest) => rest,
+            None => match rest.strip_suffix('.') {
+                Some(rest) => rest,
+                None => break,
+            },
+        };
+    }
+    chain.reverse();
+    return Some(chain);
+}
+
+/// Strips a bracketed group whose closing bracket was already removed from
+/// the end of `text`.
+fn skip_group(text: &str, open: u8, close: u8) -> Option<&str> {
+    let mut depth = 1;
+    for (i, byte) in text.bytes().enumerate().rev() {
+        if byte == close {
+            depth += 1;
+        } else if byte == open {
+            depth -= 1;
+            if depth == 0 {
+                return Some(&text[..i]);
+            }
+        }
+    }
+    return None;
+}
@@ -0,0 +1,667 @@
+use std::collections::HashMap;
+
+use analyzer::{
+    ast::{
+        declarations::{
+            fn_decl::FnDeclaration,
+            impl_decl::{ImplDeclaration, MemberFunction},
+            Declarations,
+        },
+        expressions::{fn_call_expr::FnCallExpression, Expression},
+        identifier::Identifier,
+        program::Program,
+        statements::{
+            block_stmt::BlockStmt, for_stmt::VariableInit, let_stmt::LetStmt, match_stmt::MatchArm,
+            Statements,
+        },
+        type_specifier::TypeSpecifier,
+        variable_type::VariableType,
+    },
+    codegen::type_context::TypeContext,
+    lexer::token::Span,
+    symbol_table::{symbol::SymbolNodeRef, SymbolTableRef},
+};
+
+/// A name written in the source, with what hovering it shows and where it
+/// is defined.
+#[derive(Debug, Clone)]
+pub struct Occurrence {
+    pub span: Span,
+    /// The declaration of the name, as it would be written in code.
+    pub signature: String,
+    pub doc: Option<String>,
+    pub definition: Option<Span>,
+}
+
+/// A variable or parameter and the block it is visible in.
+#[derive(Debug, Clone)]
+pub struct Local {
+    pub name: String,
+    pub type_specifier: Option<TypeSpecifier>,
+    pub span: Span,
+    pub scope: Span,
+}
+
+/// What the editor features look up by position, built by walking an
+/// analyzed program.
+#[derive(Debug, Default)]
+pub struct Index {
+    pub occurrences: Vec<Occurrence>,
+    pub locals: Vec<Local>,
+}
+
+impl Index {
+    pub fn new(program: &Program, symbol_table: &SymbolTableRef) -> Self {
+        let mut builder = IndexBuilder {
+            context: TypeContext::new(program),
+            root: symbol_table.borrow().root(),
+            index: Index::default(),
+            globals: HashMap::new(),
+            members: HashMap::new(),
+            scopes: Vec::new(),
+        };
+        builder.declare_globals(program);
+        for decl in program.declarations.iter() {
+            builder.declaration(decl);
+        }
+        return builder.index;
+    }
+
+    /// The name written at `position`, a (row, column) pair.
+    pub fn occurrence_at(&self, position: (usize, usize)) -> Option<&Occurrence> {
+        return self
+            .occurrences
+            .iter()
+            .find(|occurrence| contains(&occurrence.span, position));
+    }
+
+    /// The variable `name` refers to at `position`.
+    pub fn local_at(&self, name: &str, position: (usize, usize)) -> Option<&Local> {
+        return self
+            .locals
+            .iter()
+            .filter(|local| {
+                local.name == name
+                    && contains(&local.scope, position)
+                    && start(&local.span) <= position
+            })
+            .max_by_key(|local| start(&local.span));
+    }
+}
+
+pub fn start(span: &Span) -> (usize, usize) {
+    return (span.start().row(), span.start().col());
+}
+
+/// Whether `position` falls in `span`, counting the position just past the
+/// end so that a name can be found while the cursor sits behind it.
+pub fn contains(span: &Span, position: (usize, usize)) -> bool {
+    let end = (span.end().row(), span.end().col());
+    return start(span) <= position && position <= end && span.start().row() > 0;
+}
+
+/// A signature and its doc comment.
+type Signature = (String, Option<String>);
+
+struct Scope {
+    span: Span,
+    locals: Vec<Local>,
+    signatures: Vec<String>,
+}
+
+struct IndexBuilder {
+    context: TypeContext,
+    root: SymbolNodeRef,
+    index: Index,
+    globals: HashMap<String, Signature>,
+    members: HashMap<(String, String), Signature>,
+    scopes: Vec<Scope>,
+}
+
+impl IndexBuilder {
+    /// Records the signatures of every declaration first, so that uses can
+    /// come before the declaration in the file.
+    fn declare_globals(&mut self, program: &Program) {
+        for decl in program.declarations.iter() {
+            match decl {
+                Declarations::Fn(fn_decl) => {
+                    let params: Vec<&VariableType> = fn_decl.params.iter().collect();
+                    let signature = fn_signature(
+                        None,
+                        fn_decl.fn_name.as_ref(),
+                        &params,
+                        &fn_decl.return_type,
+                    );
+                    self.globals.insert(
+                        fn_decl.fn_name.to_string(),
+                        (signature, fn_decl.doc.clone()),
+                    );
+                }
+                Declarations::Struct(struct_decl) => {
+                    let name = struct_decl.name.to_string();
+                    for field in struct_decl.fields.iter() {
+                        let field = &field.field;
+                        let signature =
+                            format!("{}.{}: {}", name, field.identifier, field.type_specifier);
+                        self.members.insert(
+                            (name.clone(), field.identifier.to_string()),
+                            (signature, None),
+                        );
+                    }
+                    let signature = format!("struct {}", name);
+                    self.globals
+                        .insert(name, (signature, struct_decl.doc.clone()));
+                }
+                Declarations::Enum(enum_decl) => {
+                    let name = enum_decl.name.to_string();
+                    for item in enum_decl.fields.iter() {
+                        let mut payload = String::new();
+                        if !item.payload.is_empty() {
+                            let types: Vec<String> =
+                                item.payload.iter().map(|t| t.to_string()).collect();
+                            payload = format!("({})", types.join(", "));
+                        }
+                        let signature = format!(
+                            "{}::{}{} = {}",
+                            name, item.identifier, payload, item.discriminant
+                        );
+                        self.members.insert(
+                            (name.clone(), item.identifier.to_string()),
+                            (signature, None),
+                        );
+                    }
+                    let signature = format!("enum {}", name);
+                    self.globals
+                        .insert(name, (signature, enum_decl.doc.clone()));
+                }
+                Declarations::Impl(impl_decl) => {
+                    let Ok(name) = impl_decl.name.to_symbol() else {
+                        continue;
+                    };
+                    for member_fn in impl_decl.member_functions.iter() {
+                        let prototype = &member_fn.prototype;
+                        let signature = fn_signature(
+                            Some(name),
+                            prototype.name.as_ref(),
+                            &member_fn.params(),
+                            &prototype.return_type,
+                        );
+                        self.members.insert(
+                            (name.to_owned(), prototype.name.to_string()),
+                            (signature, prototype.doc.clone()),
+                        );
+                    }
+                }
+                Declarations::Const(const_decl) => {
+                    let signature = format!(
+                        "const {}: {}",
+                        const_decl.identifier, const_decl.type_specifier
+                    );
+                    self.globals
+                        .insert(const_decl.identifier.to_string(), (signature, None));
+                }
+            }
+        }
+    }
+
+    fn declaration(&mut self, decl: &Declarations) {
+        match decl {
+            Declarations::Fn(fn_decl) => self.fn_declaration(fn_decl),
+            Declarations::Struct(struct_decl) => {
+                self.reference_global(&struct_decl.name);
+                for field in struct_decl.fields.iter() {
+                    let field = &field.field;
+                    self.reference_member(struct_decl.name.as_ref(), &field.identifier);
+                    self.type_specifier(&field.type_specifier);
+                }
+            }
+            Declarations::Enum(enum_decl) => {
+                self.reference_global(&enum_decl.name);
+                for item in enum_decl.fields.iter() {
+                    self.reference_member(enum_decl.name.as_ref(), &item.identifier);
+                    for type_specifier in item.payload.iter() {
+                        self.type_specifier(type_specifier);
+                    }
+                    if let Some(value) = &item.value {
+                        self.expression(value);
+                    }
+                }
+            }
+            Declarations::Impl(impl_decl) => self.impl_declaration(impl_decl),
+            Declarations::Const(const_decl) => {
+                self.reference_global(&const_decl.identifier);
+                self.type_specifier(&const_decl.type_specifier);
+                self.expression(&const_decl.value);
+            }
+        }
+    }
+
+    fn fn_declaration(&mut self, fn_decl: &FnDeclaration) {
+        self.reference_global(&fn_decl.fn_name);
+        self.type_specifier(&fn_decl.return_type);
+        self.push_scope(&fn_decl.body.span);
+        for param in fn_decl.params.iter() {
+            self.param(param);
+        }
+        self.statements(&fn_decl.body);
+        self.pop_scope();
+    }
+
+    fn impl_declaration(&mut self, impl_decl: &ImplDeclaration) {
+        self.type_specifier(&impl_decl.name);
+        let Ok(name) = impl_decl.name.to_symbol() else {
+            return;
+        };
+        for member_fn in impl_decl.member_functions.iter() {
+            self.member_function(name, member_fn);
+        }
+    }
+
+    fn member_function(&mut self, struct_name: &str, member_fn: &MemberFunction) {
+        let prototype = &member_fn.prototype;
+        self.reference_member(struct_name, &prototype.name);
+        self.type_specifier(&prototype.return_type);
+        self.push_scope(&member_fn.body.span);
+        for param in member_fn.params() {
+            self.param(param);
+        }
+        self.statements(&member_fn.body);
+        self.pop_scope();
+    }
+
+    fn param(&mut self, param: &VariableType) {
+        self.type_specifier(&param.type_specifier);
+        let signature = format!("{}: {}", param.identifier, param.type_specifier);
+        self.local(
+            &param.identifier,
+            Some(param.type_specifier.clone()),
+            signature,
+        );
+    }
+
+    fn statements(&mut self, block: &BlockStmt) {
+        for stmt in block.stmts.iter() {
+            self.statement(stmt);
+        }
+    }
+
+    fn block(&mut self, block: &BlockStmt) {
+        self.push_scope(&block.span);
+        self.statements(block);
+        self.pop_scope();
+    }
+
+    fn statement(&mut self, stmt: &Statements) {
+        match stmt {
+            Statements::Let(let_stmt) => self.let_stmt(let_stmt),
+            Statements::Const(const_decl) => {
+                self.type_specifier(&const_decl.type_specifier);
+                self.expression(&const_decl.value);
+                let signature = format!(
+                    "const {}: {}",
+                    const_decl.identifier, const_decl.type_specifier
+                );
+                self.local(
+                    &const_decl.identifier,
+                    Some(const_decl.type_specifier.clone()),
+                    signature,
+                );
+            }
+            Statements::Return(return_stmt) => {
+                if let Some(value) = &return_stmt.value {
+                    self.expression(value);
+                }
+            }
+            Statements::Defer(defer_stmt) => self.expression(&defer_stmt.expr),
+            Statements::For(for_stmt) => {
+                self.push_scope(&for_stmt.span);
+                match &for_stmt.init {
+                    VariableInit::Let(let_stmt) => self.let_stmt(let_stmt),
+                    VariableInit::Assign(assignment) => {
+                        self.expression(&assignment.variable);
+                        self.expression(&assignment.value);
+                    }
+                }
+                self.expression(&for_stmt.condition);
+                self.expression(&for_stmt.increment);
+                self.block(&for_stmt.block);
+                self.pop_scope();
+            }
+            Statements::While(while_stmt) => {
+                self.expression(&while_stmt.condition);
+                self.block(&while_stmt.block);
+            }
+            Statements::DoWhile(do_while_stmt) => {
+                self.block(&do_while_stmt.block);
+                self.expression(&do_while_stmt.condition);
+            }
+            Statements::If(if_stmt) => {
+                self.expression(&if_stmt.condition);
+                self.block(&if_stmt.block);
+                for else_if in if_stmt.else_ifs.iter() {
+                    self.expression(&else_if.condition);
+                    self.block(&else_if.block);
+                }
+                if let Some(else_stmt) = &if_stmt.else_stmt {
+                    self.block(else_stmt);
+                }
+            }
+            Statements::Switch(switch_stmt) => {
+                self.expression(&switch_stmt.value);
+                for case in switch_stmt.cases.iter() {
+                    self.expression(&case.value);
+                    self.block(&case.block);
+                }
+                if let Some(default) = &switch_stmt.default {
+                    self.block(default);
+                }
+            }
+            Statements::Match(match_stmt) => {
+                self.expression(&match_stmt.value);
+                for arm in match_stmt.arms.iter() {
+                    self.match_arm(arm);
+                }
+                if let Some(default) = &match_stmt.default {
+                    self.block(default);
+                }
+            }
+            Statements::Break(_) | Statements::Continue(_) => {}
+            Statements::Block(block) => self.block(block),
+            Statements::Expression(expr) => self.expression(expr),
+        }
+    }
+
+    fn match_arm(&mut self, arm: &MatchArm) {
+        self.reference_global(&arm.name);
+        self.reference_member(arm.name.as_ref(), &arm.item);
+        self.push_scope(&arm.span);
+        let payload = self
+            .context
+            .enum_payload(arm.name.as_ref(), arm.item.as_ref())
+            .map(|payload| payload.to_vec())
+            .unwrap_or_default();
+        for (index, binding) in arm.bindings.iter().enumerate() {
+            let Some(binding) = binding else {
+                continue;
+            };
+            let type_specifier = payload.get(index).cloned();
+            let signature = match &type_specifier {
+                Some(type_specifier) => format!("let {}: {}", binding, type_specifier),
+                None => format!("let {}", binding),
+            };
+            self.local(binding, type_specifier, signature);
+        }
+        self.block(&arm.block);
+        self.pop_scope();
+    }
+
+    fn let_stmt(&mut self, let_stmt: &LetStmt) {
+        if let Some(type_specifier) = &let_stmt.type_specifier {
+            self.type_specifier(type_specifier);
+        }
+        let mut type_specifier = let_stmt.type_specifier.clone();
+        if let Some(value) = &let_stmt.value {
+            self.expression(value);
+            if type_specifier.is_none() {
+                type_specifier = self.context.expr_type(value).ok();
+            }
+        }
+        let signature = match &type_specifier {
+            Some(type_specifier) => format!("let {}: {}", let_stmt.identifier, type_specifier),
+            None => format!("let {}", let_stmt.identifier),
+        };
+        self.local(&let_stmt.identifier, type_specifier, signature);
+    }
+
+    fn expression(&mut self, expr: &Expression) {
+        match expr {
+            Expression::Binary(binary) => {
+                self.expression(&binary.left);
+                self.expression(&binary.right);
+            }
+            Expression::Assignment(assignment) => {
+                self.expression(&assignment.variable);
+                self.expression(&assignment.value);
+            }
+            Expression::Unary(unary) => self.expression(&unary.value),
+            Expression::ArrayIndex(array_index) => {
+                self.expression(&array_index.array);
+                self.expression(&array_index.index);
+                if let Some(second_index) = &array_index.second_index {
+                    self.expression(second_index);
+                }
+            }
+            Expression::MemberAccess(member_access) => {
+                self.expression(&member_access.object);
+                match self.context.expr_type(&member_access.object) {
+                    Ok(object) => self.property(&object, &member_access.property),
+                    Err(_) => self.property_args(&member_access.property),
+                }
+            }
+            Expression::FnCall(fn_call) => self.fn_call(None, fn_call),
+            Expression::ArrayInit(array_init) => {
+                for item in array_init.array.iter() {
+                    self.expression(item);
+                }
+            }
+            Expression::ObjectInit(object_init) => {
+                let Expression::Identifier(name) = object_init.object.as_ref() else {
+                    return;
+                };
+                self.reference_global(&name.id);
+                for field in object_init.fields.iter() {
+                    self.reference_member(name.id.as_ref(), &field.name);
+                    self.expression(&field.value);
+                }
+            }
+            Expression::Identifier(identifier) => self.reference(&identifier.id),
+            Expression::EnumItem(enum_item) => {
+                self.reference_global(&enum_item.name);
+                self.reference_member(enum_item.name.as_ref(), &enum_item.item);
+                for arg in enum_item.args.iter() {
+                    self.expression(arg);
+                }
+            }
+            Expression::Primitive(_) => {}
+            Expression::Cast(cast) => {
+                self.type_specifier(&cast.target_type);
+                self.expression(&cast.expression);
+            }
+        }
+    }
+
+    /// Records a call of a function, or of a method of `struct_name`.
+    fn fn_call(&mut self, struct_name: Option<&str>, fn_call: &FnCallExpression) {
+        let namespace =
+            fn_call
+                .namespace
+                .as_ref()
+                .and_then(|namespace| match namespace.name.as_ref() {
+                    Expression::Identifier(name) => Some(&name.id),
+                    _ => None,
+                });
+        if let Expression::Identifier(name) = fn_call.fn_name.as_ref() {
+            match (struct_name, namespace) {
+                (Some(struct_name), _) => self.reference_member(struct_name, &name.id),
+                (None, Some(namespace)) => {
+                    self.reference_global(namespace);
+                    self.reference_member(namespace.as_ref(), &name.id);
+                }
+                (None, None) => self.reference_global(&name.id),
+            }
+        }
+        for arg in fn_call.args.iter() {
+            self.expression(arg);
+        }
+    }
+
+    /// Records the names of `property`, accessed on a value of type `object`.
+    fn property(&mut self, object: &TypeSpecifier, property: &Expression) {
+        let Ok(struct_name) = TypeContext::struct_name(object) else {
+            return self.property_args(property);
+        };
+        let struct_name = struct_name.to_owned();
+        match property {
+            Expression::Identifier(identifier) => {
+                self.reference_member(&struct_name, &identifier.id)
+            }
+            Expression::FnCall(fn_call) => self.fn_call(Some(&struct_name), fn_call),
+            Expression::MemberAccess(member_access) => {
+                self.property(object, &member_access.object);
+                match self.context.property_type(object, &member_access.object) {
+                    Ok(inner) => self.property(&inner, &member_access.property),
+                    Err(_) => self.property_args(&member_access.property),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Records the arguments of method calls in a property whose type is
+    /// unknown.
+    fn property_args(&mut self, property: &Expression) {
+        match property {
+            Expression::FnCall(fn_call) => {
+                for arg in fn_call.args.iter() {
+                    self.expression(arg);
+                }
+            }
+            Expression::MemberAccess(member_access) => {
+                self.property_args(&member_access.object);
+                self.property_args(&member_access.property);
+            }
+            _ => {}
+        }
+    }
+
+    fn type_specifier(&mut self, type_specifier: &TypeSpecifier) {
+        match type_specifier {
+            TypeSpecifier::UserDefine(identifier) => self.reference_global(identifier),
+            TypeSpecifier::Pointer(inner) => self.type_specifier(inner),
+            TypeSpecifier::ArrayType { type_specifier, .. } => self.type_specifier(type_specifier),
+            _ => {}
+        }
+    }
+
+    fn push_scope(&mut self, span: &Span) {
+        self.context.push_scope();
+        self.scopes.push(Scope {
+            span: span.clone(),
+            locals: Vec::new(),
+            signatures: Vec::new(),
+        });
+    }
+
+    fn pop_scope(&mut self) {
+        self.context.pop_scope();
+        if let Some(scope) = self.scopes.pop() {
+            self.index.locals.extend(scope.locals);
+        }
+    }
+
+    /// Declares a variable in the innermost scope.
+    fn local(
+        &mut self,
+        name: &Identifier,
+        type_specifier: Option<TypeSpecifier>,
+        signature: String,
+    ) {
+        self.occurrence(
+            &name.span,
+            (signature.clone(), None),
+            Some(name.span.clone()),
+        );
+        if let Some(type_specifier) = &type_specifier {
+            self.context.declare(name.as_ref(), type_specifier.clone());
+        }
+        let Some(scope) = self.scopes.last_mut() else {
+            return;
+        };
+        scope.locals.push(Local {
+            name: name.to_string(),
+            type_specifier,
+            span: name.span.clone(),
+            scope: scope.span.clone(),
+        });
+        scope.signatures.push(signature);
+    }
+
+    /// Records a use of a name, which is a local when one is in scope and a
+    /// top-level declaration otherwise.
+    fn reference(&mut self, name: &Identifier) {
+        let local = self.scopes.iter().rev().find_map(|scope| {
+            let position = scope
+                .locals
+                .iter()
+                .rposition(|local| local.name == name.as_ref())?;
+            Some((
+                scope.locals[position].span.clone(),
+                scope.signatures[position].clone(),
+            ))
+        });
+        match local {
+            Some((definition, signature)) => {
+                self.occurrence(&name.span, (signature, None), Some(definition))
+            }
+            None => self.reference_global(name),
+        }
+    }
+
+    fn reference_global(&mut self, name: &Identifier) {
+        let Some(signature) = self.globals.get(name.as_ref()).cloned() else {
+            return;
+        };
+        let definition = self
+            .root
+            .borrow()
+            .children
+            .get(name.as_ref())
+            .map(|node| node.borrow().data.span.clone());
+        self.occurrence(&name.span, signature, definition);
+    }
+
+    fn reference_member(&mut self, parent: &str, name: &Identifier) {
+        let key = (parent.to_owned(), name.to_string());
+        let Some(signature) = self.members.get(&key).cloned() else {
+            return;
+        };
+        let definition = self.root.borrow().children.get(parent).and_then(|node| {
+            let node = node.borrow();
+            let member = node.children.get(name.as_ref())?;
+            let span = member.borrow().data.span.clone();
+            Some(span)
+        });
+        self.occurrence(&name.span, signature, definition);
+    }
+
+    fn occurrence(&mut self, span: &Span, (signature, doc): Signature, definition: Option<Span>) {
+        // Names the parser made up, like a missing identifier, have no place
+        // in the source.
+        if span.start().row() == 0 {
+            return;
+        }
+        self.index.occurrences.push(Occurrence {
+            span: span.clone(),
+            signature,
+            doc,
+            definition: definition.filter(|definition| definition.start().row() > 0),
+        });
+    }
+}
+
+fn fn_signature(
+    struct_name: Option<&str>,
+    name: &str,
+    params: &[&VariableType],
+    return_type: &TypeSpecifier,
+) -> String {
+    let params: Vec<String> = params
+        .iter()
+        .map(|param| format!("{}: {}", param.identifier, param.type_specifier))
+        .collect();
+    let name = match struct_name {
+        Some(struct_name) => format!("{}::{}", struct_name, name),
+        None => name.to_owned(),
+    };
+    return format!("fn {}({}) -> {}", name, params.join(", "), return_type);
+}
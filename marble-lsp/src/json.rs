@@ -0,0 +1,322 @@
+use std::fmt;
+
+/// A JSON value, enough of it for the protocol messages.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    /// Members in the order they were written.
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub fn object(members: Vec<(&str, Value)>) -> Self {
+        return Value::Object(
+            members
+                .into_iter()
+                .map(|(key, value)| (key.to_owned(), value))
+                .collect(),
+        );
+    }
+
+    /// The member named `key`, or `Null` when there is none.
+    pub fn get(&self, key: &str) -> &Value {
+        if let Value::Object(members) = self {
+            if let Some((_, value)) = members.iter().find(|(name, _)| name == key) {
+                return value;
+            }
+        }
+        return &Value::Null;
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        return match self {
+            Value::String(value) => Some(value),
+            _ => None,
+        };
+    }
+
+    pub fn as_usize(&self) -> Option<usize> {
+        return match self {
+            Value::Number(value) if *value >= 0.0 => Some(*value as usize),
+            _ => None,
+        };
+    }
+
+    pub fn as_array(&self) -> Option<&Vec<Value>> {
+        return match self {
+            Value::Array(values) => Some(values),
+            _ => None,
+        };
+    }
+
+    pub fn is_null(&self) -> bool {
+        return matches!(self, Value::Null);
+    }
+
+    pub fn parse(text: &str) -> Result<Value, String> {
+        let mut parser = JsonParser {
+            bytes: text.as_bytes(),
+            cursor: 0,
+        };
+        let value = parser.value()?;
+        parser.skip_space();
+        if parser.cursor != parser.bytes.len() {
+            return Err(format!("Unexpected data at {}", parser.cursor));
+        }
+        return Ok(value);
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        return Value::String(value.to_owned());
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        return Value::String(value);
+    }
+}
+
+impl From<usize> for Value {
+    fn from(value: usize) -> Self {
+        return Value::Number(value as f64);
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        return Value::Bool(value);
+    }
+}
+
+impl From<Vec<Value>> for Value {
+    fn from(values: Vec<Value>) -> Self {
+        return Value::Array(values);
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        return match value {
+            Some(value) => value.into(),
+            None => Value::Null,
+        };
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Value::Null => write!(f, "null"),
+            Value::Bool(value) => write!(f, "{}", value),
+            Value::Number(value) if value.fract() == 0.0 && value.abs() < 1e15 => {
+                write!(f, "{}", *value as i64)
+            }
+            Value::Number(value) => write!(f, "{}", value),
+            Value::String(value) => write_string(f, value),
+            Value::Array(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            }
+            Value::Object(members) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in members.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        };
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in value.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    return write!(f, "\"");
+}
+
+struct JsonParser<'a> {
+    bytes: &'a [u8],
+    cursor: usize,
+}
+
+impl JsonParser<'_> {
+    fn value(&mut self) -> Result<Value, String> {
+        self.skip_space();
+        return match self.peek() {
+            Some(b'{') => self.object(),
+            Some(b'[') => self.array(),
+            Some(b'"') => Ok(Value::String(self.string()?)),
+            Some(b't') => self.keyword("true", Value::Bool(true)),
+            Some(b'f') => self.keyword("false", Value::Bool(false)),
+            Some(b'n') => self.keyword("null", Value::Null),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            _ => Err(format!("Unexpected character at {}", self.cursor)),
+        };
+    }
+
+    fn object(&mut self) -> Result<Value, String> {
+        self.cursor += 1; // skip '{'
+        let mut members = Vec::new();
+        self.skip_space();
+        if self.peek() == Some(b'}') {
+            self.cursor += 1;
+            return Ok(Value::Object(members));
+        }
+        loop {
+            self.skip_space();
+            let key = self.string()?;
+            self.skip_space();
+            self.expect(b':')?;
+            members.push((key, self.value()?));
+            self.skip_space();
+            match self.next() {
+                Some(b',') => continue,
+                Some(b'}') => return Ok(Value::Object(members)),
+                _ => return Err(format!("Expected ',' or '}}' at {}", self.cursor)),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, String> {
+        self.cursor += 1; // skip '['
+        let mut values = Vec::new();
+        self.skip_space();
+        if self.peek() == Some(b']') {
+            self.cursor += 1;
+            return Ok(Value::Array(values));
+        }
+        loop {
+            values.push(self.value()?);
+            self.skip_space();
+            match self.next() {
+                Some(b',') => continue,
+                Some(b']') => return Ok(Value::Array(values)),
+                _ => return Err(format!("Expected ',' or ']' at {}", self.cursor)),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect(b'"')?;
+        let mut bytes = Vec::new();
+        loop {
+            match self.next() {
+                None => return Err(String::from("Unterminated string")),
+                Some(b'"') => break,
+                Some(b'\\') => match self.next() {
+                    Some(b'"') => bytes.push(b'"'),
+                    Some(b'\\') => bytes.push(b'\\'),
+                    Some(b'/') => bytes.push(b'/'),
+                    Some(b'b') => bytes.push(0x08),
+                    Some(b'f') => bytes.push(0x0c),
+                    Some(b'n') => bytes.push(b'\n'),
+                    Some(b'r') => bytes.push(b'\r'),
+                    Some(b't') => bytes.push(b'\t'),
+                    Some(b'u') => {
+                        let c = self.unicode_escape()?;
+                        let mut buffer = [0; 4];
+                        bytes.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+                    }
+                    _ => return Err(format!("Invalid escape at {}", self.cursor)),
+                },
+                Some(byte) => bytes.push(byte),
+            }
+        }
+        return String::from_utf8(bytes).map_err(|err| err.to_string());
+    }
+
+    /// Reads the digits of a `\u` escape, joining surrogate pairs.
+    fn unicode_escape(&mut self) -> Result<char, String> {
+        let high = self.hex()?;
+        if !(0xD800..0xDC00).contains(&high) {
+            return Ok(char::from_u32(high).unwrap_or(char::REPLACEMENT_CHARACTER));
+        }
+        if self.next() != Some(b'\\') || self.next() != Some(b'u') {
+            return Ok(char::REPLACEMENT_CHARACTER);
+        }
+        let low = self.hex()?;
+        let code = 0x10000 + ((high - 0xD800) << 10) + (low.wrapping_sub(0xDC00) & 0x3FF);
+        return Ok(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+    }
+
+    fn hex(&mut self) -> Result<u32, String> {
+        let digits = self
+            .bytes
+            .get(self.cursor..self.cursor + 4)
+            .ok_or_else(|| String::from("Truncated unicode escape"))?;
+        let digits = std::str::from_utf8(digits).map_err(|err| err.to_string())?;
+        let code = u32::from_str_radix(digits, 16).map_err(|err| err.to_string())?;
+        self.cursor += 4;
+        return Ok(code);
+    }
+
+    fn number(&mut self) -> Result<Value, String> {
+        let start = self.cursor;
+        while let Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9') = self.peek() {
+            self.cursor += 1;
+        }
+        let text = std::str::from_utf8(&self.bytes[start..self.cursor]).unwrap_or_default();
+        return text
+            .parse()
+            .map(Value::Number)
+            .map_err(|_| format!("Invalid number at {}", start));
+    }
+
+    fn keyword(&mut self, keyword: &str, value: Value) -> Result<Value, String> {
+        if self.bytes[self.cursor..].starts_with(keyword.as_bytes()) {
+            self.cursor += keyword.len();
+            return Ok(value);
+        }
+        return Err(format!("Unexpected character at {}", self.cursor));
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), String> {
+        if self.next() == Some(byte) {
+            return Ok(());
+        }
+        return Err(format!("Expected '{}' at {}", byte as char, self.cursor));
+    }
+
+    fn skip_space(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.cursor += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        return self.bytes.get(self.cursor).copied();
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let byte = self.peek();
+        self.cursor += 1;
+        return byte;
+    }
+}
@@ -0,0 +1,48 @@
+#![allow(clippy::needless_return)]
+
+mod document;
+mod features;
+mod index;
+mod json;
+mod server;
+mod transport;
+
+use std::{
+    io,
+    panic::{self, AssertUnwindSafe},
+    process::exit,
+};
+
+use server::Server;
+
+fn main() {
+    let stdin = io::stdin();
+    let mut reader = stdin.lock();
+    let mut writer = io::stdout().lock();
+    let mut server = Server::new();
+
+    loop {
+        let message = match transport::read_message(&mut reader) {
+            Ok(Some(message)) => message,
+            Ok(None) => break,
+            Err(err) => {
+                eprintln!("marble-lsp: {}", err);
+                continue;
+            }
+        };
+        // A bug in the analyzer must not take the editor's session down with it
+        let id = message.get("id").clone();
+        let replies = panic::catch_unwind(AssertUnwindSafe(|| server.handle(message)))
+            .unwrap_or_else(|_| server::internal_error(id));
+        for reply in replies {
+            if let Err(err) = transport::write_message(&mut writer, &reply) {
+                eprintln!("marble-lsp: {}", err);
+                exit(1);
+            }
+        }
+        if server.exited() {
+            break;
+        }
+    }
+    exit(if server.clean_exit() { 0 } else { 1 });
+}
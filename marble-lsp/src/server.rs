@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+
+use crate::{document::Document, features, json::Value};
+
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_REQUEST: i64 = -32600;
+const INTERNAL_ERROR: i64 = -32603;
+
+/// The open documents and the state of the session.
+#[derive(Default)]
+pub struct Server {
+    documents: HashMap<String, Document>,
+    shutdown: bool,
+    exit: bool,
+}
+
+impl Server {
+    pub fn new() -> Self {
+        return Server::default();
+    }
+
+    /// Set once the client sent `exit`.
+    pub fn exited(&self) -> bool {
+        return self.exit;
+    }
+
+    /// Whether `shutdown` came before `exit`, as the protocol asks.
+    pub fn clean_exit(&self) -> bool {
+        return self.shutdown;
+    }
+
+    /// Handles one message and returns the responses and notifications to
+    /// send back.
+    pub fn handle(&mut self, message: Value) -> Vec<Value> {
+        let method = message
+            .get("method")
+            .as_str()
+            .unwrap_or_default()
+            .to_owned();
+        let id = message.get("id").clone();
+        let params = message.get("params");
+
+        if id.is_null() {
+            return self.notification(&method, params);
+        }
+        if self.shutdown {
+            return vec![error(id, INVALID_REQUEST, "the server is shutting down")];
+        }
+        let result = match method.as_str() {
+            "initialize" => capabilities(),
+            "shutdown" => {
+                self.shutdown = true;
+                Value::Null
+            }
+            "textDocument/hover" => self
+                .document_at(params)
+                .map(|(_, document, position)| features::hover(document, position))
+                .unwrap_or(Value::Null),
+            "textDocument/definition" => self
+                .document_at(params)
+                .map(|(uri, document, position)| features::definition(uri, document, position))
+                .unwrap_or(Value::Null),
+            "textDocument/documentSymbol" => self
+                .document(params)
+                .map(|(_, document)| features::document_symbols(document))
+                .unwrap_or(Value::Null),
+            "textDocument/completion" => self
+                .document_at(params)
+                .map(|(_, document, position)| features::completion(document, position))
+                .unwrap_or(Value::Null),
+            _ => {
+                let message = format!("unknown method `{}`", method);
+                return vec![error(id, METHOD_NOT_FOUND, &message)];
+            }
+        };
+        return vec![Value::object(vec![
+            ("jsonrpc", Value::from("2.0")),
+            ("id", id),
+            ("result", result),
+        ])];
+    }
+
+    fn notification(&mut self, method: &str, params: &Value) -> Vec<Value> {
+        let uri = params
+            .get("textDocument")
+            .get("uri")
+            .as_str()
+            .unwrap_or_default()
+            .to_owned();
+        return match method {
+            "exit" => {
+                self.exit = true;
+                Vec::new()
+            }
+            "textDocument/didOpen" => {
+                let text = params.get("textDocument").get("text");
+                self.update(uri, text)
+            }
+            "textDocument/didChange" => {
+                // The server asks for full syncs, so the last change holds
+                // the whole text.
+                let changes = params.get("contentChanges").as_array();
+                match changes.and_then(|changes| changes.last()) {
+                    Some(change) => self.update(uri, change.get("text")),
+                    None => Vec::new(),
+                }
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                let params = Value::object(vec![
+                    ("uri", Value::from(uri)),
+                    ("diagnostics", Value::Array(Vec::new())),
+                ]);
+                vec![notification("textDocument/publishDiagnostics", params)]
+            }
+            _ => Vec::new(),
+        };
+    }
+
+    /// Re-analyzes a document and publishes its diagnostics.
+    fn update(&mut self, uri: String, text: &Value) -> Vec<Value> {
+        let Some(text) = text.as_str() else {
+            return Vec::new();
+        };
+        let file_name = uri.rsplit('/').next().unwrap_or_default();
+        let document = Document::new(file_name, text.to_owned());
+        let params = features::diagnostics(&uri, &document);
+        self.documents.insert(uri, document);
+        return vec![notification("textDocument/publishDiagnostics", params)];
+    }
+
+    fn document<'a>(&'a self, params: &'a Value) -> Option<(&'a str, &'a Document)> {
+        let uri = params.get("textDocument").get("uri").as_str()?;
+        return Some((uri, self.documents.get(uri)?));
+    }
+
+    fn document_at<'a>(
+        &'a self,
+        params: &'a Value,
+    ) -> Option<(&'a str, &'a Document, (usize, usize))> {
+        let (uri, document) = self.document(params)?;
+        let position = document.position(params.get("position"))?;
+        return Some((uri, document, position));
+    }
+}
+
+fn capabilities() -> Value {
+    return Value::object(vec![
+        (
+            "capabilities",
+            Value::object(vec![
+                ("textDocumentSync", Value::from(1)),
+                ("hoverProvider", Value::from(true)),
+                ("definitionProvider", Value::from(true)),
+                ("documentSymbolProvider", Value::from(true)),
+                (
+                    "completionProvider",
+                    Value::object(vec![(
+                        "triggerCharacters",
+                        Value::from(vec![Value::from("."), Value::from(">")]),
+                    )]),
+                ),
+            ]),
+        ),
+        (
+            "serverInfo",
+            Value::object(vec![
+                ("name", Value::from("marble-lsp")),
+                ("version", Value::from(env!("CARGO_PKG_VERSION"))),
+            ]),
+        ),
+    ]);
+}
+
+/// The reply to a message whose handling panicked. Requests still get an
+/// answer so the client does not wait for it forever.
+pub fn internal_error(id: Value) -> Vec<Value> {
+    if id.is_null() {
+        return Vec::new();
+    }
+    return vec![error(
+        id,
+        INTERNAL_ERROR,
+        "the server failed to handle the request",
+    )];
+}
+
+fn notification(method: &str, params: Value) -> Value {
+    return Value::object(vec![
+        ("jsonrpc", Value::from("2.0")),
+        ("method", Value::from(method)),
+        ("params", params),
+    ]);
+}
+
+fn error(id: Value, code: i64, message: &str) -> Value {
+    return Value::object(vec![
+        ("jsonrpc", Value::from("2.0")),
+        ("id", id),
+        (
+            "error",
+            Value::object(vec![
+                ("code", Value::Number(code as f64)),
+                ("message", Value::from(message)),
+            ]),
+        ),
+    ]);
+}
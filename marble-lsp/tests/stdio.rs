@@ -0,0 +1,179 @@
+//! Drives the server over stdio the way an editor does: every message is
+//! written up front and the replies are read back once the server exits.
+
+#![allow(clippy::needless_return)]
+
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+const URI: &str = "file:///project/main.mb";
+
+const SOURCE: &str = "struct Point {\\n    pub x: int,\\n}\\n\\nfn make() -> Point {\\n    return Point { x: 1 };\\n}\\n\\nfn main() -> int {\\n    let p = make();\\n    return p.x;\\n}\\n";
+
+fn frame(body: &str) -> String {
+    return format!("Content-Length: {}\r\n\r\n{}", body.len(), body);
+}
+
+fn request(id: usize, method: &str, params: &str) -> String {
+    return frame(&format!(
+        r#"{{"jsonrpc":"2.0","id":{},"method":"{}","params":{}}}"#,
+        id, method, params
+    ));
+}
+
+fn notification(method: &str, params: &str) -> String {
+    return frame(&format!(
+        r#"{{"jsonrpc":"2.0","method":"{}","params":{}}}"#,
+        method, params
+    ));
+}
+
+fn did_open(uri: &str, text: &str) -> String {
+    return notification(
+        "textDocument/didOpen",
+        &format!(
+            r#"{{"textDocument":{{"uri":"{}","languageId":"marble","version":1,"text":"{}"}}}}"#,
+            uri, text
+        ),
+    );
+}
+
+fn at(uri: &str, line: usize, character: usize) -> String {
+    return format!(
+        r#"{{"textDocument":{{"uri":"{}"}},"position":{{"line":{},"character":{}}}}}"#,
+        uri, line, character
+    );
+}
+
+/// Runs the server on `messages` and returns its exit code and the bodies
+/// of the messages it wrote.
+fn session(messages: &[String]) -> (Option<i32>, Vec<String>) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_marble-lsp"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("cannot start marble-lsp");
+    let mut stdin = child.stdin.take().unwrap();
+    stdin.write_all(messages.concat().as_bytes()).unwrap();
+    drop(stdin);
+
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let mut bodies = Vec::new();
+    let mut rest = stdout.as_str();
+    while let Some(start) = rest.find("Content-Length: ") {
+        let header = &rest[start + "Content-Length: ".len()..];
+        let (length, header) = header.split_once("\r\n\r\n").unwrap();
+        let length: usize = length.trim().parse().unwrap();
+        bodies.push(header[..length].to_owned());
+        rest = &header[length..];
+    }
+    return (output.status.code(), bodies);
+}
+
+/// The response to the request `id`.
+fn response(bodies: &[String], id: usize) -> &str {
+    let key = format!(r#""id":{},"#, id);
+    return bodies
+        .iter()
+        .find(|body| body.contains(&key))
+        .unwrap_or_else(|| panic!("no response to request {}", id));
+}
+
+#[test]
+fn answers_an_editor_session() {
+    let (code, bodies) = session(&[
+        request(1, "initialize", "{}"),
+        notification("initialized", "{}"),
+        did_open(URI, SOURCE),
+        request(2, "textDocument/hover", &at(URI, 9, 13)),
+        request(3, "textDocument/definition", &at(URI, 9, 13)),
+        request(4, "textDocument/documentSymbol", &at(URI, 0, 0)),
+        request(5, "textDocument/completion", &at(URI, 10, 13)),
+        request(6, "shutdown", "null"),
+        notification("exit", "null"),
+    ]);
+
+    assert_eq!(code, Some(0));
+    assert!(response(&bodies, 1).contains(r#""hoverProvider":true"#));
+    assert!(bodies
+        .iter()
+        .any(|body| body.contains("publishDiagnostics") && body.contains(r#""diagnostics":[]"#)));
+    assert!(response(&bodies, 2).contains("fn make() -> Point"));
+    assert!(response(&bodies, 3).contains(r#""start":{"line":4,"character":3}"#));
+    let symbols = response(&bodies, 4);
+    for name in ["Point", "make", "main"] {
+        assert!(symbols.contains(&format!(r#""name":"{}""#, name)));
+    }
+    assert!(response(&bodies, 5).contains(r#""label":"x""#));
+    assert!(response(&bodies, 6).contains(r#""result":null"#));
+}
+
+#[test]
+fn completes_members_with_receivers() {
+    let uri = "file:///project/methods.mb";
+    let source = "struct Point {\\n    pub x: int,\\n}\\n\\nimpl Point {\\n    pub fn origin() -> Point {\\n        return Point { x: 0 };\\n    }\\n\\n    pub fn (self: Point) get() -> int {\\n        return self.x;\\n    }\\n}\\n\\nfn main() -> int {\\n    let p = Point::origin();\\n    return p.x;\\n}\\n";
+    let (_, bodies) = session(&[
+        request(1, "initialize", "{}"),
+        did_open(uri, source),
+        request(2, "textDocument/completion", &at(uri, 16, 13)),
+        request(3, "shutdown", "null"),
+        notification("exit", "null"),
+    ]);
+
+    let items = response(&bodies, 2);
+    assert!(items.contains(r#""label":"x""#));
+    assert!(items.contains(r#""label":"get""#));
+    assert!(!items.contains(r#""label":"origin""#));
+}
+
+#[test]
+fn hides_private_members_outside_their_impl() {
+    let uri = "file:///project/private.mb";
+    let source = "struct Point {\\n    pub x: int,\\n    y: int,\\n}\\n\\nimpl Point {\\n    pub fn make() -> Point {\\n        return Point { x: 1, y: 2 };\\n    }\\n\\n    fn (self: Point) secret() -> int {\\n        return self.y;\\n    }\\n}\\n\\nfn main() -> int {\\n    let p = Point::make();\\n    return p.x;\\n}\\n";
+    let (_, bodies) = session(&[
+        request(1, "initialize", "{}"),
+        did_open(uri, source),
+        request(2, "textDocument/completion", &at(uri, 11, 20)),
+        request(3, "textDocument/completion", &at(uri, 17, 13)),
+        request(4, "shutdown", "null"),
+        notification("exit", "null"),
+    ]);
+
+    let inside = response(&bodies, 2);
+    for label in ["x", "y", "secret"] {
+        assert!(inside.contains(&format!(r#""label":"{}""#, label)));
+    }
+    let outside = response(&bodies, 3);
+    assert!(outside.contains(r#""label":"x""#));
+    assert!(!outside.contains(r#""label":"y""#));
+    assert!(!outside.contains(r#""label":"secret""#));
+}
+
+#[test]
+fn survives_invalid_documents() {
+    let uri = "file:///project/bad.mb";
+    let (code, bodies) = session(&[
+        request(1, "initialize", "{}"),
+        did_open(
+            uri,
+            "struct S { pub x: int }\\nimpl S* { pub fn f() -> int { return 1; } }",
+        ),
+        request(2, "textDocument/hover", &at(uri, 1, 5)),
+        request(3, "shutdown", "null"),
+        notification("exit", "null"),
+    ]);
+
+    assert_eq!(code, Some(0));
+    assert!(bodies.iter().any(|body| body.contains(r#""code":"E0312""#)));
+    response(&bodies, 2);
+}
+
+#[test]
+fn exits_with_an_error_without_shutdown() {
+    let (code, _) = session(&[request(1, "initialize", "{}"), notification("exit", "null")]);
+    assert_eq!(code, Some(1));
+}